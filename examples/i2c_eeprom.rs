@@ -42,7 +42,8 @@ fn main() -> ! {
         syscon.uartfrg.set_clkdiv(6);
         syscon.uartfrg.set_frgmult(22);
         syscon.uartfrg.set_frgdiv(0xff);
-        usart::Clock::new(&syscon.uartfrg, 0, 16)
+        let uartfrg = syscon.uartfrg.share();
+        usart::Clock::new(&uartfrg, 0, 16)
     };
     #[cfg(feature = "845")]
     // Set baud rate to 115200 baud
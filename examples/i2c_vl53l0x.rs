@@ -64,8 +64,9 @@ fn main() -> ! {
         .u0_txd
         .assign(p.pins.pio0_4.into_swm_pin(), &mut swm.handle);
 
+    let uartfrg = syscon.uartfrg.share();
     let mut serial = p.USART0.enable(
-        &usart::Clock::new(&syscon.uartfrg, 0, 16),
+        &usart::Clock::new(&uartfrg, 0, 16),
         &mut syscon.handle,
         u0_rxd,
         u0_txd,
@@ -10,7 +10,6 @@ use lpc8xx_hal::{
     Peripherals,
 };
 use panic_halt as _;
-use void::ResultVoidExt;
 
 #[rtfm::app(device = lpc8xx_hal::pac)]
 const APP: () = {
@@ -48,7 +47,7 @@ const APP: () = {
         let int = context.resources.int;
         let led = context.resources.led;
 
-        led.toggle().void_unwrap();
+        led.toggle().unwrap();
 
         int.clear_rising_edge_flag();
         int.clear_falling_edge_flag();
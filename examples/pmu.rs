@@ -27,7 +27,8 @@ fn main() -> ! {
     syscon.uartfrg.set_clkdiv(6);
     syscon.uartfrg.set_frgmult(22);
     syscon.uartfrg.set_frgdiv(0xff);
-    let clock_config = usart::Clock::new(&syscon.uartfrg, 0, 16);
+    let uartfrg = syscon.uartfrg.share();
+    let clock_config = usart::Clock::new(&uartfrg, 0, 16);
 
     let (u0_rxd, _) = swm
         .movable_functions
@@ -3,3 +3,6 @@ MEMORY
     FLASH : ORIGIN = 0x00000000, LENGTH = 32K
     RAM   : ORIGIN = 0x10000000, LENGTH = 8K
 }
+
+/* The IAP functions of the boot ROM use the top 32 bytes of RAM */
+_stack_start = ORIGIN(RAM) + LENGTH(RAM) - 32;
@@ -0,0 +1,110 @@
+//! Hooks into long-running blocking operations
+//!
+//! This module is only available, if the `activity-hook` feature is enabled.
+//!
+//! Some blocking operations of this HAL can take a long time to complete, for
+//! example I2C transactions with a slave that stretches the clock, waiting for
+//! DMA transfers, or erasing flash using IAP. If the watchdog timer (WWDT) is
+//! running, it might reset the microcontroller in the meantime.
+//!
+//! An application can register an [`ActivityHook`] using [`set_hook`], which
+//! is then called periodically while such operations wait. This hook would
+//! typically feed the watchdog. The following operations call the hook:
+//!
+//! - The blocking I2C transactions, while waiting for the bus
+//! - [`dma::Transfer::wait`], [`dma::Fill::wait`], and
+//!   [`dma::TriggeredTransfer::wait`]
+//! - The IAP functions, before and after each call into the boot ROM. The
+//!   boot ROM itself can't call the hook, so the watchdog timeout must be
+//!   longer than the longest single IAP command.
+//!
+//! The hook is called often, possibly every few microseconds. If the watchdog
+//! is used in windowed mode, the hook must not feed it before the window has
+//! opened.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use core::cell::RefCell;
+//!
+//! use lpc8xx_hal::{
+//!     activity::{self, ActivityHook},
+//!     cortex_m::interrupt::{self, Mutex},
+//!     pac, Peripherals,
+//! };
+//!
+//! static WWDT: Mutex<RefCell<Option<pac::WWDT>>> =
+//!     Mutex::new(RefCell::new(None));
+//!
+//! struct FeedWatchdog;
+//!
+//! impl ActivityHook for FeedWatchdog {
+//!     fn on_activity(&self) {
+//!         interrupt::free(|cs| {
+//!             if let Some(wwdt) = WWDT.borrow(cs).borrow().as_ref() {
+//!                 wwdt.feed.write(|w| unsafe { w.feed().bits(0xaa) });
+//!                 wwdt.feed.write(|w| unsafe { w.feed().bits(0x55) });
+//!             }
+//!         });
+//!     }
+//! }
+//!
+//! static FEED_WATCHDOG: FeedWatchdog = FeedWatchdog;
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! // Configure and start the watchdog, then
+//! interrupt::free(|cs| *WWDT.borrow(cs).borrow_mut() = Some(p.WWDT));
+//! activity::set_hook(&FEED_WATCHDOG);
+//! ```
+//!
+//! [`ActivityHook`]: trait.ActivityHook.html
+//! [`set_hook`]: fn.set_hook.html
+//! [`dma::Transfer::wait`]: ../dma/struct.Transfer.html#method.wait
+//! [`dma::Fill::wait`]: ../dma/struct.Fill.html#method.wait
+//! [`dma::TriggeredTransfer::wait`]: ../dma/struct.TriggeredTransfer.html#method.wait
+
+use core::cell::Cell;
+
+use cortex_m::interrupt::{self, Mutex};
+
+/// Called periodically by long-running blocking operations
+///
+/// See [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub trait ActivityHook: Sync {
+    /// Called periodically, while an operation waits
+    ///
+    /// This can be called from within a critical section, or from an
+    /// interrupt handler, if the blocking operation is used there.
+    fn on_activity(&self);
+}
+
+static HOOK: Mutex<Cell<Option<&'static dyn ActivityHook>>> =
+    Mutex::new(Cell::new(None));
+
+/// Register the activity hook
+///
+/// Replaces the previously registered hook, if any, which is returned.
+pub fn set_hook(
+    hook: &'static dyn ActivityHook,
+) -> Option<&'static dyn ActivityHook> {
+    interrupt::free(|cs| HOOK.borrow(cs).replace(Some(hook)))
+}
+
+/// Remove the activity hook
+///
+/// Returns the hook that was registered, if any.
+pub fn clear_hook() -> Option<&'static dyn ActivityHook> {
+    interrupt::free(|cs| HOOK.borrow(cs).take())
+}
+
+/// Call the activity hook, if one is registered
+pub(crate) fn notify() {
+    let hook = interrupt::free(|cs| HOOK.borrow(cs).get());
+
+    if let Some(hook) = hook {
+        hook.on_activity();
+    }
+}
@@ -1,5 +1,29 @@
 //! API for ADC
 //!
+//! # Channels
+//!
+//! The ADC has 12 channels, `ADC_0` to `ADC_11`. Each of them is a fixed
+//! function that needs to be assigned to its pin using the [switch matrix],
+//! before it can be passed to the ADC.
+//!
+//! Neither LPC82x nor LPC845 have any internal ADC channels. There is no
+//! on-chip temperature sensor, and the supply voltage can't be selected as an
+//! ADC input. If you need to measure temperature, you need an external sensor
+//! connected to one of the channels. The supply voltage can be measured using
+//! the analog comparator and its internal reference instead, as described in
+//! [`VoltageScale::from_supply`].
+//!
+//! # PWM synchronization
+//!
+//! Conversions can be triggered by the SCT, at a fixed point within each PWM
+//! period. Please refer to [`ADC::sync_to_pwm`] for details.
+//!
+//! # Comparator-triggered capture
+//!
+//! A conversion can be triggered by an edge of the analog comparator output,
+//! to capture the analog value at the moment a threshold is crossed. Please
+//! refer to [`ADC::capture_on_comparator`] for details.
+//!
 //! # Examples
 //!
 //! Read a single value:
@@ -29,16 +53,31 @@
 //! // Read a single value
 //! let adc_value = nb::block!(adc.read(&mut adc_pin))
 //!     .expect("Read should never fail");
+//!
+//! // Average 16 samples, to get a less noisy value
+//! let samples = adc.read_samples(&mut adc_pin, 16);
+//! let average = samples.average();
+//!
+//! // Oversample to get a 14-bit value
+//! let value = adc.read_oversampled(&mut adc_pin, 2);
 //! ```
 //!
 //! Please refer to the [examples in the repository] for more example code.
 //!
+//! [switch matrix]: ../swm/index.html
+//! [`VoltageScale::from_supply`]: struct.VoltageScale.html#method.from_supply
+//! [`ADC::sync_to_pwm`]: struct.ADC.html#method.sync_to_pwm
+//! [`ADC::capture_on_comparator`]: struct.ADC.html#method.capture_on_comparator
 //! [examples in the repository]: https://github.com/lpc-rs/lpc8xx-hal/tree/master/examples
 
 use embedded_hal::adc::{Channel, OneShot};
+use void::Void;
 
 use crate::{
-    init_state, pac, swm,
+    init_state,
+    pac::{self, Interrupt, NVIC},
+    sct::{DetachedPwmPin, SCT},
+    swm::{self, SCT_OUT3},
     syscon::{self, clock_source::AdcClock},
 };
 
@@ -131,6 +170,506 @@ impl ADC<init_state::Enabled> {
             _state: init_state::Disabled,
         }
     }
+
+    /// Enable interrupts for the ADC in the NVIC
+    ///
+    /// The ADC has four interrupts (sequence A, sequence B, threshold compare,
+    /// and overrun). This method enables all of them in the NVIC. It doesn't
+    /// enable any specific interrupt in the ADC.
+    pub fn enable_in_nvic(&mut self) {
+        for &interrupt in &INTERRUPTS {
+            // Safe, because there's no critical section here that this could
+            // interfere with.
+            unsafe { NVIC::unmask(interrupt) };
+        }
+    }
+
+    /// Disable interrupts for the ADC in the NVIC
+    ///
+    /// This disables all four ADC interrupts in the NVIC. It doesn't change
+    /// anything about the interrupt configuration within the ADC.
+    pub fn disable_in_nvic(&mut self) {
+        for &interrupt in &INTERRUPTS {
+            NVIC::mask(interrupt);
+        }
+    }
+
+    /// Clear the pending flags of all ADC interrupts in the NVIC
+    ///
+    /// This only clears the interrupts' pending flags in the NVIC. It does not
+    /// affect any of the interrupt-related flags in the peripheral.
+    pub fn clear_nvic_pending(&mut self) {
+        for &interrupt in &INTERRUPTS {
+            NVIC::unpend(interrupt);
+        }
+    }
+
+    /// Set the priority of all ADC interrupts in the NVIC
+    ///
+    /// Lower values mean higher priority. Only the two most significant bits of
+    /// `priority` are used by the hardware.
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections,
+    /// like the ones used by RTFM.
+    pub unsafe fn set_nvic_priority(&mut self, nvic: &mut NVIC, priority: u8) {
+        for &interrupt in &INTERRUPTS {
+            nvic.set_priority(interrupt, priority);
+        }
+    }
+
+    /// Read all flags at once, and clear the events among them
+    ///
+    /// Reads the FLAGS register in a single volatile read, which makes this
+    /// the right way to find out what happened in an interrupt handler. The
+    /// threshold crossing flags and the sequence completion flags are then
+    /// cleared, if they were set. Events that occur after the read are not
+    /// lost, as only flags that have been read are cleared.
+    ///
+    /// The overrun flags can't be cleared this way. They are cleared by
+    /// reading the data register of the respective channel or sequence.
+    pub fn read_and_clear_events(&mut self) -> Events {
+        let events = Events::from_bits_truncate(self.adc.flags.read().bits());
+
+        let clear = events & Events::CLEARABLE;
+        if !clear.is_empty() {
+            self.adc.flags.write(|w| unsafe { w.bits(clear.bits()) });
+        }
+
+        events
+    }
+
+    /// Save the configuration of the ADC
+    ///
+    /// Reads the control, sequence, threshold, and interrupt enable registers.
+    /// The returned [`Snapshot`] can be passed to [`resume`], to restore the
+    /// configuration after the peripheral's state has been lost, for example
+    /// because it was reset or powered down while the microcontroller was in
+    /// a low-power mode.
+    ///
+    /// [`Snapshot`]: struct.Snapshot.html
+    /// [`resume`]: #method.resume
+    pub fn suspend(&mut self) -> Snapshot {
+        Snapshot {
+            ctrl: self.adc.ctrl.read().bits(),
+            seq_ctrla: self.adc.seq_ctrla.read().bits(),
+            seq_ctrlb: self.adc.seq_ctrlb.read().bits(),
+            thr0_low: self.adc.thr0_low.read().bits(),
+            thr1_low: self.adc.thr1_low.read().bits(),
+            thr0_high: self.adc.thr0_high.read().bits(),
+            thr1_high: self.adc.thr1_high.read().bits(),
+            chan_thrsel: self.adc.chan_thrsel.read().bits(),
+            inten: self.adc.inten.read().bits(),
+            trm: self.adc.trm.read().bits(),
+        }
+    }
+
+    /// Restore a configuration that was saved using [`suspend`]
+    ///
+    /// Enables the peripheral clock and powers up the ADC, in case either has
+    /// been switched off, and writes the saved configuration back. The
+    /// sequences are enabled last, and no conversion is started, unless a
+    /// sequence is in burst mode.
+    ///
+    /// The ADC is not calibrated again. If it has been powered down, the
+    /// calibration that was done by [`ADC::enable`] might no longer be
+    /// accurate.
+    ///
+    /// [`suspend`]: #method.suspend
+    /// [`ADC::enable`]: struct.ADC.html#method.enable
+    pub fn resume(&mut self, snapshot: &Snapshot, syscon: &mut syscon::Handle) {
+        syscon.enable_clock(&self.adc);
+        syscon.power_up(&self.adc);
+
+        // Sound, as all values have been read from the same registers.
+        unsafe {
+            self.adc.ctrl.write(|w| w.bits(snapshot.ctrl));
+            self.adc.trm.write(|w| w.bits(snapshot.trm));
+            self.adc.thr0_low.write(|w| w.bits(snapshot.thr0_low));
+            self.adc.thr1_low.write(|w| w.bits(snapshot.thr1_low));
+            self.adc.thr0_high.write(|w| w.bits(snapshot.thr0_high));
+            self.adc.thr1_high.write(|w| w.bits(snapshot.thr1_high));
+            self.adc.chan_thrsel.write(|w| w.bits(snapshot.chan_thrsel));
+            self.adc.inten.write(|w| w.bits(snapshot.inten));
+
+            for &(register, value) in &[
+                (&self.adc.seq_ctrla, snapshot.seq_ctrla),
+                (&self.adc.seq_ctrlb, snapshot.seq_ctrlb),
+            ] {
+                // The sequence must be disabled while it is configured.
+                register.write(|w| {
+                    w.bits(value).seq_ena().disabled().start().clear_bit()
+                });
+                register.write(|w| w.bits(value).start().clear_bit());
+            }
+        }
+    }
+
+    /// Take multiple samples of a channel
+    ///
+    /// Performs `count` conversions of the given channel, back to back, and
+    /// returns the accumulated results. Individual conversions are quite noisy,
+    /// so averaging a number of them often gives much more stable readings.
+    ///
+    /// Blocks until all conversions have finished. If `count` is 0, a single
+    /// conversion is performed.
+    pub fn read_samples<PIN>(&mut self, _: &mut PIN, count: u16) -> Samples
+    where
+        PIN: Channel<ADC, ID = u8>,
+    {
+        let count = count.max(1);
+
+        let mut samples = Samples {
+            sum: 0,
+            count,
+            min: u16::MAX,
+            max: 0,
+        };
+
+        for _ in 0..count {
+            let value = self.convert(PIN::channel());
+
+            samples.sum += u32::from(value);
+            samples.min = samples.min.min(value);
+            samples.max = samples.max.max(value);
+        }
+
+        samples
+    }
+
+    /// Oversample a channel to gain additional bits of resolution
+    ///
+    /// Takes `4^extra_bits` samples and decimates them, resulting in a value
+    /// with a resolution of `12 + extra_bits` bits. This only works, if there
+    /// is some noise on the input, which is usually the case.
+    ///
+    /// Blocks until all conversions have finished. Use [`read_samples`] and
+    /// [`Samples::oversampled`], if you need the raw samples too.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `extra_bits` is larger than [`MAX_EXTRA_BITS`].
+    ///
+    /// [`read_samples`]: #method.read_samples
+    /// [`Samples::oversampled`]: struct.Samples.html#method.oversampled
+    /// [`MAX_EXTRA_BITS`]: constant.MAX_EXTRA_BITS.html
+    pub fn read_oversampled<PIN>(
+        &mut self,
+        pin: &mut PIN,
+        extra_bits: u8,
+    ) -> u16
+    where
+        PIN: Channel<ADC, ID = u8>,
+    {
+        assert!(extra_bits <= MAX_EXTRA_BITS);
+
+        self.read_samples(pin, 1 << (2 * extra_bits))
+            .oversampled(extra_bits)
+    }
+
+    /// Convert multiple channels in one sequence
+    ///
+    /// `channels` is a tuple of mutable references to the channels that
+    /// should be converted. Performs a single conversion of each of them, as
+    /// part of one conversion sequence, and writes the 12-bit results into
+    /// `buffer`. The results are sorted by channel number (the order in which
+    /// the ADC converts the channels), regardless of the order of the tuple.
+    /// Any elements of `buffer` beyond the number of channels are left
+    /// untouched.
+    ///
+    /// Blocks until all conversions have finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `buffer` is shorter than the number of channels.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// # use lpc8xx_hal::{Peripherals, syscon::clock_source::AdcClock};
+    /// #
+    /// # let mut p = Peripherals::take().unwrap();
+    /// #
+    /// # let mut syscon = p.SYSCON.split();
+    /// # let mut swm    = p.SWM.split();
+    /// #
+    /// # #[cfg(feature = "82x")]
+    /// # let mut swm_handle = swm.handle;
+    /// # #[cfg(feature = "845")]
+    /// # let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    /// #
+    /// # let adc_clock = AdcClock::new_default();
+    /// # let mut adc = p.ADC.enable(&adc_clock, &mut syscon.handle);
+    /// #
+    /// let (mut adc_0, _) = swm
+    ///     .fixed_functions
+    ///     .adc_0
+    ///     .assign(p.pins.pio0_7.into_swm_pin(), &mut swm_handle);
+    /// let (mut adc_1, _) = swm
+    ///     .fixed_functions
+    ///     .adc_1
+    ///     .assign(p.pins.pio0_6.into_swm_pin(), &mut swm_handle);
+    ///
+    /// let mut values = [0; 2];
+    /// adc.read_sequence_into((&mut adc_0, &mut adc_1), &mut values);
+    ///
+    /// let [value_0, value_1] = values;
+    /// ```
+    pub fn read_sequence_into<S>(&mut self, _: S, buffer: &mut [u16])
+    where
+        S: Sequence,
+    {
+        let mask = S::mask();
+        let count = mask.count_ones() as usize;
+
+        assert!(buffer.len() >= count);
+
+        let channels = || (0..12).filter(move |&i| mask & (1 << i) != 0);
+
+        // Reading the data registers clears their DATAVALID flags, which may
+        // still be set from a previous sequence.
+        for i in channels() {
+            self.adc.dat[i].read();
+        }
+
+        // A single sequence, without burst mode, converts each channel once.
+        self.adc.seq_ctrla.write(|w| {
+            unsafe { w.channels().bits(mask) };
+            w.start().set_bit();
+            w.trigpol().set_bit();
+            w.seq_ena().enabled();
+            w.mode().end_of_sequence()
+        });
+
+        for (i, value) in channels().zip(buffer) {
+            let mut read = self.adc.dat[i].read();
+            while read.datavalid().bit_is_clear() {
+                read = self.adc.dat[i].read();
+            }
+
+            *value = read.result().bits();
+        }
+    }
+
+    /// Sample a channel at a fixed point within each PWM period
+    ///
+    /// Uses SCT output 3 as a hardware trigger for conversion sequence A. Once
+    /// per PWM period, when the SCT counter reaches `phase`, the given channel
+    /// is converted. This can be used to measure a motor or LED current in the
+    /// middle of a pulse, for example, where it isn't distorted by switching
+    /// transients.
+    ///
+    /// `trigger` is the PWM channel of SCT output 3, as returned by
+    /// [`SCT::start_pwm`]. It is used internally and can't be attached to a pin
+    /// while the ADC is synchronized. All other PWM channels are unaffected.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `phase` is 0, or not smaller than the PWM period.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::prelude::*;
+    /// use lpc8xx_hal::Peripherals;
+    /// use lpc8xx_hal::syscon::clock_source::AdcClock;
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    /// let mut swm    = p.SWM.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// let channels = p.SCT0.start_pwm(1000, 0, &mut syscon.handle);
+    ///
+    /// let (pwm_output, _) = swm.movable_functions.sct_out0.assign(
+    ///     p.pins.pio0_14.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    /// let mut pwm_pin = channels.out0.attach(pwm_output);
+    ///
+    /// let (adc_pin, _) = swm
+    ///     .fixed_functions
+    ///     .adc_0
+    ///     .assign(p.pins.pio0_7.into_swm_pin(), &mut swm_handle);
+    ///
+    /// let adc_clock = AdcClock::new_default();
+    /// let adc = p.ADC.enable(&adc_clock, &mut syscon.handle);
+    ///
+    /// // Sample the current in the middle of each pulse
+    /// pwm_pin.set_duty(400);
+    /// let mut sync = adc.sync_to_pwm(channels.out3, adc_pin, 200);
+    ///
+    /// let current = nb::block!(sync.read()).unwrap();
+    /// ```
+    ///
+    /// [`SCT::start_pwm`]: ../sct/struct.SCT.html#method.start_pwm
+    pub fn sync_to_pwm<PIN>(
+        self,
+        trigger: DetachedPwmPin<SCT_OUT3>,
+        pin: PIN,
+        phase: u32,
+    ) -> PwmSync<PIN>
+    where
+        PIN: Channel<ADC, ID = u8>,
+    {
+        let mut sync = PwmSync {
+            adc: self,
+            trigger,
+            pin,
+        };
+        sync.set_phase(phase);
+
+        // The trigger must only be changed while the sequence is disabled.
+        sync.adc.adc.seq_ctrla.write(|w| w.seq_ena().disabled());
+        sync.adc.adc.seq_ctrla.write(|w| {
+            unsafe { w.channels().bits(1 << PIN::channel()) };
+            unsafe { w.trigger().bits(SCT_OUT3_TRIGGER) };
+            // The output is set at the start of each period, and cleared when
+            // the counter reaches `phase`.
+            w.trigpol().negative_edge();
+            w.mode().end_of_conversion();
+            w.seq_ena().enabled()
+        });
+
+        sync
+    }
+
+    /// Convert a channel, when the analog comparator output changes
+    ///
+    /// Uses the comparator output as a hardware trigger for conversion
+    /// sequence A. When the output has the given `edge`, the channel is
+    /// converted once. No further conversions are triggered, until the
+    /// capture is re-armed using [`ComparatorCapture::arm`]. This captures
+    /// the analog value at the moment a threshold is crossed, for example to
+    /// record the peak of a transient.
+    ///
+    /// The time of the edge is latched in hardware. The comparator output is
+    /// routed to an SCT input, and the same edge that triggers the conversion
+    /// loads an SCT capture register. The SCT is operated as a single,
+    /// free-running 32-bit counter, which is clocked by the system clock,
+    /// divided by `prescaler + 1`. `clock` is the frequency of the system clock
+    /// in Hz. It is only used to compute the tick frequency, which is
+    /// available via [`ComparatorCapture::tick_frequency`].
+    ///
+    /// The sequence A interrupt is enabled in the ADC by this method, but still
+    /// needs to be enabled in the NVIC, if you want to use it.
+    ///
+    /// The comparator itself isn't configured by this method. It can be set up
+    /// using the raw `ACOMP` peripheral.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `clock` is smaller than `prescaler + 1`, which includes a
+    /// `clock` of zero.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::prelude::*;
+    /// use lpc8xx_hal::Peripherals;
+    /// use lpc8xx_hal::adc::Edge;
+    /// use lpc8xx_hal::syscon::clock_source::AdcClock;
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    /// let mut swm    = p.SWM.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// let (adc_pin, _) = swm
+    ///     .fixed_functions
+    ///     .adc_0
+    ///     .assign(p.pins.pio0_7.into_swm_pin(), &mut swm_handle);
+    ///
+    /// let adc_clock = AdcClock::new_default();
+    /// let adc = p.ADC.enable(&adc_clock, &mut syscon.handle);
+    ///
+    /// // The comparator is configured using `p.ACOMP`
+    ///
+    /// // The system clock runs at 12 MHz by default
+    /// let mut capture = adc.capture_on_comparator(
+    ///     adc_pin,
+    ///     Edge::Rising,
+    ///     p.SCT0,
+    ///     0,
+    ///     12_000_000,
+    ///     &mut syscon.handle,
+    /// );
+    ///
+    /// let (value, ticks) = nb::block!(capture.read()).unwrap();
+    /// capture.arm();
+    /// ```
+    ///
+    /// [`ComparatorCapture::arm`]: struct.ComparatorCapture.html#method.arm
+    /// [`ComparatorCapture::tick_frequency`]: struct.ComparatorCapture.html#method.tick_frequency
+    pub fn capture_on_comparator<PIN>(
+        self,
+        pin: PIN,
+        edge: Edge,
+        sct: SCT,
+        prescaler: u8,
+        clock: u32,
+        syscon: &mut syscon::Handle,
+    ) -> ComparatorCapture<PIN>
+    where
+        PIN: Channel<ADC, ID = u8>,
+    {
+        let tick_frequency = clock / (prescaler as u32 + 1);
+        assert!(tick_frequency > 0);
+
+        // The trigger must only be changed while the sequence is disabled.
+        self.adc.seq_ctrla.write(|w| w.seq_ena().disabled());
+
+        // Clear the data valid flag, which may be left over from earlier use.
+        self.adc.seq_gdata.read();
+
+        self.adc.inten.modify(|_, w| w.seqa_inten().enabled());
+
+        sct.start_comparator_capture(edge, prescaler, syscon);
+
+        let mut capture = ComparatorCapture {
+            adc: self,
+            sct,
+            pin,
+            edge,
+            tick_frequency,
+        };
+        capture.arm();
+
+        capture
+    }
+
+    /// Perform a single conversion and return the 12-bit result
+    fn convert(&mut self, channel: u8) -> u16 {
+        // Start the measurement of the given channel
+        // Follows the description in the um
+        self.adc.seq_ctrla.write(|w| {
+            unsafe { w.channels().bits(1 << channel) };
+            w.start().set_bit();
+            w.trigpol().set_bit();
+            w.seq_ena().enabled();
+            w.mode().end_of_conversion()
+        });
+
+        let mut read = self.adc.seq_gdata.read();
+
+        // Wait until the conversion is done
+        while read.datavalid().bit_is_clear() {
+            read = self.adc.seq_gdata.read();
+        }
+
+        read.result().bits()
+    }
 }
 
 impl<State> ADC<State> {
@@ -151,6 +690,30 @@ impl<State> ADC<State> {
     }
 }
 
+/// The ADC trigger input that is connected to SCT output 3
+///
+/// See the table "ADC hardware trigger inputs" in the user manuals (UM10800
+/// for LPC82x, UM11029 for LPC84x). On both families, inputs 0 to 3 are
+/// PININT0_IRQ, PININT1_IRQ, SCT0_OUT3, and SCT0_OUT4.
+const SCT_OUT3_TRIGGER: u8 = 2;
+
+/// The ADC trigger input that is connected to the analog comparator output
+///
+/// In the same tables as above, ACMP_O follows SCT0_OUT4 on LPC82x. LPC84x has
+/// T0_MAT3 in between.
+#[cfg(feature = "82x")]
+const ACMP_TRIGGER: u8 = 4;
+#[cfg(feature = "845")]
+const ACMP_TRIGGER: u8 = 5;
+
+/// The interrupts that are triggered by the ADC
+const INTERRUPTS: [Interrupt; 4] = [
+    Interrupt::ADC0_SEQA,
+    Interrupt::ADC0_SEQB,
+    Interrupt::ADC0_THCMP,
+    Interrupt::ADC0_OVR,
+];
+
 impl<PIN> OneShot<ADC, u16, PIN> for ADC
 where
     PIN: Channel<ADC, ID = u8>,
@@ -159,28 +722,623 @@ where
 
     /// Request that the ADC begin a conversion on the specified pin
     fn read(&mut self, _: &mut PIN) -> nb::Result<u16, Self::Error> {
-        // Start the measurement of the given channel
-        // Follows the description in the um
-        self.adc.seq_ctrla.write(|w| {
+        // Returns the result as a 16 bit value
+        Ok(self.convert(PIN::channel()) << 4)
+    }
+}
+
+/// An ADC channel that is sampled in sync with the SCT's PWM output
+///
+/// Returned by [`ADC::sync_to_pwm`]. Owns the ADC, the channel, and the PWM
+/// channel that triggers the conversions, so neither can be reconfigured
+/// behind its back.
+///
+/// [`ADC::sync_to_pwm`]: struct.ADC.html#method.sync_to_pwm
+pub struct PwmSync<PIN> {
+    adc: ADC,
+    trigger: DetachedPwmPin<SCT_OUT3>,
+    pin: PIN,
+}
+
+impl<PIN> PwmSync<PIN> {
+    /// The point within the PWM period at which the channel is sampled
+    pub fn phase(&self) -> u32 {
+        self.trigger.duty()
+    }
+
+    /// Change the point within the PWM period at which the channel is sampled
+    ///
+    /// Like a change of the duty cycle, this takes effect at the start of the
+    /// next period.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `phase` is 0, or not smaller than the PWM period.
+    pub fn set_phase(&mut self, phase: u32) {
+        // The trigger output is set at the start of the period. If it were
+        // cleared at the same time, it would stay high, and no conversion
+        // would ever be triggered.
+        assert!(phase > 0 && phase < self.trigger.period());
+
+        self.trigger.set_duty(phase);
+    }
+
+    /// Return the result of the latest conversion
+    ///
+    /// Returns the 12-bit result, if a conversion has finished since the last
+    /// call, `nb::Error::WouldBlock` otherwise. If this isn't called once per
+    /// PWM period, older results are overwritten by newer ones.
+    pub fn read(&mut self) -> nb::Result<u16, Void> {
+        // Reading the register clears the data valid flag.
+        let read = self.adc.adc.seq_gdata.read();
+
+        if read.datavalid().bit_is_clear() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        Ok(read.result().bits())
+    }
+
+    /// Stop sampling and release the ADC, the PWM channel, and the pin
+    pub fn free(self) -> (ADC, DetachedPwmPin<SCT_OUT3>, PIN) {
+        self.adc.adc.seq_ctrla.write(|w| w.seq_ena().disabled());
+
+        (self.adc, self.trigger, self.pin)
+    }
+}
+
+/// An ADC channel that is converted, when the analog comparator output changes
+///
+/// Returned by [`ADC::capture_on_comparator`].
+///
+/// [`ADC::capture_on_comparator`]: struct.ADC.html#method.capture_on_comparator
+pub struct ComparatorCapture<PIN> {
+    adc: ADC,
+    sct: SCT,
+    pin: PIN,
+    edge: Edge,
+    tick_frequency: u32,
+}
+
+impl<PIN> ComparatorCapture<PIN>
+where
+    PIN: Channel<ADC, ID = u8>,
+{
+    /// Wait for the next edge of the comparator output
+    ///
+    /// Has no effect, if the capture is already armed. Any result that hasn't
+    /// been read yet is discarded.
+    pub fn arm(&mut self) {
+        let adc = &self.adc.adc;
+
+        // Arm the SCT first, so an edge that triggers a conversion always
+        // loads the capture register too. Only an edge within the few cycles
+        // between the two could load the capture register without triggering
+        // a conversion.
+        self.sct.arm_comparator_capture();
+
+        adc.seq_ctrla.write(|w| {
             unsafe { w.channels().bits(1 << PIN::channel()) };
-            w.start().set_bit();
-            w.trigpol().set_bit();
-            w.seq_ena().enabled();
-            w.mode().end_of_conversion()
+            unsafe { w.trigger().bits(ACMP_TRIGGER) };
+            match self.edge {
+                Edge::Rising => w.trigpol().positive_edge(),
+                Edge::Falling => w.trigpol().negative_edge(),
+            };
+            w.mode().end_of_conversion();
+            w.seq_ena().enabled()
         });
+    }
 
-        let mut read = self.adc.seq_gdata.read();
+    /// Return the captured value and the time of the edge
+    ///
+    /// Returns the 12-bit result and the SCT counter value that was latched by
+    /// the edge that triggered the conversion, if a conversion has been
+    /// triggered since the capture was last armed, `nb::Error::WouldBlock`
+    /// otherwise. Call [`arm`] to capture the next edge.
+    ///
+    /// The counter value is in SCT clock ticks. Use [`tick_frequency`] to
+    /// convert it. The counter wraps around, so the difference between two
+    /// captures should be computed using `wrapping_sub`.
+    ///
+    /// [`arm`]: #method.arm
+    /// [`tick_frequency`]: #method.tick_frequency
+    pub fn read(&mut self) -> nb::Result<(u16, u32), Void> {
+        // Reading the register clears the data valid flag, so make sure the
+        // capture register has been loaded before.
+        let ticks = match self.sct.comparator_capture() {
+            Some(ticks) => ticks,
+            None => return Err(nb::Error::WouldBlock),
+        };
 
-        // Wait until the conversion is done
-        while read.datavalid().bit_is_clear() {
-            read = self.adc.seq_gdata.read();
+        let read = self.adc.adc.seq_gdata.read();
+
+        if read.datavalid().bit_is_clear() {
+            return Err(nb::Error::WouldBlock);
         }
 
-        // Returns the result as a 16 bit value
-        Ok(read.result().bits() << 4)
+        // Ignore further edges, until the capture is re-armed. The interrupt
+        // flag mirrors the data valid flag, which has been cleared by reading.
+        self.adc.adc.seq_ctrla.write(|w| w.seq_ena().disabled());
+
+        Ok((read.result().bits(), ticks))
+    }
+
+    /// The frequency of the SCT clock in Hz
+    pub fn tick_frequency(&self) -> u32 {
+        self.tick_frequency
+    }
+
+    /// Stop capturing and release the ADC, the pin, and the SCT
+    ///
+    /// The SCT is left in its current configuration, except for the counter,
+    /// which is halted.
+    pub fn free(self) -> (ADC, PIN, SCT) {
+        self.adc.adc.seq_ctrla.write(|w| w.seq_ena().disabled());
+        self.adc.adc.inten.modify(|_, w| w.seqa_inten().disabled());
+        self.sct.stop_comparator_capture();
+
+        (self.adc, self.pin, self.sct)
+    }
+}
+
+/// An edge of a trigger signal
+///
+/// Used by [`ADC::capture_on_comparator`].
+///
+/// [`ADC::capture_on_comparator`]: struct.ADC.html#method.capture_on_comparator
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Edge {
+    /// A rising edge
+    Rising,
+
+    /// A falling edge
+    Falling,
+}
+
+/// The configuration of the ADC
+///
+/// Returned by [`ADC::suspend`], and passed to [`ADC::resume`].
+///
+/// [`ADC::suspend`]: struct.ADC.html#method.suspend
+/// [`ADC::resume`]: struct.ADC.html#method.resume
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+    ctrl: u32,
+    seq_ctrla: u32,
+    seq_ctrlb: u32,
+    thr0_low: u32,
+    thr1_low: u32,
+    thr0_high: u32,
+    thr1_high: u32,
+    chan_thrsel: u32,
+    inten: u32,
+    trm: u32,
+}
+
+flags! {
+    /// The flags in the FLAGS register of the ADC
+    ///
+    /// Returned by [`ADC::read_and_clear_events`].
+    ///
+    /// [`ADC::read_and_clear_events`]: struct.ADC.html#method.read_and_clear_events
+    pub struct Events {
+        /// Channel 0 has crossed a threshold (THCMP0)
+        const THRESHOLD0 = 0;
+
+        /// Channel 1 has crossed a threshold (THCMP1)
+        const THRESHOLD1 = 1;
+
+        /// Channel 2 has crossed a threshold (THCMP2)
+        const THRESHOLD2 = 2;
+
+        /// Channel 3 has crossed a threshold (THCMP3)
+        const THRESHOLD3 = 3;
+
+        /// Channel 4 has crossed a threshold (THCMP4)
+        const THRESHOLD4 = 4;
+
+        /// Channel 5 has crossed a threshold (THCMP5)
+        const THRESHOLD5 = 5;
+
+        /// Channel 6 has crossed a threshold (THCMP6)
+        const THRESHOLD6 = 6;
+
+        /// Channel 7 has crossed a threshold (THCMP7)
+        const THRESHOLD7 = 7;
+
+        /// Channel 8 has crossed a threshold (THCMP8)
+        const THRESHOLD8 = 8;
+
+        /// Channel 9 has crossed a threshold (THCMP9)
+        const THRESHOLD9 = 9;
+
+        /// Channel 10 has crossed a threshold (THCMP10)
+        const THRESHOLD10 = 10;
+
+        /// Channel 11 has crossed a threshold (THCMP11)
+        const THRESHOLD11 = 11;
+
+        /// A result of channel 0 was overwritten before being read
+        /// (OVERRUN0)
+        const OVERRUN0 = 12;
+
+        /// A result of channel 1 was overwritten before being read
+        /// (OVERRUN1)
+        const OVERRUN1 = 13;
+
+        /// A result of channel 2 was overwritten before being read
+        /// (OVERRUN2)
+        const OVERRUN2 = 14;
+
+        /// A result of channel 3 was overwritten before being read
+        /// (OVERRUN3)
+        const OVERRUN3 = 15;
+
+        /// A result of channel 4 was overwritten before being read
+        /// (OVERRUN4)
+        const OVERRUN4 = 16;
+
+        /// A result of channel 5 was overwritten before being read
+        /// (OVERRUN5)
+        const OVERRUN5 = 17;
+
+        /// A result of channel 6 was overwritten before being read
+        /// (OVERRUN6)
+        const OVERRUN6 = 18;
+
+        /// A result of channel 7 was overwritten before being read
+        /// (OVERRUN7)
+        const OVERRUN7 = 19;
+
+        /// A result of channel 8 was overwritten before being read
+        /// (OVERRUN8)
+        const OVERRUN8 = 20;
+
+        /// A result of channel 9 was overwritten before being read
+        /// (OVERRUN9)
+        const OVERRUN9 = 21;
+
+        /// A result of channel 10 was overwritten before being read
+        /// (OVERRUN10)
+        const OVERRUN10 = 22;
+
+        /// A result of channel 11 was overwritten before being read
+        /// (OVERRUN11)
+        const OVERRUN11 = 23;
+
+        /// A result of sequence A was overwritten before being read
+        /// (SEQA_OVR)
+        const SEQA_OVERRUN = 24;
+
+        /// A result of sequence B was overwritten before being read
+        /// (SEQB_OVR)
+        const SEQB_OVERRUN = 25;
+
+        /// Sequence A has requested an interrupt (SEQA_INT)
+        const SEQA_INT = 28;
+
+        /// Sequence B has requested an interrupt (SEQB_INT)
+        const SEQB_INT = 29;
+
+        /// The threshold compare interrupt is asserted (THCMP_INT)
+        const THRESHOLD_INT = 30;
+
+        /// The overrun interrupt is asserted (OVR_INT)
+        const OVERRUN_INT = 31;
+    }
+}
+
+impl Events {
+    /// The flags that are cleared by writing a 1 to them
+    ///
+    /// The sequence flags are only cleared like this, if the sequence is in
+    /// end-of-sequence mode. Otherwise, they mirror the data registers.
+    const CLEARABLE: Self = Self::from_bits_truncate(0x3000_0fff);
+}
+
+/// The accumulated result of multiple conversions of the same channel
+///
+/// Returned by [`ADC::read_samples`]. All values have the native 12-bit
+/// resolution of the ADC, unless noted otherwise.
+///
+/// [`ADC::read_samples`]: struct.ADC.html#method.read_samples
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Samples {
+    /// The sum of all samples
+    pub sum: u32,
+
+    /// The number of samples
+    pub count: u16,
+
+    /// The smallest sample
+    pub min: u16,
+
+    /// The largest sample
+    pub max: u16,
+}
+
+impl Samples {
+    /// The average of all samples, rounded to the nearest value
+    pub fn average(&self) -> u16 {
+        self.scaled_average(0)
     }
+
+    /// The average of all samples, with additional bits of resolution
+    ///
+    /// Returns the average with a resolution of `12 + extra_bits` bits. This
+    /// is only meaningful, if there is some noise on the input (which is
+    /// usually the case), and if at least `4^extra_bits` samples were taken.
+    /// [`ADC::read_oversampled`] takes care of the latter.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `extra_bits` is larger than 4.
+    ///
+    /// [`ADC::read_oversampled`]: struct.ADC.html#method.read_oversampled
+    pub fn oversampled(&self, extra_bits: u8) -> u16 {
+        assert!(extra_bits <= MAX_EXTRA_BITS);
+        self.scaled_average(extra_bits)
+    }
+
+    /// The spread between the smallest and the largest sample
+    ///
+    /// Can be used as a simple indication of how noisy the input is.
+    pub fn spread(&self) -> u16 {
+        self.max - self.min
+    }
+
+    fn scaled_average(&self, extra_bits: u8) -> u16 {
+        let count = u64::from(self.count.max(1));
+        let sum = u64::from(self.sum) << extra_bits;
+
+        ((sum + count / 2) / count) as u16
+    }
+}
+
+/// The maximum number of extra bits that can be gained by oversampling
+///
+/// 4 extra bits require 256 samples, and result in a 16-bit value.
+pub const MAX_EXTRA_BITS: u8 = 4;
+
+/// Converts 12-bit ADC values into millivolts
+///
+/// The ADC measures voltages relative to its reference voltage, VREFP. To
+/// convert its results into actual voltages, you need to know the reference
+/// voltage. If the voltage is measured through a resistor divider (for
+/// example, to measure a battery voltage that exceeds the reference voltage),
+/// the divider needs to be taken into account too.
+///
+/// If the reference voltage isn't known (for example, because VREFP is
+/// connected to an unregulated supply, like a battery), use
+/// [`VoltageScale::from_supply`] to measure the supply voltage, or
+/// [`VoltageScale::from_known`] to determine the reference voltage using an
+/// external reference.
+///
+/// # Example
+///
+/// ```
+/// use lpc8xx_hal::adc::VoltageScale;
+///
+/// // 3.3 V reference, battery connected through a 100k/100k divider
+/// let scale = VoltageScale::new(3300).with_divider(100_000, 100_000);
+///
+/// assert_eq!(scale.millivolts(2048), 3301);
+/// ```
+///
+/// [`VoltageScale::from_supply`]: #method.from_supply
+/// [`VoltageScale::from_known`]: #method.from_known
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VoltageScale {
+    reference: u32,
+    top: u32,
+    bottom: u32,
 }
 
+impl VoltageScale {
+    /// Create a scale for the given reference voltage, in millivolts
+    pub fn new(reference: u32) -> Self {
+        Self {
+            reference,
+            top: 0,
+            bottom: 1,
+        }
+    }
+
+    /// Measure the supply voltage, and use it as the reference voltage
+    ///
+    /// This is the right scale, if VREFP is connected to VDD (which is the
+    /// case on packages that don't have a separate VREFP pin). Use
+    /// [`reference`] to get the supply voltage itself, for example to gauge a
+    /// battery that the MCU is powered from directly.
+    ///
+    /// The ADC can't measure its own reference, so the analog comparator is
+    /// used instead. It compares its internal reference voltage (the band gap,
+    /// nominally 900 mV) against the voltage ladder, which divides VDD into
+    /// 31 steps. The ladder step at which the comparator output changes
+    /// determines VDD. This resolution is coarse: At 3.3 V, one step
+    /// corresponds to about 400 mV, and the result is the middle of that step.
+    /// The band gap voltage also varies from part to part. If you need better
+    /// accuracy, use [`from_known`] with an external reference.
+    ///
+    /// Powers up the analog comparator and the brown-out detector, which the
+    /// internal reference depends on. The comparator is left powered up, with
+    /// its voltage ladder enabled, and needs to be reconfigured before it is
+    /// used for anything else.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{adc::VoltageScale, Peripherals};
+    ///
+    /// let mut p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// let scale = VoltageScale::from_supply(
+    ///     &mut p.ACOMP,
+    ///     &syscon.bod,
+    ///     &mut syscon.handle,
+    /// );
+    /// let battery_millivolts = scale.reference();
+    /// ```
+    ///
+    /// [`reference`]: #method.reference
+    /// [`from_known`]: #method.from_known
+    pub fn from_supply(
+        acomp: &mut pac::ACOMP,
+        bod: &syscon::BOD,
+        syscon: &mut syscon::Handle,
+    ) -> Self {
+        syscon.enable_clock(&*acomp);
+        syscon.power_up(&*acomp);
+        syscon.power_up(bod);
+
+        acomp.ctrl.write(|w| {
+            w.comp_vp_sel().band_gap();
+            w.comp_vm_sel().voltage_ladder_output();
+            w.hys().hys_0()
+        });
+
+        // Find the lowest ladder step that is above the band gap voltage. The
+        // comparator output is high, as long as the band gap voltage is
+        // higher.
+        let mut step = LADDER_STEPS;
+        for n in 1..=LADDER_STEPS {
+            acomp.lad.write(|w| {
+                w.laden().set_bit();
+                unsafe { w.ladsel().bits(n as u8) };
+                w.ladref().ladref_0()
+            });
+
+            // Wait for the ladder to settle. This is a few tens of
+            // microseconds, at the highest system clock frequency.
+            cortex_m::asm::delay(LADDER_SETTLING_ITERATIONS);
+
+            if acomp.ctrl.read().compstat().bit_is_clear() {
+                step = n;
+                break;
+            }
+        }
+
+        // VDD is between `BAND_GAP * 31 / step` and
+        // `BAND_GAP * 31 / (step - 1)`. Use the middle of the step.
+        let reference =
+            (BAND_GAP * LADDER_STEPS * 2 + step - 1) / (step * 2 - 1);
+
+        Self::new(reference)
+    }
+
+    /// Determine the reference voltage by measuring a known voltage
+    ///
+    /// `known` is the voltage in millivolts, `value` is the 12-bit value that
+    /// the ADC returned when measuring it. The known voltage should be as high
+    /// as possible, to minimize the error.
+    ///
+    /// This can be used to measure the supply voltage, if VREFP is connected to
+    /// VDD (which is the case on packages that don't have a separate VREFP
+    /// pin). Connect a stable voltage reference (like a TL431 shunt regulator)
+    /// to one of the ADC channels, measure it, and the resulting scale's
+    /// [`reference`] is the supply voltage.
+    ///
+    /// [`reference`]: #method.reference
+    pub fn from_known(known: u32, value: u16) -> Self {
+        let value = u64::from(value.max(1));
+        let reference =
+            (u64::from(known) * u64::from(MAX_VALUE) + value / 2) / value;
+
+        Self::new(reference as u32)
+    }
+
+    /// Account for a resistor divider in front of the ADC input
+    ///
+    /// `top` is the resistance between the measured voltage and the ADC input,
+    /// `bottom` the resistance between the ADC input and ground. Only the ratio
+    /// matters, so any unit can be used, as long as it's the same for both.
+    pub fn with_divider(self, top: u32, bottom: u32) -> Self {
+        Self {
+            top,
+            bottom: bottom.max(1),
+            ..self
+        }
+    }
+
+    /// The reference voltage, in millivolts
+    pub fn reference(&self) -> u32 {
+        self.reference
+    }
+
+    /// Convert a 12-bit ADC value into millivolts
+    ///
+    /// Returns the voltage in front of the divider, if one has been configured
+    /// using [`with_divider`].
+    ///
+    /// [`with_divider`]: #method.with_divider
+    pub fn millivolts(&self, value: u16) -> u32 {
+        let numerator = u64::from(value)
+            * u64::from(self.reference)
+            * (u64::from(self.top) + u64::from(self.bottom));
+        let denominator = u64::from(MAX_VALUE) * u64::from(self.bottom);
+
+        ((numerator + denominator / 2) / denominator) as u32
+    }
+}
+
+/// Implemented for tuples of ADC channels that can be converted in sequence
+///
+/// See [`ADC::read_sequence_into`].
+///
+/// This trait is an internal implementation detail and should neither be
+/// implemented nor used outside of LPC8xx HAL. Any changes to this trait won't
+/// be considered breaking changes.
+///
+/// [`ADC::read_sequence_into`]: struct.ADC.html#method.read_sequence_into
+pub trait Sequence {
+    /// The channels of the sequence, as a bit mask
+    fn mask() -> u16;
+}
+
+macro_rules! sequence {
+    ($($pin:ident),*) => {
+        impl<$($pin,)*> Sequence for ($(&mut $pin,)*)
+        where
+            $($pin: Channel<ADC, ID = u8>,)*
+        {
+            fn mask() -> u16 {
+                0 $(| 1 << $pin::channel())*
+            }
+        }
+    };
+}
+
+sequence!(T0);
+sequence!(T0, T1);
+sequence!(T0, T1, T2);
+sequence!(T0, T1, T2, T3);
+sequence!(T0, T1, T2, T3, T4);
+sequence!(T0, T1, T2, T3, T4, T5);
+sequence!(T0, T1, T2, T3, T4, T5, T6);
+sequence!(T0, T1, T2, T3, T4, T5, T6, T7);
+sequence!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+sequence!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+sequence!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+sequence!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
+/// The largest 12-bit value the ADC can return
+const MAX_VALUE: u16 = 0xfff;
+
+/// The nominal voltage of the analog comparator's internal reference, in
+/// millivolts
+const BAND_GAP: u32 = 900;
+
+/// The number of steps the analog comparator's voltage ladder divides its
+/// reference voltage into
+const LADDER_STEPS: u32 = 31;
+
+/// The number of `asm::delay` iterations to wait for the voltage ladder
+///
+/// Each iteration takes 4 cycles, so this is about 27 us at 30 MHz.
+const LADDER_SETTLING_ITERATIONS: u32 = 200;
+
 macro_rules! adc_channel {
     ($pin:ident, $num:expr) => {
         impl<PIN> Channel<ADC>
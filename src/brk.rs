@@ -0,0 +1,184 @@
+//! Board support for the LPC845-BRK
+//!
+//! This module is only available, if the `brk` feature is enabled. It names
+//! the resources of the [LPC845-BRK] development board, so they can be used
+//! without looking up the schematics:
+//!
+//! - The RGB LED, whose green, blue, and red parts are connected to PIO1_0,
+//!   PIO1_1, and PIO1_2 respectively. See [`Leds`].
+//! - The ISP button on PIO0_12. See [`IspButton`].
+//! - The virtual COM port of the on-board debugger, which is connected to
+//!   PIO0_24 (target RXD) and PIO0_25 (target TXD). See [`vcom_functions`].
+//!
+//! The LEDs and the button are active-low. They are wrapped in [`ActiveLow`],
+//! so `set_high` turns an LED on, and `is_high` returns `true` while the
+//! button is pressed.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{brk, prelude::*, usart, Peripherals};
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//! let swm = p.SWM.split();
+//! let gpio = p.GPIO.enable(&mut syscon.handle);
+//!
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! let mut leds = brk::Leds::new(
+//!     p.pins.pio1_0,
+//!     gpio.tokens.pio1_0,
+//!     p.pins.pio1_1,
+//!     gpio.tokens.pio1_1,
+//!     p.pins.pio1_2,
+//!     gpio.tokens.pio1_2,
+//! );
+//! let button = brk::IspButton::new(p.pins.pio0_12, gpio.tokens.pio0_12);
+//!
+//! let (u0_rxd, u0_txd) = brk::vcom_functions(
+//!     p.pins.pio0_24,
+//!     p.pins.pio0_25,
+//!     swm.movable_functions.u0_rxd,
+//!     swm.movable_functions.u0_txd,
+//!     &mut swm_handle,
+//! );
+//! let clock_config = usart::Clock::new_with_baudrate(115200);
+//! let mut serial =
+//!     p.USART0.enable(&clock_config, &mut syscon.handle, u0_rxd, u0_txd);
+//!
+//! loop {
+//!     if button.is_high().unwrap() {
+//!         leds.green.set_high().unwrap();
+//!         serial.bwrite_all(b"Button pressed\n").unwrap();
+//!     } else {
+//!         leds.green.set_low().unwrap();
+//!     }
+//! }
+//! ```
+//!
+//! [LPC845-BRK]: https://www.nxp.com/design/development-boards/lpcxpresso-boards/lpc845-breakout-board-for-lpc84x-family-mcus:LPC845-BRK
+//! [`Leds`]: struct.Leds.html
+//! [`IspButton`]: struct.IspButton.html
+//! [`vcom_functions`]: fn.vcom_functions.html
+//! [`ActiveLow`]: ../gpio/struct.ActiveLow.html
+
+use core::convert::Infallible;
+
+use embedded_hal::digital::v2::InputPin;
+
+use crate::{
+    gpio::{direction, ActiveLow, GpioPin, Level},
+    init_state,
+    pac::USART0,
+    pins::{
+        self, Pin, Token, PIO0_12, PIO0_24, PIO0_25, PIO1_0, PIO1_1, PIO1_2,
+    },
+    swm::{self, U0_RXD, U0_TXD},
+    usart,
+};
+
+/// An LED on the LPC845-BRK
+pub type Led<T> = ActiveLow<GpioPin<T, direction::Output>>;
+
+/// The RGB LED of the LPC845-BRK
+///
+/// All LEDs are off initially.
+pub struct Leds {
+    /// The green LED on PIO1_0
+    pub green: Led<PIO1_0>,
+
+    /// The blue LED on PIO1_1
+    pub blue: Led<PIO1_1>,
+
+    /// The red LED on PIO1_2
+    pub red: Led<PIO1_2>,
+}
+
+impl Leds {
+    /// Configure the LED pins as outputs
+    pub fn new(
+        pio1_0: Pin<PIO1_0, pins::state::Unused>,
+        pio1_0_token: Token<PIO1_0, init_state::Enabled>,
+        pio1_1: Pin<PIO1_1, pins::state::Unused>,
+        pio1_1_token: Token<PIO1_1, init_state::Enabled>,
+        pio1_2: Pin<PIO1_2, pins::state::Unused>,
+        pio1_2_token: Token<PIO1_2, init_state::Enabled>,
+    ) -> Self {
+        Self {
+            green: led(pio1_0, pio1_0_token),
+            blue: led(pio1_1, pio1_1_token),
+            red: led(pio1_2, pio1_2_token),
+        }
+    }
+}
+
+fn led<T>(
+    pin: Pin<T, pins::state::Unused>,
+    token: Token<T, init_state::Enabled>,
+) -> Led<T>
+where
+    T: pins::Trait,
+{
+    ActiveLow::new(pin.into_output_pin(token, Level::High))
+}
+
+/// The ISP button of the LPC845-BRK
+///
+/// The button is connected to PIO0_12, and pulls it low while pressed. If
+/// it's pressed during reset, the microcontroller enters the ISP boot loader.
+/// Once the application is running, it can be used as a regular button.
+///
+/// Implements `InputPin`. `is_high` returns `true`, while the button is
+/// pressed.
+pub struct IspButton(ActiveLow<GpioPin<PIO0_12, direction::Input>>);
+
+impl IspButton {
+    /// Configure the button pin as an input
+    pub fn new(
+        pio0_12: Pin<PIO0_12, pins::state::Unused>,
+        token: Token<PIO0_12, init_state::Enabled>,
+    ) -> Self {
+        Self(ActiveLow::new(pio0_12.into_input_pin(token)))
+    }
+
+    /// Return the button pin
+    pub fn free(self) -> GpioPin<PIO0_12, direction::Input> {
+        self.0.free()
+    }
+}
+
+impl InputPin for IspButton {
+    type Error = Infallible;
+
+    /// Indicates whether the button is pressed
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        self.0.is_high()
+    }
+
+    /// Indicates whether the button is released
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.0.is_low()
+    }
+}
+
+/// Assign the USART0 functions to the virtual COM port pins
+///
+/// The on-board debugger provides a virtual COM port over USB, which is
+/// connected to PIO0_24 and PIO0_25. This assigns U0_RXD and U0_TXD to those
+/// pins, so the returned functions can be passed to [`USART::enable`].
+///
+/// [`USART::enable`]: ../usart/struct.USART.html#method.enable
+pub fn vcom_functions(
+    pio0_24: Pin<PIO0_24, pins::state::Unused>,
+    pio0_25: Pin<PIO0_25, pins::state::Unused>,
+    u0_rxd: swm::Function<U0_RXD, swm::state::Unassigned>,
+    u0_txd: swm::Function<U0_TXD, swm::state::Unassigned>,
+    swm: &mut swm::Handle,
+) -> usart::SwmFunctions<USART0, PIO0_24, PIO0_25> {
+    let (u0_rxd, _) = u0_rxd.assign(pio0_24.into_swm_pin(), swm);
+    let (u0_txd, _) = u0_txd.assign(pio0_25.into_swm_pin(), swm);
+
+    (u0_rxd, u0_txd)
+}
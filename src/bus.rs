@@ -0,0 +1,217 @@
+//! API for sharing a bus between multiple drivers
+//!
+//! Drivers for I2C or SPI devices usually take ownership of the bus they are
+//! using. This makes it impossible to use multiple of them with the same bus.
+//! [`BusManager`] solves this problem by handing out any number of
+//! [`BusProxy`] instances, which implement the same `embedded-hal` traits as the
+//! bus itself.
+//!
+//! Access to the bus is synchronized using critical sections, which is
+//! appropriate for the single-core microcontrollers supported by this HAL. As a
+//! consequence, interrupts are disabled for the duration of every bus
+//! transaction. Please keep that in mind, if your application has strict
+//! requirements regarding interrupt latency.
+//!
+//! Please note that only single operations are synchronized. SPI device
+//! drivers usually control the chip select pin themselves, so a transaction
+//! can consist of multiple operations. If you share an SPI bus between drivers
+//! that run in different interrupt contexts, you need to make sure that those
+//! transactions don't interleave.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     prelude::*,
+//!     Peripherals,
+//!     bus::BusManager,
+//!     syscon::clock_source::I2cClock,
+//! };
+//!
+//! let mut p = Peripherals::take().unwrap();
+//!
+//! let mut swm    = p.SWM.split();
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! let (i2c0_sda, _) = swm.fixed_functions.i2c0_sda.assign(
+//!     p.pins.pio0_11.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//! let (i2c0_scl, _) = swm.fixed_functions.i2c0_scl.assign(
+//!     p.pins.pio0_10.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//!
+//! let i2c = p.I2C0.enable(
+//!     &I2cClock::new_400khz(),
+//!     &mut syscon.handle,
+//!     i2c0_sda,
+//!     i2c0_scl,
+//! );
+//!
+//! let bus = BusManager::new(i2c);
+//!
+//! // Each of these could be passed to a different driver
+//! let mut sensor_a = bus.acquire();
+//! let mut sensor_b = bus.acquire();
+//!
+//! sensor_a.write(0x10, &[0x00]).expect("Failed to write to sensor A");
+//! sensor_b.write(0x20, &[0x00]).expect("Failed to write to sensor B");
+//! ```
+//!
+//! [`BusManager`]: struct.BusManager.html
+//! [`BusProxy`]: struct.BusProxy.html
+
+use core::cell::RefCell;
+
+use cortex_m::interrupt::{self, Mutex};
+use embedded_hal::blocking::{i2c, spi};
+
+/// Manages shared access to a bus
+///
+/// Can wrap any bus, but is mainly intended to be used with [`I2C`] and
+/// [`SPI`]. Please refer to the [module documentation] for more information.
+///
+/// [`I2C`]: ../i2c/struct.I2C.html
+/// [`SPI`]: ../spi/struct.SPI.html
+/// [module documentation]: index.html
+pub struct BusManager<T> {
+    bus: Mutex<RefCell<T>>,
+}
+
+impl<T> BusManager<T> {
+    /// Create a new bus manager that wraps the given bus
+    pub fn new(bus: T) -> Self {
+        BusManager {
+            bus: Mutex::new(RefCell::new(bus)),
+        }
+    }
+
+    /// Create a proxy that provides access to the bus
+    ///
+    /// This method can be called any number of times. All returned proxies
+    /// share the same bus.
+    pub fn acquire(&self) -> BusProxy<'_, T> {
+        BusProxy { manager: self }
+    }
+
+    fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        interrupt::free(|cs| {
+            // This can't panic. Proxies don't hand out references to the bus,
+            // and interrupts can't preempt us within the critical section, so
+            // the `RefCell` can never be borrowed at this point.
+            let mut bus = self.bus.borrow(cs).borrow_mut();
+            f(&mut bus)
+        })
+    }
+}
+
+/// Provides access to a shared bus
+///
+/// Implements the same `embedded-hal` traits as the bus it proxies. Every
+/// operation is executed within a critical section.
+///
+/// You can get an instance of this struct from [`BusManager::acquire`].
+///
+/// # `embedded-hal` traits
+/// - [`embedded_hal::blocking::i2c::Read`]
+/// - [`embedded_hal::blocking::i2c::Write`]
+/// - [`embedded_hal::blocking::i2c::WriteRead`]
+/// - [`embedded_hal::blocking::spi::Transfer`]
+/// - [`embedded_hal::blocking::spi::Write`]
+///
+/// [`BusManager::acquire`]: struct.BusManager.html#method.acquire
+/// [`embedded_hal::blocking::i2c::Read`]: #impl-Read
+/// [`embedded_hal::blocking::i2c::Write`]: #impl-Write
+/// [`embedded_hal::blocking::i2c::WriteRead`]: #impl-WriteRead
+/// [`embedded_hal::blocking::spi::Transfer`]: #impl-Transfer%3Cu8%3E
+/// [`embedded_hal::blocking::spi::Write`]: #impl-Write%3Cu8%3E
+pub struct BusProxy<'a, T> {
+    manager: &'a BusManager<T>,
+}
+
+impl<'a, T> Clone for BusProxy<'a, T> {
+    fn clone(&self) -> Self {
+        BusProxy {
+            manager: self.manager,
+        }
+    }
+}
+
+impl<'a, T> i2c::Read for BusProxy<'a, T>
+where
+    T: i2c::Read,
+{
+    type Error = T::Error;
+
+    fn read(
+        &mut self,
+        address: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.manager.lock(|bus| bus.read(address, buffer))
+    }
+}
+
+impl<'a, T> i2c::Write for BusProxy<'a, T>
+where
+    T: i2c::Write,
+{
+    type Error = T::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.manager.lock(|bus| bus.write(address, bytes))
+    }
+}
+
+impl<'a, T> i2c::WriteRead for BusProxy<'a, T>
+where
+    T: i2c::WriteRead,
+{
+    type Error = T::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.manager
+            .lock(|bus| bus.write_read(address, bytes, buffer))
+    }
+}
+
+impl<'a, T> spi::Transfer<u8> for BusProxy<'a, T>
+where
+    T: spi::Transfer<u8>,
+{
+    type Error = T::Error;
+
+    fn transfer<'w>(
+        &mut self,
+        words: &'w mut [u8],
+    ) -> Result<&'w [u8], Self::Error> {
+        self.manager.lock(|bus| {
+            bus.transfer(words)?;
+            Ok(())
+        })?;
+
+        Ok(words)
+    }
+}
+
+impl<'a, T> spi::Write<u8> for BusProxy<'a, T>
+where
+    T: spi::Write<u8>,
+{
+    type Error = T::Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.manager.lock(|bus| bus.write(words))
+    }
+}
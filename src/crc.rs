@@ -0,0 +1,254 @@
+//! API for the CRC engine
+//!
+//! The entry point to this API is [`CRC`].
+//!
+//! # Examples
+//!
+//! ``` no_run
+//! use lpc8xx_hal::Peripherals;
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//! let mut crc = p.CRC.enable(&mut syscon.handle);
+//!
+//! // Read Holding Registers request to device 1
+//! let checksum = crc.crc16_modbus(&[0x01, 0x03, 0x00, 0x00, 0x00, 0x01]);
+//! assert_eq!(checksum, 0x0a84);
+//! ```
+//!
+//! Code that is written against `core::hash::Hasher` can use the CRC engine
+//! to compute a CRC-32, using [`Crc32Digest`]:
+//!
+//! ``` no_run
+//! use core::hash::Hasher;
+//!
+//! use lpc8xx_hal::Peripherals;
+//!
+//! fn checksum(hasher: &mut impl Hasher, data: &[u8]) -> u64 {
+//!     hasher.write(data);
+//!     hasher.finish()
+//! }
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//! let mut crc = p.CRC.enable(&mut syscon.handle);
+//!
+//! let mut digest = crc.crc32_digest();
+//! assert_eq!(checksum(&mut digest, b"123456789"), 0xcbf43926);
+//! ```
+//!
+//! [`CRC`]: struct.CRC.html
+//! [`Crc32Digest`]: struct.Crc32Digest.html
+
+use core::{hash::Hasher, ptr};
+
+use crate::{init_state, pac, syscon};
+
+/// Interface to the CRC engine
+///
+/// Controls the CRC engine. Use [`Peripherals`] to gain access to an instance
+/// of this struct.
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [`Peripherals`]: ../struct.Peripherals.html
+/// [module documentation]: index.html
+pub struct CRC<State = init_state::Enabled> {
+    crc: pac::CRC,
+    _state: State,
+}
+
+impl CRC<init_state::Disabled> {
+    pub(crate) fn new(crc: pac::CRC) -> Self {
+        CRC {
+            crc,
+            _state: init_state::Disabled,
+        }
+    }
+
+    /// Enable the CRC engine
+    ///
+    /// This method is only available, if `CRC` is in the [`Disabled`] state.
+    /// Code that attempts to call this method when the peripheral is already
+    /// enabled will not compile.
+    ///
+    /// Consumes this instance of `CRC` and returns another instance that has
+    /// its `State` type parameter set to [`Enabled`].
+    ///
+    /// [`Disabled`]: ../init_state/struct.Disabled.html
+    /// [`Enabled`]: ../init_state/struct.Enabled.html
+    pub fn enable(
+        self,
+        syscon: &mut syscon::Handle,
+    ) -> CRC<init_state::Enabled> {
+        syscon.enable_clock(&self.crc);
+
+        CRC {
+            crc: self.crc,
+            _state: init_state::Enabled(()),
+        }
+    }
+}
+
+impl CRC<init_state::Enabled> {
+    /// Disable the CRC engine
+    ///
+    /// This method is only available, if `CRC` is in the [`Enabled`] state.
+    /// Code that attempts to call this method when the peripheral is already
+    /// disabled will not compile.
+    ///
+    /// Consumes this instance of `CRC` and returns another instance that has
+    /// its `State` type parameter set to [`Disabled`].
+    ///
+    /// [`Enabled`]: ../init_state/struct.Enabled.html
+    /// [`Disabled`]: ../init_state/struct.Disabled.html
+    pub fn disable(
+        self,
+        syscon: &mut syscon::Handle,
+    ) -> CRC<init_state::Disabled> {
+        syscon.disable_clock(&self.crc);
+
+        CRC {
+            crc: self.crc,
+            _state: init_state::Disabled,
+        }
+    }
+
+    /// Compute the CRC-16 that is used by Modbus RTU
+    ///
+    /// This is the CRC-16 polynomial (0x8005), with reflected input and
+    /// output, and an initial value of 0xffff. Modbus sends it in
+    /// little-endian byte order.
+    pub fn crc16_modbus(&mut self, data: &[u8]) -> u16 {
+        self.crc.mode.write(|w| {
+            // Selects CRC-16
+            unsafe { w.crc_poly().bits(0b01) };
+            w.bit_rvs_wr().set_bit();
+            w.cmpl_wr().clear_bit();
+            w.bit_rvs_sum().set_bit();
+            w.cmpl_sum().clear_bit()
+        });
+        self.crc
+            .seed
+            .write(|w| unsafe { w.crc_seed().bits(0xffff) });
+
+        self.write_bytes(data);
+
+        self.crc.sum().read().crc_sum().bits() as u16
+    }
+
+    /// Compute the standard CRC-32
+    ///
+    /// This is the CRC-32 that is used by Ethernet, zlib, and many others
+    /// (also known as CRC-32/ISO-HDLC): polynomial 0x04c11db7, with reflected
+    /// input and output, an initial value of 0xffffffff, and a complemented
+    /// result.
+    ///
+    /// Use [`crc32_digest`], if the data isn't available all at once.
+    ///
+    /// [`crc32_digest`]: #method.crc32_digest
+    pub fn crc32(&mut self, data: &[u8]) -> u32 {
+        let mut digest = self.crc32_digest();
+        digest.update(data);
+        digest.finalize()
+    }
+
+    /// Start computing a CRC-32 incrementally
+    ///
+    /// Computes the same CRC-32 as [`crc32`], but the data can be passed in
+    /// multiple parts. The returned [`Crc32Digest`] borrows the CRC engine,
+    /// until it's finalized or dropped.
+    ///
+    /// [`crc32`]: #method.crc32
+    /// [`Crc32Digest`]: struct.Crc32Digest.html
+    pub fn crc32_digest(&mut self) -> Crc32Digest<'_> {
+        self.crc.mode.write(|w| {
+            // Selects CRC-32
+            unsafe { w.crc_poly().bits(0b10) };
+            w.bit_rvs_wr().set_bit();
+            w.cmpl_wr().clear_bit();
+            w.bit_rvs_sum().set_bit();
+            w.cmpl_sum().set_bit()
+        });
+        self.crc
+            .seed
+            .write(|w| unsafe { w.crc_seed().bits(0xffffffff) });
+
+        Crc32Digest { crc: self }
+    }
+
+    fn write_bytes(&mut self, data: &[u8]) {
+        // The data register is at offset 0x08.
+        let wr_data = (pac::CRC::ptr() as usize + 0x08) as *mut u8;
+
+        for &b in data {
+            // Sound, as the data register accepts writes of any width, and we
+            // have exclusive access to the peripheral.
+            unsafe { ptr::write_volatile(wr_data, b) };
+        }
+    }
+}
+
+/// A CRC-32 that is being computed
+///
+/// Created by [`CRC::crc32_digest`]. Works like the `Digest` type of the `crc`
+/// crate: data is passed using [`update`], and the result is returned by
+/// [`finalize`].
+///
+/// Also implements `core::hash::Hasher`, so it can be used with code that is
+/// generic over hashers. `finish` returns the CRC-32 as `u64`, without
+/// resetting the digest. Please note that the `write_*` methods of `Hasher`
+/// pass integers in native (little-endian) byte order.
+///
+/// [`CRC::crc32_digest`]: struct.CRC.html#method.crc32_digest
+/// [`update`]: #method.update
+/// [`finalize`]: #method.finalize
+pub struct Crc32Digest<'crc> {
+    crc: &'crc mut CRC<init_state::Enabled>,
+}
+
+impl Crc32Digest<'_> {
+    /// Add data to the CRC
+    pub fn update(&mut self, data: &[u8]) {
+        self.crc.write_bytes(data);
+    }
+
+    /// Return the CRC of the data that has been added
+    pub fn finalize(self) -> u32 {
+        self.sum()
+    }
+
+    fn sum(&self) -> u32 {
+        self.crc.crc.sum().read().crc_sum().bits()
+    }
+}
+
+impl Hasher for Crc32Digest<'_> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.sum() as u64
+    }
+}
+
+impl<State> CRC<State> {
+    /// Return the raw peripheral
+    ///
+    /// This method serves as an escape hatch from the HAL API. It returns the
+    /// raw peripheral, allowing you to do whatever you want with it, without
+    /// limitations imposed by the API.
+    ///
+    /// If you are using this method because a feature you need is missing from
+    /// the HAL API, please [open an issue] or, if an issue for your feature
+    /// request already exists, comment on the existing issue, so we can
+    /// prioritize it accordingly.
+    ///
+    /// [open an issue]: https://github.com/lpc-rs/lpc8xx-hal/issues
+    pub fn free(self) -> pac::CRC {
+        self.crc
+    }
+}
@@ -43,16 +43,19 @@
 
 use crate::{
     pac::{
-        ctimer0::{MR, MSR},
-        CTIMER0,
+        ctimer0::{MCR, MR, MSR},
+        Interrupt, CTIMER0, NVIC,
     },
     pins,
     reg_proxy::RegProxy,
+    servo::ServoPin,
     swm::{self, T0_MAT0, T0_MAT1, T0_MAT2},
     syscon,
+    tone::TonePin,
 };
 
 use core::marker::PhantomData;
+use cortex_m::interrupt;
 use embedded_hal::PwmPin;
 
 /// Interface to a CTimer peripheral
@@ -86,10 +89,11 @@ pub struct DetachedPwmPin<CTOutput> {
 /// - [`embedded_hal::PwmPin`]
 ///
 /// [`embedded_hal::PwmPin`]: #impl-PwmPin
-pub struct CTimerPwmPin {
+pub struct CTimerPwmPin<CTOutput, PWM> {
     mr: RegProxy<MR>,
     msr: RegProxy<MSR>,
     number: u8,
+    function: swm::Function<CTOutput, swm::state::Assigned<PWM>>,
 }
 
 impl CTimer {
@@ -153,6 +157,45 @@ impl CTimer {
         )
     }
 
+    /// Enable interrupts for this instance in the NVIC
+    ///
+    /// This only enables the interrupts in the NVIC. It doesn't enable any
+    /// specific interrupt in this CTimer instance.
+    pub fn enable_in_nvic(&mut self) {
+        // Safe, because there's no critical section here that this could
+        // interfere with.
+        unsafe { NVIC::unmask(Interrupt::CTIMER0) };
+    }
+
+    /// Disable interrupts for this instance in the NVIC
+    ///
+    /// This only disables the interrupts in the NVIC. It doesn't change
+    /// anything about the interrupt configuration within this CTimer instance.
+    pub fn disable_in_nvic(&mut self) {
+        NVIC::mask(Interrupt::CTIMER0);
+    }
+
+    /// Clear this instance's interrupt pending flag in the NVIC
+    ///
+    /// This only clears the interrupt's pending flag in the NVIC. It does not
+    /// affect any of the interrupt-related flags in the peripheral.
+    pub fn clear_nvic_pending(&mut self) {
+        NVIC::unpend(Interrupt::CTIMER0);
+    }
+
+    /// Set the priority of this instance's interrupt in the NVIC
+    ///
+    /// Lower values mean higher priority. Only the two most significant bits of
+    /// `priority` are used by the hardware.
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections,
+    /// like the ones used by RTFM.
+    pub unsafe fn set_nvic_priority(&mut self, nvic: &mut NVIC, priority: u8) {
+        nvic.set_priority(Interrupt::CTIMER0, priority);
+    }
+
     /// Return the raw peripheral
     ///
     /// This method serves as an escape hatch from the HAL API. It returns the
@@ -175,8 +218,8 @@ impl<CTOutput> DetachedPwmPin<CTOutput> {
     /// allowing it to be used as a pwm output
     pub fn attach<PWM>(
         self,
-        _: swm::Function<CTOutput, swm::state::Assigned<PWM>>,
-    ) -> CTimerPwmPin
+        function: swm::Function<CTOutput, swm::state::Assigned<PWM>>,
+    ) -> CTimerPwmPin<CTOutput, PWM>
     where
         PWM: pins::Trait,
     {
@@ -184,11 +227,41 @@ impl<CTOutput> DetachedPwmPin<CTOutput> {
             mr: self.mr,
             msr: self.msr,
             number: self.number,
+            function,
         }
     }
 }
 
-impl PwmPin for CTimerPwmPin {
+impl<CTOutput, PWM> CTimerPwmPin<CTOutput, PWM> {
+    /// Detaches the pin from this pwm channel
+    ///
+    /// Returns the `DetachedPwmPin`, which can be attached to another output,
+    /// and the SWM function that was passed to [`attach`]. The function can
+    /// then be unassigned, to reuse the pin for something else.
+    ///
+    /// Please note that the pwm channel keeps running after it's been
+    /// detached. Set the duty cycle to 0 before calling this method, if that
+    /// is not what you want.
+    ///
+    /// [`attach`]: struct.DetachedPwmPin.html#method.attach
+    pub fn detach(
+        self,
+    ) -> (
+        DetachedPwmPin<CTOutput>,
+        swm::Function<CTOutput, swm::state::Assigned<PWM>>,
+    ) {
+        let detached = DetachedPwmPin {
+            number: self.number,
+            mr: self.mr,
+            msr: self.msr,
+            output: PhantomData,
+        };
+
+        (detached, self.function)
+    }
+}
+
+impl<CTOutput, PWM> PwmPin for CTimerPwmPin<CTOutput, PWM> {
     type Duty = u32;
     /// The behaviour of `enable` is implementation defined and does nothing in
     /// this implementation
@@ -219,5 +292,54 @@ impl PwmPin for CTimerPwmPin {
     }
 }
 
+/// Changes the period of all PWM outputs of the CTimer
+impl<CTOutput, PWM> TonePin for CTimerPwmPin<CTOutput, PWM> {
+    fn set_period(&mut self, period: u32) {
+        assert!(period > 0);
+
+        // The counter is reset after it reaches the match value, so the
+        // period is one tick longer than that.
+        unsafe { self.msr[3].write(|w| w.match_shadow().bits(period - 1)) };
+
+        // Load the new period from the shadow register at the start of the
+        // next period. Writing MR3 directly could put it below the current
+        // count, making the counter run until it overflows.
+        let mcr = RegProxy::<MCR>::new();
+        interrupt::free(|_| mcr.modify(|_, w| w.mr3rl().set_bit()));
+    }
+
+    fn silence(&mut self) {
+        self.set_duty(IDLE);
+    }
+}
+
+/// Converts the pulse width, as CTimer outputs are high at the end of the
+/// period
+impl<CTOutput, PWM> ServoPin for CTimerPwmPin<CTOutput, PWM> {
+    fn set_pulse(&mut self, ticks: u32) {
+        let period = self.get_max_duty().saturating_add(1);
+
+        let duty = if ticks == 0 {
+            IDLE
+        } else {
+            period.saturating_sub(ticks)
+        };
+        self.set_duty(duty);
+    }
+
+    fn pulse(&self) -> u32 {
+        let period = self.get_max_duty().saturating_add(1);
+        period.saturating_sub(self.get_duty())
+    }
+}
+
+/// A match value that the counter never reaches, to hold an output low
+///
+/// In PWM mode, an output is low until the counter reaches its match value,
+/// and high from then on until the end of the period. A duty cycle of 0 keeps
+/// the output high.
+const IDLE: u32 = u32::MAX;
+
+reg!(MCR, MCR, CTIMER0, mcr);
 reg!(MR, [MR; 4], CTIMER0, mr);
 reg!(MSR, [MSR; 4], CTIMER0, msr);
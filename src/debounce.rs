@@ -0,0 +1,185 @@
+//! Debouncing for digital inputs
+//!
+//! Mechanical switches and buttons don't switch cleanly. When pressed or
+//! released, their contacts bounce for a few milliseconds, which shows up as a
+//! burst of edges on the input. [`DebouncedInput`] filters those out by
+//! sampling the input at regular intervals, and only accepting a new level once
+//! it has been stable for a configurable number of samples.
+//!
+//! The sampling interval is determined by the caller. Either call
+//! [`DebouncedInput::update`] at regular intervals (for example from a timer
+//! interrupt handler), or pass a periodic timer to [`DebouncedInput::poll`].
+//! Sampling every 1 to 5 ms with 5 to 10 samples works for most buttons.
+//!
+//! [`DebouncedInput`] works with any input that implements [`InputPin`]. To
+//! suppress short glitches on inputs that are not sampled, like those that
+//! trigger pin interrupts, use the digital glitch filter of the pin in IOCON
+//! instead. The HAL doesn't provide an API for that yet, so you need to use
+//! the `IOCON` field of [`Peripherals`] directly.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     prelude::*,
+//!     Peripherals,
+//!     debounce::{DebouncedInput, Event},
+//!     gpio::Level,
+//! };
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let gpio = p.GPIO;
+//! #[cfg(feature = "845")]
+//! let gpio = p.GPIO.enable(&mut syscon.handle);
+//!
+//! let pin = p.pins.pio0_4.into_input_pin(gpio.tokens.pio0_4);
+//! let mut timer = p.MRT0.split(&mut syscon.handle).mrt0;
+//!
+//! // The button pulls the pin low when pressed
+//! let mut button = DebouncedInput::new(pin, Level::Low, 5);
+//!
+//! // Sample every 2 ms (the MRT runs at 12 MHz by default)
+//! timer.start(24_000u32);
+//!
+//! loop {
+//!     match nb::block!(button.poll(&mut timer)).unwrap() {
+//!         Event::Pressed => {
+//!             // button was pressed
+//!         }
+//!         Event::Released => {
+//!             // button was released
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`DebouncedInput`]: struct.DebouncedInput.html
+//! [`DebouncedInput::update`]: struct.DebouncedInput.html#method.update
+//! [`DebouncedInput::poll`]: struct.DebouncedInput.html#method.poll
+//! [`InputPin`]: ../../embedded_hal/digital/v2/trait.InputPin.html
+//! [`Peripherals`]: ../struct.Peripherals.html
+
+use embedded_hal::{digital::v2::InputPin, timer::CountDown};
+
+use crate::gpio::Level;
+
+/// A debounced digital input
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct DebouncedInput<P> {
+    pin: P,
+    active_high: bool,
+    samples: u8,
+    count: u8,
+    pressed: bool,
+}
+
+impl<P> DebouncedInput<P>
+where
+    P: InputPin,
+{
+    /// Create a new debounced input
+    ///
+    /// `active` is the level of the input while the button is pressed.
+    /// `samples` is the number of consecutive samples that need to agree,
+    /// before a change of the input level is accepted.
+    ///
+    /// The input is initially considered released.
+    pub fn new(pin: P, active: Level, samples: u8) -> Self {
+        Self {
+            pin,
+            active_high: matches!(active, Level::High),
+            samples: samples.max(1),
+            count: 0,
+            pressed: false,
+        }
+    }
+
+    /// Sample the input
+    ///
+    /// Needs to be called at regular intervals. Returns an event, if the
+    /// debounced state of the input has changed.
+    pub fn update(&mut self) -> Result<Option<Event>, P::Error> {
+        let active = self.pin.is_high()? == self.active_high;
+
+        if active == self.pressed {
+            self.count = 0;
+            return Ok(None);
+        }
+
+        self.count += 1;
+        if self.count < self.samples {
+            return Ok(None);
+        }
+
+        self.count = 0;
+        self.pressed = active;
+
+        if active {
+            Ok(Some(Event::Pressed))
+        } else {
+            Ok(Some(Event::Released))
+        }
+    }
+
+    /// Sample the input, whenever a periodic timer has expired
+    ///
+    /// Returns `nb::Error::WouldBlock`, if the timer hasn't expired yet, or if
+    /// sampling the input didn't result in an event. The timer needs to have
+    /// been started with the desired sampling interval.
+    pub fn poll<T>(&mut self, timer: &mut T) -> nb::Result<Event, P::Error>
+    where
+        T: CountDown,
+    {
+        if timer.wait().is_err() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.update()?.ok_or(nb::Error::WouldBlock)
+    }
+
+    /// Indicates whether the input is currently pressed
+    ///
+    /// Returns the debounced state, as determined by the last call to
+    /// [`update`] or [`poll`].
+    ///
+    /// [`update`]: #method.update
+    /// [`poll`]: #method.poll
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Indicates whether the input is currently released
+    ///
+    /// Returns the debounced state, as determined by the last call to
+    /// [`update`] or [`poll`].
+    ///
+    /// [`update`]: #method.update
+    /// [`poll`]: #method.poll
+    pub fn is_released(&self) -> bool {
+        !self.pressed
+    }
+
+    /// Return the wrapped input
+    pub fn free(self) -> P {
+        self.pin
+    }
+}
+
+/// A change of the debounced state of a [`DebouncedInput`]
+///
+/// [`DebouncedInput`]: struct.DebouncedInput.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// The input has been pressed
+    Pressed,
+
+    /// The input has been released
+    Released,
+}
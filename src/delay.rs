@@ -1,4 +1,10 @@
-//! API for delays with the systick timer
+//! API for delays
+//!
+//! [`Delay`] uses the SysTick timer as a delay provider. If SysTick is not
+//! available, for example because it's used by a scheduler, [`TimerDelay`] can
+//! be used with an MRT channel or the WKT instead. [`CycleDelay`] doesn't
+//! require any peripheral at all, which makes it suitable for early
+//! initialization code, or situations where all timers are already in use.
 //!
 //! Please be aware of potential overflows when using `delay_us`.
 //! E.g. at 30MHz the maximum delay is 146 seconds.
@@ -19,11 +25,20 @@
 //!     delay.delay_ms(1_000_u16);
 //! }
 //! ```
+//!
+//! [`Delay`]: struct.Delay.html
+//! [`TimerDelay`]: struct.TimerDelay.html
+//! [`CycleDelay`]: struct.CycleDelay.html
 
-use cortex_m::peripheral::syst::SystClkSource;
+use cortex_m::{asm, peripheral::syst::SystClkSource};
+use embedded_hal::{
+    blocking::delay::{DelayMs, DelayUs},
+    timer::CountDown,
+};
+use nb::block;
+use void::ResultVoidExt;
 
-use crate::pac::SYST;
-use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use crate::{init_state, mrt, pac::SYST, wkt::WKT};
 
 const SYSTICK_RANGE: u32 = 0x0100_0000;
 const SYSTEM_CLOCK: u32 = 12_000_000;
@@ -131,3 +146,273 @@ impl DelayUs<u8> for Delay {
         self.delay_us(us as u32)
     }
 }
+
+/// A delay provider based on a timer
+///
+/// Can be used with an MRT channel or the WKT, leaving SysTick available for
+/// other uses, like an RTFM scheduler.
+///
+/// # `embedded-hal` traits
+/// - [`embedded_hal::blocking::delay::DelayUs`]
+/// - [`embedded_hal::blocking::delay::DelayMs`]
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     prelude::*,
+///     delay::TimerDelay,
+///     Peripherals,
+/// };
+///
+/// let p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+/// let mrt_channels = p.MRT0.split(&mut syscon.handle);
+///
+/// // The MRT runs at the system clock frequency, 12 MHz by default
+/// let mut delay = TimerDelay::new(mrt_channels.mrt0, 12_000_000);
+///
+/// delay.delay_ms(1_000_u16);
+/// ```
+///
+/// [`embedded_hal::blocking::delay::DelayUs`]: #impl-DelayUs%3Cu32%3E
+/// [`embedded_hal::blocking::delay::DelayMs`]: #impl-DelayMs%3Cu32%3E
+pub struct TimerDelay<T> {
+    timer: T,
+    frequency: u32,
+}
+
+impl<T> TimerDelay<T>
+where
+    T: Timer,
+{
+    /// Create a delay provider from a timer
+    ///
+    /// `frequency` is the frequency of the clock that drives the timer, in Hz.
+    /// For MRT channels, this is the system clock frequency. For the WKT, it's
+    /// the frequency of the clock that has been selected using
+    /// [`WKT::select_clock`].
+    ///
+    /// [`WKT::select_clock`]: ../wkt/struct.WKT.html#method.select_clock
+    pub fn new(timer: T, frequency: u32) -> Self {
+        assert!(frequency > 0);
+        TimerDelay { timer, frequency }
+    }
+
+    /// Return the timer
+    pub fn free(self) -> T {
+        self.timer
+    }
+
+    fn delay_ticks(&mut self, mut ticks: u64) {
+        while ticks != 0 {
+            let current_ticks = if ticks <= T::MAX_TICKS as u64 {
+                ticks as u32
+            } else {
+                T::MAX_TICKS
+            };
+
+            self.timer.start(current_ticks);
+            block!(self.timer.wait()).void_unwrap();
+
+            ticks -= current_ticks as u64;
+        }
+    }
+}
+
+impl<T> DelayMs<u32> for TimerDelay<T>
+where
+    T: Timer,
+{
+    /// Pauses execution for `ms` milliseconds
+    fn delay_ms(&mut self, ms: u32) {
+        // Round up, so we never wait less than requested
+        let ticks = (ms as u64 * self.frequency as u64).div_ceil(1_000);
+        self.delay_ticks(ticks);
+    }
+}
+
+impl<T> DelayMs<u16> for TimerDelay<T>
+where
+    T: Timer,
+{
+    /// Pauses execution for `ms` milliseconds
+    fn delay_ms(&mut self, ms: u16) {
+        self.delay_ms(ms as u32);
+    }
+}
+
+impl<T> DelayMs<u8> for TimerDelay<T>
+where
+    T: Timer,
+{
+    /// Pauses execution for `ms` milliseconds
+    fn delay_ms(&mut self, ms: u8) {
+        self.delay_ms(ms as u32);
+    }
+}
+
+impl<T> DelayUs<u32> for TimerDelay<T>
+where
+    T: Timer,
+{
+    /// Pauses execution for `us` microseconds
+    fn delay_us(&mut self, us: u32) {
+        // Round up, so we never wait less than requested
+        let ticks = (us as u64 * self.frequency as u64).div_ceil(1_000_000);
+        self.delay_ticks(ticks);
+    }
+}
+
+impl<T> DelayUs<u16> for TimerDelay<T>
+where
+    T: Timer,
+{
+    /// Pauses execution for `us` microseconds
+    fn delay_us(&mut self, us: u16) {
+        self.delay_us(us as u32);
+    }
+}
+
+impl<T> DelayUs<u8> for TimerDelay<T>
+where
+    T: Timer,
+{
+    /// Pauses execution for `us` microseconds
+    fn delay_us(&mut self, us: u8) {
+        self.delay_us(us as u32);
+    }
+}
+
+/// A delay provider based on busy-waiting
+///
+/// Waits using [`cortex_m::asm::delay`]. Doesn't require any peripheral, but
+/// keeps the CPU busy while waiting.
+///
+/// The delay is at least as long as requested. It's longer, if interrupts are
+/// handled while waiting, or if the flash is configured with additional wait
+/// states, which slow down the delay loop. Use [`TimerDelay`] for more
+/// accurate delays.
+///
+/// # `embedded-hal` traits
+/// - [`embedded_hal::blocking::delay::DelayUs`]
+/// - [`embedded_hal::blocking::delay::DelayMs`]
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     prelude::*,
+///     delay::CycleDelay,
+/// };
+///
+/// // The system clock runs at 12 MHz by default
+/// let mut delay = CycleDelay::new(12_000_000);
+///
+/// delay.delay_us(100_u8);
+/// ```
+///
+/// [`cortex_m::asm::delay`]: ../../cortex_m/asm/fn.delay.html
+/// [`TimerDelay`]: struct.TimerDelay.html
+/// [`embedded_hal::blocking::delay::DelayUs`]: #impl-DelayUs%3Cu32%3E
+/// [`embedded_hal::blocking::delay::DelayMs`]: #impl-DelayMs%3Cu32%3E
+#[derive(Clone, Copy)]
+pub struct CycleDelay {
+    frequency: u32,
+}
+
+impl CycleDelay {
+    /// Create a delay provider for the given core clock frequency
+    ///
+    /// `frequency` is the frequency of the system clock, in Hz.
+    pub fn new(frequency: u32) -> Self {
+        assert!(frequency > 0);
+        CycleDelay { frequency }
+    }
+
+    fn delay_cycles(&self, cycles: u64) {
+        // The argument of `asm::delay` is the number of iterations of its
+        // loop, not the number of cycles. Each iteration is a `nop`, a `subs`,
+        // and a taken `bne`, which take 4 cycles on the Cortex-M0+.
+        let mut iterations = cycles.div_ceil(CYCLES_PER_ITERATION);
+
+        // `asm::delay` loops almost forever, if passed 0.
+        while iterations != 0 {
+            let current = if iterations <= u32::MAX as u64 {
+                iterations as u32
+            } else {
+                u32::MAX
+            };
+
+            asm::delay(current);
+            iterations -= current as u64;
+        }
+    }
+}
+
+impl DelayMs<u32> for CycleDelay {
+    /// Pauses execution for `ms` milliseconds
+    fn delay_ms(&mut self, ms: u32) {
+        self.delay_cycles((ms as u64 * self.frequency as u64).div_ceil(1_000));
+    }
+}
+
+impl DelayMs<u16> for CycleDelay {
+    /// Pauses execution for `ms` milliseconds
+    fn delay_ms(&mut self, ms: u16) {
+        self.delay_ms(ms as u32);
+    }
+}
+
+impl DelayMs<u8> for CycleDelay {
+    /// Pauses execution for `ms` milliseconds
+    fn delay_ms(&mut self, ms: u8) {
+        self.delay_ms(ms as u32);
+    }
+}
+
+impl DelayUs<u32> for CycleDelay {
+    /// Pauses execution for `us` microseconds
+    fn delay_us(&mut self, us: u32) {
+        self.delay_cycles(
+            (us as u64 * self.frequency as u64).div_ceil(1_000_000),
+        );
+    }
+}
+
+impl DelayUs<u16> for CycleDelay {
+    /// Pauses execution for `us` microseconds
+    fn delay_us(&mut self, us: u16) {
+        self.delay_us(us as u32);
+    }
+}
+
+impl DelayUs<u8> for CycleDelay {
+    /// Pauses execution for `us` microseconds
+    fn delay_us(&mut self, us: u8) {
+        self.delay_us(us as u32);
+    }
+}
+
+/// The number of cycles per iteration of the loop in `asm::delay`
+const CYCLES_PER_ITERATION: u64 = 4;
+
+/// Implemented by timers that can be used with [`TimerDelay`]
+///
+/// [`TimerDelay`]: struct.TimerDelay.html
+pub trait Timer: CountDown<Time = u32> {
+    /// The maximum number of ticks the timer can count down from
+    const MAX_TICKS: u32;
+}
+
+impl<T> Timer for mrt::Channel<T>
+where
+    T: mrt::Trait,
+{
+    const MAX_TICKS: u32 = mrt::MAX_VALUE;
+}
+
+impl Timer for WKT<init_state::Enabled> {
+    const MAX_TICKS: u32 = u32::MAX;
+}
@@ -0,0 +1,229 @@
+//! Interrupt handlers that can be registered at runtime
+//!
+//! This module is only available, if the `interrupt-dispatch` feature is
+//! enabled. It provides the interrupt handlers for the USART, SPI, and pin
+//! interrupts. Each of them calls a function that has been registered for its
+//! interrupt [`Source`] using [`register`], if any.
+//!
+//! This allows applications to handle those interrupts from safe code. Any
+//! peripheral the handler needs can be moved into a `static` during
+//! initialization, and accessed from within a critical section, instead of
+//! being reconstructed unsafely within an `#[interrupt]` function.
+//!
+//! Handlers are plain function pointers, so closures that don't capture
+//! anything can be registered too. Registering a handler doesn't enable the
+//! interrupt. This still needs to be done using the respective peripheral API,
+//! for example [`USART::enable_in_nvic`].
+//!
+//! Since this module defines the interrupt handlers, the application can't
+//! define its own handlers for the same interrupts. If an interrupt occurs
+//! while no function is registered for it, it is ignored.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use core::cell::{Cell, RefCell};
+//!
+//! use lpc8xx_hal::{
+//!     cortex_m::interrupt::{self, Mutex},
+//!     dispatch::{self, Source},
+//!     init_state::Enabled,
+//!     pac::USART0,
+//!     pins::PIO0_0,
+//!     prelude::*,
+//!     usart::{self, RxOnlyFunctions},
+//!     Peripherals,
+//! };
+//!
+//! type Rx = usart::Rx<USART0, Enabled<RxOnlyFunctions<USART0, PIO0_0>>>;
+//!
+//! static RX: Mutex<RefCell<Option<Rx>>> = Mutex::new(RefCell::new(None));
+//! static LAST: Mutex<Cell<u8>> = Mutex::new(Cell::new(0));
+//!
+//! fn on_usart0() {
+//!     interrupt::free(|cs| {
+//!         if let Some(rx) = RX.borrow(cs).borrow_mut().as_mut() {
+//!             if let Ok(b) = rx.read() {
+//!                 LAST.borrow(cs).set(b);
+//!             }
+//!         }
+//!     });
+//! }
+//!
+//! let mut p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//! let mut swm = p.SWM.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! #[cfg(feature = "82x")]
+//! let clock_config = {
+//!     syscon.uartfrg.set_clkdiv(6);
+//!     syscon.uartfrg.set_frgmult(22);
+//!     syscon.uartfrg.set_frgdiv(0xff);
+//!     let uartfrg = syscon.uartfrg.share();
+//!     usart::Clock::new(&uartfrg, 0, 16)
+//! };
+//! #[cfg(feature = "845")]
+//! let clock_config = usart::Clock::new_with_baudrate(115200);
+//!
+//! let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+//!     p.pins.pio0_0.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//!
+//! let mut serial =
+//!     p.USART0.enable_rx_only(&clock_config, &mut syscon.handle, u0_rxd);
+//!
+//! dispatch::register(Source::USART0, on_usart0);
+//! serial.enable_in_nvic();
+//!
+//! let mut rx = serial.rx;
+//! rx.enable_rxrdy();
+//! interrupt::free(|cs| *RX.borrow(cs).borrow_mut() = Some(rx));
+//! ```
+//!
+//! [`Source`]: enum.Source.html
+//! [`register`]: fn.register.html
+//! [`USART::enable_in_nvic`]: ../usart/struct.USART.html#method.enable_in_nvic
+
+use core::cell::Cell;
+
+use cortex_m::interrupt::{free, Mutex};
+
+use crate::pac::interrupt;
+
+/// An interrupt source, for which a handler can be registered
+///
+/// On LPC845, some of the sources share an interrupt. If that interrupt
+/// occurs, the handlers of all its sources are called.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Source {
+    /// SPI0
+    SPI0,
+
+    /// SPI1
+    SPI1,
+
+    /// USART0
+    USART0,
+
+    /// USART1
+    USART1,
+
+    /// USART2
+    USART2,
+
+    /// USART3 (shares its interrupt with PININT6)
+    #[cfg(feature = "845")]
+    USART3,
+
+    /// USART4 (shares its interrupt with PININT7)
+    #[cfg(feature = "845")]
+    USART4,
+
+    /// Pin interrupt 0
+    PININT0,
+
+    /// Pin interrupt 1
+    PININT1,
+
+    /// Pin interrupt 2
+    PININT2,
+
+    /// Pin interrupt 3
+    PININT3,
+
+    /// Pin interrupt 4
+    PININT4,
+
+    /// Pin interrupt 5
+    PININT5,
+
+    /// Pin interrupt 6
+    PININT6,
+
+    /// Pin interrupt 7
+    PININT7,
+}
+
+#[cfg(feature = "82x")]
+const SOURCES: usize = 13;
+#[cfg(feature = "845")]
+const SOURCES: usize = 15;
+
+/// Holds the handler that is registered for one source
+type Slot = Cell<Option<fn()>>;
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NO_HANDLER: Slot = Cell::new(None);
+
+static HANDLERS: Mutex<[Slot; SOURCES]> = Mutex::new([NO_HANDLER; SOURCES]);
+
+/// Register a handler for an interrupt source
+///
+/// The handler is called from the interrupt handler, each time the interrupt
+/// occurs. It replaces the previously registered handler, if any, which is
+/// returned.
+pub fn register(source: Source, handler: fn()) -> Option<fn()> {
+    free(|cs| HANDLERS.borrow(cs)[source as usize].replace(Some(handler)))
+}
+
+/// Remove the handler of an interrupt source
+///
+/// Returns the handler that was registered, if any. The interrupt itself is
+/// left enabled.
+pub fn unregister(source: Source) -> Option<fn()> {
+    free(|cs| HANDLERS.borrow(cs)[source as usize].take())
+}
+
+fn dispatch(source: Source) {
+    // The handler is called outside of the critical section, so it can be
+    // preempted by higher-priority interrupts.
+    let handler = free(|cs| HANDLERS.borrow(cs)[source as usize].get());
+
+    if let Some(handler) = handler {
+        handler();
+    }
+}
+
+macro_rules! handlers {
+    ($($(#[$attr:meta])* $interrupt:ident => $($source:ident),*;)*) => {
+        $(
+            $(#[$attr])*
+            #[interrupt]
+            fn $interrupt() {
+                $(dispatch(Source::$source);)*
+            }
+        )*
+    };
+}
+
+handlers!(
+    SPI0 => SPI0;
+    SPI1 => SPI1;
+    USART0 => USART0;
+    USART1 => USART1;
+    USART2 => USART2;
+    PIN_INT0 => PININT0;
+    PIN_INT1 => PININT1;
+    PIN_INT2 => PININT2;
+    PIN_INT3 => PININT3;
+    PIN_INT4 => PININT4;
+    #[cfg(feature = "82x")]
+    PIN_INT5 => PININT5;
+    #[cfg(feature = "82x")]
+    PIN_INT6 => PININT6;
+    #[cfg(feature = "82x")]
+    PIN_INT7 => PININT7;
+    #[cfg(feature = "845")]
+    PIN_INT5_DAC1 => PININT5;
+    #[cfg(feature = "845")]
+    PIN_INT6_USART3 => PININT6, USART3;
+    #[cfg(feature = "845")]
+    PIN_INT7_USART4 => PININT7, USART4;
+);
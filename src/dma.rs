@@ -2,19 +2,28 @@
 //!
 //! The DMA controller is described in the user manual, chapter 12.
 
+mod double_buffered;
+mod pool;
+
+pub use self::{
+    double_buffered::{DoubleBuffer, DoubleBuffered, Error},
+    pool::Pool,
+};
+
 use core::ptr;
 use core::sync::atomic::{compiler_fence, Ordering};
 
 use nb;
 
 use crate::{
-    init_state,
+    activity, init_state,
     pac::{
         self,
         dma0::{
-            channel::{CFG, XFERCFG},
+            channel::{xfercfg::WIDTH_A, CFG, XFERCFG},
             ACTIVE0, ENABLESET0, SETTRIG0,
         },
+        Interrupt, NVIC,
     },
     reg_proxy::{Reg, RegProxy},
     syscon,
@@ -118,6 +127,45 @@ impl<'dma> Handle<init_state::Disabled> {
             srambase: self.srambase,
         }
     }
+
+    /// Enable interrupts for this instance in the NVIC
+    ///
+    /// This only enables the interrupts in the NVIC. It doesn't enable any
+    /// specific interrupt in this DMA instance.
+    pub fn enable_in_nvic(&mut self) {
+        // Safe, because there's no critical section here that this could
+        // interfere with.
+        unsafe { NVIC::unmask(Interrupt::DMA0) };
+    }
+
+    /// Disable interrupts for this instance in the NVIC
+    ///
+    /// This only disables the interrupts in the NVIC. It doesn't change
+    /// anything about the interrupt configuration within this DMA instance.
+    pub fn disable_in_nvic(&mut self) {
+        NVIC::mask(Interrupt::DMA0);
+    }
+
+    /// Clear this instance's interrupt pending flag in the NVIC
+    ///
+    /// This only clears the interrupt's pending flag in the NVIC. It does not
+    /// affect any of the interrupt-related flags in the peripheral.
+    pub fn clear_nvic_pending(&mut self) {
+        NVIC::unpend(Interrupt::DMA0);
+    }
+
+    /// Set the priority of this instance's interrupt in the NVIC
+    ///
+    /// Lower values mean higher priority. Only the two most significant bits of
+    /// `priority` are used by the hardware.
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections,
+    /// like the ones used by RTFM.
+    pub unsafe fn set_nvic_priority(&mut self, nvic: &mut NVIC, priority: u8) {
+        nvic.set_priority(Interrupt::DMA0, priority);
+    }
 }
 
 impl Handle<init_state::Enabled> {
@@ -310,6 +358,362 @@ where
             dest,
         }
     }
+
+    /// Fills a buffer with a pattern, without involving the CPU
+    ///
+    /// Copies `pattern` into every element of `dest`. The pattern can be 1, 2,
+    /// or 4 bytes wide, depending on the type of the buffer (see [`Word`]).
+    /// Wider patterns require fewer DMA cycles, so filling a large buffer is
+    /// fastest with `u32`.
+    ///
+    /// `pattern` is read for every element, which is why it needs to be
+    /// `'static`. References to constants are promoted to `'static`
+    /// automatically, so clearing a buffer is as simple as passing `&0`.
+    ///
+    /// # Limitations
+    ///
+    /// The length of `dest` must be 1024 or less.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{cortex_m::singleton, dma, Peripherals};
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// let descriptors = singleton!(: dma::DescriptorTable =
+    ///     dma::DescriptorTable::new()).unwrap();
+    /// let dma = p.DMA.split(descriptors);
+    /// let dma_handle = dma.handle.enable(&mut syscon.handle);
+    ///
+    /// let frame = singleton!(: [u32; 256] = [0xffff_ffff; 256]).unwrap();
+    ///
+    /// let fill = dma.channels.channel0.enable(&dma_handle).fill(&0, frame);
+    ///
+    /// // Do something else, while the buffer is being cleared
+    ///
+    /// let (channel, frame) = fill.wait();
+    /// ```
+    ///
+    /// [`Word`]: trait.Word.html
+    pub fn fill<W>(
+        self,
+        pattern: &'static W,
+        dest: &'static mut [W],
+    ) -> Fill<'dma, T, W>
+    where
+        W: Word,
+    {
+        assert!(dest.len() <= 1024);
+
+        compiler_fence(Ordering::SeqCst);
+
+        // We need to substract 1 from the length below. If the destination is
+        // empty, return early to prevent underflow.
+        if dest.is_empty() {
+            return Fill {
+                channel: self,
+                dest,
+            };
+        }
+
+        // Memory-to-memory transfer, so no DMA request is involved. The
+        // transfer is started by a software trigger below.
+        // See user manual, section 12.6.16.
+        self.cfg.write(|w| {
+            w.periphreqen().disabled();
+            w.hwtrigen().disabled();
+            w.trigburst().single();
+            unsafe { w.chpriority().bits(0) }
+        });
+
+        // The source address doesn't increment, so every transfer reads the
+        // pattern again.
+        // See user manual, section 12.6.18.
+        self.xfercfg.write(|w| {
+            w.cfgvalid().valid();
+            w.reload().disabled();
+            w.swtrig().not_set();
+            w.clrtrig().cleared();
+            w.setinta().no_effect();
+            w.setintb().no_effect();
+            w.width().variant(W::WIDTH);
+            w.srcinc().no_increment();
+            w.dstinc().width_x_1();
+            unsafe { w.xfercount().bits(dest.len() as u16 - 1) }
+        });
+
+        let dest_end = unsafe { dest.as_mut_ptr().add(dest.len() - 1) };
+
+        // Configure channel descriptor
+        // See user manual, sections 12.5.2 and 12.5.3.
+        self.descriptor.source_end = pattern as *const W as *const u8;
+        self.descriptor.dest_end = dest_end as *mut u8;
+
+        self.enableset0.write(|w| unsafe { w.ena().bits(T::FLAG) });
+        self.settrig0.write(|w| unsafe { w.trig().bits(T::FLAG) });
+
+        Fill {
+            channel: self,
+            dest,
+        }
+    }
+
+    /// Read from a fixed address into a buffer, whenever a trigger occurs
+    ///
+    /// Each time `trigger` occurs, one value (or one burst of values, see
+    /// [`Trigger::with_burst`]) is read from `source` and written to the next
+    /// element of `dest`. `source` is typically a peripheral register. This
+    /// can be used to sample a GPIO port into RAM on every timer tick, for
+    /// example, without involving the CPU at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `dest` is empty or longer than 1024 elements, which is the
+    /// maximum length of a DMA transfer.
+    ///
+    /// # Safety
+    ///
+    /// `source` must be valid for reads of `W` for as long as the transfer is
+    /// in progress.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{
+    ///     cortex_m::singleton,
+    ///     dma::{self, Trigger, TriggerSource},
+    ///     Peripherals,
+    /// };
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// let descriptors = singleton!(: dma::DescriptorTable =
+    ///     dma::DescriptorTable::new()).unwrap();
+    /// let dma = p.DMA.split(descriptors);
+    /// let dma_handle = dma.handle.enable(&mut syscon.handle);
+    ///
+    /// let samples = singleton!(: [u32; 128] = [0; 128]).unwrap();
+    ///
+    /// // The PIN register of GPIO port 0
+    /// let port0 = 0xa000_2100 as *const u32;
+    ///
+    /// // Sample the port on every SCT DMA request 0. The SCT needs to be
+    /// // configured to generate those, which is not covered here.
+    /// let transfer = unsafe {
+    ///     dma.channels.channel0.enable(&dma_handle).read_on_trigger(
+    ///         Trigger::new(TriggerSource::SctDma0),
+    ///         port0,
+    ///         samples,
+    ///     )
+    /// };
+    ///
+    /// let (channel, samples) = transfer.wait();
+    /// ```
+    ///
+    /// [`Trigger::with_burst`]: struct.Trigger.html#method.with_burst
+    pub unsafe fn read_on_trigger<W>(
+        mut self,
+        trigger: Trigger,
+        source: *const W,
+        dest: &'static mut [W],
+    ) -> TriggeredTransfer<'dma, T, W>
+    where
+        W: Word,
+    {
+        assert!(!dest.is_empty() && dest.len() <= 1024);
+
+        let dest_end = dest.as_mut_ptr().add(dest.len() - 1);
+        self.start_triggered::<W>(
+            &trigger,
+            source as *const u8,
+            dest_end as *mut u8,
+            false,
+            true,
+            dest.len(),
+        );
+
+        TriggeredTransfer {
+            channel: self,
+            buffer: dest,
+        }
+    }
+
+    /// Write from a buffer to a fixed address, whenever a trigger occurs
+    ///
+    /// Each time `trigger` occurs, the next element (or burst of elements,
+    /// see [`Trigger::with_burst`]) of `source` is written to `dest`. `dest`
+    /// is typically a peripheral register. Otherwise works like
+    /// [`read_on_trigger`].
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `source` is empty or longer than 1024 elements, which is the
+    /// maximum length of a DMA transfer.
+    ///
+    /// # Safety
+    ///
+    /// `dest` must be valid for writes of `W` for as long as the transfer is in
+    /// progress.
+    ///
+    /// [`Trigger::with_burst`]: struct.Trigger.html#method.with_burst
+    /// [`read_on_trigger`]: #method.read_on_trigger
+    pub unsafe fn write_on_trigger<W>(
+        mut self,
+        trigger: Trigger,
+        source: &'static mut [W],
+        dest: *mut W,
+    ) -> TriggeredTransfer<'dma, T, W>
+    where
+        W: Word,
+    {
+        assert!(!source.is_empty() && source.len() <= 1024);
+
+        let source_end = source.as_ptr().add(source.len() - 1);
+        self.start_triggered::<W>(
+            &trigger,
+            source_end as *const u8,
+            dest as *mut u8,
+            true,
+            false,
+            source.len(),
+        );
+
+        TriggeredTransfer {
+            channel: self,
+            buffer: source,
+        }
+    }
+
+    fn start_triggered<W>(
+        &mut self,
+        trigger: &Trigger,
+        source_end: *const u8,
+        dest_end: *mut u8,
+        source_inc: bool,
+        dest_inc: bool,
+        len: usize,
+    ) where
+        W: Word,
+    {
+        compiler_fence(Ordering::SeqCst);
+
+        self.clear_interrupt_flags();
+        self.set_trigger(Some(trigger));
+
+        // INTA is set once the descriptor is exhausted. We use it to detect
+        // the end of the transfer, as the channel isn't active while it's
+        // waiting for the next trigger.
+        // See user manual, section 12.6.18.
+        self.xfercfg.write(|w| {
+            w.cfgvalid().valid();
+            w.reload().disabled();
+            w.swtrig().not_set();
+            w.clrtrig().cleared();
+            w.setinta().set();
+            w.setintb().no_effect();
+            w.width().variant(W::WIDTH);
+            if source_inc {
+                w.srcinc().width_x_1();
+            } else {
+                w.srcinc().no_increment();
+            }
+            if dest_inc {
+                w.dstinc().width_x_1();
+            } else {
+                w.dstinc().no_increment();
+            }
+            unsafe { w.xfercount().bits(len as u16 - 1) }
+        });
+
+        // See user manual, sections 12.5.2 and 12.5.3.
+        self.descriptor.source_end = source_end;
+        self.descriptor.dest_end = dest_end;
+
+        self.enableset0.write(|w| unsafe { w.ena().bits(T::FLAG) });
+    }
+
+    /// Configure how transfers are started
+    ///
+    /// If `trigger` is `None`, the channel is configured to wait for its
+    /// peripheral's DMA request.
+    fn set_trigger(&mut self, trigger: Option<&Trigger>) {
+        let trigger = match trigger {
+            Some(trigger) => trigger,
+            None => {
+                // See user manual, section 12.6.16.
+                self.cfg.write(|w| {
+                    w.periphreqen().enabled();
+                    w.hwtrigen().disabled();
+                    w.trigburst().single();
+                    unsafe { w.chpriority().bits(0) }
+                });
+                return;
+            }
+        };
+
+        // Sound, as the only other part of the HAL API that accesses
+        // `INPUTMUX` is the SCT, which only writes to `SCT_INMUX`. Of the DMA
+        // trigger registers, we only write the one that belongs to this
+        // channel, which we own.
+        let inputmux = unsafe { &*pac::INPUTMUX::ptr() };
+        inputmux.dma_itrig_inmux[T::INDEX]
+            .write(|w| unsafe { w.inp().bits(trigger.source as u8) });
+
+        // See user manual, section 12.6.16.
+        self.cfg.write(|w| {
+            w.periphreqen().disabled();
+            w.hwtrigen().enabled();
+            match trigger.mode {
+                TriggerMode::RisingEdge => {
+                    w.trigpol().active_high_rising().trigtype().edge()
+                }
+                TriggerMode::FallingEdge => {
+                    w.trigpol().active_low_falling().trigtype().edge()
+                }
+                TriggerMode::HighLevel => {
+                    w.trigpol().active_high_rising().trigtype().level()
+                }
+                TriggerMode::LowLevel => {
+                    w.trigpol().active_low_falling().trigtype().level()
+                }
+            };
+            if trigger.burst_power > 0 {
+                w.trigburst().burst();
+            } else {
+                w.trigburst().single();
+            }
+            unsafe {
+                w.burstpower().bits(trigger.burst_power);
+                w.chpriority().bits(0)
+            }
+        });
+    }
+
+    /// Stop the channel, discarding any transfer that is in progress
+    fn abort(&mut self) {
+        // Sound, as we only write the bit that belongs to this channel, and
+        // writing zeros to these registers has no effect.
+        let dma = unsafe { &*pac::DMA0::ptr() };
+
+        // See user manual, section 12.5.4.
+        dma.enableclr0.write(|w| unsafe { w.clr().bits(T::FLAG) });
+        while dma.busy0.read().bsy().bits() & T::FLAG != 0 {}
+        dma.abort0.write(|w| unsafe { w.abortctrl().bits(T::FLAG) });
+    }
+
+    /// Clear the channel's INTA and INTB flags
+    fn clear_interrupt_flags(&mut self) {
+        // Sound, as we only write the bit that belongs to this channel, and
+        // writing zeros to these registers has no effect.
+        let dma = unsafe { &*pac::DMA0::ptr() };
+        dma.inta0.write(|w| unsafe { w.ia().bits(T::FLAG) });
+        dma.intb0.write(|w| unsafe { w.ib().bits(T::FLAG) });
+    }
 }
 
 /// Implemented for each DMA channel
@@ -444,6 +848,168 @@ channels!(
     channel24, Channel24, 24, CFG24, XFERCFG24;
 );
 
+/// A hardware trigger for a DMA channel
+///
+/// Used with [`Channel::read_on_trigger`] and [`Channel::write_on_trigger`].
+/// By default, every rising edge of the trigger input starts a single
+/// transfer.
+///
+/// [`Channel::read_on_trigger`]: struct.Channel.html#method.read_on_trigger
+/// [`Channel::write_on_trigger`]: struct.Channel.html#method.write_on_trigger
+#[derive(Clone, Copy, Debug)]
+pub struct Trigger {
+    source: TriggerSource,
+    mode: TriggerMode,
+    burst_power: u8,
+}
+
+impl Trigger {
+    /// Create a trigger that starts a single transfer on every rising edge
+    pub fn new(source: TriggerSource) -> Self {
+        Self {
+            source,
+            mode: TriggerMode::RisingEdge,
+            burst_power: 0,
+        }
+    }
+
+    /// Select the edge or level that activates the trigger
+    pub fn with_mode(self, mode: TriggerMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    /// Transfer a burst of values on each trigger, instead of a single one
+    ///
+    /// The burst size is `2^burst_power`. With a level trigger, bursts are
+    /// repeated for as long as the trigger is active.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `burst_power` is larger than 10, as a burst can't be longer
+    /// than a DMA transfer (1024 values).
+    pub fn with_burst(self, burst_power: u8) -> Self {
+        assert!(burst_power <= 10);
+        Self {
+            burst_power,
+            ..self
+        }
+    }
+}
+
+/// The edge or level that activates a [`Trigger`]
+///
+/// [`Trigger`]: struct.Trigger.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TriggerMode {
+    /// The trigger is activated by a rising edge
+    RisingEdge,
+
+    /// The trigger is activated by a falling edge
+    FallingEdge,
+
+    /// The trigger is active, while the trigger input is high
+    HighLevel,
+
+    /// The trigger is active, while the trigger input is low
+    LowLevel,
+}
+
+/// The input that activates a [`Trigger`]
+///
+/// The pin interrupts need to be configured using the [`pinint`] API. The
+/// other peripherals need to be configured to generate a DMA request, which
+/// isn't supported by the HAL API yet.
+///
+/// [`Trigger`]: struct.Trigger.html
+/// [`pinint`]: ../pinint/index.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TriggerSource {
+    /// ADC sequence A interrupt
+    AdcSeqA = 0,
+
+    /// ADC sequence B interrupt
+    AdcSeqB = 1,
+
+    /// SCT DMA request 0
+    SctDma0 = 2,
+
+    /// SCT DMA request 1
+    SctDma1 = 3,
+
+    /// Analog comparator output
+    Acmp = 4,
+
+    /// Pin interrupt 0
+    #[cfg(feature = "82x")]
+    PinInt0 = 5,
+
+    /// Pin interrupt 1
+    #[cfg(feature = "82x")]
+    PinInt1 = 6,
+
+    /// Pin interrupt 4
+    #[cfg(feature = "845")]
+    PinInt4 = 5,
+
+    /// Pin interrupt 5
+    #[cfg(feature = "845")]
+    PinInt5 = 6,
+
+    /// Pin interrupt 6
+    #[cfg(feature = "845")]
+    PinInt6 = 7,
+
+    /// Pin interrupt 7
+    #[cfg(feature = "845")]
+    PinInt7 = 8,
+
+    /// CTIMER0 match 0 DMA request
+    #[cfg(feature = "845")]
+    CTimer0Match0 = 9,
+
+    /// CTIMER0 match 1 DMA request
+    #[cfg(feature = "845")]
+    CTimer0Match1 = 10,
+
+    /// DMA output trigger mux 0
+    #[cfg(feature = "82x")]
+    TriggerMux0 = 7,
+
+    /// DMA output trigger mux 1
+    #[cfg(feature = "82x")]
+    TriggerMux1 = 8,
+
+    /// DMA output trigger mux 0
+    #[cfg(feature = "845")]
+    TriggerMux0 = 11,
+
+    /// DMA output trigger mux 1
+    #[cfg(feature = "845")]
+    TriggerMux1 = 12,
+}
+
+/// Implemented for types that can be transferred by the DMA controller
+///
+/// Determines the width of each DMA transfer. This trait is an internal
+/// implementation detail and should neither be implemented nor used outside of
+/// LPC8xx HAL. Any changes to this trait won't be considered breaking changes.
+pub trait Word: Copy + 'static {
+    /// The transfer width for this type
+    const WIDTH: WIDTH_A;
+}
+
+impl Word for u8 {
+    const WIDTH: WIDTH_A = WIDTH_A::BIT_8;
+}
+
+impl Word for u16 {
+    const WIDTH: WIDTH_A = WIDTH_A::BIT_16;
+}
+
+impl Word for u32 {
+    const WIDTH: WIDTH_A = WIDTH_A::BIT_32;
+}
+
 /// A destination for a DMA transfer
 pub trait Dest {
     /// The error that can occur while waiting for the destination to be idle
@@ -471,6 +1037,33 @@ where
     T: ChannelTrait,
     D: Dest,
 {
+    /// Indicates whether the DMA channel is still transferring data
+    ///
+    /// Once this returns `false`, all data has been written to the
+    /// destination, but the destination might still be busy processing it.
+    /// [`wait`] waits for both.
+    ///
+    /// [`wait`]: #method.wait
+    pub fn is_active(&self) -> bool {
+        self.channel.active0.read().act().bits() & T::FLAG != 0
+    }
+
+    /// Return the transfer's parts, without waiting for the destination
+    ///
+    /// Must only be called once [`is_active`] has returned `false`.
+    ///
+    /// [`is_active`]: #method.is_active
+    pub(crate) fn into_parts(
+        self,
+    ) -> (
+        Channel<T, init_state::Enabled<&'dma Handle>>,
+        &'static mut [u8],
+        D,
+    ) {
+        compiler_fence(Ordering::SeqCst);
+        (self.channel, self.source, self.dest)
+    }
+
     /// Waits for the transfer to finish
     pub fn wait(
         mut self,
@@ -491,11 +1084,13 @@ where
         //
         // This needs some further looking into.
 
-        while self.channel.active0.read().act().bits() & T::FLAG != 0 {}
+        while self.is_active() {
+            activity::notify();
+        }
 
         loop {
             match self.dest.wait() {
-                Err(nb::Error::WouldBlock) => continue,
+                Err(nb::Error::WouldBlock) => activity::notify(),
                 Ok(()) => break,
 
                 Err(nb::Error::Other(error)) => {
@@ -511,6 +1106,116 @@ where
     }
 }
 
+/// A DMA fill operation
+///
+/// Returned by [`Channel::fill`].
+///
+/// [`Channel::fill`]: struct.Channel.html#method.fill
+pub struct Fill<'dma, T, W>
+where
+    T: ChannelTrait,
+    W: Word,
+{
+    channel: Channel<T, init_state::Enabled<&'dma Handle>>,
+    dest: &'static mut [W],
+}
+
+impl<'dma, T, W> Fill<'dma, T, W>
+where
+    T: ChannelTrait,
+    W: Word,
+{
+    /// Indicates whether the buffer is still being filled
+    pub fn is_active(&self) -> bool {
+        self.channel.active0.read().act().bits() & T::FLAG != 0
+    }
+
+    /// Waits for the buffer to be filled
+    pub fn wait(
+        self,
+    ) -> (
+        Channel<T, init_state::Enabled<&'dma Handle>>,
+        &'static mut [W],
+    ) {
+        while self.is_active() {
+            activity::notify();
+        }
+
+        compiler_fence(Ordering::SeqCst);
+
+        (self.channel, self.dest)
+    }
+}
+
+/// A DMA transfer that is driven by a hardware trigger
+///
+/// Returned by [`Channel::read_on_trigger`] and
+/// [`Channel::write_on_trigger`].
+///
+/// [`Channel::read_on_trigger`]: struct.Channel.html#method.read_on_trigger
+/// [`Channel::write_on_trigger`]: struct.Channel.html#method.write_on_trigger
+pub struct TriggeredTransfer<'dma, T, W>
+where
+    T: ChannelTrait,
+    W: Word,
+{
+    channel: Channel<T, init_state::Enabled<&'dma Handle>>,
+    buffer: &'static mut [W],
+}
+
+impl<'dma, T, W> TriggeredTransfer<'dma, T, W>
+where
+    T: ChannelTrait,
+    W: Word,
+{
+    /// Indicates whether all values have been transferred
+    pub fn is_complete(&self) -> bool {
+        // Sound, as we're only reading from a register.
+        let dma = unsafe { &*pac::DMA0::ptr() };
+        dma.inta0.read().ia().bits() & T::FLAG != 0
+    }
+
+    /// Waits for all values to be transferred
+    pub fn wait(
+        self,
+    ) -> (
+        Channel<T, init_state::Enabled<&'dma Handle>>,
+        &'static mut [W],
+    ) {
+        while !self.is_complete() {
+            activity::notify();
+        }
+
+        self.finish()
+    }
+
+    /// Stops the transfer, before all values have been transferred
+    ///
+    /// Any triggers that occur after this has been called are ignored.
+    pub fn abort(
+        mut self,
+    ) -> (
+        Channel<T, init_state::Enabled<&'dma Handle>>,
+        &'static mut [W],
+    ) {
+        self.channel.abort();
+        self.finish()
+    }
+
+    fn finish(
+        mut self,
+    ) -> (
+        Channel<T, init_state::Enabled<&'dma Handle>>,
+        &'static mut [W],
+    ) {
+        self.channel.clear_interrupt_flags();
+
+        compiler_fence(Ordering::SeqCst);
+
+        (self.channel, self.buffer)
+    }
+}
+
 reg!(ACTIVE0, ACTIVE0, pac::DMA0, active0);
 reg!(ENABLESET0, ENABLESET0, pac::DMA0, enableset0);
 reg!(SETTRIG0, SETTRIG0, pac::DMA0, settrig0);
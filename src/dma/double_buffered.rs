@@ -0,0 +1,306 @@
+use core::sync::atomic::{compiler_fence, Ordering};
+
+use crate::{init_state, pac};
+
+use super::{Channel, ChannelDescriptor, ChannelTrait, Handle, Trigger, Word};
+
+/// Memory for a [`DoubleBuffered`] transfer
+///
+/// Consists of two halves of `N` values each, as well as the linked DMA
+/// descriptors that switch between them. Needs to be `'static`, for example
+/// by using `cortex_m::singleton!`.
+///
+/// [`DoubleBuffered`]: struct.DoubleBuffered.html
+#[repr(C)]
+pub struct DoubleBuffer<W, const N: usize> {
+    descriptors: [ChannelDescriptor; 2],
+    halves: [[W; N]; 2],
+}
+
+impl<W, const N: usize> DoubleBuffer<W, N>
+where
+    W: Word,
+{
+    /// Create a new double buffer, with all values set to `init`
+    pub const fn new(init: W) -> Self {
+        Self {
+            descriptors: [ChannelDescriptor::new(), ChannelDescriptor::new()],
+            halves: [[init; N]; 2],
+        }
+    }
+}
+
+impl<'dma, T> Channel<T, init_state::Enabled<&'dma Handle>>
+where
+    T: ChannelTrait,
+{
+    /// Continuously read from a fixed address into a double buffer
+    ///
+    /// Reads from `source` into the first half of `buffer`, then into the
+    /// second half, then into the first half again, and so on, until the
+    /// transfer is stopped. While the DMA controller fills one half, the
+    /// application can process the other. This is the usual way to stream
+    /// data from the ADC or a USART receiver without losing any of it.
+    ///
+    /// `source` is typically a peripheral's data register. If `trigger` is
+    /// `None`, a value is read whenever the peripheral that is connected to
+    /// this channel requests it (for example channel 0 for the USART0
+    /// receiver). Otherwise, values are read whenever the trigger occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `N` is 0 or larger than 1024, which is the maximum length
+    /// of a DMA transfer.
+    ///
+    /// # Safety
+    ///
+    /// `source` must be valid for reads of `W` for as long as the transfer is
+    /// running.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{
+    ///     cortex_m::singleton,
+    ///     dma::{self, DoubleBuffer},
+    ///     pac,
+    ///     Peripherals,
+    /// };
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// let descriptors = singleton!(: dma::DescriptorTable =
+    ///     dma::DescriptorTable::new()).unwrap();
+    /// let dma = p.DMA.split(descriptors);
+    /// let dma_handle = dma.handle.enable(&mut syscon.handle);
+    ///
+    /// let buffer = singleton!(: DoubleBuffer<u8, 64> =
+    ///     DoubleBuffer::new(0)).unwrap();
+    ///
+    /// // USART0 needs to be enabled. Its receiver is connected to DMA
+    /// // channel 0.
+    /// let rxdat = unsafe { &(*pac::USART0::ptr()).rxdat };
+    ///
+    /// let mut stream = unsafe {
+    ///     dma.channels.channel0.enable(&dma_handle).start_double_buffered(
+    ///         rxdat as *const _ as *const u8,
+    ///         None,
+    ///         buffer,
+    ///     )
+    /// };
+    ///
+    /// loop {
+    ///     match nb::block!(stream.poll()) {
+    ///         Ok(received) => {
+    ///             // process received data
+    ///         }
+    ///         Err(dma::Error::Overrun) => {
+    ///             // data was lost
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub unsafe fn start_double_buffered<W, const N: usize>(
+        mut self,
+        source: *const W,
+        trigger: Option<Trigger>,
+        buffer: &'static mut DoubleBuffer<W, N>,
+    ) -> DoubleBuffered<'dma, T, W, N>
+    where
+        W: Word,
+    {
+        assert!(N > 0 && N <= 1024);
+
+        compiler_fence(Ordering::SeqCst);
+
+        self.clear_interrupt_flags();
+        self.set_trigger(trigger.as_ref());
+
+        let source = source as *const u8;
+        let end = |half: &mut [W; N]| half.as_mut_ptr().add(N - 1) as *mut u8;
+        let config_a = xfercfg::<W>(N, false);
+        let config_b = xfercfg::<W>(N, true);
+
+        let (first, second) = buffer.descriptors.split_at_mut(1);
+        let (first, second) = (&mut first[0], &mut second[0]);
+
+        // The descriptors reference each other, so the DMA controller
+        // alternates between both halves forever. The first descriptor is
+        // only loaded from the descriptor table once. After that, it is
+        // reloaded from the copy in `DoubleBuffer`.
+        // See user manual, section 12.5.2.
+        first.config = config_a;
+        first.source_end = source;
+        first.dest_end = end(&mut buffer.halves[0]);
+        first.next_desc = second;
+
+        second.config = config_b;
+        second.source_end = source;
+        second.dest_end = end(&mut buffer.halves[1]);
+        second.next_desc = first;
+
+        self.descriptor.source_end = source;
+        self.descriptor.dest_end = first.dest_end;
+        self.descriptor.next_desc = second;
+
+        // Without a hardware trigger, the channel only runs, if its trigger is
+        // set. Since the trigger is never cleared, setting it once is enough.
+        let config = if trigger.is_none() {
+            config_a | SWTRIG
+        } else {
+            config_a
+        };
+        self.xfercfg.write(|w| w.bits(config));
+
+        self.enableset0.write(|w| w.ena().bits(T::FLAG));
+
+        DoubleBuffered {
+            channel: self,
+            buffer,
+            next: 0,
+        }
+    }
+}
+
+/// A continuous DMA transfer into a double buffer
+///
+/// Returned by [`Channel::start_double_buffered`].
+///
+/// [`Channel::start_double_buffered`]: struct.Channel.html#method.start_double_buffered
+pub struct DoubleBuffered<'dma, T, W, const N: usize>
+where
+    T: ChannelTrait,
+    W: Word,
+{
+    channel: Channel<T, init_state::Enabled<&'dma Handle>>,
+    buffer: &'static mut DoubleBuffer<W, N>,
+
+    /// The half that is expected to be filled next
+    next: usize,
+}
+
+impl<'dma, T, W, const N: usize> DoubleBuffered<'dma, T, W, N>
+where
+    T: ChannelTrait,
+    W: Word,
+{
+    /// Return the half that has just been filled
+    ///
+    /// Returns `nb::Error::WouldBlock`, if the DMA controller is still busy
+    /// filling the current half. Otherwise, returns the half that it has just
+    /// filled, while it moves on to the other one. The returned half must be
+    /// processed before the other half has been filled, as it will be
+    /// overwritten after that.
+    ///
+    /// Returns [`Error::Overrun`], if both halves have been filled since the
+    /// last call, which means data has been lost. The transfer keeps running
+    /// in that case.
+    ///
+    /// [`Error::Overrun`]: enum.Error.html#variant.Overrun
+    pub fn poll(&mut self) -> nb::Result<&[W], Error> {
+        // Sound, as we only write the bits that belong to this channel, and
+        // writing zeros to these registers has no effect.
+        let dma = unsafe { &*pac::DMA0::ptr() };
+
+        let a = dma.inta0.read().ia().bits() & T::FLAG != 0;
+        let b = dma.intb0.read().ib().bits() & T::FLAG != 0;
+
+        let (done, other) = if self.next == 0 { (a, b) } else { (b, a) };
+        if !done {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        if self.next == 0 {
+            dma.inta0.write(|w| unsafe { w.ia().bits(T::FLAG) });
+        } else {
+            dma.intb0.write(|w| unsafe { w.ib().bits(T::FLAG) });
+        }
+
+        if other {
+            // Both halves have been filled. The DMA controller is now filling
+            // the half we expected, so we stay in sync by leaving `next`
+            // unchanged.
+            self.channel.clear_interrupt_flags();
+            return Err(nb::Error::Other(Error::Overrun));
+        }
+
+        compiler_fence(Ordering::SeqCst);
+
+        let half = self.next;
+        self.next = 1 - self.next;
+
+        Ok(&self.buffer.halves[half])
+    }
+
+    /// Enable the DMA interrupt for this transfer
+    ///
+    /// Once enabled, the DMA interrupt fires whenever a half has been filled.
+    /// The interrupt handler needs to call [`poll`], which clears the
+    /// interrupt flags. The interrupt also needs to be enabled in the NVIC
+    /// (see [`Handle::enable_in_nvic`]).
+    ///
+    /// [`poll`]: #method.poll
+    /// [`Handle::enable_in_nvic`]: struct.Handle.html#method.enable_in_nvic
+    pub fn enable_interrupts(&mut self) {
+        // Sound, as we only write the bit that belongs to this channel, and
+        // writing zeros to this register has no effect.
+        let dma = unsafe { &*pac::DMA0::ptr() };
+        dma.intenset0.write(|w| unsafe { w.inten().bits(T::FLAG) });
+    }
+
+    /// Disable the DMA interrupt for this transfer
+    pub fn disable_interrupts(&mut self) {
+        // Sound, as we only write the bit that belongs to this channel, and
+        // writing zeros to this register has no effect.
+        let dma = unsafe { &*pac::DMA0::ptr() };
+        dma.intenclr0.write(|w| unsafe { w.clr().bits(T::FLAG) });
+    }
+
+    /// Stop the transfer
+    ///
+    /// Disables the DMA interrupt for this channel, and returns the channel
+    /// and the buffer.
+    pub fn stop(
+        mut self,
+    ) -> (
+        Channel<T, init_state::Enabled<&'dma Handle>>,
+        &'static mut DoubleBuffer<W, N>,
+    ) {
+        self.disable_interrupts();
+        self.channel.abort();
+        self.channel.clear_interrupt_flags();
+
+        compiler_fence(Ordering::SeqCst);
+
+        (self.channel, self.buffer)
+    }
+}
+
+/// An error that can occur during a DMA transfer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Data was lost, because it wasn't processed in time
+    Overrun,
+}
+
+const SWTRIG: u32 = 1 << 2;
+
+/// Compute the value of XFERCFG for one half of a double buffer
+///
+/// See user manual, section 12.6.18.
+fn xfercfg<W: Word>(len: usize, second: bool) -> u32 {
+    const CFGVALID: u32 = 1 << 0;
+    const RELOAD: u32 = 1 << 1;
+    const SETINTA: u32 = 1 << 4;
+    const SETINTB: u32 = 1 << 5;
+    const DSTINC_WIDTH_X_1: u32 = 1 << 14;
+
+    let interrupt = if second { SETINTB } else { SETINTA };
+    let width = u32::from(u8::from(W::WIDTH)) << 8;
+    let count = (len as u32 - 1) << 16;
+
+    // The source address doesn't increment, and the trigger is never cleared.
+    CFGVALID | RELOAD | interrupt | width | DSTINC_WIDTH_X_1 | count
+}
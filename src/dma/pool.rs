@@ -0,0 +1,229 @@
+use core::cell::{Cell, UnsafeCell};
+
+use cortex_m::interrupt::{self, Mutex};
+
+use super::Word;
+
+/// A pool of statically allocated DMA buffers
+///
+/// The DMA API requires buffers with a `'static` lifetime, since the DMA
+/// controller keeps accessing a buffer, even if the code that started a
+/// transfer forgets about it. `Pool` provides such buffers, without requiring
+/// any `unsafe` code, or `static mut` variables.
+///
+/// A `Pool` holds `N` buffers of `LEN` values of type `W` each, and lives in a
+/// `static`. Buffers are allocated using [`alloc`], which returns a
+/// `&'static mut [W; LEN]` that can be passed to any DMA API. Each buffer is
+/// aligned as required by `W`.
+///
+/// Once the DMA API has returned a buffer, it can be given back to the pool
+/// using [`free`]. If only a part of the buffer was used for a transfer, split
+/// it using `split_at_mut`, and give both parts back using [`free_parts`]. A
+/// buffer that is never given back, for example because the
+/// transfer using it was forgotten, is never handed out again. This means the
+/// DMA controller can't write into a buffer that has been allocated again,
+/// at the cost of that buffer being lost.
+///
+/// `N` must be 32 or less.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     cortex_m::singleton,
+///     dma::{self, Pool},
+///     usart, Peripherals,
+/// };
+///
+/// static POOL: Pool<u8, 4, 64> = Pool::new(0);
+///
+/// let mut p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+/// let mut swm = p.SWM.split();
+///
+/// #[cfg(feature = "82x")]
+/// let mut swm_handle = swm.handle;
+/// #[cfg(feature = "845")]
+/// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+///
+/// #[cfg(feature = "82x")]
+/// let clock_config = {
+///     syscon.uartfrg.set_clkdiv(6);
+///     syscon.uartfrg.set_frgmult(22);
+///     syscon.uartfrg.set_frgdiv(0xff);
+///     let uartfrg = syscon.uartfrg.share();
+///     usart::Clock::new(&uartfrg, 0, 16)
+/// };
+/// #[cfg(feature = "845")]
+/// let clock_config = usart::Clock::new_with_baudrate(115200);
+///
+/// let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+///     p.pins.pio0_0.into_swm_pin(),
+///     &mut swm_handle,
+/// );
+/// let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+///     p.pins.pio0_4.into_swm_pin(),
+///     &mut swm_handle,
+/// );
+///
+/// let serial = p.USART0.enable(
+///     &clock_config,
+///     &mut syscon.handle,
+///     u0_rxd,
+///     u0_txd,
+/// );
+///
+/// let descriptors = singleton!(: dma::DescriptorTable =
+///     dma::DescriptorTable::new()).unwrap();
+/// let dma = p.DMA.split(descriptors);
+/// let dma_handle = dma.handle.enable(&mut syscon.handle);
+///
+/// // Channel 1 is connected to the USART0 transmitter
+/// let channel = dma.channels.channel1.enable(&dma_handle);
+///
+/// let buffer = POOL.alloc().unwrap();
+/// let (message, rest) = buffer.split_at_mut(6);
+/// message.copy_from_slice(b"Hello\n");
+///
+/// let (channel, message, tx) = channel
+///     .start_transfer(message, serial.tx)
+///     .wait()
+///     .unwrap();
+///
+/// POOL.free_parts(message, rest);
+/// ```
+///
+/// [`alloc`]: #method.alloc
+/// [`free`]: #method.free
+/// [`free_parts`]: #method.free_parts
+pub struct Pool<W, const N: usize, const LEN: usize> {
+    buffers: UnsafeCell<[[W; LEN]; N]>,
+
+    /// Bit `i` is set, while buffer `i` is allocated
+    allocated: Mutex<Cell<u32>>,
+}
+
+impl<W, const N: usize, const LEN: usize> Pool<W, N, LEN>
+where
+    W: Word,
+{
+    /// Create a new pool, with all values set to `init`
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `N` is larger than 32.
+    pub const fn new(init: W) -> Self {
+        assert!(N <= 32);
+
+        Self {
+            buffers: UnsafeCell::new([[init; LEN]; N]),
+            allocated: Mutex::new(Cell::new(0)),
+        }
+    }
+
+    /// Allocate a buffer
+    ///
+    /// Returns `None`, if all buffers are allocated. The buffer still contains
+    /// the values it had when it was freed.
+    // Each buffer is only handed out once, until it's given back.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc(&'static self) -> Option<&'static mut [W; LEN]> {
+        let index = interrupt::free(|cs| {
+            let allocated = self.allocated.borrow(cs);
+
+            let index = (0..N).find(|i| allocated.get() & (1 << i) == 0)?;
+            allocated.set(allocated.get() | 1 << index);
+
+            Some(index)
+        })?;
+
+        // Sound, as the bit we just set prevents this buffer from being
+        // handed out again, until the reference is passed to `free`.
+        let buffers = self.buffers.get() as *mut [W; LEN];
+        Some(unsafe { &mut *buffers.add(index) })
+    }
+
+    /// Give a buffer back to the pool
+    ///
+    /// Only accepts whole buffers, as returned by [`alloc`]. Freeing a part of
+    /// a buffer, while another part is still in use, would allow the buffer to
+    /// be allocated again while it's still being accessed:
+    ///
+    /// ``` compile_fail
+    /// use lpc8xx_hal::dma::Pool;
+    ///
+    /// static POOL: Pool<u8, 1, 64> = Pool::new(0);
+    ///
+    /// let buffer = POOL.alloc().unwrap();
+    /// let (head, tail) = buffer.split_at_mut(1);
+    ///
+    /// POOL.free(head);
+    /// ```
+    ///
+    /// Use [`free_parts`] to give back a buffer that has been split.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `buffer` is not from this pool.
+    ///
+    /// [`alloc`]: #method.alloc
+    /// [`free_parts`]: #method.free_parts
+    pub fn free(&'static self, buffer: &'static mut [W; LEN]) {
+        self.release(buffer.as_ptr());
+    }
+
+    /// Give a buffer back to the pool, that has been split in two
+    ///
+    /// `head` and `tail` must be the result of calling `split_at_mut` on a
+    /// buffer returned by [`alloc`]. Since they're contiguous and cover the
+    /// whole buffer together, no other reference to any part of the buffer can
+    /// exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `head` and `tail` don't make up a whole buffer from this
+    /// pool.
+    ///
+    /// [`alloc`]: #method.alloc
+    pub fn free_parts(
+        &'static self,
+        head: &'static mut [W],
+        tail: &'static mut [W],
+    ) {
+        assert!(head.len() + tail.len() == LEN);
+        assert!(head.as_ptr().wrapping_add(head.len()) == tail.as_ptr());
+
+        self.release(head.as_ptr());
+    }
+
+    /// Mark the buffer that starts at `start` as no longer allocated
+    fn release(&self, start: *const W) {
+        let first = self.buffers.get() as *const [W; LEN] as usize;
+        let size = LEN * core::mem::size_of::<W>();
+
+        let offset = (start as usize).wrapping_sub(first);
+        assert!(
+            size > 0 && offset < size * N && offset / size * size == offset
+        );
+
+        let index = offset / size;
+        interrupt::free(|cs| {
+            let allocated = self.allocated.borrow(cs);
+            allocated.set(allocated.get() & !(1 << index));
+        });
+    }
+
+    /// The number of buffers that can still be allocated
+    pub fn available(&self) -> usize {
+        let allocated = interrupt::free(|cs| self.allocated.borrow(cs).get());
+        N - allocated.count_ones() as usize
+    }
+}
+
+// Sound, as access to each buffer is handed out only once, and access to the
+// allocation state is protected by a critical section.
+unsafe impl<W, const N: usize, const LEN: usize> Sync for Pool<W, N, LEN> where
+    W: Send
+{
+}
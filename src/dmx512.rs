@@ -0,0 +1,307 @@
+//! DMX512 transmitter
+//!
+//! DMX512 is the standard protocol for controlling stage lighting. A
+//! controller repeatedly sends frames of up to 512 slots (one byte each, for
+//! example the brightness of one dimmer channel) at 250 kbaud, with 8 data
+//! bits, no parity, and 2 stop bits. Each frame starts with a break, followed
+//! by the mark-after-break, and a start code, which is 0 for regular dimmer
+//! data.
+//!
+//! [`Dmx512`] generates the break using the USART transmitter, then sends the
+//! start code and all slots using DMA. The slots can be changed at any time.
+//! Changes are picked up by the next frame.
+//!
+//! The USART needs to be configured for 250 kbaud, and two stop bits (see
+//! [`USART::set_stop_bits`]). Break and mark-after-break are timed using a
+//! delay, which blocks for about 110 µs at the start of each frame. The rest of
+//! the frame, which takes about 22.7 ms, is sent in the background.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     cortex_m::singleton,
+//!     delay::Delay,
+//!     dma,
+//!     dmx512::{self, Dmx512},
+//!     usart::{self, StopBits},
+//!     CorePeripherals, Peripherals,
+//! };
+//!
+//! let cp = CorePeripherals::take().unwrap();
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//! let mut swm = p.SWM.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! // 12 MHz / (3 * 16) = 250 kbaud
+//! #[cfg(feature = "82x")]
+//! let clock_config = {
+//!     syscon.uartfrg.set_clkdiv(1);
+//!     syscon.uartfrg.set_frgmult(0);
+//!     syscon.uartfrg.set_frgdiv(0xff);
+//!     let uartfrg = syscon.uartfrg.share();
+//!     usart::Clock::new(&uartfrg, 2, 16)
+//! };
+//! #[cfg(feature = "845")]
+//! let clock_config = usart::Clock::new_with_baudrate(250_000);
+//!
+//! let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+//!     p.pins.pio0_4.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//!
+//! let mut serial =
+//!     p.USART0.enable_tx_only(&clock_config, &mut syscon.handle, u0_txd);
+//! serial.set_stop_bits(StopBits::Two);
+//!
+//! let descriptors = singleton!(: dma::DescriptorTable =
+//!     dma::DescriptorTable::new()).unwrap();
+//! let dma = p.DMA.split(descriptors);
+//! let dma_handle = dma.handle.enable(&mut syscon.handle);
+//!
+//! let buffer = singleton!(: [u8; dmx512::FRAME_LEN] =
+//!     [0; dmx512::FRAME_LEN]).unwrap();
+//!
+//! // USART0 TX is connected to DMA channel 1
+//! let mut dmx = Dmx512::new(
+//!     dma.channels.channel1.enable(&dma_handle),
+//!     serial.tx,
+//!     buffer,
+//!     Delay::new(cp.SYST),
+//! );
+//!
+//! let mut level = 0u8;
+//! loop {
+//!     // Fade in the dimmer on the first slot
+//!     dmx.slots_mut()[0] = level;
+//!     level = level.wrapping_add(1);
+//!
+//!     nb::block!(dmx.send_frame()).unwrap();
+//! }
+//! ```
+//!
+//! [`Dmx512`]: struct.Dmx512.html
+//! [`USART::set_stop_bits`]: ../usart/struct.USART.html#method.set_stop_bits
+
+use embedded_hal::blocking::delay::DelayUs;
+use void::{ResultVoidExt, Void};
+
+use crate::{
+    dma::{self, ChannelTrait, Transfer},
+    init_state,
+    usart::{Instance, Tx},
+};
+
+/// The number of slots in a DMX512 frame
+pub const SLOTS: usize = 512;
+
+/// The length of a complete frame, including the start code
+///
+/// This is the length of the buffer that needs to be passed to
+/// [`Dmx512::new`].
+///
+/// [`Dmx512::new`]: struct.Dmx512.html#method.new
+pub const FRAME_LEN: usize = SLOTS + 1;
+
+/// The start code of frames that carry dimmer data
+pub const START_CODE_DIMMER: u8 = 0x00;
+
+/// The length of the break, in microseconds
+///
+/// The standard requires at least 88 µs.
+pub const BREAK_US: u32 = 100;
+
+/// The length of the mark-after-break, in microseconds
+///
+/// The standard requires at least 8 µs.
+pub const MARK_AFTER_BREAK_US: u32 = 12;
+
+/// DMX512 transmitter
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct Dmx512<'dma, C, I, Pins, D>
+where
+    C: ChannelTrait,
+{
+    state: Option<State<'dma, C, I, Pins>>,
+    start_code: u8,
+    slots: [u8; SLOTS],
+    delay: D,
+}
+
+impl<'dma, C, I, Pins, D> Dmx512<'dma, C, I, Pins, D>
+where
+    C: ChannelTrait,
+    I: Instance,
+    D: DelayUs<u32>,
+{
+    /// Create a new instance of `Dmx512`
+    ///
+    /// `channel` must be the DMA channel that is connected to the TX DMA
+    /// request of the USART instance that `tx` belongs to. `buffer` holds the
+    /// frame while it is being sent. `delay` is used to time break and
+    /// mark-after-break.
+    ///
+    /// All slots start out as 0, and the start code as
+    /// [`START_CODE_DIMMER`]. No frame is sent until [`send_frame`] is called.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `buffer` is not [`FRAME_LEN`] bytes long.
+    ///
+    /// [`START_CODE_DIMMER`]: constant.START_CODE_DIMMER.html
+    /// [`send_frame`]: #method.send_frame
+    /// [`FRAME_LEN`]: constant.FRAME_LEN.html
+    pub fn new(
+        channel: dma::Channel<C, init_state::Enabled<&'dma dma::Handle>>,
+        tx: Tx<I, init_state::Enabled<Pins>>,
+        buffer: &'static mut [u8],
+        delay: D,
+    ) -> Self {
+        assert_eq!(buffer.len(), FRAME_LEN);
+
+        Self {
+            state: Some(State::Idle {
+                channel,
+                tx,
+                buffer,
+            }),
+            start_code: START_CODE_DIMMER,
+            slots: [0; SLOTS],
+            delay,
+        }
+    }
+
+    /// Change the start code of the following frames
+    pub fn set_start_code(&mut self, start_code: u8) {
+        self.start_code = start_code;
+    }
+
+    /// Access the slots
+    ///
+    /// Slot 1 of the DMX512 standard is at index 0. Changes are sent with the
+    /// next frame that is started.
+    pub fn slots(&self) -> &[u8; SLOTS] {
+        &self.slots
+    }
+
+    /// Mutably access the slots
+    ///
+    /// See [`slots`].
+    ///
+    /// [`slots`]: #method.slots
+    pub fn slots_mut(&mut self) -> &mut [u8; SLOTS] {
+        &mut self.slots
+    }
+
+    /// Indicates whether a frame is currently being sent
+    pub fn is_busy(&self) -> bool {
+        match &self.state {
+            Some(State::Busy(transfer)) => transfer.is_active(),
+            _ => false,
+        }
+    }
+
+    /// Start sending a frame
+    ///
+    /// Returns `WouldBlock`, while the previous frame is still being sent.
+    /// Otherwise, waits for the USART to finish the previous frame, sends
+    /// break and mark-after-break, and starts sending the current start code
+    /// and slots. Returns once the DMA transfer has been started.
+    ///
+    /// DMX512 receivers expect a new frame at least once per second. Call this
+    /// method regularly, even if the slots haven't changed.
+    pub fn send_frame(&mut self) -> nb::Result<(), Void> {
+        self.finish()?;
+
+        let (channel, mut tx, buffer) = match self.state.take() {
+            Some(State::Idle {
+                channel,
+                tx,
+                buffer,
+            }) => (channel, tx, buffer),
+            _ => unreachable!(),
+        };
+
+        // The transmitter must be idle, or the break would only start after
+        // the last stop bit has been sent, shortening it.
+        nb::block!(embedded_hal::serial::Write::flush(&mut tx)).void_unwrap();
+
+        tx.start_break();
+        self.delay.delay_us(BREAK_US);
+        tx.stop_break();
+        self.delay.delay_us(MARK_AFTER_BREAK_US);
+
+        buffer[0] = self.start_code;
+        buffer[1..].copy_from_slice(&self.slots);
+
+        self.state = Some(State::Busy(channel.start_transfer(buffer, tx)));
+
+        Ok(())
+    }
+
+    /// Wait until the current frame has been sent, then release all resources
+    #[allow(clippy::type_complexity)]
+    pub fn free(
+        mut self,
+    ) -> (
+        dma::Channel<C, init_state::Enabled<&'dma dma::Handle>>,
+        Tx<I, init_state::Enabled<Pins>>,
+        &'static mut [u8],
+        D,
+    ) {
+        nb::block!(self.finish()).void_unwrap();
+
+        match self.state {
+            Some(State::Idle {
+                channel,
+                tx,
+                buffer,
+            }) => (channel, tx, buffer, self.delay),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Return to the idle state, once the current transfer has finished
+    fn finish(&mut self) -> nb::Result<(), Void> {
+        let state = match self.state.take() {
+            Some(State::Busy(transfer)) if transfer.is_active() => {
+                self.state = Some(State::Busy(transfer));
+                return Err(nb::Error::WouldBlock);
+            }
+            Some(State::Busy(transfer)) => {
+                let (channel, buffer, tx) = transfer.into_parts();
+                State::Idle {
+                    channel,
+                    tx,
+                    buffer,
+                }
+            }
+            Some(state) => state,
+            None => unreachable!(),
+        };
+
+        self.state = Some(state);
+        Ok(())
+    }
+}
+
+enum State<'dma, C, I, Pins>
+where
+    C: ChannelTrait,
+{
+    Idle {
+        channel: dma::Channel<C, init_state::Enabled<&'dma dma::Handle>>,
+        tx: Tx<I, init_state::Enabled<Pins>>,
+        buffer: &'static mut [u8],
+    },
+    Busy(Transfer<'dma, C, Tx<I, init_state::Enabled<Pins>>>),
+}
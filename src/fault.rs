@@ -0,0 +1,168 @@
+//! Post-mortem reporting of hard faults
+//!
+//! This module is only available, if the `fault-report` feature is enabled.
+//! It provides a HardFault handler, which records the registers that were
+//! stacked when the fault occurred (the [`ExceptionFrame`]), then resets the
+//! microcontroller. The record is kept in a RAM region that isn't initialized
+//! on startup, so it survives the reset. After the reset, the application can
+//! retrieve the record using [`last_fault`], for example to log it or send
+//! it somewhere, and then remove it using [`clear_last_fault`].
+//!
+//! The program counter (`pc`) of the exception frame points to the
+//! instruction that caused the fault. It can be looked up in the firmware's
+//! disassembly, or using `addr2line`.
+//!
+//! Since this module defines the HardFault handler, the application can't
+//! define its own.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{fault, Peripherals};
+//!
+//! let p = Peripherals::take().unwrap();
+//! let mut syscon = p.SYSCON.split();
+//!
+//! if let Some(fault) = fault::last_fault(&syscon.handle) {
+//!     // The device has been reset by the HardFault handler. Report the
+//!     // fault, then remove the record.
+//!     let pc = fault.frame.pc;
+//!
+//!     fault::clear_last_fault();
+//! }
+//!
+//! syscon.handle.clear_reset_cause();
+//! ```
+//!
+//! [`ExceptionFrame`]: ../../cortex_m_rt/struct.ExceptionFrame.html
+//! [`last_fault`]: fn.last_fault.html
+//! [`clear_last_fault`]: fn.clear_last_fault.html
+
+use core::{cell::UnsafeCell, mem::MaybeUninit, ptr};
+
+use cortex_m_rt::ExceptionFrame;
+
+use crate::syscon::{self, ResetCause};
+
+/// A hard fault that occurred before the last reset
+#[derive(Clone, Copy, Debug)]
+pub struct Fault {
+    /// The registers that were stacked when the fault occurred
+    pub frame: ExceptionFrame,
+
+    /// The cause of the last reset
+    ///
+    /// Since the HardFault handler resets the microcontroller using a system
+    /// reset, `system` is expected to be set.
+    pub reset_cause: ResetCause,
+}
+
+/// Return the fault that was recorded before the last reset, if any
+///
+/// A recorded fault is returned, until it is removed using
+/// [`clear_last_fault`].
+///
+/// [`clear_last_fault`]: fn.clear_last_fault.html
+pub fn last_fault(syscon: &syscon::Handle) -> Option<Fault> {
+    // Sound, as the record is only accessed with interrupts disabled (in the
+    // HardFault handler) or on the thread that owns `syscon`. Memory that
+    // hasn't been written to since power-on contains an arbitrary bit pattern,
+    // which is valid for `Record`, and will be rejected by `is_valid`.
+    let record = unsafe { ptr::read_volatile(RECORD.get()) };
+
+    if !record.is_valid() {
+        return None;
+    }
+
+    let [r0, r1, r2, r3, r12, lr, pc, xpsr] = record.frame;
+
+    Some(Fault {
+        frame: ExceptionFrame {
+            r0,
+            r1,
+            r2,
+            r3,
+            r12,
+            lr,
+            pc,
+            xpsr,
+        },
+        reset_cause: syscon.reset_cause(),
+    })
+}
+
+/// Remove the recorded fault
+pub fn clear_last_fault() {
+    // Sound, for the same reasons given in `last_fault`.
+    unsafe {
+        ptr::write_volatile(&mut (*RECORD.get()).magic, 0);
+    }
+}
+
+#[link_section = ".uninit.lpc8xx_hal.fault"]
+static RECORD: RecordCell = RecordCell(UnsafeCell::new(MaybeUninit::uninit()));
+
+struct RecordCell(UnsafeCell<MaybeUninit<Record>>);
+
+impl RecordCell {
+    fn get(&self) -> *mut Record {
+        self.0.get() as *mut Record
+    }
+}
+
+// Sound, as all accesses go through raw pointers, and are synchronized as
+// described in `last_fault`.
+unsafe impl Sync for RecordCell {}
+
+/// Marks a valid record ("FLT!")
+const MAGIC: u32 = 0x464c_5421;
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct Record {
+    magic: u32,
+    frame: [u32; 8],
+    checksum: u32,
+}
+
+impl Record {
+    fn is_valid(&self) -> bool {
+        self.magic == MAGIC && self.checksum == checksum(&self.frame)
+    }
+}
+
+fn checksum(frame: &[u32; 8]) -> u32 {
+    frame
+        .iter()
+        .fold(MAGIC, |sum, &word| sum.rotate_left(5) ^ word)
+}
+
+// Defining an exception handler only makes sense on the target. This also
+// keeps the doc tests building on the host.
+#[cfg(target_os = "none")]
+mod handler {
+    use core::ptr;
+
+    use cortex_m::peripheral::SCB;
+    use cortex_m_rt::{exception, ExceptionFrame};
+
+    use super::{checksum, Record, MAGIC, RECORD};
+
+    #[exception]
+    fn HardFault(frame: &ExceptionFrame) -> ! {
+        let frame = [
+            frame.r0, frame.r1, frame.r2, frame.r3, frame.r12, frame.lr,
+            frame.pc, frame.xpsr,
+        ];
+        let record = Record {
+            magic: MAGIC,
+            frame,
+            checksum: checksum(&frame),
+        };
+
+        // Sound, as the HardFault handler can't be interrupted.
+        unsafe { ptr::write_volatile(RECORD.get(), record) };
+
+        SCB::sys_reset();
+    }
+}
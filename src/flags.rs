@@ -0,0 +1,98 @@
+//! Support for sets of flags from status registers
+//!
+//! Many peripherals report events through flags in a status register. Reading
+//! those one by one means multiple volatile reads, between which the flags can
+//! change. The types generated by `flags!` represent all flags of such a
+//! register, as read by a single volatile read.
+
+/// Define a type that represents a set of flags
+///
+/// Generates a `Copy` type that wraps the raw register value, with an
+/// associated constant for each flag, and the usual set operations.
+macro_rules! flags {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$flag_attr:meta])*
+                const $flag:ident = $bit:expr;
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+        pub struct $name(u32);
+
+        impl $name {
+            $(
+                $(#[$flag_attr])*
+                pub const $flag: Self = Self(1 << $bit);
+            )*
+
+            /// The empty set, which contains no flags
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            /// The set that contains all flags
+            pub const fn all() -> Self {
+                Self(0 $(| 1 << $bit)*)
+            }
+
+            /// Create a set from a raw register value
+            ///
+            /// Bits that don't correspond to a flag are ignored.
+            pub const fn from_bits_truncate(bits: u32) -> Self {
+                Self(bits & Self::all().0)
+            }
+
+            /// The flags as a raw register value
+            pub const fn bits(&self) -> u32 {
+                self.0
+            }
+
+            /// Indicates whether no flags are set
+            pub const fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+
+            /// Indicates whether all flags in `other` are set
+            pub const fn contains(&self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            /// Indicates whether any flag in `other` is set
+            pub const fn intersects(&self, other: Self) -> bool {
+                self.0 & other.0 != 0
+            }
+        }
+
+        impl core::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, other: Self) -> Self {
+                Self(self.0 | other.0)
+            }
+        }
+
+        impl core::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+        }
+
+        impl core::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, other: Self) -> Self {
+                Self(self.0 & other.0)
+            }
+        }
+
+        impl core::ops::BitAndAssign for $name {
+            fn bitand_assign(&mut self, other: Self) {
+                self.0 &= other.0;
+            }
+        }
+    };
+}
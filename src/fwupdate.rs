@@ -0,0 +1,425 @@
+//! Firmware updates through a staging region in flash
+//!
+//! This module is only available, if the `fwupdate` feature is enabled. It
+//! provides a reference implementation of a self-update mechanism, that
+//! receives a new firmware image over any serial interface, and installs it
+//! on the next reboot.
+//!
+//! # Flash layout
+//!
+//! The flash memory is divided into three regions, all of which consist of
+//! whole sectors (see [`iap::SECTOR_SIZE`]):
+//!
+//! 1. A small bootloader at the start of the flash, which calls
+//!    [`Updater::install`] on startup, then jumps to the application.
+//! 2. The application region, which contains the currently running firmware.
+//! 3. The staging region, which receives the new firmware image. Its last page
+//!    contains a marker that indicates that a valid image is waiting to be
+//!    installed.
+//!
+//! LPC800 microcontrollers can't swap or remap flash regions, so installing an
+//! image means copying it from the staging region into the application region.
+//! This has to be done by code that isn't located in the application region,
+//! which is why a separate bootloader is required.
+//!
+//! On LPC845, with its 64 KiB of flash, a typical layout would be a 4 KiB
+//! bootloader (sectors 0-3), and 30 KiB each for the application (sectors
+//! 4-33) and the staging region (sectors 34-63).
+//!
+//! # Protocol
+//!
+//! [`Updater::receive`] expects an 8-byte header, followed by the image. The
+//! header consists of the image length in bytes, and the CRC-32 (as used by
+//! Ethernet and zlib) of the image, both as little-endian 32-bit integers.
+//!
+//! Erasing and programming the flash blocks the receiver, so the sender has to
+//! wait for an acknowledgement after each step, similar to XMODEM:
+//!
+//! 1. The sender sends the header. The updater checks it, erases the staging
+//!    region, and responds with [`ACK`].
+//! 2. The sender sends the image in blocks of [`iap::PAGE_SIZE`] bytes (the
+//!    last block may be shorter). After each block, it waits for a response
+//!    before sending the next one. The updater writes the block to flash and
+//!    responds with [`ACK`].
+//! 3. After the last block, the updater verifies the CRC of the staged image
+//!    and writes the marker, before responding with [`ACK`].
+//!
+//! If anything goes wrong, the updater responds with [`NAK`] instead, and
+//! `receive` returns the error. The sender must then start over with a new
+//! header. Erasing the staging region can take a while, so the sender should
+//! allow at least a few seconds for each response.
+//!
+//! The image is only installed, if all of this succeeds.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::fwupdate::Updater;
+//!
+//! // 4 KiB bootloader, 30 KiB application, 30 KiB staging region
+//! let updater = Updater::new(4, 34, 30, 12_000_000).unwrap();
+//!
+//! // In the bootloader
+//! updater.install().unwrap();
+//! // now jump to the application
+//!
+//! // In the application, when an update is requested
+//! # let mut rx: lpc8xx_hal::usart::Rx<lpc8xx_hal::pac::USART0> =
+//! #     unsafe { lpc8xx_hal::usart::Rx::steal() };
+//! # let mut tx: lpc8xx_hal::usart::Tx<lpc8xx_hal::pac::USART0> =
+//! #     unsafe { lpc8xx_hal::usart::Tx::steal() };
+//! updater.receive(&mut rx, &mut tx).unwrap();
+//! lpc8xx_hal::cortex_m::peripheral::SCB::sys_reset();
+//! ```
+//!
+//! [`iap::SECTOR_SIZE`]: ../iap/constant.SECTOR_SIZE.html
+//! [`iap::PAGE_SIZE`]: ../iap/constant.PAGE_SIZE.html
+//! [`ACK`]: constant.ACK.html
+//! [`NAK`]: constant.NAK.html
+//! [`Updater::install`]: struct.Updater.html#method.install
+//! [`Updater::receive`]: struct.Updater.html#method.receive
+
+use core::slice;
+
+use embedded_hal::serial::{Read, Write};
+
+use crate::iap::{self, FLASH_SIZE, PAGE_SIZE, SECTOR_SIZE};
+
+/// Receives, stages, and installs firmware images
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct Updater {
+    application: u32,
+    staging: u32,
+    sectors: u32,
+    system_clock: u32,
+}
+
+impl Updater {
+    /// Create a new instance of `Updater`
+    ///
+    /// `application` and `staging` are the first sectors of the application
+    /// and staging regions, `sectors` the number of sectors in each region.
+    /// `system_clock` is the frequency of the system clock in Hz, which is
+    /// required for programming the flash.
+    ///
+    /// Returns an error, if the regions are empty, overlap, or extend beyond
+    /// the end of the flash (see [`iap::FLASH_SIZE`]).
+    ///
+    /// [`iap::FLASH_SIZE`]: ../iap/constant.FLASH_SIZE.html
+    pub fn new(
+        application: u32,
+        staging: u32,
+        sectors: u32,
+        system_clock: u32,
+    ) -> Result<Self, LayoutError> {
+        if sectors == 0 {
+            return Err(LayoutError::Empty);
+        }
+
+        let application_end = application
+            .checked_add(sectors)
+            .ok_or(LayoutError::OutOfBounds)?;
+        let staging_end = staging
+            .checked_add(sectors)
+            .ok_or(LayoutError::OutOfBounds)?;
+
+        if application_end.max(staging_end) as usize > FLASH_SIZE / SECTOR_SIZE
+        {
+            return Err(LayoutError::OutOfBounds);
+        }
+        if application < staging_end && staging < application_end {
+            return Err(LayoutError::Overlap);
+        }
+
+        Ok(Self {
+            application,
+            staging,
+            sectors,
+            system_clock,
+        })
+    }
+
+    /// The maximum size of an image in bytes
+    ///
+    /// This is the size of the staging region, minus the page that holds the
+    /// marker.
+    pub fn capacity(&self) -> usize {
+        self.sectors as usize * SECTOR_SIZE - PAGE_SIZE
+    }
+
+    /// Receive an image and write it to the staging region
+    ///
+    /// Reads the image from `rx`, and sends the responses required by the
+    /// protocol to `tx` (see [module documentation]). Blocks until the image
+    /// has been received completely. Returns `Ok`, if the image has been
+    /// staged successfully, and will be installed by [`install`] on the next
+    /// reboot.
+    ///
+    /// [module documentation]: index.html#protocol
+    /// [`install`]: #method.install
+    pub fn receive<R, W>(
+        &self,
+        rx: &mut R,
+        tx: &mut W,
+    ) -> Result<(), Error<R::Error, W::Error>>
+    where
+        R: Read<u8>,
+        W: Write<u8>,
+    {
+        match self.stage(rx, tx) {
+            Ok(()) => respond(tx, ACK).map_err(Error::Respond),
+            Err(err) => {
+                // The error that caused this is more useful than one that
+                // occurs while reporting it.
+                let _ = respond(tx, NAK);
+                Err(err)
+            }
+        }
+    }
+
+    /// Receive and stage an image, acknowledging all steps but the last
+    fn stage<R, W>(
+        &self,
+        rx: &mut R,
+        tx: &mut W,
+    ) -> Result<(), Error<R::Error, W::Error>>
+    where
+        R: Read<u8>,
+        W: Write<u8>,
+    {
+        let mut header = [0; 8];
+        for byte in &mut header {
+            *byte = nb::block!(rx.read()).map_err(Error::Serial)?;
+        }
+        let len =
+            u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let crc =
+            u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+        if len == 0 {
+            return Err(Error::Empty);
+        }
+        if len as usize > self.capacity() {
+            return Err(Error::TooLarge);
+        }
+
+        // This also removes any marker left over from a previous update.
+        self.erase(self.staging)?;
+        respond(tx, ACK).map_err(Error::Respond)?;
+
+        let first_page = self.first_page(self.staging);
+        let mut page = [0xff; PAGE_SIZE];
+        let mut received = 0;
+
+        while received < len as usize {
+            let i = received % PAGE_SIZE;
+            page[i] = nb::block!(rx.read()).map_err(Error::Serial)?;
+            received += 1;
+
+            if i == PAGE_SIZE - 1 || received == len as usize {
+                let n = (received - 1) / PAGE_SIZE;
+                iap::write_page(
+                    first_page + n as u32,
+                    &page,
+                    self.system_clock,
+                )?;
+                page = [0xff; PAGE_SIZE];
+
+                // The last block is acknowledged by `receive`, once the image
+                // has been verified.
+                if received < len as usize {
+                    respond(tx, ACK).map_err(Error::Respond)?;
+                }
+            }
+        }
+
+        if crc32(self.image(self.staging, len)) != crc {
+            return Err(Error::Crc);
+        }
+
+        let mut marker = [0xff; PAGE_SIZE];
+        marker[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+        marker[4..8].copy_from_slice(&len.to_le_bytes());
+        marker[8..12].copy_from_slice(&crc.to_le_bytes());
+        iap::write_page(self.marker_page(), &marker, self.system_clock)?;
+
+        Ok(())
+    }
+
+    /// Install the staged image, if there is one
+    ///
+    /// Copies the staged image to the application region, verifies it, then
+    /// removes the marker. Returns `Ok(true)`, if an image has been installed,
+    /// `Ok(false)`, if there was nothing to install.
+    ///
+    /// Must not be called from code located in the application region. If
+    /// this is interrupted (for example by a power loss), the staged image is
+    /// still marked as pending, and installation is restarted on the next
+    /// call.
+    pub fn install(&self) -> Result<bool, Error<void::Void, void::Void>> {
+        let (len, crc) = match self.pending() {
+            Some(pending) => pending,
+            None => return Ok(false),
+        };
+
+        if crc32(self.image(self.staging, len)) != crc {
+            // The staged image has been corrupted. Don't try again.
+            self.clear_marker()?;
+            return Err(Error::Crc);
+        }
+
+        self.erase(self.application)?;
+
+        let source = self.image(self.staging, len);
+        let first_page = self.first_page(self.application);
+        for (n, chunk) in source.chunks(PAGE_SIZE).enumerate() {
+            let mut page = [0xff; PAGE_SIZE];
+            page[..chunk.len()].copy_from_slice(chunk);
+            iap::write_page(first_page + n as u32, &page, self.system_clock)?;
+        }
+
+        if crc32(self.image(self.application, len)) != crc {
+            return Err(Error::Crc);
+        }
+
+        self.clear_marker()?;
+
+        Ok(true)
+    }
+
+    /// Returns length and CRC of the staged image, if one is pending
+    fn pending(&self) -> Option<(u32, u32)> {
+        let marker = self.image(self.staging, self.capacity() as u32 + 12);
+        let marker = &marker[self.capacity()..];
+
+        let word = |i: usize| {
+            u32::from_le_bytes([
+                marker[i],
+                marker[i + 1],
+                marker[i + 2],
+                marker[i + 3],
+            ])
+        };
+
+        let (magic, len, crc) = (word(0), word(4), word(8));
+        if magic != MAGIC || len == 0 || len as usize > self.capacity() {
+            return None;
+        }
+
+        Some((len, crc))
+    }
+
+    fn clear_marker(&self) -> Result<(), iap::Error> {
+        let page = self.marker_page();
+        iap::erase_pages(page, page, self.system_clock)
+    }
+
+    fn erase(&self, first: u32) -> Result<(), iap::Error> {
+        iap::erase_sectors(first, first + self.sectors - 1, self.system_clock)
+    }
+
+    fn first_page(&self, sector: u32) -> u32 {
+        sector * (SECTOR_SIZE / PAGE_SIZE) as u32
+    }
+
+    fn marker_page(&self) -> u32 {
+        self.first_page(self.staging + self.sectors) - 1
+    }
+
+    fn image(&self, sector: u32, len: u32) -> &'static [u8] {
+        let address = sector as usize * SECTOR_SIZE;
+
+        // Sound, as the flash is always mapped, and we're only reading from
+        // it between write operations, all of which require `&self`.
+        unsafe { slice::from_raw_parts(address as *const u8, len as usize) }
+    }
+}
+
+/// An error that can occur while updating the firmware
+///
+/// `E` is the error type of the serial receiver, `W` that of the transmitter.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error<E, W> {
+    /// An error occurred while receiving the image
+    Serial(E),
+
+    /// An error occurred while sending a response
+    Respond(W),
+
+    /// An error occurred while programming the flash
+    Iap(iap::Error),
+
+    /// The image is larger than the staging region
+    TooLarge,
+
+    /// The header announced an image with a length of zero
+    Empty,
+
+    /// The CRC of the image doesn't match the one from the header
+    Crc,
+}
+
+/// An error that can occur when creating an [`Updater`]
+///
+/// [`Updater`]: struct.Updater.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LayoutError {
+    /// The regions have a size of zero sectors
+    Empty,
+
+    /// The application and staging regions overlap
+    Overlap,
+
+    /// A region extends beyond the end of the flash
+    OutOfBounds,
+}
+
+impl<E, W> From<iap::Error> for Error<E, W> {
+    fn from(error: iap::Error) -> Self {
+        Error::Iap(error)
+    }
+}
+
+/// The response that acknowledges a step of the protocol
+///
+/// Please refer to the [module documentation] for details.
+///
+/// [module documentation]: index.html#protocol
+pub const ACK: u8 = 0x06;
+
+/// The response that reports an error, and aborts the transfer
+///
+/// Please refer to the [module documentation] for details.
+///
+/// [module documentation]: index.html#protocol
+pub const NAK: u8 = 0x15;
+
+/// Send a response, and wait until it has been sent
+fn respond<W>(tx: &mut W, response: u8) -> Result<(), W::Error>
+where
+    W: Write<u8>,
+{
+    nb::block!(tx.write(response))?;
+    nb::block!(tx.flush())
+}
+
+/// Marks a staged image as pending ("FWUP")
+const MAGIC: u32 = 0x4657_5550;
+
+/// CRC-32, as used by Ethernet and zlib
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff;
+
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (!(crc & 1)).wrapping_add(1);
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
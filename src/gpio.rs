@@ -34,19 +34,24 @@
 //! );
 //! ```
 //!
+//! Multiple pins on the same port can be grouped into a [`PinBus`], to read or
+//! write them as a single value. Pins whose signals are active-low can be
+//! wrapped in [`ActiveLow`], to invert their logic.
+//!
 //! Please refer to the [examples in the repository] for more example code.
 //!
 //! [`GPIO`]: struct.GPIO.html
+//! [`PinBus`]: struct.PinBus.html
+//! [`ActiveLow`]: struct.ActiveLow.html
 //! [`Pin`]: ../pins/struct.Pin.html
 //! [`GpioPin`]: struct.GpioPin.html
 //! [examples in the repository]: https://github.com/lpc-rs/lpc8xx-hal/tree/master/examples
 
-use core::marker::PhantomData;
+use core::{convert::Infallible, marker::PhantomData, ptr};
 
 use embedded_hal::digital::v2::{
-    toggleable, InputPin, OutputPin, StatefulOutputPin,
+    InputPin, OutputPin, StatefulOutputPin, ToggleableOutputPin,
 };
-use void::Void;
 
 use crate::{
     init_state, pac,
@@ -55,14 +60,25 @@ use crate::{
 };
 
 #[cfg(feature = "845")]
-use crate::pac::gpio::{CLR, DIRCLR, DIRSET, PIN, SET};
+use crate::pac::gpio::{CLR, DIRCLR, DIRSET, MASK, MPIN, PIN, SET};
 #[cfg(feature = "82x")]
 use crate::pac::gpio::{
-    CLR0 as CLR, DIRCLR0 as DIRCLR, DIRSET0 as DIRSET, PIN0 as PIN, SET0 as SET,
+    CLR0 as CLR, DIRCLR0 as DIRCLR, DIRSET0 as DIRSET, MASK0 as MASK,
+    MPIN0 as MPIN, PIN0 as PIN, SET0 as SET,
 };
 
 use self::direction::Direction;
 
+mod active_low;
+mod level;
+mod pin_bus;
+
+pub use self::{
+    active_low::ActiveLow,
+    level::Level,
+    pin_bus::{BusPins, PinBus},
+};
+
 /// Interface to the GPIO peripheral
 ///
 /// Controls the GPIO peripheral. Can be used to enable, disable, or free the
@@ -190,6 +206,12 @@ impl GPIO<init_state::Enabled> {
 ///   - [`embedded_hal::digital::v2::OutputPin`] for setting the pin state
 ///   - [`embedded_hal::digital::v2::StatefulOutputPin`] for reading the pin output state
 ///   - [`embedded_hal::digital::v2::ToggleableOutputPin`] for toggling the pin state
+///   - [`embedded_hal::digital::v2::InputPin`][InputPin for output] for reading
+///     the actual level of the pin
+///
+/// None of these operations can fail, so all implementations use
+/// [`Infallible`] as their error type. `OutputPin::set_state` accepts a
+/// [`PinState`], which can be converted to and from [`Level`].
 ///
 /// [`Pin::into_input_pin`]: ../pins/struct.Pin.html#method.into_input_pin
 /// [`Pin::into_output_pin`]: ../pins/struct.Pin.html#method.into_output_pin
@@ -197,7 +219,12 @@ impl GPIO<init_state::Enabled> {
 /// [`embedded_hal::digital::v2::OutputPin`]: #impl-OutputPin
 /// [`embedded_hal::digital::v2::StatefulOutputPin`]: #impl-StatefulOutputPin
 /// [`embedded_hal::digital::v2::ToggleableOutputPin`]: #impl-ToggleableOutputPin
+/// [InputPin for output]: #impl-InputPin-1
+/// [`Infallible`]: https://doc.rust-lang.org/core/convert/enum.Infallible.html
+/// [`PinState`]: ../../embedded_hal/digital/v2/enum.PinState.html
+/// [`Level`]: enum.Level.html
 pub struct GpioPin<T, D> {
+    ty: T,
     token: pins::Token<T, init_state::Enabled>,
     _direction: D,
 }
@@ -208,6 +235,7 @@ where
     D: Direction,
 {
     pub(crate) fn new(
+        ty: T,
         token: Token<T, init_state::Enabled>,
         arg: D::SwitchArg,
     ) -> Self {
@@ -221,10 +249,115 @@ where
         let direction = D::switch::<T>(&registers, arg);
 
         Self {
+            ty,
             token,
             _direction: direction,
         }
     }
+
+    /// Transition pin back to the unused state
+    ///
+    /// Switches the pin to input mode, so it no longer drives its output, then
+    /// consumes this `GpioPin` instance and returns the [`Pin`], in the unused
+    /// state, together with the GPIO token that was used to create this
+    /// instance. This makes it possible to use the pin for something else.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{
+    ///     Peripherals,
+    ///     gpio,
+    /// };
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let gpio = p.GPIO;
+    /// #[cfg(feature = "845")]
+    /// let gpio = p.GPIO.enable(&mut syscon.handle);
+    ///
+    /// let pin = p.pins.pio0_12.into_output_pin(
+    ///     gpio.tokens.pio0_12,
+    ///     gpio::Level::Low,
+    /// );
+    ///
+    /// // Get the pin and token back, to use them for something else
+    /// let (pin, token) = pin.into_unused_pin();
+    /// ```
+    ///
+    /// [`Pin`]: ../pins/struct.Pin.html
+    pub fn into_unused_pin(
+        self,
+    ) -> (
+        pins::Pin<T, pins::state::Unused>,
+        Token<T, init_state::Enabled>,
+    ) {
+        // This is sound, as we only do a stateless write to a bit that no other
+        // `GpioPin` instance writes to.
+        let gpio = unsafe { &*pac::GPIO::ptr() };
+        let registers = Registers::new(gpio);
+
+        direction::Input::switch::<T>(&registers, ());
+
+        let pin = pins::Pin {
+            ty: self.ty,
+            _state: pins::state::Unused,
+        };
+
+        (pin, self.token)
+    }
+
+    /// Return the port of the pin
+    ///
+    /// See [`Pin::port`].
+    ///
+    /// [`Pin::port`]: ../pins/struct.Pin.html#method.port
+    pub fn port(&self) -> u8 {
+        T::PORT as u8
+    }
+
+    /// Return the number of the pin within its port
+    ///
+    /// See [`Pin::number`].
+    ///
+    /// [`Pin::number`]: ../pins/struct.Pin.html#method.number
+    pub fn number(&self) -> u8 {
+        T::ID
+    }
+
+    /// Return the [`PinId`] that identifies this pin at runtime
+    ///
+    /// See [`Pin::id`].
+    ///
+    /// [`PinId`]: ../pins/struct.PinId.html
+    /// [`Pin::id`]: ../pins/struct.Pin.html#method.id
+    pub fn id(&self) -> pins::PinId {
+        pins::PinId::of::<T>()
+    }
+
+    /// Read the pin's IOCON configuration
+    ///
+    /// Enables the IOCON clock, if it isn't already enabled. The IOCON
+    /// configuration is kept when a pin is converted into or out of a
+    /// `GpioPin`. See [`Pin`] for more information.
+    ///
+    /// [`Pin`]: ../pins/struct.Pin.html#iocon-configuration
+    pub fn iocon(&self, syscon: &mut syscon::Handle) -> pins::IoconConfig {
+        pins::iocon::read::<T>(syscon)
+    }
+
+    /// Return the pin's IOCON configuration to its reset value
+    ///
+    /// Enables the IOCON clock, if it isn't already enabled. See [`Pin`] for
+    /// more information.
+    ///
+    /// [`Pin`]: ../pins/struct.Pin.html#iocon-configuration
+    pub fn reset_iocon(&mut self, syscon: &mut syscon::Handle) {
+        pins::iocon::reset::<T>(syscon)
+    }
 }
 
 impl<T> GpioPin<T, direction::Input>
@@ -276,6 +409,7 @@ where
         let direction = direction::Output::switch::<T>(&registers, initial);
 
         GpioPin {
+            ty: self.ty,
             token: self.token,
             _direction: direction,
         }
@@ -329,17 +463,83 @@ where
         let direction = direction::Input::switch::<T>(&registers, ());
 
         GpioPin {
+            ty: self.ty,
             token: self.token,
             _direction: direction,
         }
     }
+
+    /// The address of the register that sets this pin's output to HIGH
+    ///
+    /// Writing [`MASK`] to this address sets the pin, without affecting any
+    /// other pins. Can be used for direct register access, for example from a
+    /// DMA transfer or assembly code.
+    ///
+    /// [`MASK`]: #associatedconstant.MASK
+    pub const SET_ADDRESS: usize = GPIO_BASE + SET_OFFSET + T::PORT * 4;
+
+    /// The address of the register that sets this pin's output to LOW
+    ///
+    /// Works like [`SET_ADDRESS`].
+    ///
+    /// [`SET_ADDRESS`]: #associatedconstant.SET_ADDRESS
+    pub const CLR_ADDRESS: usize = GPIO_BASE + CLR_OFFSET + T::PORT * 4;
+
+    /// The address of the register that toggles this pin's output
+    ///
+    /// Works like [`SET_ADDRESS`].
+    ///
+    /// [`SET_ADDRESS`]: #associatedconstant.SET_ADDRESS
+    pub const NOT_ADDRESS: usize = GPIO_BASE + NOT_OFFSET + T::PORT * 4;
+
+    /// This pin's bit within its port's registers
+    pub const MASK: u32 = T::MASK;
+
+    /// Set the pin output to HIGH, as fast as possible
+    ///
+    /// Compiles down to a single store to [`SET_ADDRESS`], which makes it
+    /// suitable for bit-banging. Does the same as [`OutputPin::set_high`],
+    /// without the `Result`.
+    ///
+    /// [`SET_ADDRESS`]: #associatedconstant.SET_ADDRESS
+    /// [`OutputPin::set_high`]: #method.set_high
+    #[inline(always)]
+    pub fn set_high_fast(&mut self) {
+        // This is sound, as we only do a stateless write to a bit that no
+        // other `GpioPin` instance writes to.
+        unsafe { ptr::write_volatile(Self::SET_ADDRESS as *mut u32, T::MASK) }
+    }
+
+    /// Set the pin output to LOW, as fast as possible
+    ///
+    /// Works like [`set_high_fast`].
+    ///
+    /// [`set_high_fast`]: #method.set_high_fast
+    #[inline(always)]
+    pub fn set_low_fast(&mut self) {
+        // This is sound, as we only do a stateless write to a bit that no
+        // other `GpioPin` instance writes to.
+        unsafe { ptr::write_volatile(Self::CLR_ADDRESS as *mut u32, T::MASK) }
+    }
+
+    /// Toggle the pin output, as fast as possible
+    ///
+    /// Works like [`set_high_fast`].
+    ///
+    /// [`set_high_fast`]: #method.set_high_fast
+    #[inline(always)]
+    pub fn toggle_fast(&mut self) {
+        // This is sound, as we only do a stateless write to a bit that no
+        // other `GpioPin` instance writes to.
+        unsafe { ptr::write_volatile(Self::NOT_ADDRESS as *mut u32, T::MASK) }
+    }
 }
 
 impl<T> InputPin for GpioPin<T, direction::Input>
 where
     T: pins::Trait,
 {
-    type Error = Void;
+    type Error = Infallible;
 
     /// Indicates wether the pin input is HIGH
     ///
@@ -386,7 +586,7 @@ impl<T> OutputPin for GpioPin<T, direction::Output>
 where
     T: pins::Trait,
 {
-    type Error = Void;
+    type Error = Infallible;
 
     /// Set the pin output to HIGH
     ///
@@ -399,14 +599,9 @@ where
     ///
     /// [`into_gpio_pin`]: #method.into_gpio_pin
     /// [`into_output`]: #method.into_output
+    #[inline(always)]
     fn set_high(&mut self) -> Result<(), Self::Error> {
-        // This is sound, as we only do a stateless write to a bit that no other
-        // `GpioPin` instance writes to.
-        let gpio = unsafe { &*pac::GPIO::ptr() };
-        let registers = Registers::new(gpio);
-
-        set_high::<T>(&registers);
-
+        self.set_high_fast();
         Ok(())
     }
 
@@ -421,15 +616,38 @@ where
     ///
     /// [`into_gpio_pin`]: #method.into_gpio_pin
     /// [`into_output`]: #method.into_output
+    #[inline(always)]
     fn set_low(&mut self) -> Result<(), Self::Error> {
-        // This is sound, as we only do a stateless write to a bit that no other
-        // `GpioPin` instance writes to.
+        self.set_low_fast();
+        Ok(())
+    }
+}
+
+impl<T> InputPin for GpioPin<T, direction::Output>
+where
+    T: pins::Trait,
+{
+    type Error = Infallible;
+
+    /// Indicates whether the pin is actually HIGH
+    ///
+    /// Reads the level of the pin, which is usually the output level. If the
+    /// pin has been configured as open-drain in IOCON, it can also be pulled
+    /// low externally while the output is high. This makes it possible to use
+    /// the pin for bidirectional signals, like the lines of an I2C bus.
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        // This is sound, as we only read from a stateless register.
         let gpio = unsafe { &*pac::GPIO::ptr() };
         let registers = Registers::new(gpio);
 
-        set_low::<T>(&registers);
+        Ok(registers.pin[T::PORT].read().port().bits() & T::MASK == T::MASK)
+    }
 
-        Ok(())
+    /// Indicates whether the pin is actually LOW
+    ///
+    /// See `is_high` for details.
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.is_high().map(|high| !high)
     }
 }
 
@@ -478,20 +696,30 @@ where
     }
 }
 
-impl<T> toggleable::Default for GpioPin<T, direction::Output> where
-    T: pins::Trait
+impl<T> ToggleableOutputPin for GpioPin<T, direction::Output>
+where
+    T: pins::Trait,
 {
+    type Error = Infallible;
+
+    /// Toggle the pin output
+    ///
+    /// Uses the port's toggle register, so the pin's current state doesn't
+    /// need to be read first.
+    #[inline(always)]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.toggle_fast();
+        Ok(())
+    }
 }
 
-/// The voltage level of a pin
-#[derive(Debug)]
-pub enum Level {
-    /// High voltage
-    High,
+/// The address of the GPIO register block, on all supported targets
+const GPIO_BASE: usize = 0xa000_0000;
 
-    /// Low voltage
-    Low,
-}
+// The offsets of the port registers within the GPIO register block
+const SET_OFFSET: usize = 0x2200;
+const CLR_OFFSET: usize = 0x2280;
+const NOT_OFFSET: usize = 0x2300;
 
 fn set_high<T: pins::Trait>(registers: &Registers) {
     registers.set[T::PORT].write(|w| unsafe { w.setp().bits(T::MASK) });
@@ -508,6 +736,8 @@ pub struct Registers<'gpio> {
     pin: &'gpio [PIN],
     set: &'gpio [SET],
     clr: &'gpio [CLR],
+    mask: &'gpio [MASK],
+    mpin: &'gpio [MPIN],
 }
 
 impl<'gpio> Registers<'gpio> {
@@ -517,6 +747,8 @@ impl<'gpio> Registers<'gpio> {
     /// caller, accessing all registers is still completely race-free, as long
     /// as the following rules are upheld:
     /// - Never write to `pin`, only use it for reading.
+    /// - Only access `mask` and `mpin` within a critical section, and don't
+    ///   rely on the contents of `mask` from one critical section to the next.
     /// - For all other registers, only set bits that no other callers are
     ///   setting.
     fn new(gpio: &'gpio pac::gpio::RegisterBlock) -> Self {
@@ -530,6 +762,8 @@ impl<'gpio> Registers<'gpio> {
                 pin: slice::from_ref(&gpio.pin0),
                 set: slice::from_ref(&gpio.set0),
                 clr: slice::from_ref(&gpio.clr0),
+                mask: slice::from_ref(&gpio.mask0),
+                mpin: slice::from_ref(&gpio.mpin0),
             }
         }
 
@@ -540,6 +774,8 @@ impl<'gpio> Registers<'gpio> {
             pin: &gpio.pin,
             set: &gpio.set,
             clr: &gpio.clr,
+            mask: &gpio.mask,
+            mpin: &gpio.mpin,
         }
     }
 }
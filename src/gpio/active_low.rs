@@ -0,0 +1,125 @@
+use embedded_hal::digital::v2::{
+    InputPin, OutputPin, StatefulOutputPin, ToggleableOutputPin,
+};
+
+/// Inverts the logic of a digital input or output pin
+///
+/// Many signals are active-low, like chip selects, reset lines, or LEDs that
+/// are connected between the supply voltage and a pin. Wrapping such a pin in
+/// `ActiveLow` makes `set_high` assert the signal (by driving the pin low), and
+/// `is_high` return `true`, if the signal is asserted (the pin is low). This
+/// way, driver code can work with the logical state of a signal, without having
+/// to know about its polarity.
+///
+/// `ActiveLow` works with any pin that implements the `embedded-hal` digital
+/// traits, not just [`GpioPin`]. Please note that only the logic is inverted.
+/// The initial level that is passed to [`Pin::into_output_pin`] is still the
+/// physical level of the pin.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     gpio::{ActiveLow, Level},
+///     prelude::*,
+///     Peripherals,
+/// };
+///
+/// let p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+///
+/// #[cfg(feature = "82x")]
+/// let gpio = p.GPIO;
+/// #[cfg(feature = "845")]
+/// let gpio = p.GPIO.enable(&mut syscon.handle);
+///
+/// // An LED between VDD and the pin, which starts out being off
+/// let mut led = ActiveLow::new(
+///     p.pins
+///         .pio0_12
+///         .into_output_pin(gpio.tokens.pio0_12, Level::High),
+/// );
+///
+/// // Turns on the LED, by driving the pin low
+/// led.set_high().unwrap();
+/// ```
+///
+/// [`GpioPin`]: struct.GpioPin.html
+/// [`Pin::into_output_pin`]: ../pins/struct.Pin.html#method.into_output_pin
+pub struct ActiveLow<P>(P);
+
+impl<P> ActiveLow<P> {
+    /// Wrap a pin, to invert its logic
+    pub fn new(pin: P) -> Self {
+        Self(pin)
+    }
+
+    /// Return the wrapped pin
+    pub fn free(self) -> P {
+        self.0
+    }
+}
+
+impl<P> InputPin for ActiveLow<P>
+where
+    P: InputPin,
+{
+    type Error = P::Error;
+
+    /// Indicates whether the signal is asserted (the pin is LOW)
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        self.0.is_low()
+    }
+
+    /// Indicates whether the signal is deasserted (the pin is HIGH)
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.0.is_high()
+    }
+}
+
+impl<P> OutputPin for ActiveLow<P>
+where
+    P: OutputPin,
+{
+    type Error = P::Error;
+
+    /// Assert the signal, by setting the pin to LOW
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.set_low()
+    }
+
+    /// Deassert the signal, by setting the pin to HIGH
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.set_high()
+    }
+}
+
+impl<P> StatefulOutputPin for ActiveLow<P>
+where
+    P: StatefulOutputPin,
+{
+    /// Indicates whether the signal is asserted (the pin is set to LOW)
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        self.0.is_set_low()
+    }
+
+    /// Indicates whether the signal is deasserted (the pin is set to HIGH)
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        self.0.is_set_high()
+    }
+}
+
+impl<P> ToggleableOutputPin for ActiveLow<P>
+where
+    P: ToggleableOutputPin,
+{
+    type Error = P::Error;
+
+    /// Toggle the pin
+    ///
+    /// Toggling is the same, regardless of the polarity.
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.0.toggle()
+    }
+}
@@ -0,0 +1,29 @@
+use embedded_hal::digital::v2::PinState;
+
+/// The voltage level of a pin
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Level {
+    /// High voltage
+    High,
+
+    /// Low voltage
+    Low,
+}
+
+impl From<PinState> for Level {
+    fn from(state: PinState) -> Self {
+        match state {
+            PinState::High => Level::High,
+            PinState::Low => Level::Low,
+        }
+    }
+}
+
+impl From<Level> for PinState {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::High => PinState::High,
+            Level::Low => PinState::Low,
+        }
+    }
+}
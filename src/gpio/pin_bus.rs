@@ -0,0 +1,191 @@
+use cortex_m::interrupt;
+
+use crate::{pac, pins};
+
+use super::{
+    direction::{Direction, Input, Output},
+    GpioPin, Registers,
+};
+
+/// A group of GPIO pins that are read or written as a single value
+///
+/// Combines up to 8 GPIO pins of the same direction into a bus, whose value
+/// can be written or read at once. Bit 0 of the value corresponds to the first
+/// pin, bit 1 to the second pin, and so on. The pins don't need to be adjacent
+/// or in order, but all of them need to be on the same port.
+///
+/// Writing uses the port's MASK and MPIN registers, so all pins of an output
+/// bus change their level at the same time. This is useful for parallel
+/// interfaces, like the data lines of an HD44780 character LCD. An input bus
+/// can be used to read a set of DIP switches, for example.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     gpio::{self, PinBus},
+///     Peripherals,
+/// };
+///
+/// let p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+///
+/// #[cfg(feature = "82x")]
+/// let gpio = p.GPIO;
+/// #[cfg(feature = "845")]
+/// let gpio = p.GPIO.enable(&mut syscon.handle);
+///
+/// // Data lines D4-D7 of an LCD in 4-bit mode
+/// let d4 = p
+///     .pins
+///     .pio0_12
+///     .into_output_pin(gpio.tokens.pio0_12, gpio::Level::Low);
+/// let d5 = p
+///     .pins
+///     .pio0_13
+///     .into_output_pin(gpio.tokens.pio0_13, gpio::Level::Low);
+/// let d6 = p
+///     .pins
+///     .pio0_14
+///     .into_output_pin(gpio.tokens.pio0_14, gpio::Level::Low);
+/// let d7 = p
+///     .pins
+///     .pio0_15
+///     .into_output_pin(gpio.tokens.pio0_15, gpio::Level::Low);
+///
+/// let mut data = PinBus::new((d4, d5, d6, d7));
+///
+/// // Sets D4 and D5 high, D6 and D7 low
+/// data.write(0b0011);
+/// ```
+pub struct PinBus<P, const N: usize> {
+    pins: P,
+    port: usize,
+    mask: u32,
+}
+
+impl<P, const N: usize> PinBus<P, N>
+where
+    P: BusPins<N>,
+{
+    /// Create a new bus from a tuple of GPIO pins
+    ///
+    /// All pins must have the same direction. The first pin of the tuple
+    /// corresponds to bit 0 of the bus value.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the pins are not all on the same port.
+    pub fn new(pins: P) -> Self {
+        let port = P::PORTS[0];
+        assert!(P::PORTS.iter().all(|&p| p == port));
+
+        let mask = P::MASKS.iter().fold(0, |mask, &pin| mask | pin);
+
+        Self { pins, port, mask }
+    }
+
+    /// Release the pins
+    pub fn free(self) -> P {
+        self.pins
+    }
+}
+
+impl<P, const N: usize> PinBus<P, N>
+where
+    P: BusPins<N, Direction = Output>,
+{
+    /// Set the output levels of all pins at once
+    ///
+    /// Bit `i` of `value` sets the level of the `i`th pin. Bits that don't
+    /// correspond to a pin are ignored.
+    pub fn write(&mut self, value: u32) {
+        let mut bits = 0;
+        for (i, &pin) in P::MASKS.iter().enumerate() {
+            if value & (1 << i) != 0 {
+                bits |= pin;
+            }
+        }
+
+        // The MASK register is shared by all pins of the port, so we need to
+        // make sure no one else uses it until we're done.
+        interrupt::free(|_| {
+            // Sound, as we're only writing to the MASK and MPIN registers
+            // within a critical section, and the MPIN write only affects the
+            // pins we own, as all others are masked.
+            let gpio = unsafe { &*pac::GPIO::ptr() };
+            let registers = Registers::new(gpio);
+
+            registers.mask[self.port]
+                .write(|w| unsafe { w.maskp().bits(!self.mask) });
+            registers.mpin[self.port]
+                .write(|w| unsafe { w.mportp().bits(bits) });
+        });
+    }
+}
+
+impl<P, const N: usize> PinBus<P, N>
+where
+    P: BusPins<N, Direction = Input>,
+{
+    /// Read the input levels of all pins at once
+    ///
+    /// Bit `i` of the returned value is set, if the `i`th pin is high. Bits
+    /// that don't correspond to a pin are always zero.
+    pub fn read(&self) -> u32 {
+        // Sound, as we're only reading from the PIN register.
+        let gpio = unsafe { &*pac::GPIO::ptr() };
+        let registers = Registers::new(gpio);
+
+        let levels = registers.pin[self.port].read().port().bits();
+
+        P::MASKS
+            .iter()
+            .enumerate()
+            .filter(|(_, &pin)| levels & pin != 0)
+            .fold(0, |value, (i, _)| value | 1 << i)
+    }
+}
+
+/// Implemented for tuples of GPIO pins that can be grouped into a [`PinBus`]
+///
+/// This trait is an internal implementation detail and should neither be
+/// implemented nor used outside of LPC8xx HAL. Any changes to this trait won't
+/// be considered breaking changes.
+///
+/// [`PinBus`]: struct.PinBus.html
+pub trait BusPins<const N: usize> {
+    /// The direction of the pins
+    type Direction: Direction;
+
+    /// The port of each pin
+    const PORTS: [usize; N];
+
+    /// The mask of each pin
+    const MASKS: [u32; N];
+}
+
+macro_rules! bus_pins {
+    ($n:expr; $($pin:ident),*) => {
+        impl<D, $($pin,)*> BusPins<$n> for ($(GpioPin<$pin, D>,)*)
+        where
+            D: Direction,
+            $($pin: pins::Trait,)*
+        {
+            type Direction = D;
+
+            const PORTS: [usize; $n] = [$($pin::PORT,)*];
+            const MASKS: [u32; $n] = [$($pin::MASK,)*];
+        }
+    };
+}
+
+bus_pins!(1; T0);
+bus_pins!(2; T0, T1);
+bus_pins!(3; T0, T1, T2);
+bus_pins!(4; T0, T1, T2, T3);
+bus_pins!(5; T0, T1, T2, T3, T4);
+bus_pins!(6; T0, T1, T2, T3, T4, T5);
+bus_pins!(7; T0, T1, T2, T3, T4, T5, T6);
+bus_pins!(8; T0, T1, T2, T3, T4, T5, T6, T7);
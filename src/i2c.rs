@@ -53,20 +53,28 @@
 //!
 //! [examples in the repository]: https://github.com/lpc-rs/lpc8xx-hal/tree/master/examples
 
+#[cfg(feature = "serial-stats")]
+use core::cell::Cell;
 use core::ops::Deref;
-use embedded_hal::blocking::i2c;
-use void::Void;
+#[cfg(feature = "serial-stats")]
+use cortex_m::interrupt::{self, Mutex};
+use embedded_hal::{blocking::i2c, timer::CountDown};
 
 use crate::{
-    init_state,
-    pac::{self, Interrupt},
+    activity, init_state,
+    pac::{self, Interrupt, NVIC},
     swm::{self},
     syscon::{
         self,
         clock_source::{I2cClock, PeripheralClock},
     },
+    timeout::{self, Deadline, Expire, Never},
 };
 
+mod error;
+
+pub use self::error::Error;
+
 /// Interface to an I2C peripheral
 ///
 /// Please refer to the [module documentation] for more information.
@@ -76,7 +84,6 @@ use crate::{
 /// This API has the following limitations:
 /// - Only I2C0 is supported.
 /// - Only master mode is supported.
-/// - Errors are not handled.
 ///
 /// Additional limitations are documented on the specific methods that they
 /// apply to.
@@ -84,9 +91,15 @@ use crate::{
 /// # `embedded-hal` traits
 /// - [`embedded_hal::blocking::i2c::Read`] for synchronous reading
 /// - [`embedded_hal::blocking::i2c::Write`] for synchronous writing
+/// - [`embedded_hal::blocking::i2c::WriteIter`] for synchronous writing from
+///   an iterator
+/// - [`embedded_hal::blocking::i2c::WriteIterRead`] for synchronous writing
+///   from an iterator, followed by reading
 ///
 /// [`embedded_hal::blocking::i2c::Read`]: #impl-Read
 /// [`embedded_hal::blocking::i2c::Write`]: #impl-Write
+/// [`embedded_hal::blocking::i2c::WriteIter`]: #impl-WriteIter
+/// [`embedded_hal::blocking::i2c::WriteIterRead`]: #impl-WriteIterRead
 /// [module documentation]: index.html
 pub struct I2C<I, State = init_state::Enabled> {
     i2c: I,
@@ -111,25 +124,30 @@ where
     /// enabled will not compile.
     ///
     /// Consumes this instance of `I2C` and returns another instance that has
-    /// its `State` type parameter set to [`Enabled`].
+    /// its `State` type parameter set to [`Enabled`]. The SWM functions are
+    /// kept by the enabled instance, and returned by [`disable`].
+    ///
+    /// The SCL frequency is determined by `clock`. See
+    /// [`I2cClock::new_with_frequency`] for creating a clock config for a
+    /// specific frequency.
     ///
     /// # Limitations
     ///
     /// This method expects the mode for SDA & SCL pins to be set to
-    /// standard/fast mode. This is the default value.
-    ///
-    /// The I2C clock frequency is hardcoded to a specific value. For unknown
-    /// reasons, this seems to be 79.6 kHz.
+    /// standard/fast mode. This is the default value. If you're using
+    /// Fast-mode Plus, you need to configure the pins accordingly in IOCON.
     ///
+    /// [`I2cClock::new_with_frequency`]: ../syscon/clock_source/struct.I2cClock.html#method.new_with_frequency
     /// [`Disabled`]: ../init_state/struct.Disabled.html
     /// [`Enabled`]: ../init_state/struct.Enabled.html
+    /// [`disable`]: #method.disable
     pub fn enable<SdaPin, SclPin, Clock>(
         mut self,
         clock: &I2cClock<Clock>,
         syscon: &mut syscon::Handle,
-        _: swm::Function<I::Sda, swm::state::Assigned<SdaPin>>,
-        _: swm::Function<I::Scl, swm::state::Assigned<SclPin>>,
-    ) -> I2C<I, init_state::Enabled>
+        sda: swm::Function<I::Sda, swm::state::Assigned<SdaPin>>,
+        scl: swm::Function<I::Scl, swm::state::Assigned<SclPin>>,
+    ) -> I2C<I, init_state::Enabled<SwmFunctions<I, SdaPin, SclPin>>>
     where
         I2cClock<Clock>: PeripheralClock<I>,
     {
@@ -155,25 +173,163 @@ where
 
         I2C {
             i2c: self.i2c,
-            _state: init_state::Enabled(()),
+            _state: init_state::Enabled((sda, scl)),
         }
     }
 }
 
-impl<I> i2c::Write for I2C<I, init_state::Enabled>
+impl<I, Pins> I2C<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
-    type Error = Void;
+    /// Disable the I2C peripheral
+    ///
+    /// This method is only available, if `I2C` is in the [`Enabled`] state.
+    /// Code that attempts to call this method when the peripheral is already
+    /// disabled will not compile.
+    ///
+    /// Consumes this instance of `I2C` and returns another instance that has
+    /// its `State` type parameter set to [`Disabled`], together with the SWM
+    /// functions that were passed to [`enable`]. Those can then be unassigned,
+    /// to reuse the pins for something else.
+    ///
+    /// [`Enabled`]: ../init_state/struct.Enabled.html
+    /// [`Disabled`]: ../init_state/struct.Disabled.html
+    /// [`enable`]: #method.enable
+    pub fn disable(
+        self,
+        syscon: &mut syscon::Handle,
+    ) -> (I2C<I, init_state::Disabled>, Pins) {
+        syscon.disable_clock(&self.i2c);
 
-    /// Write to the I2C bus
+        let i2c = I2C {
+            i2c: self.i2c,
+            _state: init_state::Disabled,
+        };
+
+        (i2c, self._state.0)
+    }
+
+    /// Enable interrupts for this instance in the NVIC
     ///
-    /// Please refer to the [embedded-hal documentation] for details.
+    /// This only enables the interrupts in the NVIC. It doesn't enable any
+    /// specific interrupt in this I2C instance.
+    pub fn enable_in_nvic(&mut self) {
+        // Safe, because there's no critical section here that this could
+        // interfere with.
+        unsafe { NVIC::unmask(I::INTERRUPT) };
+    }
+
+    /// Disable interrupts for this instance in the NVIC
     ///
-    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.1/embedded_hal/blocking/i2c/trait.Write.html#tymethod.write
-    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Self::Error> {
+    /// This only disables the interrupts in the NVIC. It doesn't change
+    /// anything about the interrupt configuration within this I2C instance.
+    pub fn disable_in_nvic(&mut self) {
+        NVIC::mask(I::INTERRUPT);
+    }
+
+    /// Clear this instance's interrupt pending flag in the NVIC
+    ///
+    /// This only clears the interrupt's pending flag in the NVIC. It does not
+    /// affect any of the interrupt-related flags in the peripheral.
+    pub fn clear_nvic_pending(&mut self) {
+        NVIC::unpend(I::INTERRUPT);
+    }
+
+    /// Set this instance's interrupt pending flag in the NVIC
+    ///
+    /// The interrupt handler then runs as if the interrupt had occurred, once
+    /// the interrupt is enabled in the NVIC and its priority allows it. This
+    /// does not set any of the interrupt-related flags in the peripheral.
+    pub fn set_nvic_pending(&mut self) {
+        NVIC::pend(I::INTERRUPT);
+    }
+
+    /// Indicates whether this instance's interrupt is pending in the NVIC
+    pub fn is_nvic_pending(&self) -> bool {
+        NVIC::is_pending(I::INTERRUPT)
+    }
+
+    /// Set the priority of this instance's interrupt in the NVIC
+    ///
+    /// Lower values mean higher priority. Only the two most significant bits of
+    /// `priority` are used by the hardware.
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections,
+    /// like the ones used by RTFM.
+    pub unsafe fn set_nvic_priority(&mut self, nvic: &mut NVIC, priority: u8) {
+        nvic.set_priority(I::INTERRUPT, priority);
+    }
+
+    /// Enable wake-up from deep-sleep and power-down modes
+    ///
+    /// Configures this instance's interrupt as a wake-up source. The interrupt
+    /// also needs to be enabled in the NVIC (see [`enable_in_nvic`]).
+    ///
+    /// Please note that the I2C peripheral can only wake up the device from
+    /// deep-sleep or power-down mode, if the event that triggers the
+    /// interrupt doesn't require the peripheral clock. This is only the case
+    /// for the slave address match in slave mode, where the external master
+    /// provides the clock. Slave mode isn't supported by this API yet, so it
+    /// needs to be configured using the raw registers.
+    ///
+    /// [`enable_in_nvic`]: #method.enable_in_nvic
+    pub fn enable_wakeup(&mut self, syscon: &mut syscon::Handle) {
+        syscon.enable_interrupt_wakeup::<I::Wakeup>();
+    }
+
+    /// Disable wake-up from deep-sleep and power-down modes
+    pub fn disable_wakeup(&mut self, syscon: &mut syscon::Handle) {
+        syscon.disable_interrupt_wakeup::<I::Wakeup>();
+    }
+
+    /// Reset the peripheral, keeping its configuration
+    ///
+    /// Resets the I2C peripheral through SYSCON, then restores the clock
+    /// configuration and re-enables master mode. This can be used to recover
+    /// from a state the master function can't get out of by itself, for
+    /// example after an arbitration loss that left the bus in an unknown
+    /// state, without resetting the whole chip.
+    ///
+    /// Any transfer that is in progress is aborted. All interrupts of this
+    /// peripheral are disabled afterwards.
+    pub fn reset(&mut self, syscon: &mut syscon::Handle) {
+        let divval = self.i2c.clkdiv.read().divval().bits();
+        let msttime = self.i2c.msttime.read();
+        let high = msttime.mstsclhigh().bits();
+        let low = msttime.mstscllow().bits();
+
+        syscon.reset(&self.i2c);
+
+        self.i2c
+            .clkdiv
+            .write(|w| unsafe { w.divval().bits(divval) });
+        self.i2c.msttime.write(|w| {
+            w.mstsclhigh().bits(high);
+            w.mstscllow().bits(low)
+        });
+        self.i2c.cfg.write(|w| w.msten().enabled());
+    }
+
+    /// Check whether a slave acknowledges its address
+    ///
+    /// Sends a start condition and `address`, followed by a stop condition,
+    /// without transferring any data. Returns `Ok(true)`, if a slave
+    /// acknowledged the address, and `Ok(false)`, if none did. Other errors,
+    /// like a loss of arbitration, are returned as `Err`.
+    ///
+    /// `address` has the same format as for [`write`], meaning the 7-bit
+    /// address is in the upper 7 bits. The lowest bit is ignored.
+    ///
+    /// This is useful to find out at runtime, whether an optional device is
+    /// connected.
+    ///
+    /// [`write`]: #impl-Write
+    pub fn probe(&mut self, address: u8) -> Result<bool, Error> {
         // Wait until peripheral is idle
-        while !self.i2c.stat.read().mststate().is_idle() {}
+        untimed(self.wait_for_idle(&mut Never))?;
 
         // Write slave address with rw bit set to 0
         self.i2c
@@ -183,50 +339,464 @@ where
         // Start transmission
         self.i2c.mstctl.write(|w| w.mststart().start());
 
-        for &b in data {
-            // Wait until peripheral is ready to transmit
-            while self.i2c.stat.read().mstpending().is_in_progress() {}
+        // Wait until the address has been sent. `wait_for_transmit_ready`
+        // isn't used here, as it would count a missing acknowledge in the
+        // statistics, while that is an expected outcome of probing. If the
+        // slave doesn't acknowledge, `read_error` already sends the stop
+        // condition.
+        loop {
+            let pending = self.i2c.stat.read().mstpending().is_pending();
 
-            // Write byte
-            self.i2c.mstdat.write(|w| unsafe { w.data().bits(b) });
+            match self.read_error() {
+                Ok(()) if pending => break,
+                Ok(()) => activity::notify(),
+                Err(Error::NackAddress) => return Ok(false),
+                Err(err) => {
+                    #[cfg(feature = "serial-stats")]
+                    count_error::<I>(err);
 
-            // Continue transmission
-            self.i2c.mstctl.write(|w| w.mstcontinue().continue_());
+                    return Err(err);
+                }
+            }
+        }
+        if !self.i2c.stat.read().mststate().is_transmit_ready() {
+            return Err(Error::UnexpectedState);
         }
-
-        // Wait until peripheral is ready to transmit
-        while self.i2c.stat.read().mstpending().is_in_progress() {}
 
         // Stop transmission
         self.i2c.mstctl.modify(|_, w| w.mststop().stop());
 
-        Ok(())
+        Ok(true)
+    }
+
+    /// Scan the bus for slaves
+    ///
+    /// Returns an iterator that [`probe`]s every address that isn't reserved
+    /// by the I2C specification (7-bit addresses 0x08 to 0x77), and yields
+    /// the ones that were acknowledged. Addresses are yielded in the same
+    /// format that [`probe`] and [`write`] expect.
+    ///
+    /// If an error other than a missing acknowledge occurs, it is yielded,
+    /// and the scan ends.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// # use lpc8xx_hal::{Peripherals, syscon::clock_source::I2cClock};
+    /// #
+    /// # let mut p = Peripherals::take().unwrap();
+    /// #
+    /// # let mut swm    = p.SWM.split();
+    /// # let mut syscon = p.SYSCON.split();
+    /// #
+    /// # #[cfg(feature = "82x")]
+    /// # let mut swm_handle = swm.handle;
+    /// # #[cfg(feature = "845")]
+    /// # let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    /// #
+    /// # let (i2c0_sda, _) = swm.fixed_functions.i2c0_sda.assign(
+    /// #     p.pins.pio0_11.into_swm_pin(),
+    /// #     &mut swm_handle,
+    /// # );
+    /// # let (i2c0_scl, _) = swm.fixed_functions.i2c0_scl.assign(
+    /// #     p.pins.pio0_10.into_swm_pin(),
+    /// #     &mut swm_handle,
+    /// # );
+    /// #
+    /// # let mut i2c = p.I2C0.enable(
+    /// #     &I2cClock::new_400khz(),
+    /// #     &mut syscon.handle,
+    /// #     i2c0_sda,
+    /// #     i2c0_scl,
+    /// # );
+    /// #
+    /// for result in i2c.scan() {
+    ///     let address = result.expect("Bus error");
+    ///     // a slave responded to `address`
+    /// }
+    /// ```
+    ///
+    /// [`probe`]: #method.probe
+    /// [`write`]: #impl-Write
+    pub fn scan(&mut self) -> Scan<'_, I, Pins> {
+        Scan {
+            i2c: self,
+            next: Some(0x08),
+        }
+    }
+
+    /// Read all status flags at once, and clear the events among them
+    ///
+    /// Reads the STAT register in a single volatile read, which makes this
+    /// the right way to find out what happened in an interrupt handler. The
+    /// flags that are cleared by software (see [`Events`]) are then cleared,
+    /// if they were set. Events that occur after the read are not lost, as
+    /// only flags that have been read are cleared.
+    ///
+    /// The other flags reflect the current state of the peripheral, and are
+    /// returned as they are.
+    ///
+    /// Please note that errors that are cleared by this method are no longer
+    /// reported by an ongoing transfer.
+    ///
+    /// [`Events`]: struct.Events.html
+    pub fn read_and_clear_events(&mut self) -> Events {
+        let events = Events::from_bits_truncate(self.i2c.stat.read().bits());
+
+        let clear = events & Events::CLEARABLE;
+        if !clear.is_empty() {
+            self.i2c.stat.write(|w| unsafe { w.bits(clear.bits()) });
+        }
+
+        events
+    }
+
+    /// Return the statistics of this I2C instance
+    ///
+    /// Counts the bytes and errors of the transactions that are performed
+    /// using this API, as the master. Only available, if the `serial-stats`
+    /// feature is enabled.
+    #[cfg(feature = "serial-stats")]
+    pub fn stats(&self) -> Stats {
+        interrupt::free(|cs| STATS.borrow(cs)[I::NUM].get())
+    }
+
+    /// Reset the statistics of this I2C instance
+    ///
+    /// Returns the statistics from before the reset. Only available, if the
+    /// `serial-stats` feature is enabled.
+    #[cfg(feature = "serial-stats")]
+    pub fn reset_stats(&mut self) -> Stats {
+        interrupt::free(|cs| STATS.borrow(cs)[I::NUM].replace(Stats::new()))
+    }
+
+    /// Save the configuration of this I2C instance
+    ///
+    /// Reads the configuration, timing, slave address, and interrupt enable
+    /// registers. The returned [`Snapshot`] can be passed to [`resume`], to
+    /// restore the configuration after the peripheral's state has been lost,
+    /// for example because it was reset or powered down while the
+    /// microcontroller was in a low-power mode.
+    ///
+    /// [`Snapshot`]: struct.Snapshot.html
+    /// [`resume`]: #method.resume
+    pub fn suspend(&mut self) -> Snapshot {
+        let mut slvadr = [0; 4];
+        for (value, register) in slvadr.iter_mut().zip(self.i2c.slvadr.iter()) {
+            *value = register.read().bits();
+        }
+
+        Snapshot {
+            cfg: self.i2c.cfg.read().bits(),
+            clkdiv: self.i2c.clkdiv.read().bits(),
+            msttime: self.i2c.msttime.read().bits(),
+            timeout: self.i2c.timeout.read().bits(),
+            slvadr,
+            slvqual0: self.i2c.slvqual0.read().bits(),
+            intenset: self.i2c.intenset.read().bits(),
+        }
+    }
+
+    /// Restore a configuration that was saved using [`suspend`]
+    ///
+    /// Enables the peripheral clock, in case it has been disabled, and writes
+    /// the saved configuration back, while all functions of the I2C instance
+    /// are disabled. They are enabled again afterwards, according to the saved
+    /// configuration.
+    ///
+    /// [`suspend`]: #method.suspend
+    pub fn resume(&mut self, snapshot: &Snapshot, syscon: &mut syscon::Handle) {
+        syscon.enable_clock(&self.i2c);
+
+        // Sound, as all values have been read from the same registers.
+        unsafe {
+            self.i2c.cfg.write(|w| w.bits(0));
+            self.i2c.clkdiv.write(|w| w.bits(snapshot.clkdiv));
+            self.i2c.msttime.write(|w| w.bits(snapshot.msttime));
+            self.i2c.timeout.write(|w| w.bits(snapshot.timeout));
+            for (&value, register) in
+                snapshot.slvadr.iter().zip(self.i2c.slvadr.iter())
+            {
+                register.write(|w| w.bits(value));
+            }
+            self.i2c.slvqual0.write(|w| w.bits(snapshot.slvqual0));
+            self.i2c.intenclr.write(|w| w.bits(!snapshot.intenset));
+            self.i2c.intenset.write(|w| w.bits(snapshot.intenset));
+            self.i2c.cfg.write(|w| w.bits(snapshot.cfg));
+        }
+    }
+
+    /// Wait until the master function is idle
+    ///
+    /// Returns an error, if any error flags are set in the meantime.
+    fn wait_for_idle(
+        &self,
+        deadline: &mut impl Expire,
+    ) -> Result<(), timeout::Error<Error>> {
+        loop {
+            self.check_for_error().map_err(timeout::Error::Other)?;
+
+            if self.i2c.stat.read().mststate().is_idle() {
+                return Ok(());
+            }
+            if deadline.has_expired() {
+                #[cfg(feature = "serial-stats")]
+                update_stats::<I>(|stats| {
+                    stats.timeouts = stats.timeouts.wrapping_add(1);
+                });
+
+                return Err(timeout::Error::Timeout);
+            }
+
+            activity::notify();
+        }
+    }
+
+    /// Wait until the master function is ready to transmit
+    ///
+    /// Returns an error, if the slave did not acknowledge, or if any other
+    /// error occurs in the meantime.
+    fn wait_for_transmit_ready(
+        &self,
+        deadline: &mut impl Expire,
+    ) -> Result<(), timeout::Error<Error>> {
+        self.wait_for_pending(deadline)?;
+
+        if self.i2c.stat.read().mststate().is_transmit_ready() {
+            Ok(())
+        } else {
+            Err(timeout::Error::Other(Error::UnexpectedState))
+        }
+    }
+
+    /// Wait until the master function has received data
+    ///
+    /// Returns an error, if the slave did not acknowledge, or if any other
+    /// error occurs in the meantime.
+    fn wait_for_receive_ready(
+        &self,
+        deadline: &mut impl Expire,
+    ) -> Result<(), timeout::Error<Error>> {
+        self.wait_for_pending(deadline)?;
+
+        if self.i2c.stat.read().mststate().is_receive_ready() {
+            Ok(())
+        } else {
+            Err(timeout::Error::Other(Error::UnexpectedState))
+        }
+    }
+
+    /// Wait until the master function needs attention
+    fn wait_for_pending(
+        &self,
+        deadline: &mut impl Expire,
+    ) -> Result<(), timeout::Error<Error>> {
+        while self.i2c.stat.read().mstpending().is_in_progress() {
+            self.check_for_error().map_err(timeout::Error::Other)?;
+
+            if deadline.has_expired() {
+                #[cfg(feature = "serial-stats")]
+                update_stats::<I>(|stats| {
+                    stats.timeouts = stats.timeouts.wrapping_add(1);
+                });
+
+                return Err(timeout::Error::Timeout);
+            }
+
+            activity::notify();
+        }
+
+        self.check_for_error().map_err(timeout::Error::Other)
+    }
+
+    /// Check the status register for errors
+    ///
+    /// Works like `read_error`, and also counts any error in the statistics.
+    fn check_for_error(&self) -> Result<(), Error> {
+        let result = self.read_error();
+
+        #[cfg(feature = "serial-stats")]
+        if let Err(err) = result {
+            count_error::<I>(err);
+        }
+
+        result
+    }
+
+    /// Check the status register for errors, without counting them
+    ///
+    /// Clears the respective flag, if an error is found. If the slave didn't
+    /// acknowledge, this method will also send a stop condition, to release
+    /// the bus.
+    fn read_error(&self) -> Result<(), Error> {
+        let stat = self.i2c.stat.read();
+
+        if stat.mstarbloss().is_arbitration_loss() {
+            self.i2c.stat.write(|w| w.mstarbloss().set_bit());
+            Err(Error::ArbitrationLoss)
+        } else if stat.mstststperr().is_error() {
+            self.i2c.stat.write(|w| w.mstststperr().set_bit());
+            Err(Error::StartStopError)
+        } else if stat.mstpending().is_pending()
+            && stat.mststate().is_nack_address()
+        {
+            self.i2c.mstctl.write(|w| w.mststop().stop());
+            Err(Error::NackAddress)
+        } else if stat.mstpending().is_pending()
+            && stat.mststate().is_nack_data()
+        {
+            self.i2c.mstctl.write(|w| w.mststop().stop());
+            Err(Error::NackData)
+        } else {
+            Ok(())
+        }
     }
 }
 
-impl<I> i2c::Read for I2C<I, init_state::Enabled>
+impl<I, Pins> I2C<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
-    type Error = Void;
+    /// Write to the I2C bus, giving up after a timeout
+    ///
+    /// Works like [`write`], but returns [`timeout::Error::Timeout`], if the
+    /// transaction doesn't complete within `ticks` ticks of `timer`. This can
+    /// happen, if a slave holds SCL low indefinitely.
+    ///
+    /// The timeout applies to the whole transaction. After a timeout, the
+    /// peripheral might still be in the middle of the transaction. Use
+    /// [`reset`] to bring it back into a known state.
+    ///
+    /// [`write`]: #impl-Write
+    /// [`timeout::Error::Timeout`]: ../timeout/enum.Error.html#variant.Timeout
+    /// [`reset`]: #method.reset
+    pub fn write_with_timeout<T>(
+        &mut self,
+        address: u8,
+        data: &[u8],
+        timer: &mut T,
+        ticks: u32,
+    ) -> Result<(), timeout::Error<Error>>
+    where
+        T: CountDown<Time = u32>,
+    {
+        let mut deadline = Deadline::start(timer, ticks);
 
-    /// Read from the I2C bus
+        self.write_bytes(address, data.iter().copied(), &mut deadline)?;
+
+        // Stop transmission
+        self.i2c.mstctl.modify(|_, w| w.mststop().stop());
+
+        Ok(())
+    }
+
+    /// Read from the I2C bus, giving up after a timeout
     ///
-    /// Please refer to the [embedded-hal documentation] for details.
+    /// Works like [`read`], but returns [`timeout::Error::Timeout`], if the
+    /// transaction doesn't complete within `ticks` ticks of `timer`. Please
+    /// refer to [`write_with_timeout`] for details.
     ///
-    /// # Limitations
+    /// [`read`]: #impl-Read
+    /// [`timeout::Error::Timeout`]: ../timeout/enum.Error.html#variant.Timeout
+    /// [`write_with_timeout`]: #method.write_with_timeout
+    pub fn read_with_timeout<T>(
+        &mut self,
+        address: u8,
+        buffer: &mut [u8],
+        timer: &mut T,
+        ticks: u32,
+    ) -> Result<(), timeout::Error<Error>>
+    where
+        T: CountDown<Time = u32>,
+    {
+        let mut deadline = Deadline::start(timer, ticks);
+
+        // Wait until peripheral is idle
+        self.wait_for_idle(&mut deadline)?;
+
+        self.read_bytes(address, buffer, &mut deadline)
+    }
+
+    /// Write to, then read from the I2C bus, giving up after a timeout
     ///
-    /// Reading multiple bytes should work, but has not been tested.
+    /// Works like [`write_iter_read`], but returns
+    /// [`timeout::Error::Timeout`], if the transaction doesn't complete within
+    /// `ticks` ticks of `timer`. Please refer to [`write_with_timeout`] for
+    /// details.
     ///
-    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.1/embedded_hal/blocking/i2c/trait.Read.html#tymethod.read
-    fn read(
+    /// [`write_iter_read`]: #impl-WriteIterRead
+    /// [`timeout::Error::Timeout`]: ../timeout/enum.Error.html#variant.Timeout
+    /// [`write_with_timeout`]: #method.write_with_timeout
+    pub fn write_read_with_timeout<T>(
         &mut self,
         address: u8,
+        bytes: &[u8],
         buffer: &mut [u8],
-    ) -> Result<(), Self::Error> {
+        timer: &mut T,
+        ticks: u32,
+    ) -> Result<(), timeout::Error<Error>>
+    where
+        T: CountDown<Time = u32>,
+    {
+        let mut deadline = Deadline::start(timer, ticks);
+
+        self.write_bytes(address, bytes.iter().copied(), &mut deadline)?;
+        self.read_bytes(address, buffer, &mut deadline)
+    }
+
+    /// Write bytes to a slave, without sending a stop condition
+    ///
+    /// The peripheral is ready to transmit afterwards, so either a stop
+    /// condition or a repeated start can follow.
+    fn write_bytes<B>(
+        &mut self,
+        address: u8,
+        bytes: B,
+        deadline: &mut impl Expire,
+    ) -> Result<(), timeout::Error<Error>>
+    where
+        B: IntoIterator<Item = u8>,
+    {
         // Wait until peripheral is idle
-        while !self.i2c.stat.read().mststate().is_idle() {}
+        self.wait_for_idle(deadline)?;
+
+        // Write slave address with rw bit set to 0
+        self.i2c
+            .mstdat
+            .write(|w| unsafe { w.data().bits(address & 0xfe) });
 
+        // Start transmission
+        self.i2c.mstctl.write(|w| w.mststart().start());
+
+        for b in bytes {
+            // Wait until peripheral is ready to transmit
+            self.wait_for_transmit_ready(deadline)?;
+
+            // Write byte
+            self.i2c.mstdat.write(|w| unsafe { w.data().bits(b) });
+
+            #[cfg(feature = "serial-stats")]
+            update_stats::<I>(|stats| {
+                stats.bytes_written = stats.bytes_written.wrapping_add(1);
+            });
+
+            // Continue transmission
+            self.i2c.mstctl.write(|w| w.mstcontinue().continue_());
+        }
+
+        // Wait until peripheral is ready to transmit
+        self.wait_for_transmit_ready(deadline)
+    }
+
+    /// Send a (repeated) start condition, then read bytes from a slave
+    ///
+    /// Sends a stop condition afterwards.
+    fn read_bytes(
+        &mut self,
+        address: u8,
+        buffer: &mut [u8],
+        deadline: &mut impl Expire,
+    ) -> Result<(), timeout::Error<Error>> {
         // Write slave address with rw bit set to 1
         self.i2c
             .mstdat
@@ -240,10 +810,15 @@ where
             self.i2c.mstctl.write(|w| w.mstcontinue().continue_());
 
             // Wait until peripheral is ready to receive
-            while self.i2c.stat.read().mstpending().is_in_progress() {}
+            self.wait_for_receive_ready(deadline)?;
 
             // Read received byte
             *b = self.i2c.mstdat.read().data().bits();
+
+            #[cfg(feature = "serial-stats")]
+            update_stats::<I>(|stats| {
+                stats.bytes_read = stats.bytes_read.wrapping_add(1);
+            });
         }
 
         // Stop transmission
@@ -253,6 +828,290 @@ where
     }
 }
 
+impl<I, Pins> I2C<I, init_state::Enabled<Pins>>
+where
+    I: Instance,
+{
+    /// Write to an EEPROM, or a similar memory, one page at a time
+    ///
+    /// Writes `data` to the memory at `address`, starting at `mem_address`.
+    /// The transfer is split at the page boundaries described by `paging`,
+    /// and each page is written in a separate transaction that starts with
+    /// the memory address.
+    ///
+    /// After each page, the memory is busy with its internal write cycle and
+    /// doesn't acknowledge its address. This method polls the memory, until
+    /// it acknowledges again, so the next page (or any other transfer) can
+    /// follow right away. If the memory doesn't acknowledge within
+    /// `paging.max_polls` attempts, [`Error::NackAddress`] is returned.
+    ///
+    /// `address` has the same format as for [`write`], meaning the 7-bit
+    /// address is in the upper 7 bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `paging.page_size` is zero, if `paging.address_len` is not
+    /// between 1 and 4, if `paging.block_bits` is larger than 3, or if the
+    /// range of memory addresses doesn't fit into the address bits described
+    /// by `paging`.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// # use lpc8xx_hal::{i2c, syscon::clock_source::I2cClock, Peripherals};
+    /// #
+    /// # let mut p = Peripherals::take().unwrap();
+    /// #
+    /// # let mut swm = p.SWM.split();
+    /// # let mut syscon = p.SYSCON.split();
+    /// #
+    /// # #[cfg(feature = "82x")]
+    /// # let mut swm_handle = swm.handle;
+    /// # #[cfg(feature = "845")]
+    /// # let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    /// #
+    /// # let (i2c0_sda, _) = swm.fixed_functions.i2c0_sda.assign(
+    /// #     p.pins.pio0_11.into_swm_pin(),
+    /// #     &mut swm_handle,
+    /// # );
+    /// # let (i2c0_scl, _) = swm.fixed_functions.i2c0_scl.assign(
+    /// #     p.pins.pio0_10.into_swm_pin(),
+    /// #     &mut swm_handle,
+    /// # );
+    /// #
+    /// # let mut i2c = p.I2C0.enable(
+    /// #     &I2cClock::new_400khz(),
+    /// #     &mut syscon.handle,
+    /// #     i2c0_sda,
+    /// #     i2c0_scl,
+    /// # );
+    /// #
+    /// // A 24LC256: 64-byte pages, 2 address bytes
+    /// let paging = i2c::Paging::new(64, 2);
+    ///
+    /// let data = [0xa5; 100];
+    /// i2c.write_paged(0xa0, 0x0030, &data, &paging).unwrap();
+    ///
+    /// let mut buffer = [0; 100];
+    /// i2c.read_large(0xa0, 0x0030, &mut buffer, &paging).unwrap();
+    /// ```
+    ///
+    /// [`Error::NackAddress`]: enum.Error.html#variant.NackAddress
+    /// [`write`]: #impl-Write
+    pub fn write_paged(
+        &mut self,
+        address: u8,
+        mem_address: u32,
+        data: &[u8],
+        paging: &Paging,
+    ) -> Result<(), Error> {
+        paging.check(mem_address, data.len());
+
+        let page_size = usize::from(paging.page_size);
+        let mut mem_address = mem_address;
+        let mut data = data;
+
+        while !data.is_empty() {
+            let offset = mem_address as usize % page_size;
+            let len = data.len().min(page_size - offset);
+            let (page, rest) = data.split_at(len);
+
+            let device = paging.device_address(address, mem_address);
+            let bytes = paging
+                .address_bytes(mem_address)
+                .chain(page.iter().copied());
+            untimed(self.write_bytes(device, bytes, &mut Never))?;
+
+            // Stop transmission. This starts the write cycle.
+            self.i2c.mstctl.modify(|_, w| w.mststop().stop());
+
+            self.poll_for_ack(device, paging.max_polls)?;
+
+            mem_address = mem_address.wrapping_add(len as u32);
+            data = rest;
+        }
+
+        Ok(())
+    }
+
+    /// Read from an EEPROM, or a similar memory, in chunks
+    ///
+    /// Reads from the memory at `address`, starting at `mem_address`, until
+    /// `buffer` is full. The transfer is split into transactions of at most
+    /// `paging.page_size` bytes, aligned to the page boundaries. Each of
+    /// them writes the memory address, followed by a repeated start and the
+    /// read.
+    ///
+    /// Most memories could be read in a single transaction, but keeping the
+    /// transactions short gives other masters on the bus a chance to take
+    /// turns.
+    ///
+    /// Please refer to [`write_paged`] for an example.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `paging.page_size` is zero, if `paging.address_len` is not
+    /// between 1 and 4, if `paging.block_bits` is larger than 3, or if the
+    /// range of memory addresses doesn't fit into the address bits described
+    /// by `paging`.
+    ///
+    /// [`write_paged`]: #method.write_paged
+    pub fn read_large(
+        &mut self,
+        address: u8,
+        mem_address: u32,
+        buffer: &mut [u8],
+        paging: &Paging,
+    ) -> Result<(), Error> {
+        paging.check(mem_address, buffer.len());
+
+        let page_size = usize::from(paging.page_size);
+        let mut mem_address = mem_address;
+
+        let mut buffer = buffer;
+        while !buffer.is_empty() {
+            let offset = mem_address as usize % page_size;
+            let len = buffer.len().min(page_size - offset);
+            let (chunk, rest) = buffer.split_at_mut(len);
+
+            let device = paging.device_address(address, mem_address);
+            let bytes = paging.address_bytes(mem_address);
+            untimed(self.write_bytes(device, bytes, &mut Never))?;
+            untimed(self.read_bytes(device, chunk, &mut Never))?;
+
+            mem_address = mem_address.wrapping_add(len as u32);
+            buffer = rest;
+        }
+
+        Ok(())
+    }
+
+    /// Wait until a memory has finished its write cycle
+    ///
+    /// Memories don't acknowledge their address, while they're busy.
+    fn poll_for_ack(
+        &mut self,
+        address: u8,
+        max_polls: u32,
+    ) -> Result<(), Error> {
+        for _ in 0..max_polls {
+            if self.probe(address)? {
+                return Ok(());
+            }
+        }
+
+        Err(Error::NackAddress)
+    }
+}
+
+impl<I, Pins> i2c::Write for I2C<I, init_state::Enabled<Pins>>
+where
+    I: Instance,
+{
+    type Error = Error;
+
+    /// Write to the I2C bus
+    ///
+    /// Please refer to the [embedded-hal documentation] for details.
+    ///
+    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.1/embedded_hal/blocking/i2c/trait.Write.html#tymethod.write
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Self::Error> {
+        untimed(self.write_bytes(address, data.iter().copied(), &mut Never))?;
+
+        // Stop transmission
+        self.i2c.mstctl.modify(|_, w| w.mststop().stop());
+
+        Ok(())
+    }
+}
+
+impl<I, Pins> i2c::WriteIter for I2C<I, init_state::Enabled<Pins>>
+where
+    I: Instance,
+{
+    type Error = Error;
+
+    /// Write to the I2C bus, taking the data from an iterator
+    ///
+    /// Each byte is taken from the iterator right before it is sent, so the
+    /// data doesn't need to be stored in a buffer first.
+    ///
+    /// Please refer to the [embedded-hal documentation] for details.
+    ///
+    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.5/embedded_hal/blocking/i2c/trait.WriteIter.html#tymethod.write
+    fn write<B>(&mut self, address: u8, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        untimed(self.write_bytes(address, bytes, &mut Never))?;
+
+        // Stop transmission
+        self.i2c.mstctl.modify(|_, w| w.mststop().stop());
+
+        Ok(())
+    }
+}
+
+impl<I, Pins> i2c::Read for I2C<I, init_state::Enabled<Pins>>
+where
+    I: Instance,
+{
+    type Error = Error;
+
+    /// Read from the I2C bus
+    ///
+    /// Please refer to the [embedded-hal documentation] for details.
+    ///
+    /// # Limitations
+    ///
+    /// Reading multiple bytes should work, but has not been tested.
+    ///
+    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.1/embedded_hal/blocking/i2c/trait.Read.html#tymethod.read
+    fn read(
+        &mut self,
+        address: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        // Wait until peripheral is idle
+        untimed(self.wait_for_idle(&mut Never))?;
+
+        untimed(self.read_bytes(address, buffer, &mut Never))
+    }
+}
+
+impl<I, Pins> i2c::WriteIterRead for I2C<I, init_state::Enabled<Pins>>
+where
+    I: Instance,
+{
+    type Error = Error;
+
+    /// Write to the I2C bus from an iterator, then read from it
+    ///
+    /// Both happen in a single transaction, separated by a repeated start
+    /// condition. Each byte is taken from the iterator right before it is
+    /// sent, so the data doesn't need to be stored in a buffer first.
+    ///
+    /// Please refer to the [embedded-hal documentation] for details.
+    ///
+    /// # Limitations
+    ///
+    /// Reading multiple bytes should work, but has not been tested.
+    ///
+    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.5/embedded_hal/blocking/i2c/trait.WriteIterRead.html#tymethod.write_iter_read
+    fn write_iter_read<B>(
+        &mut self,
+        address: u8,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        untimed(self.write_bytes(address, bytes, &mut Never))?;
+        untimed(self.read_bytes(address, buffer, &mut Never))
+    }
+}
+
 impl<I, State> I2C<I, State>
 where
     I: Instance,
@@ -274,6 +1133,298 @@ where
     }
 }
 
+/// Iterator over the slaves on an I2C bus
+///
+/// Returned by [`I2C::scan`].
+///
+/// [`I2C::scan`]: struct.I2C.html#method.scan
+pub struct Scan<'i2c, I, Pins> {
+    i2c: &'i2c mut I2C<I, init_state::Enabled<Pins>>,
+
+    /// The next 7-bit address to probe
+    next: Option<u8>,
+}
+
+impl<I, Pins> Iterator for Scan<'_, I, Pins>
+where
+    I: Instance,
+{
+    type Item = Result<u8, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(address) = self.next {
+            self.next = if address < 0x77 {
+                Some(address + 1)
+            } else {
+                None
+            };
+
+            match self.i2c.probe(address << 1) {
+                Ok(true) => return Some(Ok(address << 1)),
+                Ok(false) => {}
+                Err(err) => {
+                    self.next = None;
+                    return Some(Err(err));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// The SWM functions that are kept by an enabled [`I2C`] instance
+///
+/// [`I2C`]: struct.I2C.html
+pub type SwmFunctions<I, SdaPin, SclPin> = (
+    swm::Function<<I as Instance>::Sda, swm::state::Assigned<SdaPin>>,
+    swm::Function<<I as Instance>::Scl, swm::state::Assigned<SclPin>>,
+);
+
+/// Unwrap the result of an operation that was passed [`Never`]
+fn untimed<T>(result: Result<T, timeout::Error<Error>>) -> Result<T, Error> {
+    result.map_err(|err| match err {
+        timeout::Error::Other(err) => err,
+        timeout::Error::Timeout => unreachable!("`Never` doesn't expire"),
+    })
+}
+
+/// Statistics of an I2C instance
+///
+/// Returned by [`I2C::stats`]. The counters wrap around on overflow.
+///
+/// Only available, if the `serial-stats` feature is enabled.
+///
+/// [`I2C::stats`]: struct.I2C.html#method.stats
+#[cfg(feature = "serial-stats")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stats {
+    /// The number of data bytes that have been written to slaves
+    pub bytes_written: u32,
+
+    /// The number of data bytes that have been read from slaves
+    pub bytes_read: u32,
+
+    /// The number of times a slave didn't acknowledge its address or a byte
+    ///
+    /// Addresses that aren't acknowledged during [`I2C::probe`] or
+    /// [`I2C::scan`] are not counted.
+    ///
+    /// [`I2C::probe`]: struct.I2C.html#method.probe
+    /// [`I2C::scan`]: struct.I2C.html#method.scan
+    pub nacks: u32,
+
+    /// The number of times another master took control of the bus
+    pub arbitration_losses: u32,
+
+    /// The number of start or stop conditions at an illegal time
+    pub start_stop_errors: u32,
+
+    /// The number of transactions that didn't complete within their timeout
+    pub timeouts: u32,
+}
+
+#[cfg(feature = "serial-stats")]
+impl Stats {
+    const fn new() -> Self {
+        Self {
+            bytes_written: 0,
+            bytes_read: 0,
+            nacks: 0,
+            arbitration_losses: 0,
+            start_stop_errors: 0,
+            timeouts: 0,
+        }
+    }
+}
+
+// The statistics are kept per instance, so they survive `disable`/`enable`.
+#[cfg(feature = "serial-stats")]
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: Cell<Stats> = Cell::new(Stats::new());
+
+#[cfg(feature = "serial-stats")]
+static STATS: Mutex<[Cell<Stats>; 4]> = Mutex::new([ZERO; 4]);
+
+#[cfg(feature = "serial-stats")]
+fn update_stats<I: Instance>(f: impl FnOnce(&mut Stats)) {
+    interrupt::free(|cs| {
+        let cell = &STATS.borrow(cs)[I::NUM];
+        let mut stats = cell.get();
+        f(&mut stats);
+        cell.set(stats);
+    })
+}
+
+#[cfg(feature = "serial-stats")]
+fn count_error<I: Instance>(err: Error) {
+    update_stats::<I>(|stats| {
+        let counter = match err {
+            Error::ArbitrationLoss => &mut stats.arbitration_losses,
+            Error::StartStopError => &mut stats.start_stop_errors,
+            Error::NackAddress | Error::NackData => &mut stats.nacks,
+            Error::UnexpectedState => return,
+        };
+        *counter = counter.wrapping_add(1);
+    });
+}
+
+/// The configuration of an I2C instance
+///
+/// Returned by [`I2C::suspend`], and passed to [`I2C::resume`].
+///
+/// [`I2C::suspend`]: struct.I2C.html#method.suspend
+/// [`I2C::resume`]: struct.I2C.html#method.resume
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+    cfg: u32,
+    clkdiv: u32,
+    msttime: u32,
+    timeout: u32,
+    slvadr: [u32; 4],
+    slvqual0: u32,
+    intenset: u32,
+}
+
+/// Describes how transfers to and from a memory are split
+///
+/// EEPROMs, and similar memories, are organized in pages. A single write
+/// can't cross a page boundary. Instead, the memory wraps around to the start
+/// of the same page, overwriting data that was written before. Please refer to
+/// the data sheet of the memory for its page size.
+///
+/// Passed to [`I2C::write_paged`] and [`I2C::read_large`].
+///
+/// [`I2C::write_paged`]: struct.I2C.html#method.write_paged
+/// [`I2C::read_large`]: struct.I2C.html#method.read_large
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Paging {
+    /// The page size of the memory, in bytes
+    pub page_size: u16,
+
+    /// The number of bytes the memory address is sent as
+    ///
+    /// Sent most significant byte first. Small memories typically use 1 byte,
+    /// larger ones 2.
+    pub address_len: u8,
+
+    /// The number of memory address bits that are part of the I2C address
+    ///
+    /// Some memories, like the 24C04, 24C08, and 24C16, are larger than
+    /// `address_len` bytes can address. They expect the upper bits of the
+    /// memory address in the I2C address instead, right above the R/W bit.
+    /// A 24C16, for example, uses 1 address byte and 3 of these bits.
+    pub block_bits: u8,
+
+    /// How many times the memory is polled, while it finishes writing a page
+    ///
+    /// Each attempt sends the memory's address, which takes about 25 µs at
+    /// 400 kHz. The memory acknowledges as soon as it has finished writing.
+    pub max_polls: u32,
+}
+
+impl Paging {
+    /// Create a `Paging` for the given page size and address length
+    ///
+    /// `max_polls` is set to 1000, which is enough for a write cycle of 10 ms
+    /// at bus speeds of up to 400 kHz.
+    pub const fn new(page_size: u16, address_len: u8) -> Self {
+        Self {
+            page_size,
+            address_len,
+            block_bits: 0,
+            max_polls: 1000,
+        }
+    }
+
+    /// Check the configuration, and whether it can address `len` bytes,
+    /// starting at `mem_address`
+    fn check(&self, mem_address: u32, len: usize) {
+        assert!(self.page_size > 0);
+        assert!(self.address_len >= 1 && self.address_len <= 4);
+        assert!(self.block_bits <= 3);
+
+        // Memory addresses are `u32`, so more bits couldn't be used anyway.
+        let bits = u32::from(self.address_len) * 8 + u32::from(self.block_bits);
+        let end = u64::from(mem_address) + len as u64;
+        assert!(end <= 1 << bits.min(32));
+    }
+
+    /// The I2C address of the block that contains `mem_address`
+    fn device_address(&self, address: u8, mem_address: u32) -> u8 {
+        let block = mem_address
+            .checked_shr(u32::from(self.address_len) * 8)
+            .unwrap_or(0);
+        address | ((block as u8) << 1)
+    }
+
+    /// The memory address, as it's sent to the memory
+    fn address_bytes(&self, mem_address: u32) -> impl Iterator<Item = u8> {
+        let skip = 4 - usize::from(self.address_len);
+        IntoIterator::into_iter(mem_address.to_be_bytes()).skip(skip)
+    }
+}
+
+flags! {
+    /// The flags in the STAT register of an I2C instance
+    ///
+    /// Returned by [`I2C::read_and_clear_events`]. `MASTER_ARBITRATION_LOSS`,
+    /// `MASTER_START_STOP_ERROR`, `SLAVE_DESELECTED`, `MONITOR_OVERRUN`,
+    /// `MONITOR_IDLE`, `EVENT_TIMEOUT`, and `SCL_TIMEOUT` are cleared by that
+    /// method. The other flags reflect the current state of the peripheral.
+    ///
+    /// The multi-bit state fields of the register are not included.
+    ///
+    /// [`I2C::read_and_clear_events`]: struct.I2C.html#method.read_and_clear_events
+    pub struct Events {
+        /// The master function needs attention (MSTPENDING)
+        const MASTER_PENDING = 0;
+
+        /// The master function has lost arbitration (MSTARBLOSS)
+        const MASTER_ARBITRATION_LOSS = 4;
+
+        /// A start or stop condition was detected at an illegal time
+        /// (MSTSTSTPERR)
+        const MASTER_START_STOP_ERROR = 6;
+
+        /// The slave function needs attention (SLVPENDING)
+        const SLAVE_PENDING = 8;
+
+        /// The slave function is not stretching the clock (SLVNOTSTR)
+        const SLAVE_NOT_STRETCHING = 11;
+
+        /// The slave function is selected (SLVSEL)
+        const SLAVE_SELECTED = 14;
+
+        /// The slave function has been deselected (SLVDESEL)
+        const SLAVE_DESELECTED = 15;
+
+        /// The monitor function has received data (MONRDY)
+        const MONITOR_READY = 16;
+
+        /// The monitor function has lost data (MONOV)
+        const MONITOR_OVERRUN = 17;
+
+        /// The monitor function sees bus activity (MONACTIVE)
+        const MONITOR_ACTIVE = 18;
+
+        /// The bus has become idle, as seen by the monitor function (MONIDLE)
+        const MONITOR_IDLE = 19;
+
+        /// The time between bus events was longer than allowed
+        /// (EVENTTIMEOUT)
+        const EVENT_TIMEOUT = 24;
+
+        /// SCL was low for longer than allowed (SCLTIMEOUT)
+        const SCL_TIMEOUT = 25;
+    }
+}
+
+impl Events {
+    /// The flags that are cleared by writing a 1 to them
+    const CLEARABLE: Self = Self::from_bits_truncate(0x030a_8050);
+}
+
 /// Internal trait for I2C peripherals
 ///
 /// This trait is an internal implementation detail and should neither be
@@ -287,36 +1438,46 @@ pub trait Instance:
     /// The interrupt that is triggered for this I2C peripheral
     const INTERRUPT: Interrupt;
 
+    /// The number of this instance (e.g. `0` for I2C0)
+    const NUM: usize;
+
     /// The movable function that needs to be assigned to this I2C's SDA pin
     type Sda;
 
     /// The movable function that needs to be assigned to this I2C's SCL pin
     type Scl;
+
+    /// Enables this I2C's interrupt as a wake-up source
+    type Wakeup: syscon::WakeUpInterrupt;
 }
 
 macro_rules! instances {
     (
         $(
             $instance:ident,
+            $num:expr,
             $interrupt:ident,
             $rx:ident,
-            $tx:ident;
+            $tx:ident,
+            $wakeup:ident;
         )*
     ) => {
         $(
             impl Instance for pac::$instance {
                 const INTERRUPT: Interrupt = Interrupt::$interrupt;
+                const NUM: usize = $num;
 
                 type Sda = swm::$rx;
                 type Scl = swm::$tx;
+                type Wakeup = syscon::$wakeup;
             }
         )*
     };
 }
 
 instances!(
-    I2C0, I2C0, I2C0_SDA, I2C0_SCL;
-    I2C1, I2C1, I2C1_SDA, I2C1_SCL;
-    I2C2, I2C2, I2C2_SDA, I2C2_SCL;
-    I2C3, I2C3, I2C3_SDA, I2C3_SCL;
+    I2C0, 0, I2C0, I2C0_SDA, I2C0_SCL, I2c0Wakeup;
+    I2C1, 1, I2C1, I2C1_SDA, I2C1_SCL, I2c1Wakeup;
+    I2C2, 2, I2C2, I2C2_SDA, I2C2_SCL, I2c2Wakeup;
+    I2C3, 3, I2C3, I2C3_SDA, I2C3_SCL, I2c3Wakeup;
 );
@@ -0,0 +1,21 @@
+/// An I2C error
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Another master took control of the bus (MSTARBLOSS)
+    ArbitrationLoss,
+
+    /// A start or stop condition was detected at an illegal time (MSTSTSTPERR)
+    StartStopError,
+
+    /// The slave didn't acknowledge its address
+    NackAddress,
+
+    /// The slave didn't acknowledge a data byte
+    NackData,
+
+    /// The master function was in a state it should never be in
+    ///
+    /// This shouldn't happen, unless the peripheral has been manipulated
+    /// outside of this API.
+    UnexpectedState,
+}
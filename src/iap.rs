@@ -0,0 +1,633 @@
+//! API for the in-application programming (IAP) functions of the boot ROM
+//!
+//! The boot ROM provides a number of functions that can be called by the
+//! application. This module provides access to the functions for erasing and
+//! writing flash memory, as well as the "Reinvoke ISP" command, which enters
+//! the serial bootloader (in-system programming, ISP), so the firmware can be
+//! updated without access to the ISP pin.
+//!
+//! [`FlashRegion`] builds on the flash functions, to use a part of the flash
+//! that isn't occupied by the program for storing data.
+//!
+//! The IAP functions use the top 32 bytes of RAM. The linker configuration
+//! provided by LPC8xx HAL reserves those, by placing the stack below them.
+//!
+//! IAP and ISP are described in the user manual, in the chapter about the
+//! boot ROM's flash programming functions.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::iap::{self, IspMode};
+//!
+//! // Enter the bootloader, for example after receiving a command to do so.
+//! // This function never returns.
+//! iap::reinvoke_isp(IspMode::Usart);
+//! ```
+//!
+//! [`FlashRegion`]: struct.FlashRegion.html
+
+use core::{mem, slice};
+
+#[cfg(feature = "embedded-storage")]
+use embedded_storage::nor_flash;
+
+use crate::{
+    activity,
+    pac::{self, NVIC, SYST},
+};
+
+/// The size of a flash sector in bytes
+pub const SECTOR_SIZE: usize = 1024;
+
+/// The size of a flash page in bytes
+pub const PAGE_SIZE: usize = 64;
+
+/// The size of the flash memory in bytes
+///
+/// If only the `82x` feature is enabled, this is the size of the LPC822's
+/// flash, which is what the linker configuration assumes in that case too.
+#[cfg(all(feature = "82x", not(feature = "824")))]
+pub const FLASH_SIZE: usize = 16 * 1024;
+
+/// The size of the flash memory in bytes
+#[cfg(feature = "824")]
+pub const FLASH_SIZE: usize = 32 * 1024;
+
+/// The size of the flash memory in bytes
+#[cfg(feature = "845")]
+pub const FLASH_SIZE: usize = 64 * 1024;
+
+/// The entry point of the IAP functions in the boot ROM
+const IAP_LOCATION: usize = 0x1fff_1ff1;
+
+// IAP command codes
+const PREPARE_SECTORS: u32 = 50;
+const COPY_RAM_TO_FLASH: u32 = 51;
+const ERASE_SECTORS: u32 = 52;
+const REINVOKE_ISP: u32 = 57;
+const ERASE_PAGES: u32 = 59;
+
+/// Erase a range of flash sectors
+///
+/// Erases all sectors from `first` to `last` (inclusive). `system_clock` is
+/// the frequency of the system clock in Hz, which the boot ROM needs for
+/// timing the erase operation.
+///
+/// Interrupts are disabled while the flash is being erased, as no code can
+/// be executed from flash in the meantime.
+pub fn erase_sectors(
+    first: u32,
+    last: u32,
+    system_clock: u32,
+) -> Result<(), Error> {
+    call([PREPARE_SECTORS, first, last, 0, 0])?;
+    call([ERASE_SECTORS, first, last, system_clock / 1000, 0])
+}
+
+/// Erase a range of flash pages
+///
+/// Erases all pages from `first` to `last` (inclusive). Otherwise works like
+/// [`erase_sectors`].
+///
+/// [`erase_sectors`]: fn.erase_sectors.html
+pub fn erase_pages(
+    first: u32,
+    last: u32,
+    system_clock: u32,
+) -> Result<(), Error> {
+    let first_sector = first * PAGE_SIZE as u32 / SECTOR_SIZE as u32;
+    let last_sector = last * PAGE_SIZE as u32 / SECTOR_SIZE as u32;
+
+    call([PREPARE_SECTORS, first_sector, last_sector, 0, 0])?;
+    call([ERASE_PAGES, first, last, system_clock / 1000, 0])
+}
+
+/// Write a flash page
+///
+/// Writes `data` to page number `page`, which must have been erased before.
+/// Otherwise works like [`erase_sectors`].
+///
+/// [`erase_sectors`]: fn.erase_sectors.html
+pub fn write_page(
+    page: u32,
+    data: &[u8; PAGE_SIZE],
+    system_clock: u32,
+) -> Result<(), Error> {
+    // The boot ROM requires the source to be word-aligned.
+    let mut buffer = [0u32; PAGE_SIZE / 4];
+    for (word, bytes) in buffer.iter_mut().zip(data.chunks(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    let sector = page * PAGE_SIZE as u32 / SECTOR_SIZE as u32;
+    let address = page * PAGE_SIZE as u32;
+
+    call([PREPARE_SECTORS, sector, sector, 0, 0])?;
+    call([
+        COPY_RAM_TO_FLASH,
+        address,
+        buffer.as_ptr() as u32,
+        PAGE_SIZE as u32,
+        system_clock / 1000,
+    ])
+}
+
+/// A region of flash memory that is used for storing data
+///
+/// Provides byte-addressed access to a range of whole sectors, using the IAP
+/// functions for writing and erasing. Offsets are relative to the start of
+/// the region. The region must not overlap the program image, as the
+/// application would overwrite its own code otherwise. This can't be checked
+/// by the HAL, which is why [`new`] is `unsafe`.
+///
+/// The methods follow the conventions of the NOR flash traits from the
+/// `embedded-storage` crate, which `FlashRegion` implements if the
+/// `embedded-storage` feature is enabled:
+///
+/// - Any range can be read.
+/// - Writes must start at a multiple of [`WRITE_SIZE`], and their length must
+///   be a multiple of it. Flash can only be written after it has been erased.
+/// - Erasing works on whole multiples of [`ERASE_SIZE`]. Erased flash reads
+///   as `0xff`.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::iap::FlashRegion;
+///
+/// // The last 4 sectors of the LPC845's 64 KiB of flash. Sound, as long as
+/// // the program image is smaller than 60 KiB.
+/// let mut region = unsafe { FlashRegion::new(60, 4, 12_000_000) }.unwrap();
+///
+/// region.erase(0, FlashRegion::ERASE_SIZE as u32).unwrap();
+/// region.write(0, &[0x55; FlashRegion::WRITE_SIZE]).unwrap();
+///
+/// let mut buffer = [0; 16];
+/// region.read(0, &mut buffer).unwrap();
+/// ```
+///
+/// [`new`]: #method.new
+/// [`WRITE_SIZE`]: #associatedconstant.WRITE_SIZE
+/// [`ERASE_SIZE`]: #associatedconstant.ERASE_SIZE
+pub struct FlashRegion {
+    first_sector: u32,
+    sectors: u32,
+    system_clock: u32,
+}
+
+impl FlashRegion {
+    /// The alignment of reads, in bytes
+    pub const READ_SIZE: usize = 1;
+
+    /// The alignment of writes, in bytes
+    pub const WRITE_SIZE: usize = PAGE_SIZE;
+
+    /// The alignment of erase operations, in bytes
+    pub const ERASE_SIZE: usize = PAGE_SIZE;
+
+    /// Create a new instance of `FlashRegion`
+    ///
+    /// The region starts at sector `first_sector`, and consists of `sectors`
+    /// sectors. `system_clock` is the frequency of the system clock in Hz,
+    /// which is required for programming the flash.
+    ///
+    /// Returns an error, if the region is empty, or if it extends beyond the
+    /// end of the flash.
+    ///
+    /// # Safety
+    ///
+    /// The region must not overlap the program image, which includes the code,
+    /// the read-only data, and the initial values of `.data`. Otherwise the
+    /// program can erase or overwrite itself. The HAL doesn't know where the
+    /// image ends, so the caller must make sure of this, for example by
+    /// reserving the region in the linker configuration.
+    pub unsafe fn new(
+        first_sector: u32,
+        sectors: u32,
+        system_clock: u32,
+    ) -> Result<Self, RegionError> {
+        if sectors == 0 {
+            return Err(RegionError::Empty);
+        }
+
+        let end = first_sector
+            .checked_add(sectors)
+            .ok_or(RegionError::OutOfBounds)?;
+        if end as usize > FLASH_SIZE / SECTOR_SIZE {
+            return Err(RegionError::OutOfBounds);
+        }
+
+        Ok(Self {
+            first_sector,
+            sectors,
+            system_clock,
+        })
+    }
+
+    /// The size of the region in bytes
+    pub fn capacity(&self) -> usize {
+        self.sectors as usize * SECTOR_SIZE
+    }
+
+    /// Read from the region, starting at `offset`
+    ///
+    /// Fills all of `bytes`.
+    pub fn read(
+        &mut self,
+        offset: u32,
+        bytes: &mut [u8],
+    ) -> Result<(), RegionError> {
+        self.check_bounds(offset, bytes.len())?;
+
+        let address = self.start() + offset as usize;
+
+        // Sound, as flash memory is always mapped and readable, and we just
+        // checked that the range is within the region.
+        let flash =
+            unsafe { slice::from_raw_parts(address as *const u8, bytes.len()) };
+        bytes.copy_from_slice(flash);
+
+        Ok(())
+    }
+
+    /// Write to the region, starting at `offset`
+    ///
+    /// `offset` and the length of `bytes` must be multiples of
+    /// [`WRITE_SIZE`], and the range must have been erased before.
+    ///
+    /// Interrupts are disabled while each page is being written.
+    ///
+    /// [`WRITE_SIZE`]: #associatedconstant.WRITE_SIZE
+    pub fn write(
+        &mut self,
+        offset: u32,
+        bytes: &[u8],
+    ) -> Result<(), RegionError> {
+        self.check_bounds(offset, bytes.len())?;
+        if !is_aligned(offset as usize, Self::WRITE_SIZE)
+            || !is_aligned(bytes.len(), Self::WRITE_SIZE)
+        {
+            return Err(RegionError::NotAligned);
+        }
+
+        let first_page = self.first_page() + offset / PAGE_SIZE as u32;
+        for (n, chunk) in bytes.chunks(PAGE_SIZE).enumerate() {
+            let mut page = [0; PAGE_SIZE];
+            page.copy_from_slice(chunk);
+
+            write_page(first_page + n as u32, &page, self.system_clock)?;
+        }
+
+        Ok(())
+    }
+
+    /// Erase the region from `from` up to, but not including, `to`
+    ///
+    /// Both offsets must be multiples of [`ERASE_SIZE`].
+    ///
+    /// Interrupts are disabled while the flash is being erased.
+    ///
+    /// [`ERASE_SIZE`]: #associatedconstant.ERASE_SIZE
+    pub fn erase(&mut self, from: u32, to: u32) -> Result<(), RegionError> {
+        if from > to || to as usize > self.capacity() {
+            return Err(RegionError::OutOfBounds);
+        }
+        if !is_aligned(from as usize, Self::ERASE_SIZE)
+            || !is_aligned(to as usize, Self::ERASE_SIZE)
+        {
+            return Err(RegionError::NotAligned);
+        }
+        if from == to {
+            return Ok(());
+        }
+
+        let first = self.first_page() + from / PAGE_SIZE as u32;
+        let last = self.first_page() + to / PAGE_SIZE as u32 - 1;
+        erase_pages(first, last, self.system_clock)?;
+
+        Ok(())
+    }
+
+    fn start(&self) -> usize {
+        self.first_sector as usize * SECTOR_SIZE
+    }
+
+    fn first_page(&self) -> u32 {
+        self.first_sector * (SECTOR_SIZE / PAGE_SIZE) as u32
+    }
+
+    fn check_bounds(&self, offset: u32, len: usize) -> Result<(), RegionError> {
+        match (offset as usize).checked_add(len) {
+            Some(end) if end <= self.capacity() => Ok(()),
+            _ => Err(RegionError::OutOfBounds),
+        }
+    }
+}
+
+fn is_aligned(value: usize, alignment: usize) -> bool {
+    value / alignment * alignment == value
+}
+
+/// An error returned by [`FlashRegion`]
+///
+/// [`FlashRegion`]: struct.FlashRegion.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RegionError {
+    /// The region has a size of zero sectors
+    Empty,
+
+    /// The region extends beyond the end of the flash, or the access would go
+    /// beyond the end of the region
+    OutOfBounds,
+
+    /// The offset or length is not aligned as required
+    NotAligned,
+
+    /// The boot ROM returned an error
+    Iap(Error),
+}
+
+impl From<Error> for RegionError {
+    fn from(error: Error) -> Self {
+        RegionError::Iap(error)
+    }
+}
+
+#[cfg(feature = "embedded-storage")]
+impl nor_flash::NorFlashError for RegionError {
+    fn kind(&self) -> nor_flash::NorFlashErrorKind {
+        match self {
+            RegionError::OutOfBounds => {
+                nor_flash::NorFlashErrorKind::OutOfBounds
+            }
+            RegionError::NotAligned => nor_flash::NorFlashErrorKind::NotAligned,
+            _ => nor_flash::NorFlashErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(feature = "embedded-storage")]
+impl nor_flash::ErrorType for FlashRegion {
+    type Error = RegionError;
+}
+
+#[cfg(feature = "embedded-storage")]
+impl nor_flash::ReadNorFlash for FlashRegion {
+    const READ_SIZE: usize = FlashRegion::READ_SIZE;
+
+    fn read(
+        &mut self,
+        offset: u32,
+        bytes: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        FlashRegion::read(self, offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        FlashRegion::capacity(self)
+    }
+}
+
+#[cfg(feature = "embedded-storage")]
+impl nor_flash::NorFlash for FlashRegion {
+    const WRITE_SIZE: usize = FlashRegion::WRITE_SIZE;
+    const ERASE_SIZE: usize = FlashRegion::ERASE_SIZE;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        FlashRegion::erase(self, from, to)
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        FlashRegion::write(self, offset, bytes)
+    }
+}
+
+/// The interface the bootloader uses, when entered through [`reinvoke_isp`]
+///
+/// [`reinvoke_isp`]: fn.reinvoke_isp.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IspMode {
+    /// ISP via USART0
+    Usart = 1,
+
+    /// ISP via I2C0
+    I2c = 2,
+
+    /// ISP via SPI0
+    Spi = 3,
+}
+
+/// Enter the serial bootloader (ISP)
+///
+/// Calls the "Reinvoke ISP" command of the boot ROM, which doesn't return.
+/// The bootloader communicates using the interface selected by `mode`, on the
+/// same pins it uses when entered via the ISP pin after reset. Please refer to
+/// the user manual for details.
+///
+/// Since this function never returns, it doesn't matter what state the rest of
+/// the application is in. Before calling into the boot ROM, it restores the
+/// state that the bootloader expects:
+///
+/// - Interrupts are disabled, and all interrupts are disabled and unpended in
+///   the NVIC.
+/// - The SysTick timer is stopped.
+/// - The internal oscillator (IRC/FRO) is powered up, if necessary, and the
+///   main clock is switched back to it, at its default frequency of 12 MHz.
+///   The system clock divider is reset.
+/// - The peripherals that are used by the bootloader (USART0, I2C0, SPI0) are
+///   reset. On LPC845, the switch matrix and IOCON are reset too.
+///
+/// Everything else is left as configured. This includes the switch matrix
+/// and IOCON on LPC82x, which can't be reset without resetting the whole
+/// microcontroller. On LPC82x, the pins used by the bootloader must therefore
+/// not be assigned to any movable function.
+///
+/// # Limitations
+///
+/// If the watchdog timer is running, it will reset the microcontroller, unless
+/// the update finishes before it times out.
+///
+/// On LPC845, the frequency of the FRO can be changed using the boot ROM's
+/// FRO API. If that has been done, the bootloader won't communicate at the
+/// expected baud rate.
+pub fn reinvoke_isp(mode: IspMode) -> ! {
+    cortex_m::interrupt::disable();
+
+    // Sound, as interrupts are disabled and this function never returns, so
+    // any code that might rely on the state we're changing here won't run
+    // again.
+    unsafe {
+        let nvic = &*NVIC::ptr();
+        nvic.icer[0].write(0xffff_ffff);
+        nvic.icpr[0].write(0xffff_ffff);
+
+        let syst = &*SYST::PTR;
+        syst.csr.write(0);
+
+        reset_syscon(&*pac::SYSCON::ptr());
+    }
+
+    let command = [REINVOKE_ISP, mode as u32, 0, 0, 0];
+    let mut result = [0; 5];
+    iap(&command, &mut result);
+
+    // The bootloader doesn't return. If we get here, something went very
+    // wrong, and the best we can do is reset.
+    cortex_m::peripheral::SCB::sys_reset();
+}
+
+/// An error returned by the boot ROM
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Invalid command
+    InvalidCommand,
+
+    /// Source address is not on a word boundary
+    SrcAddrError,
+
+    /// Destination address is not on a correct boundary
+    DstAddrError,
+
+    /// Source address is not mapped in the memory map
+    SrcAddrNotMapped,
+
+    /// Destination address is not mapped in the memory map
+    DstAddrNotMapped,
+
+    /// Byte count is not a multiple of 4, or is not a permitted value
+    CountError,
+
+    /// Sector or page number is invalid, or end is smaller than start
+    InvalidSector,
+
+    /// Sector is not blank
+    SectorNotBlank,
+
+    /// Command to prepare sector for write operation was not executed
+    SectorNotPrepared,
+
+    /// Source and destination data is not the same
+    CompareError,
+
+    /// Flash programming interface is busy
+    Busy,
+
+    /// Another error, identified by the status code returned by the boot ROM
+    Other(u32),
+}
+
+impl Error {
+    fn from_code(code: u32) -> Self {
+        match code {
+            1 => Error::InvalidCommand,
+            2 => Error::SrcAddrError,
+            3 => Error::DstAddrError,
+            4 => Error::SrcAddrNotMapped,
+            5 => Error::DstAddrNotMapped,
+            6 => Error::CountError,
+            7 => Error::InvalidSector,
+            8 => Error::SectorNotBlank,
+            9 => Error::SectorNotPrepared,
+            10 => Error::CompareError,
+            11 => Error::Busy,
+            code => Error::Other(code),
+        }
+    }
+}
+
+/// Call an IAP command that returns a status code
+fn call(command: [u32; 5]) -> Result<(), Error> {
+    let mut result = [0; 5];
+
+    activity::notify();
+
+    // The flash can't be read while it's being written to, so no interrupt
+    // handlers may run in the meantime.
+    cortex_m::interrupt::free(|_| iap(&command, &mut result));
+
+    activity::notify();
+
+    match result[0] {
+        0 => Ok(()),
+        code => Err(Error::from_code(code)),
+    }
+}
+
+fn iap(command: &[u32; 5], result: &mut [u32; 5]) {
+    // Sound, as `IAP_LOCATION` is the entry point of the IAP functions on all
+    // supported targets.
+    let iap: extern "C" fn(*const u32, *mut u32) =
+        unsafe { mem::transmute(IAP_LOCATION) };
+    iap(command.as_ptr(), result.as_mut_ptr());
+}
+
+#[cfg(feature = "82x")]
+fn reset_syscon(syscon: &pac::syscon::RegisterBlock) {
+    let pdruncfg = syscon.pdruncfg.read();
+    if pdruncfg.irc_pd().bit_is_set() || pdruncfg.ircout_pd().bit_is_set() {
+        syscon
+            .pdruncfg
+            .modify(|_, w| w.irc_pd().powered().ircout_pd().powered());
+        wait_for_oscillator();
+    }
+
+    syscon.mainclksel.write(|w| w.sel().irc_osc());
+    syscon.mainclkuen.write(|w| w.ena().clear_bit());
+    syscon.mainclkuen.write(|w| w.ena().set_bit());
+    syscon.sysahbclkdiv.write(|w| unsafe { w.div().bits(1) });
+
+    syscon.presetctrl.modify(|_, w| {
+        w.uart0_rst_n().clear_bit();
+        w.i2c0_rst_n().clear_bit();
+        w.spi0_rst_n().clear_bit()
+    });
+    syscon.presetctrl.modify(|_, w| {
+        w.uart0_rst_n().set_bit();
+        w.i2c0_rst_n().set_bit();
+        w.spi0_rst_n().set_bit()
+    });
+}
+
+#[cfg(feature = "845")]
+fn reset_syscon(syscon: &pac::syscon::RegisterBlock) {
+    let pdruncfg = syscon.pdruncfg.read();
+    if pdruncfg.fro_pd().bit_is_set() || pdruncfg.froout_pd().bit_is_set() {
+        syscon
+            .pdruncfg
+            .modify(|_, w| w.fro_pd().powered().froout_pd().powered());
+        wait_for_oscillator();
+    }
+
+    syscon.mainclksel.write(|w| w.sel().fro());
+    syscon.mainclkuen.write(|w| w.ena().clear_bit());
+    syscon.mainclkuen.write(|w| w.ena().set_bit());
+    syscon.mainclkpllsel.write(|w| w.sel().main_clk_pre_pll());
+    syscon.mainclkplluen.write(|w| w.ena().clear_bit());
+    syscon.mainclkplluen.write(|w| w.ena().set_bit());
+    syscon.sysahbclkdiv.write(|w| unsafe { w.div().bits(1) });
+
+    syscon.presetctrl0.modify(|_, w| {
+        w.uart0_rst_n().clear_bit();
+        w.i2c0_rst_n().clear_bit();
+        w.spi0_rst_n().clear_bit();
+        w.swm_rst_n().clear_bit();
+        w.iocon_rst_n().clear_bit()
+    });
+    syscon.presetctrl0.modify(|_, w| {
+        w.uart0_rst_n().set_bit();
+        w.i2c0_rst_n().set_bit();
+        w.spi0_rst_n().set_bit();
+        w.swm_rst_n().set_bit();
+        w.iocon_rst_n().set_bit()
+    });
+}
+
+/// Wait until the internal oscillator has started up after being powered up
+///
+/// The oscillator has no status flag, so this waits for a fixed number of
+/// cycles instead. The main clock still runs from its previous source, which
+/// is at most 30 MHz, so this takes at least 33 µs.
+fn wait_for_oscillator() {
+    cortex_m::asm::delay(1000);
+}
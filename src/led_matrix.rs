@@ -0,0 +1,235 @@
+//! Driver for multiplexed LED matrices
+//!
+//! An LED matrix connects the LEDs in rows and columns, so `ROWS * COLS` LEDs
+//! can be driven using only `ROWS + COLS` pins. Only one row is lit at any
+//! time. [`LedMatrix`] cycles through the rows fast enough that the eye sees
+//! a steady image, which it reads from a frame buffer.
+//!
+//! Each LED has a brightness from 0 (off) to 255 (fully on), which is
+//! implemented by only lighting an LED during some of the refresh cycles
+//! (duty cycling). The number of brightness levels is configurable. More
+//! levels require a faster tick to avoid visible flicker.
+//!
+//! The rows and columns are each driven by a [`PinBus`], so each of them has
+//! to be on a single port, and consist of up to 8 pins. Small matrices can be
+//! driven directly from the pins, but keep the current limits of the pins in
+//! mind. Since all LEDs of a row can be lit at the same time, the row pins
+//! usually drive a transistor. The active levels of rows and columns are
+//! configurable, to support both common anode and common cathode matrices, as
+//! well as inverting drivers.
+//!
+//! The driver needs to be ticked at regular intervals, either by calling
+//! [`LedMatrix::update`] (for example from an MRT interrupt handler), or by
+//! passing a periodic timer to [`LedMatrix::poll`]. The refresh rate of the
+//! whole matrix is the tick frequency, divided by the number of rows and the
+//! number of brightness levels. It should be at least 100 Hz.
+//!
+//! Charlieplexing, which requires switching pins between output and input at
+//! runtime, is not supported.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     prelude::*,
+//!     Peripherals,
+//!     gpio::{Level, PinBus},
+//!     led_matrix::LedMatrix,
+//! };
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let gpio = p.GPIO;
+//! #[cfg(feature = "845")]
+//! let gpio = p.GPIO.enable(&mut syscon.handle);
+//!
+//! let rows = PinBus::new((
+//!     p.pins.pio0_12.into_output_pin(gpio.tokens.pio0_12, Level::Low),
+//!     p.pins.pio0_13.into_output_pin(gpio.tokens.pio0_13, Level::Low),
+//!     p.pins.pio0_14.into_output_pin(gpio.tokens.pio0_14, Level::Low),
+//! ));
+//! let cols = PinBus::new((
+//!     p.pins.pio0_15.into_output_pin(gpio.tokens.pio0_15, Level::High),
+//!     p.pins.pio0_16.into_output_pin(gpio.tokens.pio0_16, Level::High),
+//!     p.pins.pio0_17.into_output_pin(gpio.tokens.pio0_17, Level::High),
+//! ));
+//!
+//! // Rows are high-side switched, columns sink the LED current. 16
+//! // brightness levels.
+//! let mut matrix = LedMatrix::new(rows, cols, Level::High, Level::Low, 16);
+//!
+//! matrix.set(0, 0, 255);
+//! matrix.set(1, 1, 64);
+//! matrix.set(2, 2, 8);
+//!
+//! // 3 rows * 16 levels * 100 Hz = 4.8 kHz (the MRT runs at 12 MHz by
+//! // default)
+//! let mut timer = p.MRT0.split(&mut syscon.handle).mrt0;
+//! timer.start(2_500u32);
+//!
+//! loop {
+//!     nb::block!(matrix.poll(&mut timer)).unwrap();
+//! }
+//! ```
+//!
+//! [`LedMatrix`]: struct.LedMatrix.html
+//! [`PinBus`]: ../gpio/struct.PinBus.html
+//! [`LedMatrix::update`]: struct.LedMatrix.html#method.update
+//! [`LedMatrix::poll`]: struct.LedMatrix.html#method.poll
+
+use embedded_hal::timer::CountDown;
+use void::Void;
+
+use crate::gpio::{direction::Output, BusPins, Level, PinBus};
+
+/// Drives a multiplexed LED matrix
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct LedMatrix<R, C, const ROWS: usize, const COLS: usize> {
+    rows: PinBus<R, ROWS>,
+    cols: PinBus<C, COLS>,
+
+    /// XORed with bus values, to account for the active levels
+    rows_invert: u32,
+    cols_invert: u32,
+
+    frame: [[u8; COLS]; ROWS],
+    levels: u8,
+
+    /// The row that is currently lit
+    row: usize,
+
+    /// The current step of the duty cycle
+    phase: u8,
+}
+
+impl<R, C, const ROWS: usize, const COLS: usize> LedMatrix<R, C, ROWS, COLS>
+where
+    R: BusPins<ROWS, Direction = Output>,
+    C: BusPins<COLS, Direction = Output>,
+{
+    /// Create a new LED matrix driver
+    ///
+    /// `row_active` and `col_active` are the levels that light the LEDs in a
+    /// row and column. `levels` is the number of brightness levels, which
+    /// must be at least 1. With 1 level, LEDs are either on or off.
+    ///
+    /// All LEDs start out being off.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `levels` is 0.
+    pub fn new(
+        rows: PinBus<R, ROWS>,
+        cols: PinBus<C, COLS>,
+        row_active: Level,
+        col_active: Level,
+        levels: u8,
+    ) -> Self {
+        assert!(levels > 0);
+
+        let invert = |active: Level| match active {
+            Level::High => 0,
+            Level::Low => !0,
+        };
+
+        let mut matrix = Self {
+            rows,
+            cols,
+            rows_invert: invert(row_active),
+            cols_invert: invert(col_active),
+            frame: [[0; COLS]; ROWS],
+            levels,
+            row: 0,
+            phase: 0,
+        };
+
+        matrix.cols.write(matrix.cols_invert);
+        matrix.rows.write(matrix.rows_invert);
+
+        matrix
+    }
+
+    /// Set the brightness of a single LED
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `row` or `col` are out of range.
+    pub fn set(&mut self, row: usize, col: usize, brightness: u8) {
+        self.frame[row][col] = brightness;
+    }
+
+    /// Turn off all LEDs
+    pub fn clear(&mut self) {
+        self.frame = [[0; COLS]; ROWS];
+    }
+
+    /// Provide access to the frame buffer
+    ///
+    /// Contains the brightness of each LED, indexed by row, then column.
+    pub fn frame_mut(&mut self) -> &mut [[u8; COLS]; ROWS] {
+        &mut self.frame
+    }
+
+    /// Light the next row
+    ///
+    /// Needs to be called at regular intervals, as described in the
+    /// [module documentation].
+    ///
+    /// [module documentation]: index.html
+    pub fn update(&mut self) {
+        self.row += 1;
+        if self.row >= ROWS {
+            self.row = 0;
+            self.phase += 1;
+            if self.phase >= self.levels {
+                self.phase = 0;
+            }
+        }
+
+        let mut pattern = 0;
+        for (i, &brightness) in self.frame[self.row].iter().enumerate() {
+            // The number of phases during which the LED is on. Rounded up, so
+            // any non-zero brightness is visible, and 255 is always on.
+            let on = (brightness as u16 * self.levels as u16).div_ceil(256);
+            if on > self.phase as u16 {
+                pattern |= 1 << i;
+            }
+        }
+
+        // Turn off the columns while switching rows, so the previous row's
+        // pattern doesn't briefly show up in the new row.
+        self.cols.write(self.cols_invert);
+        self.rows.write((1 << self.row) ^ self.rows_invert);
+        self.cols.write(pattern ^ self.cols_invert);
+    }
+
+    /// Light the next row, whenever a periodic timer has expired
+    ///
+    /// Returns `nb::Error::WouldBlock`, if the timer hasn't expired yet. The
+    /// timer needs to have been started with the desired tick interval.
+    pub fn poll<T>(&mut self, timer: &mut T) -> nb::Result<(), Void>
+    where
+        T: CountDown,
+    {
+        if timer.wait().is_err() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.update();
+        Ok(())
+    }
+
+    /// Turn off all LEDs and release the pins
+    pub fn free(mut self) -> (PinBus<R, ROWS>, PinBus<C, COLS>) {
+        self.cols.write(self.cols_invert);
+        self.rows.write(self.rows_invert);
+
+        (self.rows, self.cols)
+    }
+}
@@ -104,28 +104,146 @@ pub extern crate cortex_m_rt;
 pub extern crate embedded_hal;
 pub extern crate nb;
 
+// Most of the HAL requires a target. Building without one is only supported
+// with the `host-test` feature. Then only the mocks and the types they use are
+// available, which allows them to be used in unit tests on the host, without
+// the PAC.
+#[cfg(any(feature = "82x", feature = "845"))]
+#[macro_use]
+pub(crate) mod flags;
+#[cfg(any(feature = "82x", feature = "845"))]
 #[macro_use]
 pub(crate) mod reg_proxy;
 
+#[cfg(any(feature = "82x", feature = "845"))]
+#[cfg(feature = "activity-hook")]
+pub mod activity;
+#[cfg(any(feature = "82x", feature = "845"))]
+#[cfg(not(feature = "activity-hook"))]
+mod activity {
+    /// Does nothing, as the `activity-hook` feature is disabled
+    #[inline(always)]
+    pub(crate) fn notify() {}
+}
+
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod adc;
+#[cfg(any(feature = "82x", feature = "845"))]
+#[cfg(feature = "brk")]
+pub mod brk;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod bus;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod clock;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod crc;
 #[cfg(feature = "845")]
 pub mod ctimer;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod debounce;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod delay;
+#[cfg(any(feature = "82x", feature = "845"))]
+#[cfg(feature = "interrupt-dispatch")]
+pub mod dispatch;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod dma;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod dmx512;
+#[cfg(any(feature = "82x", feature = "845"))]
+#[cfg(feature = "fault-report")]
+pub mod fault;
+#[cfg(any(feature = "82x", feature = "845"))]
+#[cfg(feature = "fwupdate")]
+pub mod fwupdate;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod gpio;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod i2c;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod iap;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod led_matrix;
+#[cfg(any(feature = "82x", feature = "845"))]
+#[cfg(feature = "modbus-rtu")]
+pub mod modbus;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod mrt;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod one_wire;
+#[cfg(any(feature = "82x", feature = "845"))]
+#[cfg(feature = "panic-usart")]
+pub mod panic_usart;
 #[cfg(feature = "845")]
 pub mod pinint;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod pins;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod pmu;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod rc_timer;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod rotary_encoder;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod sct;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod servo;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod sleep;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod soft_i2c;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod soft_serial;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod spi;
+#[cfg(any(feature = "82x", feature = "845"))]
+#[cfg(feature = "spi-flash")]
+pub mod spi_flash;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod swm;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod syscon;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod timeout;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod timer_queue;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod tone;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod uptime;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod usart;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod wkt;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub mod ws2812;
+
+#[cfg(any(test, feature = "host-test"))]
+pub mod mock;
+
+/// The GPIO types that are available without a target
+#[cfg(not(any(feature = "82x", feature = "845")))]
+pub mod gpio {
+    mod level;
+
+    pub use self::level::Level;
+}
+
+/// The I2C types that are available without a target
+#[cfg(not(any(feature = "82x", feature = "845")))]
+pub mod i2c {
+    mod error;
+
+    pub use self::error::Error;
+}
+
+/// The SPI types that are available without a target
+#[cfg(not(any(feature = "82x", feature = "845")))]
+pub mod spi {
+    mod error;
+
+    pub use self::error::Error;
+}
 
 /// Re-exports various traits that are required to use lpc8xx-hal
 ///
@@ -139,6 +257,7 @@ pub mod wkt;
 ///
 /// The traits in this module have been renamed, to avoid collisions with other
 /// imports.
+#[cfg(any(feature = "82x", feature = "845"))]
 pub mod prelude {
     pub use core::fmt::Write as _;
 
@@ -152,24 +271,44 @@ pub use lpc82x_pac as pac;
 #[cfg(feature = "845")]
 pub use lpc845_pac as pac;
 
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::adc::ADC;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub use self::crc::CRC;
 #[cfg(feature = "845")]
 pub use self::ctimer::CTimer;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::dma::DMA;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::gpio::GPIO;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::i2c::I2C;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::mrt::MRT;
 #[cfg(feature = "845")]
 pub use self::pinint::PININT;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::pmu::PMU;
+#[cfg(any(feature = "82x", feature = "845"))]
+pub use self::sct::SCT;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::spi::SPI;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::swm::SWM;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::syscon::SYSCON;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::usart::USART;
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use self::wkt::WKT;
 
+#[cfg(any(feature = "82x", feature = "845"))]
 pub use pac::CorePeripherals;
 
+#[cfg(any(feature = "82x", feature = "845"))]
+use core::sync::atomic::{AtomicU8, Ordering};
+
+#[cfg(any(feature = "82x", feature = "845"))]
 use embedded_hal as hal;
 
 /// Provides access to all peripherals
@@ -205,6 +344,7 @@ use embedded_hal as hal;
 ///
 /// [`Peripherals::take`]: #method.take
 /// [`Peripherals::steal`]: #method.steal
+#[cfg(any(feature = "82x", feature = "845"))]
 #[allow(non_snake_case)]
 pub struct Peripherals {
     /// Pins that can be used for GPIO or other functions
@@ -213,6 +353,9 @@ pub struct Peripherals {
     /// Analog-to-Digital Converter (ADC)
     pub ADC: ADC<init_state::Disabled>,
 
+    /// CRC engine
+    pub CRC: CRC<init_state::Disabled>,
+
     /// Standard counter/timer (CTIMER)
     #[cfg(feature = "845")]
     pub CTIMER0: CTimer,
@@ -247,6 +390,9 @@ pub struct Peripherals {
     /// Power Management Unit
     pub PMU: PMU,
 
+    /// State Configurable Timer (SCT)
+    pub SCT0: SCT,
+
     /// SPI0
     pub SPI0: SPI<pac::SPI0, init_state::Disabled>,
 
@@ -317,13 +463,6 @@ pub struct Peripherals {
     #[cfg(feature = "845")]
     pub CAPT: pac::CAPT,
 
-    /// CRC engine
-    ///
-    /// A HAL API for this peripheral has not been implemented yet. In the
-    /// meantime, this field provides you with the raw register mappings, which
-    /// allow you full, unprotected access to the peripheral.
-    pub CRC: pac::CRC,
-
     /// Digital-to-Analog Converter 0 (DAC0)
     ///
     /// A HAL API for this peripheral has not been implemented yet. In the
@@ -396,13 +535,6 @@ pub struct Peripherals {
     #[cfg(feature = "82x")]
     pub PININT: pac::PINT,
 
-    /// State Configurable Timer (SCT)
-    ///
-    /// A HAL API for this peripheral has not been implemented yet. In the
-    /// meantime, this field provides you with the raw register mappings, which
-    /// allow you full, unprotected access to the peripheral.
-    pub SCT0: pac::SCT0,
-
     /// Windowed Watchdog Timer (WWDT)
     ///
     /// A HAL API for this peripheral has not been implemented yet. In the
@@ -411,12 +543,14 @@ pub struct Peripherals {
     pub WWDT: pac::WWDT,
 }
 
+#[cfg(any(feature = "82x", feature = "845"))]
 impl Peripherals {
     /// Take the peripherals safely
     ///
     /// This method can only be called one time to access the peripherals. It
     /// will return `Some(Peripherals)` when called for the first time, then
-    /// `None` on any subsequent calls.
+    /// `None` on any subsequent calls, unless the peripherals have been
+    /// returned using [`Peripherals::free`] in the meantime.
     ///
     /// Applications should call this method once, at the beginning of their
     /// main method, to get access to the full API. Any other parts of the
@@ -429,7 +563,8 @@ impl Peripherals {
     /// them.
     ///
     /// For an alternative way to gain access to the hardware, please take a
-    /// look at [`Peripherals::steal`].
+    /// look at [`Peripherals::steal`]. If you need to know why the peripherals
+    /// couldn't be taken, use [`Peripherals::try_take`].
     ///
     /// # Example
     ///
@@ -442,7 +577,92 @@ impl Peripherals {
     /// let p = Peripherals::take().unwrap();
     /// ```
     pub fn take() -> Option<Self> {
-        Some(Self::new(pac::Peripherals::take()?))
+        Self::try_take().ok()
+    }
+
+    /// Take the peripherals safely, reporting why that failed, if it did
+    ///
+    /// Works like [`Peripherals::take`], but returns an error that explains
+    /// why the peripherals couldn't be taken. This can be used to tell a
+    /// programming error in the application (taking the peripherals twice)
+    /// apart from a conflict with other code that accessed the raw
+    /// peripherals of the PAC.
+    ///
+    /// After the peripherals have been returned using [`Peripherals::free`],
+    /// this method can take them again.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{Peripherals, TakeError};
+    ///
+    /// match Peripherals::try_take() {
+    ///     Ok(p) => {
+    ///         // use the peripherals
+    ///     }
+    ///     Err(TakeError::AlreadyTaken) => {
+    ///         // `take` or `try_take` has been called before
+    ///     }
+    ///     Err(TakeError::PacPeripheralsTaken) => {
+    ///         // the PAC peripherals have been taken by other code
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`Peripherals::take`]: #method.take
+    /// [`Peripherals::free`]: #method.free
+    pub fn try_take() -> Result<Self, TakeError> {
+        cortex_m::interrupt::free(|_| {
+            match PERIPHERALS.load(Ordering::SeqCst) {
+                UNTAKEN => {
+                    let p = pac::Peripherals::take()
+                        .ok_or(TakeError::PacPeripheralsTaken)?;
+                    PERIPHERALS.store(TAKEN, Ordering::SeqCst);
+                    Ok(Self::new(p))
+                }
+                FREED => {
+                    PERIPHERALS.store(TAKEN, Ordering::SeqCst);
+
+                    // Sound, as we took the PAC peripherals before, and the
+                    // only instance of `Peripherals` that was created from
+                    // them has since been returned to us.
+                    Ok(unsafe { Self::new(pac::Peripherals::steal()) })
+                }
+                _ => Err(TakeError::AlreadyTaken),
+            }
+        })
+    }
+
+    /// Return the peripherals, so they can be taken again
+    ///
+    /// Consumes this instance of `Peripherals`. Afterwards,
+    /// [`Peripherals::take`] and [`Peripherals::try_take`] will succeed once
+    /// more. This is useful for test harnesses that run multiple tests in
+    /// sequence, or for a bootloader that hands control over to an
+    /// application that takes the peripherals itself.
+    ///
+    /// Since all fields of `Peripherals` need to be returned, each peripheral
+    /// has to be in the state it was in when the peripherals were taken (for
+    /// example, disabled). Please note that this only applies to the type
+    /// state. Any other hardware configuration (the state of the raw
+    /// peripherals, for example) is not reset.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::Peripherals;
+    ///
+    /// let p = Peripherals::take().unwrap();
+    /// p.free();
+    ///
+    /// // The peripherals can be taken again
+    /// let p = Peripherals::take().unwrap();
+    /// ```
+    ///
+    /// [`Peripherals::take`]: #method.take
+    /// [`Peripherals::try_take`]: #method.try_take
+    pub fn free(self) {
+        PERIPHERALS.store(FREED, Ordering::SeqCst);
     }
 
     /// Steal the peripherals
@@ -495,6 +715,7 @@ impl Peripherals {
 
             // HAL peripherals
             ADC: ADC::new(p.ADC0),
+            CRC: CRC::new(p.CRC),
             #[cfg(feature = "845")]
             CTIMER0: CTimer::new(p.CTIMER0),
             DMA: DMA::new(p.DMA0),
@@ -504,6 +725,7 @@ impl Peripherals {
             #[cfg(feature = "845")]
             PININT: PININT::new(p.PINT),
             PMU: PMU::new(p.PMU),
+            SCT0: SCT::new(p.SCT0),
             SPI0: SPI::new(p.SPI0),
             SPI1: SPI::new(p.SPI1),
             SWM: SWM::new(p.SWM0),
@@ -521,7 +743,6 @@ impl Peripherals {
             ACOMP: p.ACOMP,
             #[cfg(feature = "845")]
             CAPT: p.CAPT,
-            CRC: p.CRC,
             #[cfg(feature = "845")]
             DAC0: p.DAC0,
             #[cfg(feature = "845")]
@@ -534,12 +755,42 @@ impl Peripherals {
             IOCON: p.IOCON,
             #[cfg(feature = "82x")]
             PININT: p.PINT,
-            SCT0: p.SCT0,
             WWDT: p.WWDT,
         }
     }
 }
 
+/// The reason why [`Peripherals::try_take`] failed
+///
+/// [`Peripherals::try_take`]: struct.Peripherals.html#method.try_take
+#[cfg(any(feature = "82x", feature = "845"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TakeError {
+    /// The peripherals have already been taken
+    ///
+    /// `Peripherals::take` or `Peripherals::try_take` has been called before,
+    /// and the peripherals haven't been returned using `Peripherals::free`.
+    AlreadyTaken,
+
+    /// The raw peripherals of the PAC have been taken or stolen by other code
+    ///
+    /// This happens, if `pac::Peripherals::take` or `pac::Peripherals::steal`
+    /// was called before the peripherals were first taken through this API.
+    PacPeripheralsTaken,
+}
+
+// Tracks the ownership of the peripherals, for `Peripherals::try_take` and
+// `Peripherals::free`.
+#[cfg(any(feature = "82x", feature = "845"))]
+static PERIPHERALS: AtomicU8 = AtomicU8::new(UNTAKEN);
+
+#[cfg(any(feature = "82x", feature = "845"))]
+const UNTAKEN: u8 = 0;
+#[cfg(any(feature = "82x", feature = "845"))]
+const TAKEN: u8 = 1;
+#[cfg(any(feature = "82x", feature = "845"))]
+const FREED: u8 = 2;
+
 /// Contains types that encode the state of hardware initialization
 ///
 /// The types in this module are used by structs representing peripherals or
@@ -0,0 +1,683 @@
+//! Mock pins, SPI, and I2C for unit tests on the host
+//!
+//! This module is only available, if the `host-test` feature is enabled.
+//!
+//! Driver and application code that is written against the `embedded-hal`
+//! traits can be tested on a development machine or CI server, by passing it
+//! the mocks from this module instead of the real peripherals. Each mock is
+//! created from a script: a list of the transactions that the code under test
+//! is expected to perform, in order, along with the responses to return.
+//!
+//! The mocks panic, as soon as the code under test does something that
+//! doesn't match the script. Call `done` at the end of the test, to make sure
+//! all transactions in the script have been performed.
+//!
+//! The mocks use the same error types as the real implementations, so code
+//! that expects [`i2c::Error`] or [`spi::Error`] can be tested too.
+//!
+//! Besides the traits, the mocks provide the inherent methods of the concrete
+//! HAL types that don't depend on the hardware configuration:
+//!
+//! - [`PinMock`]: `set_high_fast`, `set_low_fast`, and `toggle_fast`, like
+//!   [`GpioPin`], as well as `StatefulOutputPin` and `ToggleableOutputPin`.
+//! - [`SpiMock`]: `write_only` and `transfer_and_deselect`, like [`SPI`].
+//! - [`I2cMock`]: `probe`, like [`I2C`].
+//!
+//! Code that uses the concrete types can therefore be tested, by replacing
+//! them with the mocks in test builds, for example using a type alias:
+//!
+//! ``` ignore
+//! #[cfg(not(test))]
+//! type Led = GpioPin<PIO0_12, direction::Output>;
+//! #[cfg(test)]
+//! type Led = lpc8xx_hal::mock::PinMock<'static>;
+//! ```
+//!
+//! The `host-test` feature can be enabled without selecting a target. Then the
+//! PAC isn't built, and only this module, [`gpio::Level`], [`i2c::Error`],
+//! and [`spi::Error`] are available. For example, a crate that uses the HAL
+//! only through the `embedded-hal` traits can run its tests on the host like
+//! this:
+//!
+//! ``` toml
+//! [dev-dependencies.lpc8xx-hal]
+//! version  = "0.6"
+//! features = ["host-test"]
+//! ```
+//!
+//! If a target is selected too, the rest of the HAL is available as usual.
+//!
+//! # Example
+//!
+//! ```
+//! use embedded_hal::blocking::i2c::WriteIterRead;
+//! use lpc8xx_hal::{
+//!     i2c,
+//!     mock::{I2cMock, I2cTransaction},
+//! };
+//!
+//! // The code under test. It can be passed the real `I2C` too.
+//! fn read_temperature<I>(i2c: &mut I) -> Result<u8, i2c::Error>
+//! where
+//!     I: WriteIterRead<Error = i2c::Error>,
+//! {
+//!     let mut buffer = [0];
+//!     i2c.write_iter_read(0x90, [0x00].iter().copied(), &mut buffer)?;
+//!     Ok(buffer[0])
+//! }
+//!
+//! let mut i2c = I2cMock::new(&[
+//!     I2cTransaction::WriteRead(0x90, &[0x00], &[21]),
+//!     I2cTransaction::Error(i2c::Error::NackAddress),
+//! ]);
+//!
+//! assert_eq!(read_temperature(&mut i2c), Ok(21));
+//! assert_eq!(read_temperature(&mut i2c), Err(i2c::Error::NackAddress));
+//!
+//! i2c.done();
+//! ```
+//!
+//! [`GpioPin`]: ../gpio/struct.GpioPin.html
+//! [`SPI`]: ../spi/struct.SPI.html
+//! [`I2C`]: ../i2c/struct.I2C.html
+//! [`gpio::Level`]: ../gpio/enum.Level.html
+//! [`i2c::Error`]: ../i2c/enum.Error.html
+//! [`spi::Error`]: ../spi/enum.Error.html
+
+use core::convert::Infallible;
+
+use embedded_hal::{
+    blocking::{i2c as blocking_i2c, spi as blocking_spi},
+    digital::v2::{
+        InputPin, OutputPin, StatefulOutputPin, ToggleableOutputPin,
+    },
+};
+
+use crate::{gpio::Level, i2c, spi};
+
+/// A transaction that is expected by [`PinMock`]
+///
+/// [`PinMock`]: struct.PinMock.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PinTransaction {
+    /// The pin is read, and has the given level
+    ///
+    /// Matches a call to either `is_high` or `is_low`.
+    Get(Level),
+
+    /// The pin is set to the given level
+    ///
+    /// Matches a call to `set_high`, `set_low`, or `toggle`, or to one of
+    /// their `_fast` variants.
+    Set(Level),
+}
+
+/// A mock pin, implementing `InputPin` and `OutputPin`
+///
+/// `StatefulOutputPin` returns the level that the pin was last set to, without
+/// consuming a transaction. `toggle` panics, if the pin hasn't been set yet.
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct PinMock<'s> {
+    script: Script<'s, PinTransaction>,
+    output: Option<Level>,
+}
+
+impl<'s> PinMock<'s> {
+    /// Create a mock pin that expects the given transactions
+    pub fn new(transactions: &'s [PinTransaction]) -> Self {
+        Self {
+            script: Script::new("pin", transactions),
+            output: None,
+        }
+    }
+
+    /// Panic, if not all transactions have been performed
+    pub fn done(&self) {
+        self.script.done()
+    }
+
+    /// Set the pin output to HIGH, like [`GpioPin::set_high_fast`]
+    ///
+    /// [`GpioPin::set_high_fast`]: ../gpio/struct.GpioPin.html#method.set_high_fast
+    pub fn set_high_fast(&mut self) {
+        self.set(Level::High)
+    }
+
+    /// Set the pin output to LOW, like [`GpioPin::set_low_fast`]
+    ///
+    /// [`GpioPin::set_low_fast`]: ../gpio/struct.GpioPin.html#method.set_low_fast
+    pub fn set_low_fast(&mut self) {
+        self.set(Level::Low)
+    }
+
+    /// Toggle the pin output, like [`GpioPin::toggle_fast`]
+    ///
+    /// [`GpioPin::toggle_fast`]: ../gpio/struct.GpioPin.html#method.toggle_fast
+    pub fn toggle_fast(&mut self) {
+        match self.output {
+            Some(Level::High) => self.set(Level::Low),
+            Some(Level::Low) => self.set(Level::High),
+            None => panic!("pin: toggled before its level was set"),
+        }
+    }
+
+    fn get(&self) -> Level {
+        match self.script.next() {
+            PinTransaction::Get(level) => level,
+            transaction => {
+                panic!("pin: expected {:?}, but pin was read", transaction)
+            }
+        }
+    }
+
+    fn set(&mut self, level: Level) {
+        let transaction = self.script.next();
+        assert_eq!(transaction, PinTransaction::Set(level), "pin");
+        self.output = Some(level);
+    }
+
+    fn output(&self) -> Level {
+        self.output
+            .unwrap_or_else(|| panic!("pin: read before its level was set"))
+    }
+}
+
+impl InputPin for PinMock<'_> {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        Ok(self.get() == Level::High)
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        Ok(self.get() == Level::Low)
+    }
+}
+
+impl OutputPin for PinMock<'_> {
+    type Error = Infallible;
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(Level::High);
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(Level::Low);
+        Ok(())
+    }
+}
+
+impl StatefulOutputPin for PinMock<'_> {
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        Ok(self.output() == Level::High)
+    }
+
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        Ok(self.output() == Level::Low)
+    }
+}
+
+impl ToggleableOutputPin for PinMock<'_> {
+    type Error = Infallible;
+
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.toggle_fast();
+        Ok(())
+    }
+}
+
+/// A transaction that is expected by [`SpiMock`]
+///
+/// [`SpiMock`]: struct.SpiMock.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpiTransaction<'s> {
+    /// A transfer that sends the first slice, and receives the second
+    ///
+    /// Both slices must have the same length. Matches a call to `transfer` or
+    /// `transfer_and_deselect`.
+    Transfer(&'s [u8], &'s [u8]),
+
+    /// A write of the given data
+    ///
+    /// Matches a call to `write` or `write_only`.
+    Write(&'s [u8]),
+
+    /// The next transfer or write fails with the given error
+    Error(spi::Error),
+}
+
+/// A mock SPI, implementing the blocking `Transfer` and `Write` traits
+///
+/// Also provides [`write_only`] and [`transfer_and_deselect`], like [`SPI`].
+/// The mock doesn't model slave select, so they work like `write` and
+/// `transfer`.
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [`write_only`]: #method.write_only
+/// [`transfer_and_deselect`]: #method.transfer_and_deselect
+/// [`SPI`]: ../spi/struct.SPI.html
+/// [module documentation]: index.html
+pub struct SpiMock<'s> {
+    script: Script<'s, SpiTransaction<'s>>,
+}
+
+impl<'s> SpiMock<'s> {
+    /// Create a mock SPI that expects the given transactions
+    pub fn new(transactions: &'s [SpiTransaction<'s>]) -> Self {
+        Self {
+            script: Script::new("SPI", transactions),
+        }
+    }
+
+    /// Panic, if not all transactions have been performed
+    pub fn done(&self) {
+        self.script.done()
+    }
+
+    /// Send data without reading anything back, like [`SPI::write_only`]
+    ///
+    /// [`SPI::write_only`]: ../spi/struct.SPI.html#method.write_only
+    pub fn write_only(&mut self, words: &[u8]) -> Result<(), spi::Error> {
+        blocking_spi::Write::write(self, words)
+    }
+
+    /// Transfer data, like [`SPI::transfer_and_deselect`]
+    ///
+    /// [`SPI::transfer_and_deselect`]: ../spi/struct.SPI.html#method.transfer_and_deselect
+    pub fn transfer_and_deselect<'w>(
+        &mut self,
+        words: &'w mut [u8],
+    ) -> Result<&'w [u8], spi::Error> {
+        blocking_spi::Transfer::transfer(self, words)
+    }
+}
+
+impl blocking_spi::Transfer<u8> for SpiMock<'_> {
+    type Error = spi::Error;
+
+    fn transfer<'w>(
+        &mut self,
+        words: &'w mut [u8],
+    ) -> Result<&'w [u8], Self::Error> {
+        match self.script.next() {
+            SpiTransaction::Transfer(tx, rx) => {
+                assert_eq!(&*words, tx, "SPI: unexpected data sent");
+                words.copy_from_slice(rx);
+                Ok(words)
+            }
+            SpiTransaction::Error(err) => Err(err),
+            transaction => {
+                panic!("SPI: expected {:?}, got transfer", transaction)
+            }
+        }
+    }
+}
+
+impl blocking_spi::Write<u8> for SpiMock<'_> {
+    type Error = spi::Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        match self.script.next() {
+            SpiTransaction::Write(data) => {
+                assert_eq!(words, data, "SPI: unexpected data sent");
+                Ok(())
+            }
+            SpiTransaction::Error(err) => Err(err),
+            transaction => panic!("SPI: expected {:?}, got write", transaction),
+        }
+    }
+}
+
+/// A transaction that is expected by [`I2cMock`]
+///
+/// Addresses have the same format as for the real I2C API, meaning the 7-bit
+/// address is in the upper 7 bits.
+///
+/// [`I2cMock`]: struct.I2cMock.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum I2cTransaction<'s> {
+    /// A write of the given data to the given address
+    Write(u8, &'s [u8]),
+
+    /// A read from the given address, that receives the given data
+    Read(u8, &'s [u8]),
+
+    /// A write of the first slice, followed by a read that receives the
+    /// second slice
+    WriteRead(u8, &'s [u8], &'s [u8]),
+
+    /// A probe of the given address, which is acknowledged, if the second
+    /// field is `true`
+    Probe(u8, bool),
+
+    /// The next operation fails with the given error
+    Error(i2c::Error),
+}
+
+/// A mock I2C, implementing the same blocking traits as [`I2C`]
+///
+/// These are `Write`, `WriteIter`, `Read`, and `WriteIterRead`. Also
+/// provides [`probe`], like [`I2C`].
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [`probe`]: #method.probe
+/// [`I2C`]: ../i2c/struct.I2C.html
+/// [module documentation]: index.html
+pub struct I2cMock<'s> {
+    script: Script<'s, I2cTransaction<'s>>,
+}
+
+impl<'s> I2cMock<'s> {
+    /// Create a mock I2C that expects the given transactions
+    pub fn new(transactions: &'s [I2cTransaction<'s>]) -> Self {
+        Self {
+            script: Script::new("I2C", transactions),
+        }
+    }
+
+    /// Panic, if not all transactions have been performed
+    pub fn done(&self) {
+        self.script.done()
+    }
+
+    /// Check whether a slave acknowledges an address, like [`I2C::probe`]
+    ///
+    /// [`I2C::probe`]: ../i2c/struct.I2C.html#method.probe
+    pub fn probe(&mut self, address: u8) -> Result<bool, i2c::Error> {
+        match self.script.next() {
+            I2cTransaction::Probe(addr, acknowledged) => {
+                assert_eq!(address, addr, "I2C: unexpected address");
+                Ok(acknowledged)
+            }
+            I2cTransaction::Error(err) => Err(err),
+            transaction => panic!("I2C: expected {:?}, got probe", transaction),
+        }
+    }
+}
+
+impl blocking_i2c::Write for I2cMock<'_> {
+    type Error = i2c::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        match self.script.next() {
+            I2cTransaction::Write(addr, data) => {
+                assert_eq!(address, addr, "I2C: unexpected address");
+                assert_eq!(bytes, data, "I2C: unexpected data written");
+                Ok(())
+            }
+            I2cTransaction::Error(err) => Err(err),
+            transaction => panic!("I2C: expected {:?}, got write", transaction),
+        }
+    }
+}
+
+impl blocking_i2c::Read for I2cMock<'_> {
+    type Error = i2c::Error;
+
+    fn read(
+        &mut self,
+        address: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        match self.script.next() {
+            I2cTransaction::Read(addr, data) => {
+                assert_eq!(address, addr, "I2C: unexpected address");
+                buffer.copy_from_slice(data);
+                Ok(())
+            }
+            I2cTransaction::Error(err) => Err(err),
+            transaction => panic!("I2C: expected {:?}, got read", transaction),
+        }
+    }
+}
+
+impl blocking_i2c::WriteIter for I2cMock<'_> {
+    type Error = i2c::Error;
+
+    fn write<B>(&mut self, address: u8, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        match self.script.next() {
+            I2cTransaction::Write(addr, data) => {
+                assert_eq!(address, addr, "I2C: unexpected address");
+                assert_bytes(bytes, data);
+                Ok(())
+            }
+            I2cTransaction::Error(err) => Err(err),
+            transaction => panic!("I2C: expected {:?}, got write", transaction),
+        }
+    }
+}
+
+impl blocking_i2c::WriteIterRead for I2cMock<'_> {
+    type Error = i2c::Error;
+
+    fn write_iter_read<B>(
+        &mut self,
+        address: u8,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        match self.script.next() {
+            I2cTransaction::WriteRead(addr, written, read) => {
+                assert_eq!(address, addr, "I2C: unexpected address");
+                assert_bytes(bytes, written);
+                buffer.copy_from_slice(read);
+                Ok(())
+            }
+            I2cTransaction::Error(err) => Err(err),
+            transaction => {
+                panic!("I2C: expected {:?}, got write_iter_read", transaction)
+            }
+        }
+    }
+}
+
+/// Panic, if `bytes` doesn't yield exactly `expected`
+fn assert_bytes(bytes: impl IntoIterator<Item = u8>, expected: &[u8]) {
+    let mut bytes = bytes.into_iter();
+
+    for (i, &b) in expected.iter().enumerate() {
+        assert_eq!(bytes.next(), Some(b), "I2C: unexpected byte {}", i);
+    }
+    assert_eq!(bytes.next(), None, "I2C: more bytes written than expected");
+}
+
+/// The expected transactions of a mock, and how far they have progressed
+///
+/// Uses a `Cell`, as `InputPin` only provides `&self`.
+struct Script<'s, T> {
+    name: &'static str,
+    transactions: &'s [T],
+    next: core::cell::Cell<usize>,
+}
+
+impl<'s, T> Script<'s, T>
+where
+    T: Copy + core::fmt::Debug,
+{
+    fn new(name: &'static str, transactions: &'s [T]) -> Self {
+        Self {
+            name,
+            transactions,
+            next: core::cell::Cell::new(0),
+        }
+    }
+
+    fn next(&self) -> T {
+        let index = self.next.get();
+        let transaction = *self.transactions.get(index).unwrap_or_else(|| {
+            panic!("{}: no more transactions expected", self.name)
+        });
+        self.next.set(index + 1);
+
+        transaction
+    }
+
+    fn done(&self) {
+        let remaining = &self.transactions[self.next.get()..];
+        assert!(
+            remaining.is_empty(),
+            "{}: expected transactions weren't performed: {:?}",
+            self.name,
+            remaining,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal::{
+        blocking::{i2c::WriteIterRead as _, spi::Transfer as _},
+        digital::v2::{
+            InputPin as _, OutputPin as _, StatefulOutputPin as _,
+            ToggleableOutputPin as _,
+        },
+    };
+
+    use crate::{gpio::Level, i2c, spi};
+
+    use super::{
+        I2cMock, I2cTransaction, PinMock, PinTransaction, SpiMock,
+        SpiTransaction,
+    };
+
+    #[test]
+    fn pin_should_follow_script() {
+        let mut pin = PinMock::new(&[
+            PinTransaction::Get(Level::High),
+            PinTransaction::Set(Level::Low),
+        ]);
+
+        assert_eq!(pin.is_low(), Ok(false));
+        pin.set_low().unwrap();
+
+        pin.done();
+    }
+
+    #[test]
+    fn pin_should_track_output_level() {
+        let mut pin = PinMock::new(&[
+            PinTransaction::Set(Level::High),
+            PinTransaction::Set(Level::Low),
+            PinTransaction::Set(Level::High),
+        ]);
+
+        pin.set_high_fast();
+        pin.toggle().unwrap();
+        assert_eq!(pin.is_set_low(), Ok(true));
+        pin.toggle_fast();
+        assert_eq!(pin.is_set_high(), Ok(true));
+
+        pin.done();
+    }
+
+    #[test]
+    #[should_panic(expected = "toggled before its level was set")]
+    fn pin_should_panic_on_toggle_without_level() {
+        let mut pin = PinMock::new(&[PinTransaction::Set(Level::High)]);
+        pin.toggle_fast();
+    }
+
+    #[test]
+    #[should_panic]
+    fn pin_should_panic_on_unexpected_level() {
+        let mut pin = PinMock::new(&[PinTransaction::Set(Level::Low)]);
+        pin.set_high().unwrap();
+    }
+
+    #[test]
+    fn spi_should_return_scripted_data_and_errors() {
+        let mut spi = SpiMock::new(&[
+            SpiTransaction::Transfer(&[0x9f, 0xff], &[0x00, 0xef]),
+            SpiTransaction::Error(spi::Error::RxOverrun),
+        ]);
+
+        let mut words = [0x9f, 0xff];
+        assert_eq!(spi.transfer(&mut words), Ok(&[0x00, 0xef][..]));
+        assert_eq!(spi.transfer(&mut words), Err(spi::Error::RxOverrun));
+
+        spi.done();
+    }
+
+    #[test]
+    fn spi_should_match_inherent_methods() {
+        let mut spi = SpiMock::new(&[
+            SpiTransaction::Write(&[0x06]),
+            SpiTransaction::Transfer(&[0x05, 0xff], &[0x00, 0x02]),
+        ]);
+
+        assert_eq!(spi.write_only(&[0x06]), Ok(()));
+        assert_eq!(
+            spi.transfer_and_deselect(&mut [0x05, 0xff]),
+            Ok(&[0x00, 0x02][..])
+        );
+
+        spi.done();
+    }
+
+    #[test]
+    #[should_panic]
+    fn spi_should_panic_on_unexpected_data() {
+        let mut spi = SpiMock::new(&[SpiTransaction::Transfer(&[1], &[0])]);
+        spi.transfer(&mut [2]).unwrap();
+    }
+
+    #[test]
+    fn i2c_should_check_written_bytes() {
+        let mut i2c =
+            I2cMock::new(&[I2cTransaction::WriteRead(0x90, &[0x00], &[21])]);
+
+        let mut buffer = [0];
+        i2c.write_iter_read(0x90, [0x00].iter().copied(), &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, [21]);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn i2c_should_return_scripted_probe_results() {
+        let mut i2c = I2cMock::new(&[
+            I2cTransaction::Probe(0xa0, false),
+            I2cTransaction::Probe(0xa0, true),
+        ]);
+
+        assert_eq!(i2c.probe(0xa0), Ok(false));
+        assert_eq!(i2c.probe(0xa0), Ok(true));
+
+        i2c.done();
+    }
+
+    #[test]
+    #[should_panic(expected = "more bytes written than expected")]
+    fn i2c_should_panic_on_extra_bytes() {
+        let mut i2c =
+            I2cMock::new(&[I2cTransaction::WriteRead(0x90, &[0x00], &[21])]);
+
+        let mut buffer = [0];
+        let _ = i2c.write_iter_read(
+            0x90,
+            [0x00, 0x01].iter().copied(),
+            &mut buffer,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "expected transactions weren't performed")]
+    fn done_should_panic_on_remaining_transactions() {
+        let i2c =
+            I2cMock::new(&[I2cTransaction::Error(i2c::Error::NackAddress)]);
+        i2c.done();
+    }
+}
@@ -0,0 +1,291 @@
+//! Modbus RTU framing
+//!
+//! This module is only available, if the `modbus-rtu` feature is enabled.
+//!
+//! Modbus RTU sends binary frames over a serial line. A frame consists of the
+//! device address, the function code, the data, and a CRC-16 in little-endian
+//! byte order. There is no delimiter. Instead, frames are separated by an idle
+//! time of at least 3.5 characters.
+//!
+//! [`ModbusRtu`] detects the end of a frame using a timer, which is restarted
+//! with every character that is received. Once it expires, the frame is
+//! complete, and its CRC is checked using the [`CRC`] engine. Frames are sent
+//! with their CRC appended.
+//!
+//! The timer needs to keep running, while the USART is idle. Any timer that
+//! implements `CountDown` can be used, like an MRT channel.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     modbus::{self, ModbusRtu},
+//!     prelude::*,
+//!     usart, Peripherals,
+//! };
+//!
+//! let mut p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//! let mut swm = p.SWM.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! #[cfg(feature = "82x")]
+//! let clock_config = {
+//!     syscon.uartfrg.set_clkdiv(6);
+//!     syscon.uartfrg.set_frgmult(22);
+//!     syscon.uartfrg.set_frgdiv(0xff);
+//!     let uartfrg = syscon.uartfrg.share();
+//!     usart::Clock::new(&uartfrg, 0, 16)
+//! };
+//! #[cfg(feature = "845")]
+//! let clock_config = usart::Clock::new_with_baudrate(115200);
+//!
+//! let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+//!     p.pins.pio0_0.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//! let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+//!     p.pins.pio0_4.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//!
+//! let mut serial = p.USART0.enable(
+//!     &clock_config,
+//!     &mut syscon.handle,
+//!     u0_rxd,
+//!     u0_txd,
+//! );
+//!
+//! // The MRT runs at 12 MHz
+//! let timer = p.MRT0.split(&mut syscon.handle).mrt0;
+//! let idle = modbus::idle_ticks(115200, 12_000_000);
+//!
+//! let crc = p.CRC.enable(&mut syscon.handle);
+//! let mut modbus = ModbusRtu::<_, 256>::new(crc, timer, idle);
+//!
+//! loop {
+//!     match modbus.receive(&mut serial.rx) {
+//!         Ok(()) => {
+//!             let frame = modbus.frame().unwrap();
+//!             let response = [0; 8];
+//!             // process `frame`, prepare response
+//!
+//!             modbus.clear();
+//!             modbus.send(&mut serial.tx, &response).unwrap();
+//!         }
+//!         Err(nb::Error::WouldBlock) => continue,
+//!         Err(nb::Error::Other(_)) => {
+//!             // The broken frame has been discarded. Just ignore it.
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`ModbusRtu`]: struct.ModbusRtu.html
+//! [`CRC`]: ../crc/struct.CRC.html
+
+use embedded_hal::{blocking::serial::Write, serial::Read, timer::CountDown};
+
+use crate::{crc::CRC, init_state};
+
+/// The shortest possible frame: address, function code, and CRC
+const MIN_FRAME_LEN: usize = 4;
+
+/// Compute the inter-frame idle time, in timer ticks
+///
+/// Modbus RTU requires 3.5 characters of idle time between frames. Above
+/// 19200 baud, the standard recommends a fixed idle time of 1.75 ms instead.
+/// `baudrate` is the baud rate of the USART, `timer_frequency` the frequency
+/// at which the timer counts, both in Hz.
+pub fn idle_ticks(baudrate: u32, timer_frequency: u32) -> u32 {
+    if baudrate > 19_200 {
+        (timer_frequency as u64 * 1750 / 1_000_000) as u32
+    } else {
+        // 3.5 characters of 11 bits each
+        (timer_frequency as u64 * 385 / (baudrate as u64 * 10)) as u32
+    }
+}
+
+/// Receives and sends Modbus RTU frames
+///
+/// Frames of up to `N` bytes, including address and CRC, can be received. The
+/// Modbus standard allows frames of up to 256 bytes.
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct ModbusRtu<T, const N: usize> {
+    crc: CRC<init_state::Enabled>,
+    timer: T,
+    idle: u32,
+    buffer: [u8; N],
+    len: usize,
+    overflow: bool,
+    complete: bool,
+}
+
+impl<T, const N: usize> ModbusRtu<T, N>
+where
+    T: CountDown<Time = u32>,
+{
+    /// Create a new instance of `ModbusRtu`
+    ///
+    /// `idle` is the idle time that ends a frame, in timer ticks. Use
+    /// [`idle_ticks`] to compute it.
+    ///
+    /// [`idle_ticks`]: fn.idle_ticks.html
+    pub fn new(crc: CRC<init_state::Enabled>, timer: T, idle: u32) -> Self {
+        Self {
+            crc,
+            timer,
+            idle,
+            buffer: [0; N],
+            len: 0,
+            overflow: false,
+            complete: false,
+        }
+    }
+
+    /// Receive all characters that are available
+    ///
+    /// Reads from `rx`, until no more characters are available. Returns `Ok`,
+    /// once the line has been idle long enough to end a frame, and the frame
+    /// is valid. Returns `nb::Error::WouldBlock` otherwise.
+    ///
+    /// After a frame is complete, no more characters are read, until it has
+    /// been discarded using [`clear`]. Invalid frames are discarded right
+    /// away, and the error is returned.
+    ///
+    /// This needs to be called often enough to not miss any characters, and to
+    /// detect the end of a frame in time. Calling it from the RXRDY interrupt
+    /// handler only is not enough, as no interrupt occurs at the end of a
+    /// frame.
+    ///
+    /// [`clear`]: #method.clear
+    pub fn receive<R>(&mut self, rx: &mut R) -> nb::Result<(), Error<R::Error>>
+    where
+        R: Read<u8>,
+    {
+        if self.complete {
+            return Ok(());
+        }
+
+        loop {
+            match rx.read() {
+                Ok(b) => {
+                    if self.len < N {
+                        self.buffer[self.len] = b;
+                        self.len += 1;
+                    } else {
+                        self.overflow = true;
+                    }
+                    self.timer.start(self.idle);
+                }
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(err)) => {
+                    self.clear();
+                    return Err(nb::Error::Other(Error::Serial(err)));
+                }
+            }
+        }
+
+        if self.len == 0 && !self.overflow {
+            return Err(nb::Error::WouldBlock);
+        }
+        if self.timer.wait().is_err() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        // The line has been idle long enough. The frame is complete.
+        let error = if self.overflow {
+            Some(Error::Overflow)
+        } else if self.len < MIN_FRAME_LEN {
+            Some(Error::TooShort)
+        } else {
+            let (data, crc) = self.buffer[..self.len].split_at(self.len - 2);
+            let crc = u16::from_le_bytes([crc[0], crc[1]]);
+
+            if self.crc.crc16_modbus(data) != crc {
+                Some(Error::Crc)
+            } else {
+                None
+            }
+        };
+
+        if let Some(error) = error {
+            self.clear();
+            return Err(nb::Error::Other(error));
+        }
+
+        self.complete = true;
+        Ok(())
+    }
+
+    /// Access the complete frame, without the CRC
+    ///
+    /// The frame starts with the device address and the function code.
+    /// Returns `None`, if no frame has been completed yet.
+    pub fn frame(&self) -> Option<&[u8]> {
+        if self.complete {
+            Some(&self.buffer[..self.len - 2])
+        } else {
+            None
+        }
+    }
+
+    /// Discard the current frame, and start receiving the next one
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.overflow = false;
+        self.complete = false;
+    }
+
+    /// Send a frame
+    ///
+    /// `frame` starts with the device address and the function code. The CRC
+    /// is computed and appended. Blocks until the whole frame has been written
+    /// to `tx`.
+    ///
+    /// The caller is responsible for the idle time before the frame. If a
+    /// frame has just been received, that time has already passed.
+    pub fn send<W>(&mut self, tx: &mut W, frame: &[u8]) -> Result<(), W::Error>
+    where
+        W: Write<u8>,
+    {
+        let crc = self.crc.crc16_modbus(frame);
+
+        tx.bwrite_all(frame)?;
+        tx.bwrite_all(&crc.to_le_bytes())?;
+        tx.bflush()
+    }
+
+    /// Release the CRC engine and the timer
+    pub fn free(self) -> (CRC<init_state::Enabled>, T) {
+        (self.crc, self.timer)
+    }
+}
+
+/// An error that can occur while receiving a Modbus RTU frame
+///
+/// Returned by [`ModbusRtu::receive`].
+///
+/// [`ModbusRtu::receive`]: struct.ModbusRtu.html#method.receive
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error<E> {
+    /// The frame didn't fit into the buffer
+    Overflow,
+
+    /// The frame was too short to be valid
+    TooShort,
+
+    /// The CRC of the frame didn't match
+    Crc,
+
+    /// The USART reported an error
+    Serial(E),
+}
@@ -7,12 +7,12 @@
 //! as a run-of-the-mill timer.
 
 use crate::{
-    pac::{self, mrt0::CHANNEL},
+    pac::{self, mrt0::CHANNEL, Interrupt, NVIC},
     reg_proxy::{Reg, RegProxy},
     syscon,
 };
 
-use embedded_hal::timer::{CountDown, Periodic};
+use embedded_hal::timer::{Cancel, CountDown, Periodic};
 use nb::{Error, Result};
 use void::Void;
 
@@ -62,8 +62,10 @@ pub const MAX_VALUE: u32 = 0x7fff_ffff - 1;
 ///
 /// # `embedded-hal` traits
 /// - [`embedded_hal::timer::CountDown`]
+/// - [`embedded_hal::timer::Cancel`]
 ///
 /// [`embedded_hal::timer::CountDown`]: #impl-CountDown
+/// [`embedded_hal::timer::Cancel`]: #impl-Cancel
 pub struct Channel<T: Reg>(RegProxy<T>);
 
 impl<T> Channel<T>
@@ -78,6 +80,83 @@ where
     pub fn value(&self) -> u32 {
         self.0.timer.read().value().bits()
     }
+
+    /// Indicates whether the count down has finished
+    ///
+    /// Unlike `wait`, this doesn't clear the interrupt flag.
+    pub fn has_finished(&self) -> bool {
+        self.0.stat.read().intflag().is_pending_interrupt()
+    }
+
+    /// Enable the interrupt for this channel
+    ///
+    /// The interrupt will not actually work unless the interrupts for this
+    /// peripheral have also been enabled in the NVIC. See [`enable_in_nvic`].
+    ///
+    /// [`enable_in_nvic`]: #method.enable_in_nvic
+    pub fn enable_interrupt(&mut self) {
+        self.0.ctrl.modify(|_, w| w.inten().enabled());
+    }
+
+    /// Disable the interrupt for this channel
+    pub fn disable_interrupt(&mut self) {
+        self.0.ctrl.modify(|_, w| w.inten().disabled());
+    }
+
+    /// Enable interrupts for this instance in the NVIC
+    ///
+    /// The interrupt is shared between all MRT channels.
+    ///
+    /// This only enables the interrupts in the NVIC. It doesn't enable any
+    /// specific interrupt in this MRT channel.
+    pub fn enable_in_nvic(&mut self) {
+        // Safe, because there's no critical section here that this could
+        // interfere with.
+        unsafe { NVIC::unmask(T::INTERRUPT) };
+    }
+
+    /// Disable interrupts for this instance in the NVIC
+    ///
+    /// This only disables the interrupts in the NVIC. It doesn't change
+    /// anything about the interrupt configuration within this MRT channel.
+    pub fn disable_in_nvic(&mut self) {
+        NVIC::mask(T::INTERRUPT);
+    }
+
+    /// Clear this instance's interrupt pending flag in the NVIC
+    ///
+    /// This only clears the interrupt's pending flag in the NVIC. It does not
+    /// affect any of the interrupt-related flags in the peripheral.
+    pub fn clear_nvic_pending(&mut self) {
+        NVIC::unpend(T::INTERRUPT);
+    }
+
+    /// Set this instance's interrupt pending flag in the NVIC
+    ///
+    /// The interrupt handler then runs as if the interrupt had occurred, once
+    /// the interrupt is enabled in the NVIC and its priority allows it. This
+    /// does not set any of the interrupt-related flags in the peripheral.
+    pub fn set_nvic_pending(&mut self) {
+        NVIC::pend(T::INTERRUPT);
+    }
+
+    /// Indicates whether this instance's interrupt is pending in the NVIC
+    pub fn is_nvic_pending(&self) -> bool {
+        NVIC::is_pending(T::INTERRUPT)
+    }
+
+    /// Set the priority of this instance's interrupt in the NVIC
+    ///
+    /// Lower values mean higher priority. Only the two most significant bits of
+    /// `priority` are used by the hardware.
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections,
+    /// like the ones used by RTFM.
+    pub unsafe fn set_nvic_priority(&mut self, nvic: &mut NVIC, priority: u8) {
+        nvic.set_priority(T::INTERRUPT, priority);
+    }
 }
 
 impl<T> CountDown for Channel<T>
@@ -128,8 +207,34 @@ where
 
 impl<T> Periodic for Channel<T> where T: Trait {}
 
+impl<T> Cancel for Channel<T>
+where
+    T: Trait,
+{
+    type Error = Void;
+
+    /// Stop the timer
+    ///
+    /// Also clears the interrupt flag, so a count down that has already
+    /// finished won't be reported by `wait`.
+    fn cancel(&mut self) -> core::result::Result<(), Self::Error> {
+        self.0.intval.write(|w| {
+            w.load().set_bit();
+            unsafe { w.ivalue().bits(0) }
+        });
+        self.0.stat.write(|w| w.intflag().set_bit());
+
+        Ok(())
+    }
+}
+
 /// Implemented for types that identify MRT channels
-pub trait Trait: Reg<Target = CHANNEL> + sealed::Sealed {}
+pub trait Trait: Reg<Target = CHANNEL> + sealed::Sealed {
+    /// The NVIC interrupt that is triggered for this channel
+    ///
+    /// All channels share the same interrupt.
+    const INTERRUPT: Interrupt = Interrupt::MRT0;
+}
 
 macro_rules! channels {
     ($($channel:ident, $field:ident, $index:expr;)*) => {
@@ -0,0 +1,404 @@
+//! Bit-banged 1-Wire master
+//!
+//! 1-Wire is a bus that uses a single data line, which also powers the
+//! devices on it in some configurations. It is used by sensors like the
+//! DS18B20 temperature sensor. Each device has a unique 64-bit ROM code, which
+//! can be discovered using the ROM search (see [`OneWire::search`]).
+//!
+//! [`OneWire`] uses a GPIO pin for the data line, and a microsecond delay (like
+//! [`Delay`]) for the timing. The pin needs to be configured as an open-drain
+//! output in IOCON, and the line needs an external pull-up resistor (usually
+//! 4.7 kOhm). Setting the pin high then releases the line, so devices can pull
+//! it low.
+//!
+//! Interrupts are disabled during each time slot (up to 70 µs), and during
+//! the reset pulse (about 550 µs), as the devices expect precise timing. The
+//! overhead of the delay calls is small, compared to the tolerances of the
+//! protocol, at the default system clock of 12 MHz.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     prelude::*,
+//!     delay::Delay,
+//!     gpio::Level,
+//!     one_wire::{self, OneWire},
+//!     CorePeripherals, Peripherals,
+//! };
+//!
+//! let cp = CorePeripherals::take().unwrap();
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let gpio = p.GPIO;
+//! #[cfg(feature = "845")]
+//! let gpio = p.GPIO.enable(&mut syscon.handle);
+//!
+//! // There's no HAL API for configuring open-drain mode yet, so the raw IOCON
+//! // register is used.
+//! syscon.handle.enable_clock(&p.IOCON);
+//! p.IOCON.pio0_12.modify(|_, w| w.od().enabled());
+//!
+//! let pin = p.pins.pio0_12.into_output_pin(
+//!     gpio.tokens.pio0_12,
+//!     Level::High,
+//! );
+//! let delay = Delay::new(cp.SYST);
+//! let mut bus = OneWire::new(pin, delay);
+//!
+//! // Start a temperature conversion on all DS18B20 sensors
+//! if bus.reset().unwrap() {
+//!     bus.skip_rom().unwrap();
+//!     bus.write_byte(0x44).unwrap();
+//! }
+//!
+//! // Read the temperature from each sensor, once the conversion has finished
+//! let mut search = one_wire::Search::new();
+//! while let Some(rom) = bus.search(&mut search).unwrap() {
+//!     let mut scratchpad = [0; 9];
+//!
+//!     bus.reset().unwrap();
+//!     bus.match_rom(&rom).unwrap();
+//!     bus.write_byte(0xbe).unwrap();
+//!     bus.read_bytes(&mut scratchpad).unwrap();
+//!
+//!     if one_wire::crc8(&scratchpad[..8]) == scratchpad[8] {
+//!         // In units of 1/16 °C
+//!         let temperature =
+//!             i16::from_le_bytes([scratchpad[0], scratchpad[1]]);
+//!     }
+//! }
+//! ```
+//!
+//! [`OneWire::search`]: struct.OneWire.html#method.search
+//! [`OneWire`]: struct.OneWire.html
+//! [`Delay`]: ../delay/struct.Delay.html
+
+use cortex_m::interrupt;
+use embedded_hal::{
+    blocking::delay::DelayUs,
+    digital::v2::{InputPin, OutputPin},
+};
+
+/// Bit-banged 1-Wire master
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct OneWire<P, D> {
+    pin: P,
+    delay: D,
+}
+
+impl<P, D, E> OneWire<P, D>
+where
+    P: OutputPin<Error = E> + InputPin<Error = E>,
+    D: DelayUs<u16>,
+{
+    /// Create a new 1-Wire master
+    ///
+    /// The pin should already be high, which is the idle state of the bus.
+    pub fn new(pin: P, delay: D) -> Self {
+        Self { pin, delay }
+    }
+
+    /// Release the pin and the delay
+    pub fn free(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+
+    /// Send a reset pulse, and detect whether any devices are present
+    ///
+    /// Every transaction starts with a reset. Returns `true`, if at least one
+    /// device has answered with a presence pulse.
+    ///
+    /// Returns [`Error::BusLow`], if the line is low before the reset pulse,
+    /// which usually means it is shorted to ground, or the pull-up resistor is
+    /// missing.
+    ///
+    /// [`Error::BusLow`]: enum.Error.html#variant.BusLow
+    pub fn reset(&mut self) -> Result<bool, Error<E>> {
+        if self.pin.is_low().map_err(Error::Pin)? {
+            return Err(Error::BusLow);
+        }
+
+        let presence = interrupt::free(|_| {
+            self.pin.set_low().map_err(Error::Pin)?;
+            self.delay.delay_us(480);
+            self.pin.set_high().map_err(Error::Pin)?;
+            self.delay.delay_us(70);
+
+            self.pin.is_low().map_err(Error::Pin)
+        })?;
+
+        // Wait for the end of the presence pulse
+        self.delay.delay_us(410);
+
+        Ok(presence)
+    }
+
+    /// Write a byte, least significant bit first
+    pub fn write_byte(&mut self, byte: u8) -> Result<(), Error<E>> {
+        for i in 0..8 {
+            self.write_bit(byte & (0x1 << i) != 0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read a byte, least significant bit first
+    pub fn read_byte(&mut self) -> Result<u8, Error<E>> {
+        let mut byte = 0;
+
+        for i in 0..8 {
+            if self.read_bit()? {
+                byte |= 0x1 << i;
+            }
+        }
+
+        Ok(byte)
+    }
+
+    /// Write all of `bytes`
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error<E>> {
+        for &b in bytes {
+            self.write_byte(b)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fill `buffer` with bytes that are read from the bus
+    pub fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        for b in buffer {
+            *b = self.read_byte()?;
+        }
+
+        Ok(())
+    }
+
+    /// Address all devices on the bus (SKIP ROM)
+    ///
+    /// Must directly follow a [`reset`]. Only commands that don't make the
+    /// devices send anything should follow, unless there is only one device
+    /// on the bus.
+    ///
+    /// [`reset`]: #method.reset
+    pub fn skip_rom(&mut self) -> Result<(), Error<E>> {
+        self.write_byte(SKIP_ROM)
+    }
+
+    /// Address a single device (MATCH ROM)
+    ///
+    /// Must directly follow a [`reset`]. All other devices ignore the
+    /// following commands, until the next reset.
+    ///
+    /// [`reset`]: #method.reset
+    pub fn match_rom(&mut self, rom: &Rom) -> Result<(), Error<E>> {
+        self.write_byte(MATCH_ROM)?;
+        self.write_bytes(&rom.0)
+    }
+
+    /// Find the next device on the bus
+    ///
+    /// Sends a reset, then runs the ROM search algorithm, which discovers one
+    /// device per call. `search` keeps track of the devices that have been
+    /// found so far. Returns `None`, once all devices have been found, or if
+    /// there are no devices on the bus.
+    ///
+    /// Returns [`Error::Crc`], if the ROM code that was found is invalid,
+    /// which can be caused by devices being added to or removed from the bus
+    /// during a search. Start a new search in that case.
+    ///
+    /// [`Error::Crc`]: enum.Error.html#variant.Crc
+    pub fn search(
+        &mut self,
+        search: &mut Search,
+    ) -> Result<Option<Rom>, Error<E>> {
+        if search.done || !self.reset()? {
+            *search = Search::new();
+            return Ok(None);
+        }
+
+        self.write_byte(SEARCH_ROM)?;
+
+        let mut last_zero = 0;
+
+        for bit in 1..=64 {
+            let id_bit = self.read_bit()?;
+            let complement = self.read_bit()?;
+
+            let direction = match (id_bit, complement) {
+                // No device is participating in the search anymore
+                (true, true) => {
+                    *search = Search::new();
+                    return Ok(None);
+                }
+                // All remaining devices have the same bit here
+                (id_bit, complement) if id_bit != complement => id_bit,
+                // Discrepancy: Devices with both values are present
+                _ => {
+                    let direction = if bit < search.last_discrepancy {
+                        search.bit(bit)
+                    } else {
+                        bit == search.last_discrepancy
+                    };
+
+                    if !direction {
+                        last_zero = bit;
+                    }
+
+                    direction
+                }
+            };
+
+            search.set_bit(bit, direction);
+            self.write_bit(direction)?;
+        }
+
+        search.last_discrepancy = last_zero;
+        search.done = last_zero == 0;
+
+        if crc8(&search.rom[..7]) != search.rom[7] {
+            *search = Search::new();
+            return Err(Error::Crc);
+        }
+
+        Ok(Some(Rom(search.rom)))
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<(), Error<E>> {
+        let (low, high) = if bit { (6, 64) } else { (60, 10) };
+
+        interrupt::free(|_| {
+            self.pin.set_low().map_err(Error::Pin)?;
+            self.delay.delay_us(low);
+            self.pin.set_high().map_err(Error::Pin)?;
+            self.delay.delay_us(high);
+
+            Ok(())
+        })
+    }
+
+    fn read_bit(&mut self) -> Result<bool, Error<E>> {
+        let bit = interrupt::free(|_| {
+            self.pin.set_low().map_err(Error::Pin)?;
+            self.delay.delay_us(6);
+            self.pin.set_high().map_err(Error::Pin)?;
+            self.delay.delay_us(9);
+
+            self.pin.is_high().map_err(Error::Pin)
+        })?;
+
+        // Wait for the end of the time slot
+        self.delay.delay_us(55);
+
+        Ok(bit)
+    }
+}
+
+/// The 64-bit ROM code of a 1-Wire device
+///
+/// The first byte is the family code, which identifies the type of device
+/// (for example, 0x28 for the DS18B20). The last byte is a CRC of the others.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rom(pub [u8; 8]);
+
+impl Rom {
+    /// The family code, which identifies the type of device
+    pub fn family_code(&self) -> u8 {
+        self.0[0]
+    }
+}
+
+/// The state of a ROM search
+///
+/// Passed to [`OneWire::search`].
+///
+/// [`OneWire::search`]: struct.OneWire.html#method.search
+#[derive(Clone, Debug)]
+pub struct Search {
+    rom: [u8; 8],
+    last_discrepancy: u8,
+    done: bool,
+}
+
+impl Search {
+    /// Start a new search
+    pub const fn new() -> Self {
+        Self {
+            rom: [0; 8],
+            last_discrepancy: 0,
+            done: false,
+        }
+    }
+
+    /// Bits are numbered from 1 to 64, as in the search algorithm
+    fn bit(&self, bit: u8) -> bool {
+        let i = bit - 1;
+        self.rom[(i / 8) as usize] & (0x1 << (i % 8)) != 0
+    }
+
+    fn set_bit(&mut self, bit: u8, value: bool) {
+        let i = bit - 1;
+        let mask = 0x1 << (i % 8);
+
+        if value {
+            self.rom[(i / 8) as usize] |= mask;
+        } else {
+            self.rom[(i / 8) as usize] &= !mask;
+        }
+    }
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the CRC-8 that is used by 1-Wire devices
+///
+/// This is the CRC that protects ROM codes, and the data of many devices,
+/// like the scratchpad of the DS18B20. Computing the CRC over data that
+/// includes its CRC results in 0.
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0;
+
+    for &b in data {
+        let mut b = b;
+        for _ in 0..8 {
+            let mix = (crc ^ b) & 0x01;
+            crc >>= 1;
+            if mix != 0 {
+                crc ^= 0x8c;
+            }
+            b >>= 1;
+        }
+    }
+
+    crc
+}
+
+/// A 1-Wire error
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The line was low before a reset
+    ///
+    /// This usually means that it is shorted to ground, or that the pull-up
+    /// resistor is missing.
+    BusLow,
+
+    /// A ROM code that was found during a search has an invalid CRC
+    Crc,
+
+    /// An error occurred while accessing a pin
+    Pin(E),
+}
+
+const SEARCH_ROM: u8 = 0xf0;
+const MATCH_ROM: u8 = 0x55;
+const SKIP_ROM: u8 = 0xcc;
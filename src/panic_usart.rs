@@ -0,0 +1,165 @@
+//! Panic handler that prints panic messages over USART
+//!
+//! This module is only available, if the `panic-usart` feature is enabled. It
+//! provides a panic handler, which prints the panic message and location over
+//! a USART, then halts. This makes panics debuggable on boards that don't
+//! provide SWD access, using nothing more than a serial adapter.
+//!
+//! The panic handler needs to know which USART to use. Pass its transmitter
+//! to [`init`], once the USART has been configured. Panics that happen before
+//! that, or after the transmitter has been released using [`release`], are
+//! not printed.
+//!
+//! Since this module defines the panic handler, it can't be used together
+//! with another panic handler crate, like `panic-halt`.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{panic_usart, usart, Peripherals};
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//! let mut swm = p.SWM.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! #[cfg(feature = "82x")]
+//! let clock_config = {
+//!     syscon.uartfrg.set_clkdiv(6);
+//!     syscon.uartfrg.set_frgmult(22);
+//!     syscon.uartfrg.set_frgdiv(0xff);
+//!     let uartfrg = syscon.uartfrg.share();
+//!     usart::Clock::new(&uartfrg, 0, 16)
+//! };
+//! #[cfg(feature = "845")]
+//! let clock_config = usart::Clock::new_with_baudrate(115200);
+//!
+//! let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+//!     p.pins.pio0_0.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//! let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+//!     p.pins.pio0_4.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//!
+//! let serial = p.USART0.enable(
+//!     &clock_config,
+//!     &mut syscon.handle,
+//!     u0_rxd,
+//!     u0_txd,
+//! );
+//!
+//! // From now on, panic messages are printed over USART0
+//! panic_usart::init(serial.tx);
+//!
+//! panic!("Something went wrong");
+//! ```
+//!
+//! [`init`]: fn.init.html
+//! [`release`]: fn.release.html
+
+use core::{
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::{
+    init_state,
+    pac::usart0::RegisterBlock,
+    usart::{Instance, Tx},
+};
+
+/// The register block of the USART used by the panic handler, if any
+static USART: AtomicPtr<RegisterBlock> = AtomicPtr::new(ptr::null_mut());
+
+/// Make the panic handler print over the USART that `tx` belongs to
+///
+/// Takes ownership of the transmitter, as the panic handler can use it at any
+/// time. Replaces any transmitter that has been passed before.
+pub fn init<I, Pins>(_tx: Tx<I, init_state::Enabled<Pins>>)
+where
+    I: Instance,
+{
+    USART.store(I::REGISTERS as *mut _, Ordering::SeqCst);
+}
+
+/// Stop printing panic messages, and return the transmitter
+///
+/// # Safety
+///
+/// The type parameters must match those of the transmitter that was passed to
+/// [`init`]. The USART must still be enabled.
+///
+/// [`init`]: fn.init.html
+pub unsafe fn release<I, Pins>() -> Tx<I, init_state::Enabled<Pins>>
+where
+    I: Instance,
+{
+    USART.store(ptr::null_mut(), Ordering::SeqCst);
+    Tx::steal()
+}
+
+// A panic handler can't be defined when running the doc tests on the host,
+// as that would conflict with the one from `std`.
+#[cfg(target_os = "none")]
+mod handler {
+    use core::{
+        fmt::{self, Write as _},
+        panic::PanicInfo,
+        sync::atomic::{self, Ordering},
+    };
+
+    use crate::pac::usart0::RegisterBlock;
+
+    use super::USART;
+
+    #[panic_handler]
+    fn panic(info: &PanicInfo) -> ! {
+        cortex_m::interrupt::disable();
+
+        let usart = USART.load(Ordering::SeqCst);
+        if !usart.is_null() {
+            // Sound, as the pointer has been set by `init`, which took
+            // ownership of the transmitter, and interrupts are disabled.
+            let mut writer = Writer(unsafe { &*usart });
+
+            // There's nothing we could do about an error at this point.
+            let _ = writeln!(writer, "{}", info);
+            writer.flush();
+        }
+
+        loop {
+            atomic::compiler_fence(Ordering::SeqCst);
+        }
+    }
+
+    /// Writes to the USART, bypassing the regular API
+    struct Writer(&'static RegisterBlock);
+
+    impl Writer {
+        fn flush(&mut self) {
+            while self.0.stat.read().txidle().bit_is_clear() {}
+        }
+    }
+
+    impl fmt::Write for Writer {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            for &b in s.as_bytes() {
+                while self.0.stat.read().txrdy().bit_is_clear() {}
+
+                // Sound, as all `u8` values are valid here.
+                self.0
+                    .txdat
+                    .write(|w| unsafe { w.txdat().bits(u16::from(b)) });
+            }
+
+            Ok(())
+        }
+    }
+}
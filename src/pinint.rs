@@ -2,6 +2,16 @@
 //!
 //! This API is currently limited. It exposes a subset of the pin interrupts
 //! functionality, and none of the pattern matching functionality.
+//!
+//! The 8 pin interrupts are tracked at compile time, like pins and switch
+//! matrix functions. [`PININT`] provides exactly one [`Interrupt`] instance for
+//! each of them, which can be moved into the driver that needs it. Once a pin
+//! interrupt is no longer needed, [`Interrupt::deselect`] returns it to its
+//! unselected state, so it can be handed to another driver.
+//!
+//! [`PININT`]: struct.PININT.html
+//! [`Interrupt`]: struct.Interrupt.html
+//! [`Interrupt::deselect`]: struct.Interrupt.html#method.deselect
 
 mod gen;
 mod interrupt;
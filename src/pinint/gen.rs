@@ -1,7 +1,9 @@
+use crate::pac;
+
 use super::{interrupt::Interrupt, traits::Trait};
 
 macro_rules! interrupts {
-    ($($struct:ident, $field:ident, $index:expr;)*) => {
+    ($($struct:ident, $field:ident, $index:expr, $interrupt:ident;)*) => {
         /// Provides access to all pin interrupts
         #[allow(missing_docs)]
         pub struct Interrupts<State> {
@@ -24,6 +26,7 @@ macro_rules! interrupts {
             pub struct $struct;
 
             impl Trait for $struct {
+                const INTERRUPT: pac::Interrupt = pac::Interrupt::$interrupt;
                 const INDEX: usize = $index;
                 const MASK: u8 = 0x1 << $index;
             }
@@ -32,12 +35,12 @@ macro_rules! interrupts {
 }
 
 interrupts!(
-    PININT0, pinint0, 0;
-    PININT1, pinint1, 1;
-    PININT2, pinint2, 2;
-    PININT3, pinint3, 3;
-    PININT4, pinint4, 4;
-    PININT5, pinint5, 5;
-    PININT6, pinint6, 6;
-    PININT7, pinint7, 7;
+    PININT0, pinint0, 0, PIN_INT0;
+    PININT1, pinint1, 1, PIN_INT1;
+    PININT2, pinint2, 2, PIN_INT2;
+    PININT3, pinint3, 3, PIN_INT3;
+    PININT4, pinint4, 4, PIN_INT4;
+    PININT5, pinint5, 5, PIN_INT5_DAC1;
+    PININT6, pinint6, 6, PIN_INT6_USART3;
+    PININT7, pinint7, 7, PIN_INT7_USART4;
 );
@@ -1,10 +1,23 @@
 use core::marker::PhantomData;
 
-use crate::{init_state::Enabled, pac, pins, syscon};
+use crate::{
+    init_state::Enabled,
+    pac::{self, NVIC},
+    pins, syscon,
+};
 
 use super::traits::Trait;
 
 /// API for controlling pin interrupts
+///
+/// Each of the 8 pin interrupts is represented by exactly one instance of this
+/// struct, which is provided by [`PININT`]. A pin interrupt can only be
+/// configured by whoever owns that instance.
+///
+/// `I` identifies the pin interrupt, `P` the pin that has been selected as
+/// its source (`()`, if no pin has been selected yet).
+///
+/// [`PININT`]: struct.PININT.html
 pub struct Interrupt<I, P, State> {
     interrupt: PhantomData<I>,
     _pin: PhantomData<P>,
@@ -70,6 +83,59 @@ where
     I: Trait,
     P: pins::Trait,
 {
+    /// Enable interrupts for this pin interrupt in the NVIC
+    ///
+    /// This only enables the interrupts in the NVIC. It doesn't enable any
+    /// rising or falling edge detection for this pin interrupt.
+    pub fn enable_in_nvic(&mut self) {
+        // Safe, because there's no critical section here that this could
+        // interfere with.
+        unsafe { NVIC::unmask(I::INTERRUPT) };
+    }
+
+    /// Disable interrupts for this pin interrupt in the NVIC
+    ///
+    /// This only disables the interrupts in the NVIC. It doesn't change
+    /// anything about the configuration of this pin interrupt.
+    pub fn disable_in_nvic(&mut self) {
+        NVIC::mask(I::INTERRUPT);
+    }
+
+    /// Clear this pin interrupt's pending flag in the NVIC
+    ///
+    /// This only clears the interrupt's pending flag in the NVIC. It does not
+    /// affect any of the interrupt-related flags in the peripheral.
+    pub fn clear_nvic_pending(&mut self) {
+        NVIC::unpend(I::INTERRUPT);
+    }
+
+    /// Set this pin interrupt's interrupt pending flag in the NVIC
+    ///
+    /// The interrupt handler then runs as if the interrupt had occurred, once
+    /// the interrupt is enabled in the NVIC and its priority allows it. This
+    /// does not set any of the interrupt-related flags in the peripheral.
+    pub fn set_nvic_pending(&mut self) {
+        NVIC::pend(I::INTERRUPT);
+    }
+
+    /// Indicates whether this pin interrupt's interrupt is pending in the NVIC
+    pub fn is_nvic_pending(&self) -> bool {
+        NVIC::is_pending(I::INTERRUPT)
+    }
+
+    /// Set the priority of this pin interrupt in the NVIC
+    ///
+    /// Lower values mean higher priority. Only the two most significant bits of
+    /// `priority` are used by the hardware.
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections,
+    /// like the ones used by RTFM.
+    pub unsafe fn set_nvic_priority(&mut self, nvic: &mut NVIC, priority: u8) {
+        nvic.set_priority(I::INTERRUPT, priority);
+    }
+
     /// Returns whether a rising edge has been detected and clears the flag
     ///
     /// This method will work regardless of whether rising edge interrupts have
@@ -140,6 +206,29 @@ where
         is_set
     }
 
+    /// Deselect the pin, returning the pin interrupt to its unselected state
+    ///
+    /// Disables rising and falling edge detection and clears both edge flags.
+    /// The returned instance can be used to [`select`] another pin, possibly
+    /// by another driver.
+    ///
+    /// This doesn't change anything about the interrupt's configuration in the
+    /// NVIC.
+    ///
+    /// [`select`]: #method.select
+    pub fn deselect(mut self) -> Interrupt<I, (), Enabled> {
+        self.disable_rising_edge();
+        self.disable_falling_edge();
+        self.clear_rising_edge_flag();
+        self.clear_falling_edge_flag();
+
+        Interrupt {
+            interrupt: self.interrupt,
+            _pin: PhantomData,
+            state: self.state,
+        }
+    }
+
     /// Fire interrupt on falling edge
     pub fn enable_falling_edge(&mut self) {
         // This is sound, as we're only doing an atomic write to a single bit
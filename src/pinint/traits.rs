@@ -1,9 +1,14 @@
+use crate::pac;
+
 /// Implemented by types that identify pin interrupts
 ///
 /// This trait is an internal implementation detail and should neither be
 /// implemented nor used outside of LPC8xx HAL. Any changes to this trait won't
 /// be considered breaking changes.
 pub trait Trait {
+    /// The NVIC interrupt that is triggered for this pin interrupt
+    const INTERRUPT: pac::Interrupt;
+
     /// The index of this interrupt
     ///
     /// Used to select the PINTSEL register for this interupt.
@@ -6,9 +6,18 @@
 //! [`Pin`]: struct.Pin.html
 
 mod gen;
+mod id;
+pub(crate) mod iocon;
 mod pin;
 mod traits;
 
 pub mod state;
 
-pub use self::{gen::*, pin::Pin, state::State, traits::Trait};
+pub use self::{
+    gen::*,
+    id::PinId,
+    iocon::{IoconConfig, PullMode},
+    pin::Pin,
+    state::State,
+    traits::Trait,
+};
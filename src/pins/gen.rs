@@ -6,10 +6,12 @@ use super::{pin::Pin, state, traits::Trait};
 
 macro_rules! pins {
     ($(
+        $(#[$attr:meta])*
         $field:ident,
         $type:ident,
         $port:expr,
         $id:expr,
+        $iocon:expr,
         $default_state_ty:ty;
     )*) => {
         /// Provides access to all pins
@@ -19,21 +21,31 @@ macro_rules! pins {
         ///
         /// # Limitations
         ///
-        /// This struct currently provides access to all pins that can be
-        /// available on an LPC8xx part. Please make sure that you are aware of
-        /// which pins are actually available on your specific part, and only
-        /// use those.
+        /// If a specific part (like `845m301jbd48`) is selected as the target,
+        /// only the pins that are available in that part's package are
+        /// provided. For LPC845, this means that PIO1_10 to PIO1_21 are only
+        /// available on 64-pin packages, and PIO0_29 to PIO0_31 and all PIO1
+        /// pins are not available on 33-pin packages.
+        ///
+        /// If only a family (like `845`) is selected, this struct provides
+        /// access to all pins that can be available on a part of that family.
+        /// Please make sure that you are aware of which pins are actually
+        /// available on your specific part, and only use those.
         ///
         /// [`Peripherals`]: ../struct.Peripherals.html
         #[allow(missing_docs)]
         pub struct Pins {
-            $(pub $field: Pin<$type, $default_state_ty>,)*
+            $(
+                $(#[$attr])*
+                pub $field: Pin<$type, $default_state_ty>,
+            )*
         }
 
         impl Pins {
             pub(crate) fn new() -> Self {
                 Pins {
                     $(
+                        $(#[$attr])*
                         $field: Pin {
                             ty:     $type(()),
                             _state: <$default_state_ty>::new(),
@@ -51,13 +63,39 @@ macro_rules! pins {
             /// [`Pin`]'s documentation for more information.
             ///
             /// [`Pin`]: struct.Pin.html
+            $(#[$attr])*
             #[allow(non_camel_case_types)]
             pub struct $type(());
 
+            $(#[$attr])*
             impl Trait for $type {
-                const PORT: usize = $port;
-                const ID  : u8    = $id;
-                const MASK: u32   = 0x1 << $id;
+                const PORT : usize = $port;
+                const ID   : u8    = $id;
+                const MASK : u32   = 0x1 << $id;
+                const IOCON: usize = $iocon;
+            }
+
+            $(#[$attr])*
+            impl<State> Token<$type, State> {
+                /// Steal the token
+                ///
+                /// This function returns an instance of the token, whether or
+                /// not such an instance exists somewhere else. This is meant
+                /// for code that can't access the token the usual way, like an
+                /// interrupt or panic handler.
+                ///
+                /// # Safety
+                ///
+                /// The same caveats apply as for [`Peripherals::steal`]. Most
+                /// importantly, the caller must make sure that the `State`
+                /// type parameter matches the actual state of the GPIO
+                /// peripheral, and that no conflicting access to the pin
+                /// happens.
+                ///
+                /// [`Peripherals::steal`]: ../struct.Peripherals.html#method.steal
+                pub unsafe fn steal() -> Self {
+                    Token($type(()), PhantomData)
+                }
             }
         )*
 
@@ -71,6 +109,7 @@ macro_rules! pins {
         pub struct Tokens<State> {
             $(
                 /// A token representing a pin
+                $(#[$attr])*
                 pub $field: Token<$type, State>,
             )*
         }
@@ -79,6 +118,7 @@ macro_rules! pins {
             pub(crate) fn new() -> Self {
                 Self {
                     $(
+                        $(#[$attr])*
                         $field: Token($type(()), PhantomData),
                     )*
                 }
@@ -91,6 +131,7 @@ macro_rules! pins {
             pub(crate) fn switch_state<NewState>(self) -> Tokens<NewState> {
                 Tokens {
                     $(
+                        $(#[$attr])*
                         $field: Token(self.$field.0, PhantomData),
                     )*
                 }
@@ -109,91 +150,116 @@ macro_rules! pins {
 
 #[cfg(feature = "82x")]
 pins!(
-    pio0_0 , PIO0_0 , 0, 0x00, state::Unused;
-    pio0_1 , PIO0_1 , 0, 0x01, state::Unused;
-    pio0_2 , PIO0_2 , 0, 0x02, state::Swm<((),), ()>;
-    pio0_3 , PIO0_3 , 0, 0x03, state::Swm<((),), ()>;
-    pio0_4 , PIO0_4 , 0, 0x04, state::Unused;
-    pio0_5 , PIO0_5 , 0, 0x05, state::Swm<(), ((),)>;
-    pio0_6 , PIO0_6 , 0, 0x06, state::Unused;
-    pio0_7 , PIO0_7 , 0, 0x07, state::Unused;
-    pio0_8 , PIO0_8 , 0, 0x08, state::Unused;
-    pio0_9 , PIO0_9 , 0, 0x09, state::Unused;
-    pio0_10, PIO0_10, 0, 0x0a, state::Unused;
-    pio0_11, PIO0_11, 0, 0x0b, state::Unused;
-    pio0_12, PIO0_12, 0, 0x0c, state::Unused;
-    pio0_13, PIO0_13, 0, 0x0d, state::Unused;
-    pio0_14, PIO0_14, 0, 0x0e, state::Unused;
-    pio0_15, PIO0_15, 0, 0x0f, state::Unused;
-    pio0_16, PIO0_16, 0, 0x10, state::Unused;
-    pio0_17, PIO0_17, 0, 0x11, state::Unused;
-    pio0_18, PIO0_18, 0, 0x12, state::Unused;
-    pio0_19, PIO0_19, 0, 0x13, state::Unused;
-    pio0_20, PIO0_20, 0, 0x14, state::Unused;
-    pio0_21, PIO0_21, 0, 0x15, state::Unused;
-    pio0_22, PIO0_22, 0, 0x16, state::Unused;
-    pio0_23, PIO0_23, 0, 0x17, state::Unused;
-    pio0_24, PIO0_24, 0, 0x18, state::Unused;
-    pio0_25, PIO0_25, 0, 0x19, state::Unused;
-    pio0_26, PIO0_26, 0, 0x1a, state::Unused;
-    pio0_27, PIO0_27, 0, 0x1b, state::Unused;
-    pio0_28, PIO0_28, 0, 0x1c, state::Unused;
+    pio0_0 , PIO0_0 , 0, 0x00, 0x44, state::Unused;
+    pio0_1 , PIO0_1 , 0, 0x01, 0x2c, state::Unused;
+    pio0_2 , PIO0_2 , 0, 0x02, 0x18, state::Swm<((),), ()>;
+    pio0_3 , PIO0_3 , 0, 0x03, 0x14, state::Swm<((),), ()>;
+    pio0_4 , PIO0_4 , 0, 0x04, 0x10, state::Unused;
+    pio0_5 , PIO0_5 , 0, 0x05, 0x0c, state::Swm<(), ((),)>;
+    pio0_6 , PIO0_6 , 0, 0x06, 0x40, state::Unused;
+    pio0_7 , PIO0_7 , 0, 0x07, 0x3c, state::Unused;
+    pio0_8 , PIO0_8 , 0, 0x08, 0x38, state::Unused;
+    pio0_9 , PIO0_9 , 0, 0x09, 0x34, state::Unused;
+    pio0_10, PIO0_10, 0, 0x0a, 0x20, state::Unused;
+    pio0_11, PIO0_11, 0, 0x0b, 0x1c, state::Unused;
+    pio0_12, PIO0_12, 0, 0x0c, 0x08, state::Unused;
+    pio0_13, PIO0_13, 0, 0x0d, 0x04, state::Unused;
+    pio0_14, PIO0_14, 0, 0x0e, 0x48, state::Unused;
+    pio0_15, PIO0_15, 0, 0x0f, 0x28, state::Unused;
+    pio0_16, PIO0_16, 0, 0x10, 0x24, state::Unused;
+    pio0_17, PIO0_17, 0, 0x11, 0x00, state::Unused;
+    pio0_18, PIO0_18, 0, 0x12, 0x78, state::Unused;
+    pio0_19, PIO0_19, 0, 0x13, 0x74, state::Unused;
+    pio0_20, PIO0_20, 0, 0x14, 0x70, state::Unused;
+    pio0_21, PIO0_21, 0, 0x15, 0x6c, state::Unused;
+    pio0_22, PIO0_22, 0, 0x16, 0x68, state::Unused;
+    pio0_23, PIO0_23, 0, 0x17, 0x64, state::Unused;
+    pio0_24, PIO0_24, 0, 0x18, 0x60, state::Unused;
+    pio0_25, PIO0_25, 0, 0x19, 0x5c, state::Unused;
+    pio0_26, PIO0_26, 0, 0x1a, 0x58, state::Unused;
+    pio0_27, PIO0_27, 0, 0x1b, 0x54, state::Unused;
+    pio0_28, PIO0_28, 0, 0x1c, 0x50, state::Unused;
 );
 
 #[cfg(feature = "845")]
 pins!(
-    pio0_0 , PIO0_0 , 0, 0x00, state::Unused;
-    pio0_1 , PIO0_1 , 0, 0x01, state::Unused;
-    pio0_2 , PIO0_2 , 0, 0x02, state::Swm<((),), ()>;
-    pio0_3 , PIO0_3 , 0, 0x03, state::Swm<((),), ()>;
-    pio0_4 , PIO0_4 , 0, 0x04, state::Unused;
-    pio0_5 , PIO0_5 , 0, 0x05, state::Swm<(), ((),)>;
-    pio0_6 , PIO0_6 , 0, 0x06, state::Unused;
-    pio0_7 , PIO0_7 , 0, 0x07, state::Unused;
-    pio0_8 , PIO0_8 , 0, 0x08, state::Unused;
-    pio0_9 , PIO0_9 , 0, 0x09, state::Unused;
-    pio0_10, PIO0_10, 0, 0x0a, state::Unused;
-    pio0_11, PIO0_11, 0, 0x0b, state::Unused;
-    pio0_12, PIO0_12, 0, 0x0c, state::Unused;
-    pio0_13, PIO0_13, 0, 0x0d, state::Unused;
-    pio0_14, PIO0_14, 0, 0x0e, state::Unused;
-    pio0_15, PIO0_15, 0, 0x0f, state::Unused;
-    pio0_16, PIO0_16, 0, 0x10, state::Unused;
-    pio0_17, PIO0_17, 0, 0x11, state::Unused;
-    pio0_18, PIO0_18, 0, 0x12, state::Unused;
-    pio0_19, PIO0_19, 0, 0x13, state::Unused;
-    pio0_20, PIO0_20, 0, 0x14, state::Unused;
-    pio0_21, PIO0_21, 0, 0x15, state::Unused;
-    pio0_22, PIO0_22, 0, 0x16, state::Unused;
-    pio0_23, PIO0_23, 0, 0x17, state::Unused;
-    pio0_24, PIO0_24, 0, 0x18, state::Unused;
-    pio0_25, PIO0_25, 0, 0x19, state::Unused;
-    pio0_26, PIO0_26, 0, 0x1a, state::Unused;
-    pio0_27, PIO0_27, 0, 0x1b, state::Unused;
-    pio0_28, PIO0_28, 0, 0x1c, state::Unused;
-    pio0_29, PIO0_29, 0, 0x1d, state::Unused;
-    pio0_30, PIO0_30, 0, 0x1e, state::Unused;
-    pio0_31, PIO0_31, 0, 0x1f, state::Unused;
-    pio1_0 , PIO1_0 , 1, 0x00, state::Unused;
-    pio1_1 , PIO1_1 , 1, 0x01, state::Unused;
-    pio1_2 , PIO1_2 , 1, 0x02, state::Unused;
-    pio1_3 , PIO1_3 , 1, 0x03, state::Unused;
-    pio1_4 , PIO1_4 , 1, 0x04, state::Unused;
-    pio1_5 , PIO1_5 , 1, 0x05, state::Unused;
-    pio1_6 , PIO1_6 , 1, 0x06, state::Unused;
-    pio1_7 , PIO1_7 , 1, 0x07, state::Unused;
-    pio1_8 , PIO1_8 , 1, 0x08, state::Unused;
-    pio1_9 , PIO1_9 , 1, 0x09, state::Unused;
-    pio1_10, PIO1_10, 1, 0x0a, state::Unused;
-    pio1_11, PIO1_11, 1, 0x0b, state::Unused;
-    pio1_12, PIO1_12, 1, 0x0c, state::Unused;
-    pio1_13, PIO1_13, 1, 0x0d, state::Unused;
-    pio1_14, PIO1_14, 1, 0x0e, state::Unused;
-    pio1_15, PIO1_15, 1, 0x0f, state::Unused;
-    pio1_16, PIO1_16, 1, 0x10, state::Unused;
-    pio1_17, PIO1_17, 1, 0x11, state::Unused;
-    pio1_18, PIO1_18, 1, 0x12, state::Unused;
-    pio1_19, PIO1_19, 1, 0x13, state::Unused;
-    pio1_20, PIO1_20, 1, 0x14, state::Unused;
-    pio1_21, PIO1_21, 1, 0x15, state::Unused;
+    pio0_0 , PIO0_0 , 0, 0x00, 0x44, state::Unused;
+    pio0_1 , PIO0_1 , 0, 0x01, 0x2c, state::Unused;
+    pio0_2 , PIO0_2 , 0, 0x02, 0x18, state::Swm<((),), ()>;
+    pio0_3 , PIO0_3 , 0, 0x03, 0x14, state::Swm<((),), ()>;
+    pio0_4 , PIO0_4 , 0, 0x04, 0x10, state::Unused;
+    pio0_5 , PIO0_5 , 0, 0x05, 0x0c, state::Swm<(), ((),)>;
+    pio0_6 , PIO0_6 , 0, 0x06, 0x40, state::Unused;
+    pio0_7 , PIO0_7 , 0, 0x07, 0x3c, state::Unused;
+    pio0_8 , PIO0_8 , 0, 0x08, 0x38, state::Unused;
+    pio0_9 , PIO0_9 , 0, 0x09, 0x34, state::Unused;
+    pio0_10, PIO0_10, 0, 0x0a, 0x20, state::Unused;
+    pio0_11, PIO0_11, 0, 0x0b, 0x1c, state::Unused;
+    pio0_12, PIO0_12, 0, 0x0c, 0x08, state::Unused;
+    pio0_13, PIO0_13, 0, 0x0d, 0x04, state::Unused;
+    pio0_14, PIO0_14, 0, 0x0e, 0x48, state::Unused;
+    pio0_15, PIO0_15, 0, 0x0f, 0x28, state::Unused;
+    pio0_16, PIO0_16, 0, 0x10, 0x24, state::Unused;
+    pio0_17, PIO0_17, 0, 0x11, 0x00, state::Unused;
+    pio0_18, PIO0_18, 0, 0x12, 0x78, state::Unused;
+    pio0_19, PIO0_19, 0, 0x13, 0x74, state::Unused;
+    pio0_20, PIO0_20, 0, 0x14, 0x70, state::Unused;
+    pio0_21, PIO0_21, 0, 0x15, 0x6c, state::Unused;
+    pio0_22, PIO0_22, 0, 0x16, 0x68, state::Unused;
+    pio0_23, PIO0_23, 0, 0x17, 0x64, state::Unused;
+    pio0_24, PIO0_24, 0, 0x18, 0x60, state::Unused;
+    pio0_25, PIO0_25, 0, 0x19, 0x5c, state::Unused;
+    pio0_26, PIO0_26, 0, 0x1a, 0x58, state::Unused;
+    pio0_27, PIO0_27, 0, 0x1b, 0x54, state::Unused;
+    pio0_28, PIO0_28, 0, 0x1c, 0x50, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio0_29, PIO0_29, 0, 0x1d, 0xc8, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio0_30, PIO0_30, 0, 0x1e, 0xcc, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio0_31, PIO0_31, 0, 0x1f, 0x8c, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_0 , PIO1_0 , 1, 0x00, 0x90, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_1 , PIO1_1 , 1, 0x01, 0x94, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_2 , PIO1_2 , 1, 0x02, 0x98, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_3 , PIO1_3 , 1, 0x03, 0xa4, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_4 , PIO1_4 , 1, 0x04, 0xa8, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_5 , PIO1_5 , 1, 0x05, 0xac, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_6 , PIO1_6 , 1, 0x06, 0xb8, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_7 , PIO1_7 , 1, 0x07, 0xc4, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_8 , PIO1_8 , 1, 0x08, 0x7c, state::Unused;
+    #[cfg(not(feature = "33"))]
+    pio1_9 , PIO1_9 , 1, 0x09, 0x80, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_10, PIO1_10, 1, 0x0a, 0xdc, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_11, PIO1_11, 1, 0x0b, 0xd8, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_12, PIO1_12, 1, 0x0c, 0x84, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_13, PIO1_13, 1, 0x0d, 0x88, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_14, PIO1_14, 1, 0x0e, 0x9c, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_15, PIO1_15, 1, 0x0f, 0xa0, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_16, PIO1_16, 1, 0x10, 0xb0, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_17, PIO1_17, 1, 0x11, 0xb4, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_18, PIO1_18, 1, 0x12, 0xbc, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_19, PIO1_19, 1, 0x13, 0xc0, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_20, PIO1_20, 1, 0x14, 0xd0, state::Unused;
+    #[cfg(not(any(feature = "33", feature = "48")))]
+    pio1_21, PIO1_21, 1, 0x15, 0xd4, state::Unused;
 );
@@ -0,0 +1,69 @@
+use core::fmt;
+
+use super::Trait;
+
+/// Identifies a pin at runtime
+///
+/// The typed pin API identifies pins at compile time, which is usually what
+/// you want. `PinId` is useful where a pin needs to be referred to by number
+/// instead, like in log messages, or in tables that describe a board.
+///
+/// Use [`Pin::id`] or [`GpioPin::id`] to get the `PinId` of a pin. Its
+/// `Display` implementation formats it like the pin's name (e.g. `PIO0_12`).
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{pins::PinId, Peripherals};
+///
+/// const BUTTON: PinId = PinId::new(0, 12);
+///
+/// let p = Peripherals::take().unwrap();
+///
+/// let pin = p.pins.pio0_12;
+/// assert_eq!(pin.id(), BUTTON);
+/// assert_eq!(pin.number(), 12);
+/// ```
+///
+/// [`Pin::id`]: struct.Pin.html#method.id
+/// [`GpioPin::id`]: ../gpio/struct.GpioPin.html#method.id
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PinId {
+    port: u8,
+    number: u8,
+}
+
+impl PinId {
+    /// Create a `PinId` from a port and a pin number
+    ///
+    /// This doesn't check whether the pin actually exists on the target
+    /// hardware. A `PinId` that is created this way compares equal to the
+    /// `PinId` of the pin with the same port and number.
+    pub const fn new(port: u8, number: u8) -> Self {
+        Self { port, number }
+    }
+
+    pub(crate) fn of<T: Trait>() -> Self {
+        Self::new(T::PORT as u8, T::ID)
+    }
+
+    /// The port of the pin
+    ///
+    /// This is `0` for PIO0 pins, and `1` for PIO1 pins.
+    pub fn port(&self) -> u8 {
+        self.port
+    }
+
+    /// The number of the pin within its port
+    ///
+    /// This is `12` for `PIO0_12`, for example.
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+}
+
+impl fmt::Display for PinId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PIO{}_{}", self.port, self.number)
+    }
+}
@@ -0,0 +1,147 @@
+use core::ptr;
+
+use crate::{pac, syscon};
+
+use super::traits::Trait;
+
+/// The IOCON configuration of a pin
+///
+/// IOCON controls the electrical characteristics of a pin, like its pull-up
+/// or pull-down resistor, and whether it is open-drain. This configuration is
+/// independent of the pin's state, so it is kept when a pin is converted from
+/// one state to another (for example, from GPIO to SWM).
+///
+/// Returned by [`Pin::iocon`] and [`GpioPin::iocon`].
+///
+/// PIO0_10 and PIO0_11 are true open-drain pins for I2C, with a different
+/// register layout. For those, only [`bits`], [`inverted`], and
+/// [`glitch_filter`] are meaningful.
+///
+/// [`Pin::iocon`]: struct.Pin.html#method.iocon
+/// [`GpioPin::iocon`]: ../gpio/struct.GpioPin.html#method.iocon
+/// [`bits`]: #method.bits
+/// [`inverted`]: #method.inverted
+/// [`glitch_filter`]: #method.glitch_filter
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IoconConfig {
+    bits: u32,
+}
+
+impl IoconConfig {
+    /// The raw value of the IOCON register
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// The mode of the pin's internal resistors
+    pub fn mode(&self) -> PullMode {
+        match (self.bits >> 3) & 0x3 {
+            0 => PullMode::Inactive,
+            1 => PullMode::PullDown,
+            2 => PullMode::PullUp,
+            _ => PullMode::Repeater,
+        }
+    }
+
+    /// Indicates whether hysteresis is enabled
+    pub fn hysteresis(&self) -> bool {
+        self.bits & (0x1 << 5) != 0
+    }
+
+    /// Indicates whether the input is inverted
+    pub fn inverted(&self) -> bool {
+        self.bits & (0x1 << 6) != 0
+    }
+
+    /// Indicates whether the pin is in open-drain mode
+    pub fn open_drain(&self) -> bool {
+        self.bits & (0x1 << 10) != 0
+    }
+
+    /// The configuration of the digital glitch filter
+    ///
+    /// Returns `None`, if the filter is bypassed. Otherwise, returns the
+    /// number of clock cycles for which a pulse needs to be stable (1 to 3),
+    /// and the index of the IOCONCLKDIV register that provides the filter
+    /// clock (0 to 6).
+    pub fn glitch_filter(&self) -> Option<(u8, u8)> {
+        let cycles = ((self.bits >> 11) & 0x3) as u8;
+        let clock = ((self.bits >> 13) & 0x7) as u8;
+
+        if cycles == 0 {
+            None
+        } else {
+            Some((cycles, clock))
+        }
+    }
+}
+
+/// The mode of a pin's internal resistors
+///
+/// Part of [`IoconConfig`].
+///
+/// [`IoconConfig`]: struct.IoconConfig.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PullMode {
+    /// No pull-up or pull-down resistor is enabled
+    Inactive,
+
+    /// The pull-down resistor is enabled
+    PullDown,
+
+    /// The pull-up resistor is enabled
+    ///
+    /// This is the default after reset.
+    PullUp,
+
+    /// Repeater mode, which keeps the last level the pin was driven to
+    Repeater,
+}
+
+/// Read the IOCON configuration of a pin
+pub(crate) fn read<T>(syscon: &mut syscon::Handle) -> IoconConfig
+where
+    T: Trait,
+{
+    syscon.enable_iocon_clock();
+
+    // Sound, as the caller owns the pin, and this is a read of a register
+    // that belongs to it.
+    let bits = unsafe { ptr::read_volatile(register::<T>()) };
+
+    IoconConfig { bits }
+}
+
+/// Return the IOCON configuration of a pin to its reset value
+pub(crate) fn reset<T>(syscon: &mut syscon::Handle)
+where
+    T: Trait,
+{
+    syscon.enable_iocon_clock();
+
+    // Sound, as the caller owns the pin, and this register only affects that
+    // pin.
+    unsafe { ptr::write_volatile(register::<T>(), reset_value::<T>()) };
+}
+
+fn register<T>() -> *mut u32
+where
+    T: Trait,
+{
+    (pac::IOCON::ptr() as usize + T::IOCON) as *mut u32
+}
+
+/// The reset value of a pin's IOCON register
+///
+/// PIO0_10 and PIO0_11 are true open-drain pins, without internal resistors.
+/// All other pins have their pull-up resistor enabled after reset.
+fn reset_value<T>() -> u32
+where
+    T: Trait,
+{
+    if T::PORT == 0 && (T::ID == 10 || T::ID == 11) {
+        0x80
+    } else {
+        0x90
+    }
+}
@@ -1,10 +1,12 @@
 use crate::{
     gpio::{direction, GpioPin, Level},
-    init_state,
+    init_state, syscon,
 };
 
 use super::{
     gen::Token,
+    id::PinId,
+    iocon::{self, IoconConfig},
     state::{self, State},
     traits::Trait,
 };
@@ -157,6 +159,40 @@ use super::{
 ///     p.pins.pio0_14.into_swm_pin(),
 ///     &mut swm_handle,
 /// );
+///
+/// // While ADC_2 is assigned, no other function can be assigned to the pin.
+/// // Unassigning it returns the pin to the SWM state.
+/// let (adc_2, pio0_14) = adc_2.unassign(pio0_14, &mut swm_handle);
+/// let (spi0_sck, pio0_14) = swm.movable_functions.spi0_sck.assign(
+///     pio0_14,
+///     &mut swm_handle,
+/// );
+/// ```
+///
+/// # IOCON Configuration
+///
+/// The electrical characteristics of a pin, like its pull-up or pull-down
+/// resistor, are configured in the IOCON peripheral. This configuration is
+/// independent of the pin's state, and none of the state transitions touch it.
+/// Whatever was configured before a conversion is still in effect afterwards.
+///
+/// [`Pin::iocon`] and [`GpioPin::iocon`] return the current IOCON
+/// configuration of a pin. [`Pin::reset_iocon`] and [`GpioPin::reset_iocon`]
+/// return it to its reset value, which is useful, if a pin is re-purposed after
+/// its IOCON configuration was changed for a previous use.
+///
+/// ``` no_run
+/// use lpc8xx_hal::{pins::PullMode, Peripherals};
+///
+/// let p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+///
+/// let mut pin = p.pins.pio0_12.into_swm_pin();
+///
+/// if pin.iocon(&mut syscon.handle).mode() != PullMode::PullUp {
+///     pin.reset_iocon(&mut syscon.handle);
+/// }
 /// ```
 ///
 /// [`PIO0_0`]: struct.PIO0_0.html
@@ -170,11 +206,64 @@ use super::{
 /// [`GpioPin`]: ../gpio/struct.GpioPin.html
 /// [`Pin::into_swm_pin`]: struct.Pin.html#method.into_swm_pin
 /// [SWM API]: ../swm/index.html
+/// [`Pin::iocon`]: struct.Pin.html#method.iocon
+/// [`GpioPin::iocon`]: ../gpio/struct.GpioPin.html#method.iocon
+/// [`Pin::reset_iocon`]: struct.Pin.html#method.reset_iocon
+/// [`GpioPin::reset_iocon`]: ../gpio/struct.GpioPin.html#method.reset_iocon
 pub struct Pin<T: Trait, S: State> {
     pub(crate) ty: T,
     pub(crate) _state: S,
 }
 
+impl<T, S> Pin<T, S>
+where
+    T: Trait,
+    S: State,
+{
+    /// Return the port of the pin
+    ///
+    /// This is `0` for PIO0 pins, and `1` for PIO1 pins.
+    pub fn port(&self) -> u8 {
+        T::PORT as u8
+    }
+
+    /// Return the number of the pin within its port
+    ///
+    /// This is `12` for [`PIO0_12`], for example.
+    ///
+    /// [`PIO0_12`]: struct.PIO0_12.html
+    pub fn number(&self) -> u8 {
+        T::ID
+    }
+
+    /// Return the [`PinId`] that identifies this pin at runtime
+    ///
+    /// [`PinId`]: struct.PinId.html
+    pub fn id(&self) -> PinId {
+        PinId::of::<T>()
+    }
+
+    /// Read the pin's IOCON configuration
+    ///
+    /// Enables the IOCON clock, if it isn't already enabled. See
+    /// [IOCON Configuration] for more information.
+    ///
+    /// [IOCON Configuration]: #iocon-configuration
+    pub fn iocon(&self, syscon: &mut syscon::Handle) -> IoconConfig {
+        iocon::read::<T>(syscon)
+    }
+
+    /// Return the pin's IOCON configuration to its reset value
+    ///
+    /// Enables the IOCON clock, if it isn't already enabled. See
+    /// [IOCON Configuration] for more information.
+    ///
+    /// [IOCON Configuration]: #iocon-configuration
+    pub fn reset_iocon(&mut self, syscon: &mut syscon::Handle) {
+        iocon::reset::<T>(syscon)
+    }
+}
+
 impl<T> Pin<T, state::Unused>
 where
     T: Trait,
@@ -200,7 +289,7 @@ where
         self,
         token: Token<T, init_state::Enabled>,
     ) -> GpioPin<T, direction::Input> {
-        GpioPin::new(token, ())
+        GpioPin::new(self.ty, token, ())
     }
 
     /// Transition pin to GPIO output mode
@@ -225,7 +314,7 @@ where
         token: Token<T, init_state::Enabled>,
         initial: Level,
     ) -> GpioPin<T, direction::Output> {
-        GpioPin::new(token, initial)
+        GpioPin::new(self.ty, token, initial)
     }
 
     /// Transition pin to SWM mode
@@ -29,7 +29,12 @@ impl Unused {
 
 impl State for Unused {}
 
-/// Marks a [`Pin`]  as being assigned to the analog-to-digital converter
+/// Marks a [`Pin`]  as being assigned to an analog function
+///
+/// Analog functions include the ADC and comparator inputs, as well as any
+/// other analog function the target provides. While an analog function is
+/// assigned, no other function can be assigned to the pin. The analog function
+/// must be unassigned first, which returns the pin to the SWM state.
 ///
 /// [`Pin`]: ../struct.Pin.html
 pub struct Analog;
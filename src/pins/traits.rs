@@ -34,4 +34,12 @@ pub trait Trait {
     /// [`PIO0_1`]: struct.PIO0_1.html
     /// [`PIO0_2`]: struct.PIO0_2.html
     const MASK: u32;
+
+    /// The offset of the pin's register in the IOCON register block
+    ///
+    /// The IOCON registers are not in pin order, so this differs from pin to
+    /// pin. See [`Pin::iocon`].
+    ///
+    /// [`Pin::iocon`]: struct.Pin.html#method.iocon
+    const IOCON: usize;
 }
@@ -214,7 +214,19 @@ impl Handle {
 /// The 10 kHz low-power clock
 ///
 /// This is one of the clocks that can be used to run the self-wake-up timer
-/// (WKT). See user manual, section 18.5.1.
+/// (WKT). See user manual, section 18.5.1. It is the only clock that keeps
+/// running in deep power-down mode, if [`enable_in_deep_power_down`] has been
+/// called, which makes it the only way to wake up from deep power-down mode
+/// using a timer.
+///
+/// The low-power oscillator can't clock the windowed watchdog timer (WWDT).
+/// The WWDT is always clocked by the watchdog oscillator (see [`WdtOsc`]).
+///
+/// The low-power oscillator is very imprecise. Its frequency can deviate from
+/// the nominal 10 kHz by up to 40%.
+///
+/// [`enable_in_deep_power_down`]: #method.enable_in_deep_power_down
+/// [`WdtOsc`]: ../syscon/clock_source/struct.WdtOsc.html
 pub struct LowPowerClock<State = init_state::Enabled> {
     _state: State,
 }
@@ -270,12 +282,41 @@ impl LowPowerClock<init_state::Enabled> {
         self,
         pmu: &mut Handle,
     ) -> LowPowerClock<init_state::Disabled> {
-        pmu.pmu.dpdctrl.modify(|_, w| w.lposcen().disabled());
+        pmu.pmu.dpdctrl.modify(|_, w| {
+            w.lposcdpden().disabled();
+            w.lposcen().disabled()
+        });
 
         LowPowerClock {
             _state: init_state::Disabled,
         }
     }
+
+    /// Keep the low-power clock running in deep power-down mode
+    ///
+    /// By default, the low-power oscillator is stopped when entering deep
+    /// power-down mode. Enabling it in deep power-down mode allows the WKT to
+    /// wake up the microcontroller from that mode.
+    ///
+    /// This setting is stored in the PMU's always-on power domain, so it is
+    /// retained in deep power-down mode, and still in effect after waking up
+    /// from it. It is only reset by a power-on reset, or by calling
+    /// [`disable_in_deep_power_down`] or [`disable`].
+    ///
+    /// [`disable_in_deep_power_down`]: #method.disable_in_deep_power_down
+    /// [`disable`]: #method.disable
+    pub fn enable_in_deep_power_down(&mut self, pmu: &mut Handle) {
+        pmu.pmu.dpdctrl.modify(|_, w| w.lposcdpden().enabled());
+    }
+
+    /// Stop the low-power clock in deep power-down mode
+    ///
+    /// Reverts the effect of [`enable_in_deep_power_down`].
+    ///
+    /// [`enable_in_deep_power_down`]: #method.enable_in_deep_power_down
+    pub fn disable_in_deep_power_down(&mut self, pmu: &mut Handle) {
+        pmu.pmu.dpdctrl.modify(|_, w| w.lposcdpden().disabled());
+    }
 }
 
 impl<State> clock::Frequency for LowPowerClock<State> {
@@ -0,0 +1,277 @@
+//! Resistance and capacitance measurement using the analog comparator
+//!
+//! A resistive sensor (like a photoresistor) or a capacitive sensor (like a
+//! moisture probe) can be read without an ADC channel, by measuring how long
+//! it takes to charge a capacitor through it. [`RcTimer`] does that using the
+//! analog comparator (ACMP) and an MRT channel.
+//!
+//! The circuit consists of a resistor and a capacitor in series, between a
+//! GPIO pin and ground. The node between the two is connected to one of the
+//! ACMP inputs. One of the two is the sensor, the other has a known value.
+//!
+//! ``` text
+//! GPIO ---[ R ]---+---[ C ]--- GND
+//!                 |
+//!              ACMP_Ix
+//! ```
+//!
+//! To measure, the GPIO pin is set low, to discharge the capacitor. Then it
+//! is set high, and the time until the voltage on the capacitor reaches a
+//! threshold is measured. The threshold is set by the comparator's voltage
+//! ladder, in steps of 1/31 of the supply voltage. With a threshold of `n`,
+//! the measured time is `-ln(1 - n / 31) * R * C`, which is about `R * C` for
+//! a threshold of 20.
+//!
+//! The comparator is polled, so the resolution is a few clock cycles.
+//! Interrupts stay enabled during the measurement, except for a few cycles
+//! at a time. An interrupt that is handled right after the threshold has
+//! been reached makes that measurement longer, by the time the handler takes.
+//! Measurements are limited by a timeout, so a disconnected sensor doesn't
+//! block the program for long.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{gpio::Level, rc_timer::RcTimer, Peripherals};
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let swm = p.SWM.split();
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! #[cfg(feature = "82x")]
+//! let gpio = p.GPIO;
+//! #[cfg(feature = "845")]
+//! let gpio = p.GPIO.enable(&mut syscon.handle);
+//!
+//! let (acmp_i3, _) = swm.fixed_functions.acmp_i3.assign(
+//!     p.pins.pio0_14.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//! let charge = p.pins.pio0_12.into_output_pin(
+//!     gpio.tokens.pio0_12,
+//!     Level::Low,
+//! );
+//! let timer = p.MRT0.split(&mut syscon.handle).mrt0;
+//!
+//! // Give up after 10 ms (the MRT runs at 12 MHz)
+//! let mut rc_timer = RcTimer::new(
+//!     p.ACOMP,
+//!     acmp_i3,
+//!     charge,
+//!     timer,
+//!     20,
+//!     120_000,
+//!     &mut syscon.handle,
+//! );
+//!
+//! // Discharge for 1 ms, then measure
+//! let ticks = rc_timer.measure(12_000).unwrap();
+//! ```
+//!
+//! [`RcTimer`]: struct.RcTimer.html
+
+use cortex_m::interrupt;
+use embedded_hal::{digital::v2::OutputPin, timer::CountDown};
+use nb::block;
+
+use crate::{
+    mrt,
+    pac::{self, acomp::ctrl},
+    swm, syscon,
+};
+
+/// Measures the time it takes to charge a capacitor to a threshold
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct RcTimer<I, P, C, T>
+where
+    T: mrt::Trait,
+{
+    acomp: pac::ACOMP,
+    input: swm::Function<I, swm::state::Assigned<P>>,
+    charge: C,
+    timer: mrt::Channel<T>,
+    timeout: u32,
+}
+
+impl<I, P, C, T> RcTimer<I, P, C, T>
+where
+    I: Input,
+    C: OutputPin,
+    T: mrt::Trait,
+{
+    /// Create a new instance of `RcTimer`
+    ///
+    /// Enables and powers up the analog comparator, and configures it to
+    /// compare `input` with the voltage ladder. `charge` is the pin that
+    /// charges the capacitor. `threshold` is the output of the voltage ladder,
+    /// in multiples of 1/31 of the supply voltage.
+    ///
+    /// `timeout` is the maximum duration of a measurement, in MRT ticks. It
+    /// should be a few times the longest expected charge time.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `threshold` is not between 1 and 30, or if `timeout` is
+    /// larger than [`mrt::MAX_VALUE`].
+    ///
+    /// [`mrt::MAX_VALUE`]: ../mrt/constant.MAX_VALUE.html
+    pub fn new(
+        acomp: pac::ACOMP,
+        input: swm::Function<I, swm::state::Assigned<P>>,
+        charge: C,
+        timer: mrt::Channel<T>,
+        threshold: u8,
+        timeout: u32,
+        syscon: &mut syscon::Handle,
+    ) -> Self {
+        assert!(threshold > 0 && threshold < 31);
+        assert!(timeout <= mrt::MAX_VALUE);
+
+        syscon.enable_clock(&acomp);
+        syscon.power_up(&acomp);
+
+        acomp.lad.write(|w| {
+            w.laden().set_bit();
+            unsafe { w.ladsel().bits(threshold) };
+            w.ladref().ladref_0()
+        });
+        acomp.ctrl.write(|w| {
+            I::select(w);
+            w.comp_vm_sel().voltage_ladder_output()
+        });
+
+        Self {
+            acomp,
+            input,
+            charge,
+            timer,
+            timeout,
+        }
+    }
+
+    /// Measure the time it takes to charge the capacitor to the threshold
+    ///
+    /// Discharges the capacitor for `discharge` ticks first, which should be
+    /// at least 5 times the largest expected time constant. Returns the
+    /// charge time in MRT ticks. The capacitor is left discharging.
+    ///
+    /// Returns [`Error::NotDischarged`], if the voltage is still above the
+    /// threshold after discharging, and [`Error::Timeout`], if the threshold
+    /// isn't reached within the timeout.
+    ///
+    /// [`Error::NotDischarged`]: enum.Error.html#variant.NotDischarged
+    /// [`Error::Timeout`]: enum.Error.html#variant.Timeout
+    pub fn measure(&mut self, discharge: u32) -> Result<u32, Error<C::Error>> {
+        self.charge.set_low().map_err(Error::Pin)?;
+        self.timer.start(discharge.min(mrt::MAX_VALUE));
+        let _ = block!(self.timer.wait());
+
+        if self.is_above_threshold() {
+            return Err(Error::NotDischarged);
+        }
+
+        self.timer.start(mrt::MAX_VALUE);
+
+        let start = interrupt::free(|_| {
+            let start = self.timer.value();
+            self.charge.set_high().map(|()| start)
+        })
+        .map_err(Error::Pin)?;
+
+        let result = loop {
+            // Read the comparator and the timer together, so an interrupt
+            // can't be handled in between.
+            let (above, value) = interrupt::free(|_| {
+                (self.is_above_threshold(), self.timer.value())
+            });
+            let elapsed = start.wrapping_sub(value);
+
+            if above {
+                break Ok(elapsed);
+            }
+            // The channel reloads once it reaches zero, after which `elapsed`
+            // would start over. Check its interrupt flag too, so the loop
+            // ends, even if the timeout is the full range.
+            if elapsed >= self.timeout || self.timer.has_finished() {
+                break Err(Error::Timeout);
+            }
+        };
+
+        self.charge.set_low().map_err(Error::Pin)?;
+
+        result
+    }
+
+    /// Release the resources
+    ///
+    /// The analog comparator is left enabled, in its current configuration.
+    #[allow(clippy::type_complexity)]
+    pub fn free(
+        self,
+    ) -> (
+        pac::ACOMP,
+        swm::Function<I, swm::state::Assigned<P>>,
+        C,
+        mrt::Channel<T>,
+    ) {
+        (self.acomp, self.input, self.charge, self.timer)
+    }
+
+    fn is_above_threshold(&self) -> bool {
+        self.acomp.ctrl.read().compstat().bit_is_set()
+    }
+}
+
+/// An error that can occur during a measurement
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The capacitor was still charged above the threshold
+    ///
+    /// The discharge time is too short, or the circuit is not connected as
+    /// expected.
+    NotDischarged,
+
+    /// The threshold wasn't reached within the timeout
+    Timeout,
+
+    /// An error occurred while accessing the charge pin
+    Pin(E),
+}
+
+/// Implemented for all analog comparator inputs
+///
+/// This trait is an internal implementation detail and should neither be
+/// implemented nor used outside of LPC8xx HAL. Any changes to this trait won't
+/// be considered breaking changes.
+pub trait Input {
+    /// Select this input as the positive input of the comparator
+    fn select(w: &mut ctrl::W) -> &mut ctrl::W;
+}
+
+macro_rules! inputs {
+    ($($function:ident, $method:ident;)*) => {
+        $(
+            impl Input for swm::$function {
+                fn select(w: &mut ctrl::W) -> &mut ctrl::W {
+                    w.comp_vp_sel().$method()
+                }
+            }
+        )*
+    };
+}
+
+inputs!(
+    ACMP_I1, acmp_i1;
+    ACMP_I2, acmp_i2;
+    ACMP_I3, acmp_i3;
+    ACMP_I4, acmp_i4;
+);
@@ -0,0 +1,170 @@
+//! Driver for rotary encoders
+//!
+//! Rotary encoders, as commonly used for UI knobs, output two square waves
+//! that are 90° out of phase (quadrature signals). The direction of rotation
+//! can be determined from which of the signals leads the other.
+//!
+//! [`RotaryEncoder`] decodes these signals in software, using a state machine
+//! that only accepts complete, valid sequences of transitions. This makes it
+//! immune to contact bounce, without requiring any additional debouncing. It
+//! emits one [`Event`] per detent, for encoders that have one full quadrature
+//! cycle per detent and rest with both signals high. This is the most common
+//! type of encoder. Since the pins have their pull-up resistors enabled by
+//! default, the encoder can usually be connected directly between the pins and
+//! ground.
+//!
+//! If the SCT is available, [`SCT::start_qei`] decodes quadrature signals in
+//! hardware, which should be preferred for fast-moving encoders. This module
+//! is meant for manually operated knobs, where the SCT is either not available
+//! or needed for other purposes.
+//!
+//! # Interrupts
+//!
+//! [`RotaryEncoder::update`] needs to be called whenever either of the signals
+//! changes. Besides polling it from a loop, as shown below, it can be called
+//! from the interrupt handler of pin interrupts, which should be configured
+//! to fire on both rising and falling edges of both pins. Make sure to clear
+//! the edge flags of both pin interrupts in the interrupt handler.
+//!
+//! On LPC845, pin interrupts can be configured using the `pinint` module. On
+//! LPC82x, the HAL doesn't provide an API for pin interrupts yet, so you need
+//! to use the `PININT` field of `Peripherals` directly.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     Peripherals,
+//!     rotary_encoder::{Event, RotaryEncoder},
+//! };
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let gpio = p.GPIO;
+//! #[cfg(feature = "845")]
+//! let gpio = p.GPIO.enable(&mut syscon.handle);
+//!
+//! let a = p.pins.pio0_16.into_input_pin(gpio.tokens.pio0_16);
+//! let b = p.pins.pio0_17.into_input_pin(gpio.tokens.pio0_17);
+//!
+//! let mut knob = RotaryEncoder::new(a, b);
+//! let mut volume: i32 = 0;
+//!
+//! loop {
+//!     match knob.update().unwrap() {
+//!         Some(Event::Increment) => volume += 1,
+//!         Some(Event::Decrement) => volume -= 1,
+//!         None => {}
+//!     }
+//! }
+//! ```
+//!
+//! [`RotaryEncoder`]: struct.RotaryEncoder.html
+//! [`RotaryEncoder::update`]: struct.RotaryEncoder.html#method.update
+//! [`Event`]: enum.Event.html
+//! [`SCT::start_qei`]: ../sct/struct.SCT.html#method.start_qei
+
+use embedded_hal::digital::v2::InputPin;
+
+/// A rotary encoder, connected to two input pins
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct RotaryEncoder<A, B> {
+    a: A,
+    b: B,
+    state: u8,
+}
+
+impl<A, B, E> RotaryEncoder<A, B>
+where
+    A: InputPin<Error = E>,
+    B: InputPin<Error = E>,
+{
+    /// Create a new instance of the driver
+    ///
+    /// The encoder is expected to be at rest, on a detent, when this method is
+    /// called.
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b, state: START }
+    }
+
+    /// Sample the pins and advance the state machine
+    ///
+    /// Returns an event, once the encoder has moved to the next detent. A
+    /// rotation in which signal A leads signal B results in
+    /// [`Event::Increment`], the opposite direction in [`Event::Decrement`].
+    /// Swap the pins, to swap the direction.
+    ///
+    /// [`Event::Increment`]: enum.Event.html#variant.Increment
+    /// [`Event::Decrement`]: enum.Event.html#variant.Decrement
+    pub fn update(&mut self) -> Result<Option<Event>, E> {
+        let a = self.a.is_high()? as usize;
+        let b = self.b.is_high()? as usize;
+
+        let next = TRANSITIONS[self.state as usize][(a << 1) | b];
+        self.state = next & STATE_MASK;
+
+        let event = match next & !STATE_MASK {
+            INCREMENT => Some(Event::Increment),
+            DECREMENT => Some(Event::Decrement),
+            _ => None,
+        };
+
+        Ok(event)
+    }
+
+    /// Release the pins
+    pub fn free(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+/// An event emitted by [`RotaryEncoder`]
+///
+/// [`RotaryEncoder`]: struct.RotaryEncoder.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// The encoder has moved one detent in the direction where A leads B
+    Increment,
+
+    /// The encoder has moved one detent in the direction where B leads A
+    Decrement,
+}
+
+// States of the decoder. Both signals are high at rest (`START`). In the
+// increment direction, A goes low first, then B, then A goes high again, then
+// B. The decrement direction is the mirror image of that.
+const START: u8 = 0;
+const INC_FINAL: u8 = 1;
+const INC_BEGIN: u8 = 2;
+const INC_NEXT: u8 = 3;
+const DEC_BEGIN: u8 = 4;
+const DEC_FINAL: u8 = 5;
+const DEC_NEXT: u8 = 6;
+
+const STATE_MASK: u8 = 0x0f;
+const INCREMENT: u8 = 0x10;
+const DECREMENT: u8 = 0x20;
+
+/// The next state, indexed by current state and `(A << 1) | B`
+const TRANSITIONS: [[u8; 4]; 7] = [
+    // START
+    [START, INC_BEGIN, DEC_BEGIN, START],
+    // INC_FINAL
+    [INC_NEXT, START, INC_FINAL, START | INCREMENT],
+    // INC_BEGIN
+    [INC_NEXT, INC_BEGIN, START, START],
+    // INC_NEXT
+    [INC_NEXT, INC_BEGIN, INC_FINAL, START],
+    // DEC_BEGIN
+    [DEC_NEXT, START, DEC_BEGIN, START],
+    // DEC_FINAL
+    [DEC_NEXT, DEC_FINAL, START, START | DECREMENT],
+    // DEC_NEXT
+    [DEC_NEXT, DEC_FINAL, DEC_BEGIN, START],
+];
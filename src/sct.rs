@@ -0,0 +1,1640 @@
+//! API for the State Configurable Timer (SCT)
+//!
+//! Currently, PWM output, complementary PWM output with dead time, quadrature
+//! decoding, input capture, and microsecond timestamps are implemented.
+//!
+//! For PWM output, the SCT is operated as a single, unified 32-bit counter.
+//! Match register 0 defines the period of all PWM channels. Each SCT output
+//! gets its own match register, which defines the duty cycle of that output.
+//!
+//! Unlike the CTimer, whose PWM outputs are limited to the CTimer's match
+//! outputs, the SCT outputs are movable functions. That means any pin can be
+//! used as a PWM output.
+//!
+//! Please refer to [`SCT::start_complementary_pwm`] for information on
+//! complementary PWM, to [`SCT::start_qei`] for information on quadrature
+//! decoding, to [`SCT::start_capture`] for information on input capture, and
+//! to [`SCT::start_timestamps`] for information on timestamps.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use lpc8xx_hal::{
+//!     delay::Delay,
+//!     prelude::*,
+//!     Peripherals,
+//!     pac::CorePeripherals,
+//! };
+//!
+//! let cp = CorePeripherals::take().unwrap();
+//! let p = Peripherals::take().unwrap();
+//!
+//! let swm = p.SWM.split();
+//! let mut delay = Delay::new(cp.SYST);
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! // Use 8 bit pwm
+//! let channels = p.SCT0.start_pwm(256, 0, &mut syscon.handle);
+//!
+//! let pwm_output = p.pins.pio0_14.into_swm_pin();
+//!
+//! let (pwm_output, _) = swm.movable_functions.sct_out0.assign(
+//!     pwm_output,
+//!     &mut swm_handle,
+//! );
+//!
+//! let mut pwm_pin = channels.out0.attach(pwm_output);
+//! loop {
+//!     for i in 0..pwm_pin.get_max_duty() {
+//!         delay.delay_ms(4_u8);
+//!         pwm_pin.set_duty(i);
+//!     }
+//! }
+//! ```
+//!
+//! [`SCT::start_complementary_pwm`]: struct.SCT.html#method.start_complementary_pwm
+//! [`SCT::start_qei`]: struct.SCT.html#method.start_qei
+//! [`SCT::start_capture`]: struct.SCT.html#method.start_capture
+//! [`SCT::start_timestamps`]: struct.SCT.html#method.start_timestamps
+
+use core::{marker::PhantomData, time::Duration};
+
+use cortex_m::interrupt;
+use embedded_hal::{Direction, PwmPin};
+use void::Void;
+
+use crate::{
+    adc::Edge,
+    gpio::Level,
+    pac::{self, sct0::RegisterBlock, SCT0},
+    pins,
+    servo::ServoPin,
+    swm::{
+        self, SCT_OUT0, SCT_OUT1, SCT_OUT2, SCT_OUT3, SCT_OUT4, SCT_OUT5,
+        SCT_PIN0, SCT_PIN1, SCT_PIN2, SCT_PIN3,
+    },
+    syscon,
+    tone::TonePin,
+};
+
+#[cfg(feature = "845")]
+use crate::swm::SCT_OUT6;
+
+/// Interface to the SCT peripheral
+///
+/// Controls the SCT. Use [`Peripherals`] to gain access to an instance of this
+/// struct.
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [`Peripherals`]: ../struct.Peripherals.html
+/// [module documentation]: index.html
+pub struct SCT {
+    sct: SCT0,
+}
+
+impl SCT {
+    pub(crate) fn new(sct: SCT0) -> Self {
+        Self { sct }
+    }
+
+    /// Start the PWM timer, with a predefined period and prescaler
+    ///
+    /// The `period` sets resolution of the pwm and is returned with
+    /// `get_max_duty`. The SCT is clocked by the system clock, divided by
+    /// `prescaler + 1`.
+    ///
+    /// All PWM channels start out with a duty cycle of 0. With a duty cycle
+    /// of 0, the output stays low for the whole period.
+    pub fn start_pwm(
+        self,
+        period: u32,
+        prescaler: u8,
+        syscon: &mut syscon::Handle,
+    ) -> Channels {
+        syscon.enable_clock(&self.sct);
+
+        // Operate as one 32-bit counter, that is reset whenever it matches
+        // match register 0.
+        self.sct.config.write(|w| {
+            w.unify().set_bit();
+            w.autolimit_l().set_bit()
+        });
+
+        self.sct.sctmatch0().write(|w| unsafe { w.bits(period) });
+        self.sct.sctmatchrel0().write(|w| unsafe { w.bits(period) });
+
+        // Event 0 marks the start of each period and sets all outputs.
+        self.sct.event[0]
+            .state
+            .write(|w| unsafe { w.statemskn().bits(1) });
+        self.sct.event[0].ctrl.write(|w| {
+            unsafe { w.matchsel().bits(0) };
+            w.combmode().match_()
+        });
+
+        for (i, out) in self.sct.out.iter().enumerate() {
+            // Each output is cleared by its own event, which uses the match
+            // register with the same number. Match register 0 is reserved for
+            // the period, hence the offset.
+            let n = i + 1;
+
+            self.sct.event[n]
+                .state
+                .write(|w| unsafe { w.statemskn().bits(1) });
+            self.sct.event[n].ctrl.write(|w| {
+                unsafe { w.matchsel().bits(n as u8) };
+                w.combmode().match_()
+            });
+
+            // The match registers are 0 after reset, so the duty cycle is 0
+            // and the output isn't set yet. See `Output::set_duty`.
+            out.set.write(|w| unsafe { w.set().bits(0) });
+            out.clr.write(|w| unsafe { w.clr().bits(1 << n) });
+        }
+
+        // If an output is set and cleared at the same time, the duty cycle is
+        // equal to the period. Setting the output yields the correct result in
+        // that case.
+        self.sct.res.write(|w| {
+            w.o0res().set();
+            w.o1res().set();
+            w.o2res().set();
+            w.o3res().set();
+            w.o4res().set();
+            #[cfg(feature = "845")]
+            w.o6res().set();
+
+            w.o5res().set()
+        });
+
+        // Start the timer
+        self.sct.ctrl.write(|w| {
+            unsafe { w.pre_l().bits(prescaler) };
+            w.clrctr_l().set_bit();
+            w.halt_l().clear_bit()
+        });
+
+        Channels {
+            out0: DetachedPwmPin::new(),
+            out1: DetachedPwmPin::new(),
+            out2: DetachedPwmPin::new(),
+            out3: DetachedPwmPin::new(),
+            out4: DetachedPwmPin::new(),
+            out5: DetachedPwmPin::new(),
+            #[cfg(feature = "845")]
+            out6: DetachedPwmPin::new(),
+        }
+    }
+
+    /// Start decoding a quadrature signal
+    ///
+    /// Phase A of the signal must be connected to `SCT_PIN0`, phase B to
+    /// `SCT_PIN1`. The SCT is clocked by the rising edges of phase A, and the
+    /// level of phase B at each of those edges determines the direction. This
+    /// means one count is registered per full cycle of the quadrature signal
+    /// (sometimes called "X1 decoding"). A signal whose phase A leads phase B
+    /// counts up.
+    ///
+    /// The SCT is split into two 16-bit counters. One counts the steps in the
+    /// positive direction, the other the steps in the negative direction. The
+    /// difference between both is returned as the count, which wraps around
+    /// after 2^16 steps in either direction.
+    ///
+    /// On the LPC845, the SCT inputs go through the input multiplexer. This
+    /// method configures it to connect `SCT_PIN0` and `SCT_PIN1` to SCT inputs
+    /// 0 and 1. Please keep that in mind, if you're using `INPUTMUX` yourself.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{prelude::*, Peripherals};
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let swm = p.SWM.split();
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// let (a, _) = swm.movable_functions.sct_pin0.assign(
+    ///     p.pins.pio0_14.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    /// let (b, _) = swm.movable_functions.sct_pin1.assign(
+    ///     p.pins.pio0_15.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    ///
+    /// let qei = p.SCT0.start_qei(a, b, &mut syscon.handle);
+    ///
+    /// let position = qei.count();
+    /// ```
+    pub fn start_qei<A, B>(
+        self,
+        a: swm::Function<SCT_PIN0, swm::state::Assigned<A>>,
+        b: swm::Function<SCT_PIN1, swm::state::Assigned<B>>,
+        syscon: &mut syscon::Handle,
+    ) -> Qei<A, B>
+    where
+        A: pins::Trait,
+        B: pins::Trait,
+    {
+        syscon.enable_clock(&self.sct);
+
+        #[cfg(feature = "845")]
+        {
+            // Sound, as the only other part of the HAL API that accesses
+            // `INPUTMUX` is the DMA API, which only writes to
+            // `DMA_ITRIG_INMUX`. We only write to `SCT_INMUX`, which belongs
+            // to the SCT we own.
+            let inputmux = unsafe { &*pac::INPUTMUX::ptr() };
+            inputmux.sct_inmux[0].write(|w| unsafe { w.inp_n().bits(0) });
+            inputmux.sct_inmux[1].write(|w| unsafe { w.inp_n().bits(1) });
+        }
+
+        // Both counters only count on rising edges of phase A.
+        self.sct.config.write(|w| {
+            w.unify().dual_counter();
+            w.clkmode().sampled_system_clock_mode();
+            w.cksel().input_0_rising_edges()
+        });
+
+        // Event 0 happens on rising edges of phase B, event 1 on falling edges.
+        for (n, event) in self.sct.event[..2].iter().enumerate() {
+            event.state.write(|w| unsafe { w.statemskn().bits(1) });
+            event.ctrl.write(|w| {
+                unsafe { w.iosel().bits(1) };
+                w.outsel().input();
+                if n == 0 {
+                    w.iocond().rise();
+                } else {
+                    w.iocond().fall();
+                }
+                w.combmode().io()
+            });
+        }
+
+        // While phase B is high, a rising edge on phase A means the signal is
+        // moving in the negative direction. Start the counter for that
+        // direction, stop the one for the positive direction. Do the opposite
+        // when phase B is low.
+        self.sct.start.write(|w| unsafe {
+            w.startmsk_l().bits(1 << 1);
+            w.startmsk_h().bits(1 << 0)
+        });
+        self.sct.stop.write(|w| unsafe {
+            w.stopmsk_l().bits(1 << 0);
+            w.stopmsk_h().bits(1 << 1)
+        });
+
+        // Start the timer
+        let b_high = self.sct.input.read().sin1().bit_is_set();
+        self.sct.ctrl.write(|w| {
+            w.clrctr_l().set_bit();
+            w.clrctr_h().set_bit();
+            w.stop_l().bit(b_high);
+            w.stop_h().bit(!b_high);
+            w.halt_l().clear_bit();
+            w.halt_h().clear_bit()
+        });
+
+        Qei { sct: self, a, b }
+    }
+
+    /// Start measuring the signal on an input
+    ///
+    /// The signal must be connected to `SCT_PIN0`. The SCT is operated as a
+    /// free-running 32-bit counter, which is clocked by the system clock,
+    /// divided by `prescaler + 1`. `clock` is the frequency of the system
+    /// clock in Hz, which is required to convert the measurements into
+    /// frequencies and durations.
+    ///
+    /// Each measurement captures the counter on a rising edge, the following
+    /// falling edge, and the rising edge after that. The period of the signal
+    /// must be shorter than the time it takes the counter to overflow.
+    ///
+    /// On the LPC845, the SCT inputs go through the input multiplexer. This
+    /// method configures it to connect `SCT_PIN0` to SCT input 0. Please keep
+    /// that in mind, if you're using `INPUTMUX` yourself.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `clock` is smaller than `prescaler + 1`, which includes a
+    /// `clock` of zero. The measurements couldn't be converted otherwise.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{prelude::*, Peripherals};
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let swm = p.SWM.split();
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// let (input, _) = swm.movable_functions.sct_pin0.assign(
+    ///     p.pins.pio0_14.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    ///
+    /// // The system clock runs at 12 MHz by default
+    /// let mut capture =
+    ///     p.SCT0.start_capture(input, 0, 12_000_000, &mut syscon.handle);
+    ///
+    /// let measurement = nb::block!(capture.measure()).unwrap();
+    /// let frequency = measurement.frequency();
+    /// let duty_cycle = measurement.duty_cycle();
+    /// ```
+    pub fn start_capture<P>(
+        self,
+        input: swm::Function<SCT_PIN0, swm::state::Assigned<P>>,
+        prescaler: u8,
+        clock: u32,
+        syscon: &mut syscon::Handle,
+    ) -> InputCapture<P>
+    where
+        P: pins::Trait,
+    {
+        let tick_frequency = clock / (prescaler as u32 + 1);
+        assert!(tick_frequency > 0);
+
+        syscon.enable_clock(&self.sct);
+
+        #[cfg(feature = "845")]
+        {
+            // Sound, as the only other part of the HAL API that accesses
+            // `INPUTMUX` is the DMA API, which only writes to
+            // `DMA_ITRIG_INMUX`. We only write to `SCT_INMUX`, which belongs
+            // to the SCT we own.
+            let inputmux = unsafe { &*pac::INPUTMUX::ptr() };
+            inputmux.sct_inmux[0].write(|w| unsafe { w.inp_n().bits(0) });
+        }
+
+        self.sct.config.write(|w| w.unify().set_bit());
+
+        // Use registers 0 to 2 as capture registers. Each of them is loaded
+        // by the event with the same number.
+        self.sct
+            .regmode
+            .write(|w| unsafe { w.regmod_l().bits(0b111) });
+        self.sct
+            .sctcapctrl0()
+            .write(|w| unsafe { w.capconn_l().bits(1 << 0) });
+        self.sct
+            .sctcapctrl1()
+            .write(|w| unsafe { w.capconn_l().bits(1 << 1) });
+        self.sct
+            .sctcapctrl2()
+            .write(|w| unsafe { w.capconn_l().bits(1 << 2) });
+
+        // Every event is only enabled in the state with the same number, and
+        // advances the state machine to the next state. After the last event,
+        // the state machine ends up in state 3, which has no events enabled.
+        for (n, event) in self.sct.event[..3].iter().enumerate() {
+            event.state.write(|w| unsafe { w.statemskn().bits(1 << n) });
+            event.ctrl.write(|w| {
+                unsafe { w.iosel().bits(0) };
+                w.outsel().input();
+                if n == 1 {
+                    w.iocond().fall();
+                } else {
+                    w.iocond().rise();
+                }
+                w.combmode().io();
+                w.stateld().load();
+                unsafe { w.statev().bits(n as u8 + 1) }
+            });
+        }
+
+        // The counter is started by `InputCapture::arm`.
+        self.sct
+            .ctrl
+            .write(|w| unsafe { w.pre_l().bits(prescaler) });
+
+        let mut capture = InputCapture {
+            sct: self,
+            input,
+            tick_frequency,
+        };
+        capture.arm();
+
+        capture
+    }
+
+    /// Start a free-running microsecond counter, for timestamps
+    ///
+    /// The SCT is operated as a single, unified 32-bit counter, which is
+    /// clocked by the system clock, divided down to 1 MHz. `clock` is the
+    /// frequency of the system clock in Hz.
+    ///
+    /// The 32-bit counter overflows after about 71 minutes. [`Timestamps`]
+    /// extends it to 64 bits in software, which requires
+    /// [`Timestamps::now`] to be called at least once per overflow period.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `clock` isn't a multiple of 1 MHz, or is higher than 256 MHz.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::Peripherals;
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// // The system clock runs at 12 MHz by default
+    /// let mut timestamps =
+    ///     p.SCT0.start_timestamps(12_000_000, &mut syscon.handle);
+    ///
+    /// let start = timestamps.now();
+    /// // do stuff
+    /// let duration = timestamps.elapsed_since(start);
+    /// ```
+    ///
+    /// [`Timestamps`]: struct.Timestamps.html
+    /// [`Timestamps::now`]: struct.Timestamps.html#method.now
+    pub fn start_timestamps(
+        self,
+        clock: u32,
+        syscon: &mut syscon::Handle,
+    ) -> Timestamps {
+        let divider = clock / 1_000_000;
+        assert!(divider * 1_000_000 == clock && divider > 0 && divider <= 256);
+
+        syscon.enable_clock(&self.sct);
+
+        self.sct.config.write(|w| w.unify().set_bit());
+
+        // Without any limit events, the counter counts up to its maximum
+        // value, then wraps around to 0.
+        self.sct.ctrl.write(|w| {
+            unsafe { w.pre_l().bits((divider - 1) as u8) };
+            w.clrctr_l().set_bit();
+            w.halt_l().clear_bit()
+        });
+
+        Timestamps {
+            sct: self,
+            high: 0,
+            last: 0,
+        }
+    }
+
+    /// Start complementary PWM with dead time, for motor control
+    ///
+    /// Generates up to three pairs of complementary, center-aligned PWM
+    /// signals, as required to drive the high-side and low-side switches of
+    /// half-bridges (for example, two for an H-bridge, three for a BLDC motor).
+    /// The pairs are made up of outputs 0 and 1, 2 and 3, and 4 and 5. The
+    /// first output of each pair drives the high-side switch, the second one
+    /// the low-side switch. Both are active high.
+    ///
+    /// The SCT counts up from 0 to `period`, then back down, so each PWM
+    /// period takes `2 * period` SCT clock cycles. The SCT is clocked by the
+    /// system clock, divided by `prescaler + 1`. Between one output of a pair
+    /// turning off and the other turning on, both outputs are kept off for at
+    /// least `dead_time` SCT clock cycles.
+    ///
+    /// `fault` selects an input that switches off all PWM outputs in hardware
+    /// while it is at the `fault_active` level, without any involvement of
+    /// software. This can be one of the SCT input pins, the analog comparator
+    /// ([`ComparatorFault`]), or [`NoFault`]. Once a fault has been detected,
+    /// the outputs stay off, until [`ComplementaryPwm::clear_fault`] is
+    /// called. On the LPC845, the fault source is connected to SCT input 3
+    /// using the input multiplexer. Please keep that in mind, if you're using
+    /// `INPUTMUX` yourself.
+    ///
+    /// All pairs start out with a duty cycle of 0, which means the high-side
+    /// output is off, and the low-side output is on (except for the dead time).
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `dead_time` isn't smaller than `period - 1`, or if `period`
+    /// is `u32::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{gpio::Level, prelude::*, Peripherals};
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let swm = p.SWM.split();
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// let (high, _) = swm.movable_functions.sct_out0.assign(
+    ///     p.pins.pio0_14.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    /// let (low, _) = swm.movable_functions.sct_out1.assign(
+    ///     p.pins.pio0_15.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    /// let (fault, _) = swm.movable_functions.sct_pin0.assign(
+    ///     p.pins.pio0_16.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    ///
+    /// // 12 MHz / (2 * 300) = 20 kHz, with 250 ns of dead time. The outputs
+    /// // are switched off while the fault input is low.
+    /// let (mut pwm, pairs) = p.SCT0.start_complementary_pwm(
+    ///     300,
+    ///     3,
+    ///     0,
+    ///     fault,
+    ///     Level::Low,
+    ///     &mut syscon.handle,
+    /// );
+    ///
+    /// let mut phase = pairs.pair0.attach(high, low);
+    /// phase.set_duty(phase.get_max_duty() / 2);
+    ///
+    /// if pwm.is_faulted() {
+    ///     // Investigate and remove the cause of the fault, then
+    ///     pwm.clear_fault();
+    /// }
+    /// ```
+    ///
+    /// [`ComparatorFault`]: struct.ComparatorFault.html
+    /// [`NoFault`]: struct.NoFault.html
+    /// [`ComplementaryPwm::clear_fault`]: struct.ComplementaryPwm.html#method.clear_fault
+    pub fn start_complementary_pwm<F>(
+        self,
+        period: u32,
+        dead_time: u32,
+        prescaler: u8,
+        fault: F,
+        fault_active: Level,
+        syscon: &mut syscon::Handle,
+    ) -> (ComplementaryPwm<F>, Pairs)
+    where
+        F: FaultInput,
+    {
+        assert!(dead_time < period.saturating_sub(1) && period < u32::MAX);
+
+        syscon.enable_clock(&self.sct);
+
+        // Operate as one 32-bit counter, that counts up to match register 0,
+        // then back down to 0.
+        self.sct.config.write(|w| {
+            w.unify().set_bit();
+            w.autolimit_l().set_bit()
+        });
+
+        self.sct.sctmatch0().write(|w| unsafe { w.bits(period) });
+        self.sct.sctmatchrel0().write(|w| unsafe { w.bits(period) });
+
+        // For each output, set and clear are reversed while counting down. A
+        // single event per output then suffices to switch it on while counting
+        // up, and off again while counting down (or vice versa).
+        self.sct.outputdirctrl.write(|w| {
+            w.setclr0().l_reversed();
+            w.setclr1().l_reversed();
+            w.setclr2().l_reversed();
+            w.setclr3().l_reversed();
+            w.setclr4().l_reversed();
+            w.setclr5().l_reversed()
+        });
+
+        // Each output is switched by the event with the same number, which
+        // uses the match register with the next higher number. Match register 0
+        // is reserved for the period. Those events are only enabled in state
+        // 0. The state machine is moved to state 1 on a fault.
+        for n in 0..6 {
+            let high_side = n % 2 == 0;
+
+            self.sct.event[n]
+                .state
+                .write(|w| unsafe { w.statemskn().bits(1 << 0) });
+            self.sct.event[n].ctrl.write(|w| {
+                unsafe { w.matchsel().bits(n as u8 + 1) };
+                w.combmode().match_()
+            });
+
+            // The high-side output is on around the top of the count, the
+            // low-side output around the bottom.
+            if high_side {
+                self.sct.out[n]
+                    .set
+                    .write(|w| unsafe { w.set().bits(1 << n) });
+            } else {
+                self.sct.out[n]
+                    .clr
+                    .write(|w| unsafe { w.clr().bits(1 << n) });
+            }
+        }
+
+        // Events 6 and 7 are the fault events, for counting up and counting
+        // down respectively. They are enabled in all states, switch off all
+        // outputs (event 7 does so by "setting" them, as set and clear are
+        // reversed while counting down), and move the state machine to state 1.
+        if let Some(source) = F::SOURCE {
+            #[cfg(feature = "82x")]
+            let input = source;
+
+            #[cfg(feature = "845")]
+            let input = {
+                // Sound, as the only other part of the HAL API that accesses
+                // `INPUTMUX` is the DMA API, which only writes to
+                // `DMA_ITRIG_INMUX`. We only write to `SCT_INMUX`, which
+                // belongs to the SCT we own.
+                let inputmux = unsafe { &*pac::INPUTMUX::ptr() };
+                inputmux.sct_inmux[3]
+                    .write(|w| unsafe { w.inp_n().bits(source) });
+                3
+            };
+
+            for n in 6..8 {
+                self.sct.event[n]
+                    .state
+                    .write(|w| unsafe { w.statemskn().bits(0b11) });
+                self.sct.event[n].ctrl.write(|w| {
+                    unsafe { w.iosel().bits(input) };
+                    w.outsel().input();
+                    match fault_active {
+                        Level::High => w.iocond().high(),
+                        Level::Low => w.iocond().low(),
+                    };
+                    w.combmode().io();
+                    w.stateld().load();
+                    unsafe { w.statev().bits(1) };
+                    if n == 6 {
+                        w.direction().counting_up()
+                    } else {
+                        w.direction().counting_down()
+                    }
+                });
+            }
+
+            for out in &self.sct.out[..6] {
+                out.set.modify(|r, w| unsafe {
+                    w.set().bits(r.set().bits() | 1 << 7)
+                });
+                out.clr.modify(|r, w| unsafe {
+                    w.clr().bits(r.clr().bits() | 1 << 6)
+                });
+            }
+        }
+
+        // The match registers are only loaded from the reload registers when
+        // the counter reaches 0, so initialize them directly.
+        let (high, low) = pair_matches(period, dead_time, 0);
+        <(SCT_OUT0, SCT_OUT1)>::init(&self.sct, high, low);
+        <(SCT_OUT2, SCT_OUT3)>::init(&self.sct, high, low);
+        <(SCT_OUT4, SCT_OUT5)>::init(&self.sct, high, low);
+
+        let pwm = ComplementaryPwm { sct: self, fault };
+        let pairs = Pairs {
+            pair0: DetachedPwmPair::new(period, dead_time),
+            pair1: DetachedPwmPair::new(period, dead_time),
+            pair2: DetachedPwmPair::new(period, dead_time),
+        };
+
+        // Start the timer
+        pwm.sct.sct.ctrl.write(|w| {
+            unsafe { w.pre_l().bits(prescaler) };
+            w.bidir_l().up_down();
+            w.clrctr_l().set_bit();
+            w.halt_l().clear_bit()
+        });
+
+        (pwm, pairs)
+    }
+
+    /// Capture the counter value on an edge of the analog comparator output
+    ///
+    /// Used by `ADC::capture_on_comparator`. The SCT is operated as a single,
+    /// free-running 32-bit counter, clocked by the system clock, divided by
+    /// `prescaler + 1`. Capture register 0 is loaded by event 0, which is
+    /// triggered by the comparator output. The capture needs to be armed using
+    /// `arm_comparator_capture`.
+    pub(crate) fn start_comparator_capture(
+        &self,
+        edge: Edge,
+        prescaler: u8,
+        syscon: &mut syscon::Handle,
+    ) {
+        syscon.enable_clock(&self.sct);
+
+        #[cfg(feature = "82x")]
+        let input = COMPARATOR_SOURCE;
+
+        #[cfg(feature = "845")]
+        let input = {
+            // Sound, as the only other part of the HAL API that accesses
+            // `INPUTMUX` is the DMA API, which only writes to
+            // `DMA_ITRIG_INMUX`. We only write to `SCT_INMUX`, which belongs
+            // to the SCT we own.
+            let inputmux = unsafe { &*pac::INPUTMUX::ptr() };
+            inputmux.sct_inmux[0]
+                .write(|w| unsafe { w.inp_n().bits(COMPARATOR_SOURCE) });
+            0
+        };
+
+        self.sct.config.write(|w| w.unify().set_bit());
+
+        self.sct
+            .regmode
+            .write(|w| unsafe { w.regmod_l().bits(0b1) });
+        self.sct
+            .sctcapctrl0()
+            .write(|w| unsafe { w.capconn_l().bits(1 << 0) });
+
+        // The event isn't enabled in any state, until the capture is armed.
+        self.sct.event[0]
+            .state
+            .write(|w| unsafe { w.statemskn().bits(0) });
+        self.sct.event[0].ctrl.write(|w| {
+            unsafe { w.iosel().bits(input) };
+            w.outsel().input();
+            match edge {
+                Edge::Rising => w.iocond().rise(),
+                Edge::Falling => w.iocond().fall(),
+            };
+            w.combmode().io();
+            w.stateld().load()
+        });
+
+        // Without any limit events, the counter counts up to its maximum
+        // value, then wraps around to 0.
+        self.sct.ctrl.write(|w| {
+            unsafe { w.pre_l().bits(prescaler) };
+            w.clrctr_l().set_bit();
+            w.halt_l().clear_bit()
+        });
+    }
+
+    /// Capture the counter value on the next edge of the comparator output
+    ///
+    /// Event 0 is enabled in the current state only, and moves the state
+    /// machine to the other state. That way it only loads the capture
+    /// register once, without halting the counter to reset the state.
+    pub(crate) fn arm_comparator_capture(&self) {
+        let state = self.sct.state.read().state_l().bits() & 1;
+
+        self.sct.evflag.write(|w| unsafe { w.flag().bits(1 << 0) });
+        self.sct.event[0]
+            .ctrl
+            .modify(|_, w| unsafe { w.statev().bits(state ^ 1) });
+        self.sct.event[0]
+            .state
+            .write(|w| unsafe { w.statemskn().bits(1 << state) });
+    }
+
+    /// Return the counter value captured by event 0, if it has occurred
+    pub(crate) fn comparator_capture(&self) -> Option<u32> {
+        if self.sct.evflag.read().flag().bits() & (1 << 0) == 0 {
+            return None;
+        }
+
+        Some(self.sct.sctcap0().read().bits())
+    }
+
+    /// Stop capturing and halt the counter
+    pub(crate) fn stop_comparator_capture(&self) {
+        self.sct.event[0]
+            .state
+            .write(|w| unsafe { w.statemskn().bits(0) });
+        self.sct.ctrl.modify(|_, w| w.halt_l().set_bit());
+    }
+
+    /// Return the raw peripheral
+    ///
+    /// This method serves as an escape hatch from the HAL API. It returns the
+    /// raw peripheral, allowing you to do whatever you want with it, without
+    /// limitations imposed by the API.
+    ///
+    /// If you are using this method because a feature you need is missing from
+    /// the HAL API, please [open an issue] or, if an issue for your feature
+    /// request already exists, comment on the existing issue, so we can
+    /// prioritize it accordingly.
+    ///
+    /// [open an issue]: https://github.com/lpc-rs/lpc8xx-hal/issues
+    pub fn free(self) -> SCT0 {
+        self.sct
+    }
+}
+
+/// The PWM channels of the SCT
+///
+/// Returned by [`SCT::start_pwm`]. Each channel corresponds to one SCT output
+/// and can be attached to any pin that the output has been assigned to.
+///
+/// [`SCT::start_pwm`]: struct.SCT.html#method.start_pwm
+pub struct Channels {
+    /// PWM channel for SCT output 0
+    pub out0: DetachedPwmPin<SCT_OUT0>,
+    /// PWM channel for SCT output 1
+    pub out1: DetachedPwmPin<SCT_OUT1>,
+    /// PWM channel for SCT output 2
+    pub out2: DetachedPwmPin<SCT_OUT2>,
+    /// PWM channel for SCT output 3
+    pub out3: DetachedPwmPin<SCT_OUT3>,
+    /// PWM channel for SCT output 4
+    pub out4: DetachedPwmPin<SCT_OUT4>,
+    /// PWM channel for SCT output 5
+    pub out5: DetachedPwmPin<SCT_OUT5>,
+    #[cfg(feature = "845")]
+    /// PWM channel for SCT output 6
+    pub out6: DetachedPwmPin<SCT_OUT6>,
+}
+
+/// A detached [`SctPwmPin`]
+///
+/// Use `attach` to assign an output to it.
+///
+/// [`SctPwmPin`]: struct.SctPwmPin.html
+pub struct DetachedPwmPin<Output> {
+    output: PhantomData<Output>,
+}
+
+impl<Output> DetachedPwmPin<Output> {
+    fn new() -> Self {
+        Self {
+            output: PhantomData,
+        }
+    }
+
+    /// Assigns a pin to a `DetachedPwmPin`,
+    /// allowing it to be used as a pwm output
+    pub fn attach<PWM>(
+        self,
+        function: swm::Function<Output, swm::state::Assigned<PWM>>,
+    ) -> SctPwmPin<Output, PWM>
+    where
+        PWM: pins::Trait,
+    {
+        SctPwmPin { function }
+    }
+}
+
+impl<Output> DetachedPwmPin<Output>
+where
+    Output: self::Output,
+{
+    /// Read the duty cycle, without attaching a pin
+    ///
+    /// Used by [`adc::PwmSync`], which uses the output as an ADC trigger.
+    ///
+    /// [`adc::PwmSync`]: ../adc/struct.PwmSync.html
+    pub(crate) fn duty(&self) -> u32 {
+        Output::get_duty(sct())
+    }
+
+    /// Set the duty cycle, without attaching a pin
+    pub(crate) fn set_duty(&mut self, duty: u32) {
+        Output::set_duty(sct(), duty)
+    }
+
+    /// Read the period of the PWM timer
+    pub(crate) fn period(&self) -> u32 {
+        sct().sctmatchrel0().read().bits()
+    }
+}
+
+/// Represents a pwm channel assigned to an output pin
+///
+/// # `embedded-hal` traits
+/// - [`embedded_hal::PwmPin`]
+///
+/// [`embedded_hal::PwmPin`]: #impl-PwmPin
+pub struct SctPwmPin<Output, PWM> {
+    function: swm::Function<Output, swm::state::Assigned<PWM>>,
+}
+
+impl<Output, PWM> SctPwmPin<Output, PWM> {
+    /// Detaches the pin from this pwm channel
+    ///
+    /// Returns the `DetachedPwmPin`, which can be attached to another output,
+    /// and the SWM function that was passed to [`attach`]. The function can
+    /// then be unassigned, to reuse the pin for something else.
+    ///
+    /// Please note that the pwm channel keeps running after it's been
+    /// detached. Set the duty cycle to 0 before calling this method, if that
+    /// is not what you want.
+    ///
+    /// [`attach`]: struct.DetachedPwmPin.html#method.attach
+    pub fn detach(
+        self,
+    ) -> (
+        DetachedPwmPin<Output>,
+        swm::Function<Output, swm::state::Assigned<PWM>>,
+    ) {
+        (DetachedPwmPin::new(), self.function)
+    }
+}
+
+impl<Output, PWM> PwmPin for SctPwmPin<Output, PWM>
+where
+    Output: self::Output,
+{
+    type Duty = u32;
+
+    /// The behaviour of `enable` is implementation defined and does nothing in
+    /// this implementation
+    fn enable(&mut self) {}
+
+    /// The behaviour of `disable` is implementation defined and does nothing in
+    /// this implementation
+    fn disable(&mut self) {}
+
+    /// Returns the current duty cycle
+    fn get_duty(&self) -> Self::Duty {
+        Output::get_duty(sct())
+    }
+
+    /// Returns the maximum duty cycle value
+    fn get_max_duty(&self) -> Self::Duty {
+        sct().sctmatchrel0().read().bits()
+    }
+
+    /// Sets a new duty cycle
+    ///
+    /// The new duty cycle takes effect at the start of the next period.
+    fn set_duty(&mut self, duty: Self::Duty) {
+        Output::set_duty(sct(), duty)
+    }
+}
+
+/// Changes the period of all PWM outputs of the SCT
+impl<Output, PWM> TonePin for SctPwmPin<Output, PWM>
+where
+    Output: self::Output,
+{
+    fn set_period(&mut self, period: u32) {
+        assert!(period > 0);
+
+        // The counter is reset after it reaches the match value, so the
+        // period is one tick longer than that.
+        sct()
+            .sctmatchrel0()
+            .write(|w| unsafe { w.bits(period - 1) });
+    }
+
+    fn silence(&mut self) {
+        self.set_duty(0);
+    }
+}
+
+/// Sets the duty cycle to the pulse width
+impl<Output, PWM> ServoPin for SctPwmPin<Output, PWM>
+where
+    Output: self::Output,
+{
+    fn set_pulse(&mut self, ticks: u32) {
+        self.set_duty(ticks);
+    }
+
+    fn pulse(&self) -> u32 {
+        self.get_duty()
+    }
+}
+
+/// Implemented for all SCT outputs
+///
+/// This trait is an internal implementation detail and should neither be
+/// implemented nor used outside of LPC8xx HAL. Any changes to this trait won't
+/// be considered breaking changes.
+pub trait Output {
+    /// Read the duty cycle from this output's match reload register
+    fn get_duty(sct: &RegisterBlock) -> u32;
+
+    /// Write the duty cycle to this output's match reload register
+    ///
+    /// Also updates the output's set register, so the output isn't set at
+    /// all with a duty cycle of 0.
+    fn set_duty(sct: &RegisterBlock, duty: u32);
+}
+
+macro_rules! outputs {
+    ($($output:ident, $n:expr, $matchrel:ident;)*) => {
+        $(
+            impl Output for $output {
+                fn get_duty(sct: &RegisterBlock) -> u32 {
+                    sct.$matchrel().read().bits()
+                }
+
+                fn set_duty(sct: &RegisterBlock, duty: u32) {
+                    // Setting takes precedence over clearing, so an output
+                    // that is set at the start of each period would be high
+                    // for one tick, even with a duty cycle of 0.
+                    let set = if duty == 0 { 0 } else { 1 << 0 };
+                    sct.out[$n].set.write(|w| unsafe { w.set().bits(set) });
+
+                    sct.$matchrel().write(|w| unsafe { w.bits(duty) });
+                }
+            }
+        )*
+    };
+}
+
+outputs!(
+    SCT_OUT0, 0, sctmatchrel1;
+    SCT_OUT1, 1, sctmatchrel2;
+    SCT_OUT2, 2, sctmatchrel3;
+    SCT_OUT3, 3, sctmatchrel4;
+    SCT_OUT4, 4, sctmatchrel5;
+    SCT_OUT5, 5, sctmatchrel6;
+);
+
+#[cfg(feature = "845")]
+outputs!(
+    SCT_OUT6, 6, sctmatchrel7;
+);
+
+/// Complementary PWM with dead time and hardware fault protection
+///
+/// You can get an instance of this struct from
+/// [`SCT::start_complementary_pwm`], which also returns the [`Pairs`] that
+/// control the duty cycles.
+///
+/// [`SCT::start_complementary_pwm`]: struct.SCT.html#method.start_complementary_pwm
+/// [`Pairs`]: struct.Pairs.html
+pub struct ComplementaryPwm<F> {
+    sct: SCT,
+    fault: F,
+}
+
+impl<F> ComplementaryPwm<F> {
+    /// Indicates whether the outputs have been switched off by a fault
+    pub fn is_faulted(&self) -> bool {
+        self.sct.sct.state.read().state_l().bits() != 0
+    }
+
+    /// Switch the outputs back on after a fault
+    ///
+    /// If the fault input is still active, the outputs are switched off again
+    /// right away. The outputs resume with their current duty cycles, each
+    /// output switching on at its next regular transition.
+    pub fn clear_fault(&mut self) {
+        let sct = &self.sct.sct;
+
+        // The state can only be written while the counter is halted.
+        sct.ctrl.modify(|_, w| w.halt_l().set_bit());
+        sct.state.write(|w| unsafe { w.state_l().bits(0) });
+        sct.ctrl.modify(|_, w| w.halt_l().clear_bit());
+    }
+
+    /// Stop generating PWM and release the resources
+    ///
+    /// The SCT is left in its current configuration. Please note that the
+    /// configuration made by [`SCT::start_complementary_pwm`] isn't compatible
+    /// with the other APIs of [`SCT`], so you'll have to reset it before using
+    /// it again.
+    ///
+    /// [`SCT::start_complementary_pwm`]: struct.SCT.html#method.start_complementary_pwm
+    /// [`SCT`]: struct.SCT.html
+    pub fn free(self) -> (SCT, F) {
+        (self.sct, self.fault)
+    }
+}
+
+/// The complementary PWM pairs of the SCT
+///
+/// Returned by [`SCT::start_complementary_pwm`].
+///
+/// [`SCT::start_complementary_pwm`]: struct.SCT.html#method.start_complementary_pwm
+pub struct Pairs {
+    /// PWM pair for SCT outputs 0 (high side) and 1 (low side)
+    pub pair0: DetachedPwmPair<SCT_OUT0, SCT_OUT1>,
+    /// PWM pair for SCT outputs 2 (high side) and 3 (low side)
+    pub pair1: DetachedPwmPair<SCT_OUT2, SCT_OUT3>,
+    /// PWM pair for SCT outputs 4 (high side) and 5 (low side)
+    pub pair2: DetachedPwmPair<SCT_OUT4, SCT_OUT5>,
+}
+
+/// A detached [`SctPwmPair`]
+///
+/// Use `attach` to assign outputs to it.
+///
+/// [`SctPwmPair`]: struct.SctPwmPair.html
+pub struct DetachedPwmPair<High, Low> {
+    period: u32,
+    dead_time: u32,
+    outputs: PhantomData<(High, Low)>,
+}
+
+impl<High, Low> DetachedPwmPair<High, Low> {
+    fn new(period: u32, dead_time: u32) -> Self {
+        Self {
+            period,
+            dead_time,
+            outputs: PhantomData,
+        }
+    }
+
+    /// Assigns pins to a `DetachedPwmPair`, allowing it to be used as a
+    /// complementary PWM output
+    pub fn attach<HighPin, LowPin>(
+        self,
+        high: swm::Function<High, swm::state::Assigned<HighPin>>,
+        low: swm::Function<Low, swm::state::Assigned<LowPin>>,
+    ) -> SctPwmPair<High, Low, HighPin, LowPin>
+    where
+        HighPin: pins::Trait,
+        LowPin: pins::Trait,
+    {
+        SctPwmPair {
+            pair: self,
+            high,
+            low,
+            duty: 0,
+        }
+    }
+}
+
+/// Represents a complementary PWM pair assigned to output pins
+///
+/// The duty cycle is the fraction of the period during which the high-side
+/// output is on. The low-side output is on during the rest of the period,
+/// minus the dead time before and after.
+///
+/// Since the counter turns around at 0 and at the end of the period, the
+/// outputs can't switch right at those points. At the maximum duty cycle, the
+/// low-side output still switches on for one SCT clock cycle per period, and
+/// the high-side output switches off for slightly longer than the dead time.
+/// A duty cycle of 0 switches off the high-side output completely.
+///
+/// # `embedded-hal` traits
+/// - [`embedded_hal::PwmPin`]
+///
+/// [`embedded_hal::PwmPin`]: #impl-PwmPin
+pub struct SctPwmPair<High, Low, HighPin, LowPin> {
+    pair: DetachedPwmPair<High, Low>,
+    high: swm::Function<High, swm::state::Assigned<HighPin>>,
+    low: swm::Function<Low, swm::state::Assigned<LowPin>>,
+    duty: u32,
+}
+
+impl<High, Low, HighPin, LowPin> SctPwmPair<High, Low, HighPin, LowPin> {
+    /// Detaches the pins from this PWM pair
+    ///
+    /// Returns the `DetachedPwmPair`, which can be attached to other outputs,
+    /// and the SWM functions that were passed to [`attach`].
+    ///
+    /// Please note that the PWM pair keeps running after it's been detached.
+    /// Set the duty cycle to 0 before calling this method, if that is not
+    /// what you want.
+    ///
+    /// [`attach`]: struct.DetachedPwmPair.html#method.attach
+    #[allow(clippy::type_complexity)]
+    pub fn detach(
+        self,
+    ) -> (
+        DetachedPwmPair<High, Low>,
+        swm::Function<High, swm::state::Assigned<HighPin>>,
+        swm::Function<Low, swm::state::Assigned<LowPin>>,
+    ) {
+        (self.pair, self.high, self.low)
+    }
+}
+
+impl<High, Low, HighPin, LowPin> PwmPin
+    for SctPwmPair<High, Low, HighPin, LowPin>
+where
+    (High, Low): Pair,
+{
+    type Duty = u32;
+
+    /// The behaviour of `enable` is implementation defined and does nothing in
+    /// this implementation
+    fn enable(&mut self) {}
+
+    /// The behaviour of `disable` is implementation defined and does nothing in
+    /// this implementation
+    fn disable(&mut self) {}
+
+    /// Returns the current duty cycle
+    fn get_duty(&self) -> Self::Duty {
+        self.duty
+    }
+
+    /// Returns the maximum duty cycle value, which is equal to the period
+    fn get_max_duty(&self) -> Self::Duty {
+        self.pair.period
+    }
+
+    /// Sets a new duty cycle
+    ///
+    /// Values larger than the maximum duty cycle are treated as the maximum.
+    /// The new duty cycle takes effect at the start of the next period.
+    fn set_duty(&mut self, duty: Self::Duty) {
+        self.duty = duty.min(self.pair.period);
+
+        let (high, low) =
+            pair_matches(self.pair.period, self.pair.dead_time, self.duty);
+
+        // Both match registers must be loaded at the same time. Otherwise,
+        // the dead time could be violated for one period. The reload is
+        // controlled by a register that is shared between all pairs, hence
+        // the critical section.
+        interrupt::free(|_| {
+            let sct = sct();
+            sct.config.modify(|_, w| w.norelaod_l().set_bit());
+            <(High, Low)>::set(sct, high, low);
+            sct.config.modify(|_, w| w.norelaod_l().clear_bit());
+        });
+    }
+}
+
+/// Compute the match values of the high-side and low-side output of a pair
+///
+/// The high-side output is on while the counter is above its match value, the
+/// low-side output while the counter is below its match value.
+fn pair_matches(period: u32, dead_time: u32, duty: u32) -> (u32, u32) {
+    if duty == 0 {
+        // The high-side match is never reached, so the high-side output is
+        // never switched on. The low-side match value must not be equal to the
+        // period, as the counter only reaches that once per period.
+        let low = (period - dead_time).min(period - 1);
+        return (period + 1, low.max(1));
+    }
+
+    // Neither match value must be 0, as the counter only reaches that once
+    // per period.
+    let high = (period - duty).max(dead_time + 1);
+    (high, high - dead_time)
+}
+
+/// Implemented for the output pairs that support complementary PWM
+///
+/// This trait is an internal implementation detail and should neither be
+/// implemented nor used outside of LPC8xx HAL. Any changes to this trait won't
+/// be considered breaking changes.
+pub trait Pair {
+    /// Write the match values to the pair's match reload registers
+    fn set(sct: &RegisterBlock, high: u32, low: u32);
+
+    /// Write the match values to the pair's match and match reload registers
+    fn init(sct: &RegisterBlock, high: u32, low: u32);
+}
+
+macro_rules! pairs {
+    ($(
+        $high:ident, $low:ident,
+        $match_high:ident, $matchrel_high:ident,
+        $match_low:ident, $matchrel_low:ident;
+    )*) => {
+        $(
+            impl Pair for ($high, $low) {
+                fn set(sct: &RegisterBlock, high: u32, low: u32) {
+                    sct.$matchrel_high().write(|w| unsafe { w.bits(high) });
+                    sct.$matchrel_low().write(|w| unsafe { w.bits(low) });
+                }
+
+                fn init(sct: &RegisterBlock, high: u32, low: u32) {
+                    sct.$match_high().write(|w| unsafe { w.bits(high) });
+                    sct.$match_low().write(|w| unsafe { w.bits(low) });
+                    Self::set(sct, high, low);
+                }
+            }
+        )*
+    };
+}
+
+pairs!(
+    SCT_OUT0, SCT_OUT1, sctmatch1, sctmatchrel1, sctmatch2, sctmatchrel2;
+    SCT_OUT2, SCT_OUT3, sctmatch3, sctmatchrel3, sctmatch4, sctmatchrel4;
+    SCT_OUT4, SCT_OUT5, sctmatch5, sctmatchrel5, sctmatch6, sctmatchrel6;
+);
+
+/// Implemented for the inputs that can be used as a fault input
+///
+/// See [`SCT::start_complementary_pwm`].
+///
+/// This trait is an internal implementation detail and should neither be
+/// implemented nor used outside of LPC8xx HAL. Any changes to this trait won't
+/// be considered breaking changes.
+///
+/// [`SCT::start_complementary_pwm`]: struct.SCT.html#method.start_complementary_pwm
+pub trait FaultInput {
+    /// The number of the SCT input (LPC82x) or input multiplexer source
+    /// (LPC845), if any
+    const SOURCE: Option<u8>;
+}
+
+macro_rules! fault_inputs {
+    ($($function:ident, $source:expr;)*) => {
+        $(
+            impl<P> FaultInput for swm::Function<$function, swm::state::Assigned<P>> {
+                const SOURCE: Option<u8> = Some($source);
+            }
+        )*
+    };
+}
+
+fault_inputs!(
+    SCT_PIN0, 0;
+    SCT_PIN1, 1;
+    SCT_PIN2, 2;
+    SCT_PIN3, 3;
+);
+
+/// Use the output of the analog comparator as the fault input
+///
+/// The analog comparator needs to be configured separately. The HAL doesn't
+/// provide an API for that yet, so you need to use the `ACOMP` field of
+/// [`Peripherals`] directly.
+///
+/// [`Peripherals`]: ../struct.Peripherals.html
+pub struct ComparatorFault;
+
+impl FaultInput for ComparatorFault {
+    const SOURCE: Option<u8> = Some(COMPARATOR_SOURCE);
+}
+
+/// The SCT input (LPC82x) or input multiplexer source (LPC845) that is
+/// connected to the analog comparator output
+const COMPARATOR_SOURCE: u8 = 5;
+
+/// Don't use a fault input
+pub struct NoFault;
+
+impl FaultInput for NoFault {
+    const SOURCE: Option<u8> = None;
+}
+
+/// Decodes a quadrature signal
+///
+/// You can get an instance of this struct from [`SCT::start_qei`].
+///
+/// # `embedded-hal` traits
+/// - [`embedded_hal::Qei`]
+///
+/// [`SCT::start_qei`]: struct.SCT.html#method.start_qei
+/// [`embedded_hal::Qei`]: #impl-Qei
+pub struct Qei<A, B> {
+    sct: SCT,
+    a: swm::Function<SCT_PIN0, swm::state::Assigned<A>>,
+    b: swm::Function<SCT_PIN1, swm::state::Assigned<B>>,
+}
+
+impl<A, B> Qei<A, B> {
+    /// Reset the count to zero
+    pub fn reset(&mut self) {
+        self.sct.sct.ctrl.modify(|_, w| {
+            w.clrctr_l().set_bit();
+            w.clrctr_h().set_bit()
+        });
+    }
+
+    /// Stop decoding and release the resources
+    ///
+    /// The SCT is left in its current configuration. Please note that the
+    /// configuration made by [`SCT::start_qei`] isn't compatible with the
+    /// other APIs of [`SCT`], so you'll have to reset it before using it
+    /// again.
+    ///
+    /// [`SCT::start_qei`]: struct.SCT.html#method.start_qei
+    /// [`SCT`]: struct.SCT.html
+    pub fn free(self) -> (SCT, QeiFunctions<A, B>) {
+        (self.sct, (self.a, self.b))
+    }
+}
+
+impl<A, B> embedded_hal::Qei for Qei<A, B> {
+    type Count = u16;
+
+    /// Returns the current count
+    fn count(&self) -> Self::Count {
+        let count = self.sct.sct.count.read();
+        count.ctr_l().bits().wrapping_sub(count.ctr_h().bits())
+    }
+
+    /// Returns the direction the signal is currently moving in
+    fn direction(&self) -> Direction {
+        if self.sct.sct.ctrl.read().stop_l().bit_is_clear() {
+            Direction::Upcounting
+        } else {
+            Direction::Downcounting
+        }
+    }
+}
+
+/// The SWM functions used by [`Qei`]
+///
+/// [`Qei`]: struct.Qei.html
+pub type QeiFunctions<A, B> = (
+    swm::Function<SCT_PIN0, swm::state::Assigned<A>>,
+    swm::Function<SCT_PIN1, swm::state::Assigned<B>>,
+);
+
+/// Measures the signal on an input
+///
+/// You can get an instance of this struct from [`SCT::start_capture`].
+///
+/// [`SCT::start_capture`]: struct.SCT.html#method.start_capture
+pub struct InputCapture<P> {
+    sct: SCT,
+    input: swm::Function<SCT_PIN0, swm::state::Assigned<P>>,
+    tick_frequency: u32,
+}
+
+impl<P> InputCapture<P> {
+    /// Return a measurement, once it has been completed
+    ///
+    /// Returns `nb::Error::WouldBlock`, until a full period of the signal has
+    /// been captured. Once a measurement has been returned, the next one is
+    /// started automatically.
+    ///
+    /// Please note that this method blocks forever, if there is no signal on
+    /// the input.
+    pub fn measure(&mut self) -> nb::Result<Measurement, Void> {
+        const LAST_EVENT: u8 = 1 << 2;
+
+        if self.sct.sct.evflag.read().flag().bits() & LAST_EVENT == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let rise = self.sct.sct.sctcap0().read().bits();
+        let fall = self.sct.sct.sctcap1().read().bits();
+        let next_rise = self.sct.sct.sctcap2().read().bits();
+
+        self.arm();
+
+        Ok(Measurement {
+            period: next_rise.wrapping_sub(rise),
+            pulse_width: fall.wrapping_sub(rise),
+            tick_frequency: self.tick_frequency,
+        })
+    }
+
+    /// Stop measuring and release the resources
+    ///
+    /// The SCT is left in its current configuration. Please note that the
+    /// configuration made by [`SCT::start_capture`] isn't compatible with the
+    /// other APIs of [`SCT`], so you'll have to reset it before using it
+    /// again.
+    ///
+    /// [`SCT::start_capture`]: struct.SCT.html#method.start_capture
+    /// [`SCT`]: struct.SCT.html
+    pub fn free(
+        self,
+    ) -> (SCT, swm::Function<SCT_PIN0, swm::state::Assigned<P>>) {
+        (self.sct, self.input)
+    }
+
+    fn arm(&mut self) {
+        let sct = &self.sct.sct;
+
+        // The state can only be written while the counter is halted.
+        sct.ctrl.modify(|_, w| w.halt_l().set_bit());
+        sct.state.write(|w| unsafe { w.state_l().bits(0) });
+        sct.evflag.write(|w| unsafe { w.flag().bits(0xff) });
+        sct.ctrl.modify(|_, w| w.halt_l().clear_bit());
+    }
+}
+
+/// A measurement made by [`InputCapture`]
+///
+/// [`InputCapture`]: struct.InputCapture.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Measurement {
+    /// The period of the signal, in SCT clock ticks
+    pub period: u32,
+
+    /// The time the signal was high during the period, in SCT clock ticks
+    pub pulse_width: u32,
+
+    /// The frequency of the SCT clock in Hz
+    pub tick_frequency: u32,
+}
+
+impl Measurement {
+    /// The frequency of the signal in Hz
+    ///
+    /// Returns 0, if the period is 0.
+    pub fn frequency(&self) -> u32 {
+        self.tick_frequency.checked_div(self.period).unwrap_or(0)
+    }
+
+    /// The period of the signal in microseconds
+    pub fn period_us(&self) -> u32 {
+        self.ticks_to_us(self.period)
+    }
+
+    /// The time the signal was high during the period, in microseconds
+    pub fn pulse_width_us(&self) -> u32 {
+        self.ticks_to_us(self.pulse_width)
+    }
+
+    /// The duty cycle of the signal, as a value from 0 to 1
+    ///
+    /// Returns 0, if the period is 0.
+    pub fn duty_cycle(&self) -> f32 {
+        if self.period == 0 {
+            return 0.0;
+        }
+
+        self.pulse_width as f32 / self.period as f32
+    }
+
+    fn ticks_to_us(&self, ticks: u32) -> u32 {
+        (ticks as u64 * 1_000_000 / self.tick_frequency as u64) as u32
+    }
+}
+
+/// A free-running microsecond counter
+///
+/// You can get an instance of this struct from [`SCT::start_timestamps`].
+///
+/// [`SCT::start_timestamps`]: struct.SCT.html#method.start_timestamps
+pub struct Timestamps {
+    sct: SCT,
+    high: u32,
+    last: u32,
+}
+
+impl Timestamps {
+    /// Return the current time
+    ///
+    /// Extends the 32-bit hardware counter to 64 bits, by counting its
+    /// overflows. For this to work, this method needs to be called at least
+    /// once per overflow period of about 71 minutes. Otherwise, the returned
+    /// time is too early by a multiple of that period.
+    pub fn now(&mut self) -> Timestamp {
+        let count = self.sct.sct.count.read().bits();
+
+        if count < self.last {
+            self.high = self.high.wrapping_add(1);
+        }
+        self.last = count;
+
+        Timestamp {
+            micros: (self.high as u64) << 32 | count as u64,
+        }
+    }
+
+    /// Return the time that has passed since `earlier`
+    ///
+    /// Returns a duration of 0, if `earlier` is later than the current time.
+    pub fn elapsed_since(&mut self, earlier: Timestamp) -> Duration {
+        let micros = self.now().micros.saturating_sub(earlier.micros);
+        Duration::from_micros(micros)
+    }
+
+    /// Stop the counter and release the SCT
+    ///
+    /// The SCT is left in its current configuration, except for the counter,
+    /// which is halted.
+    pub fn free(self) -> SCT {
+        self.sct.sct.ctrl.modify(|_, w| w.halt_l().set_bit());
+        self.sct
+    }
+}
+
+/// A point in time, as returned by [`Timestamps::now`]
+///
+/// [`Timestamps::now`]: struct.Timestamps.html#method.now
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Timestamp {
+    micros: u64,
+}
+
+impl Timestamp {
+    /// The number of microseconds since the counter was started
+    pub fn as_micros(&self) -> u64 {
+        self.micros
+    }
+
+    /// Return the time that passed between `earlier` and this timestamp
+    ///
+    /// Returns a duration of 0, if `earlier` is later than this timestamp.
+    pub fn duration_since(&self, earlier: Timestamp) -> Duration {
+        Duration::from_micros(self.micros.saturating_sub(earlier.micros))
+    }
+}
+
+fn sct() -> &'static RegisterBlock {
+    // Sound, as every `SctPwmPin` (or `DetachedPwmPin`) only ever accesses the
+    // match reload and output set registers that belong to its own output,
+    // plus match reload register 0, which holds the period. The period is written by the
+    // `TonePin` implementation, using a single write that doesn't depend on
+    // the previous value, so no update can get lost. Those registers aren't
+    // accessed by anything else, as long as `SCT` has been consumed by
+    // `start_pwm`. The same goes for `SctPwmPair` and
+    // `start_complementary_pwm`, except that `SctPwmPair` also modifies the
+    // config register within a critical section.
+    unsafe { &*pac::SCT0::ptr() }
+}
@@ -0,0 +1,205 @@
+//! Driver for hobby servos
+//!
+//! Hobby servos are controlled by a pulse that repeats every 20 ms (50 Hz).
+//! The width of the pulse determines the position of the servo, usually from
+//! 1 ms for one end of its range to 2 ms for the other. Since many servos
+//! accept a wider range of pulses, and their range of motion differs, both
+//! can be configured.
+//!
+//! [`Servo`] works on top of a PWM output, like an SCT or CTimer channel. The
+//! PWM timer needs to be started with a period of 20 ms. The SCT is the better
+//! choice for multiple servos, as its outputs are movable functions, and all
+//! its PWM channels share the same period anyway.
+//!
+//! CTimer outputs are high at the end of the period, rather than the start.
+//! Servos only care about the width of the pulse, so [`Servo`] accounts for
+//! that, and both work the same.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{servo::Servo, Peripherals};
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let swm = p.SWM.split();
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! // Count at 1 MHz (the system clock runs at 12 MHz by default), with a
+//! // period of 20 ms.
+//! let channels = p.SCT0.start_pwm(20_000, 11, &mut syscon.handle);
+//!
+//! let (output, _) = swm.movable_functions.sct_out0.assign(
+//!     p.pins.pio0_14.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//!
+//! // This servo accepts pulses from 0.5 to 2.5 ms, for 180 degrees
+//! let mut servo = Servo::new(channels.out0.attach(output), 1_000_000)
+//!     .with_pulse_range(500, 2_500);
+//!
+//! servo.set_angle(90);
+//! ```
+//!
+//! [`Servo`]: struct.Servo.html
+
+use embedded_hal::PwmPin;
+
+/// The period of the pulses that control a servo, in microseconds
+pub const PERIOD_US: u32 = 20_000;
+
+/// Controls a hobby servo
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct Servo<P> {
+    pin: P,
+    clock: u32,
+    min_us: u32,
+    max_us: u32,
+    range: u16,
+}
+
+impl<P> Servo<P>
+where
+    P: ServoPin,
+{
+    /// Create a new servo driver
+    ///
+    /// `clock` is the frequency at which the PWM timer counts, in Hz. This is
+    /// the frequency of the timer's clock, divided by its prescaler. The
+    /// timer's period needs to be [`PERIOD_US`].
+    ///
+    /// Assumes the servo accepts pulses from 1 to 2 ms, which move it over a
+    /// range of 180 degrees. Use [`with_pulse_range`] and
+    /// [`with_angle_range`] to change that.
+    ///
+    /// The servo is left disabled, until a position is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `clock` is zero.
+    ///
+    /// [`PERIOD_US`]: constant.PERIOD_US.html
+    /// [`with_pulse_range`]: #method.with_pulse_range
+    /// [`with_angle_range`]: #method.with_angle_range
+    pub fn new(mut pin: P, clock: u32) -> Self {
+        assert!(clock > 0);
+
+        pin.set_pulse(0);
+
+        Self {
+            pin,
+            clock,
+            min_us: 1_000,
+            max_us: 2_000,
+            range: 180,
+        }
+    }
+
+    /// Change the range of pulse widths that the servo accepts
+    ///
+    /// `min_us` and `max_us` are the pulse widths in microseconds that move
+    /// the servo to the ends of its range. `min_us` corresponds to an angle of
+    /// 0 degrees.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `min_us` is not smaller than `max_us`, or `max_us` is larger
+    /// than [`PERIOD_US`].
+    ///
+    /// [`PERIOD_US`]: constant.PERIOD_US.html
+    pub fn with_pulse_range(mut self, min_us: u32, max_us: u32) -> Self {
+        assert!(min_us < max_us && max_us <= PERIOD_US);
+
+        self.min_us = min_us;
+        self.max_us = max_us;
+        self
+    }
+
+    /// Change the range of motion of the servo
+    ///
+    /// `degrees` is the angle between both ends of the servo's range.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `degrees` is 0.
+    pub fn with_angle_range(mut self, degrees: u16) -> Self {
+        assert!(degrees > 0);
+
+        self.range = degrees;
+        self
+    }
+
+    /// Move the servo to an angle
+    ///
+    /// Angles are in degrees, relative to the end of the servo's range that
+    /// corresponds to the shortest pulse. Angles beyond the servo's range are
+    /// limited to it.
+    pub fn set_angle(&mut self, degrees: u16) {
+        let degrees = u32::from(degrees.min(self.range));
+        let span = self.max_us - self.min_us;
+
+        let range = u32::from(self.range);
+        let us = self.min_us + (span * degrees + range / 2) / range;
+
+        self.set_pulse_width(us);
+    }
+
+    /// Send pulses of a specific width to the servo
+    ///
+    /// `us` is the pulse width in microseconds. It is limited to the range
+    /// passed to [`with_pulse_range`].
+    ///
+    /// [`with_pulse_range`]: #method.with_pulse_range
+    pub fn set_pulse_width(&mut self, us: u32) {
+        let us = us.clamp(self.min_us, self.max_us);
+        let ticks = u64::from(us) * u64::from(self.clock) / 1_000_000;
+
+        self.pin.set_pulse(ticks as u32);
+    }
+
+    /// The width of the pulses that are currently sent, in microseconds
+    ///
+    /// Returns 0, if the servo is disabled.
+    pub fn pulse_width(&self) -> u32 {
+        let ticks = u64::from(self.pin.pulse());
+        (ticks * 1_000_000 / u64::from(self.clock)) as u32
+    }
+
+    /// Stop sending pulses to the servo
+    ///
+    /// Most servos stop holding their position, once they don't receive
+    /// pulses anymore. Setting an angle or pulse width enables the servo
+    /// again.
+    pub fn disable(&mut self) {
+        self.pin.set_pulse(0);
+    }
+
+    /// Disable the servo and release the PWM output
+    pub fn free(mut self) -> P {
+        self.disable();
+        self.pin
+    }
+}
+
+/// Implemented for PWM outputs that can be used to control servos
+///
+/// This trait is an internal implementation detail and should neither be
+/// implemented nor used outside of LPC8xx HAL. Any changes to this trait won't
+/// be considered breaking changes.
+pub trait ServoPin: PwmPin<Duty = u32> {
+    /// Send pulses that are high for `ticks` timer ticks per period
+    ///
+    /// A pulse width of 0 holds the output low.
+    fn set_pulse(&mut self, ticks: u32);
+
+    /// The number of timer ticks per period that the output is high
+    fn pulse(&self) -> u32;
+}
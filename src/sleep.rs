@@ -5,11 +5,14 @@
 //!
 //! Both sleeping via busy waiting and via regular sleep mode are supported.
 //! Please refer to [`sleep::Busy`] and [`sleep::Regular`] for more details.
+//! [`sleep::Wfi`] also uses regular sleep mode, but can be woken up early by
+//! other interrupts.
 //!
 //! [`sleep::Busy`]: struct.Busy.html
 //! [`sleep::Regular`]: struct.Regular.html
+//! [`sleep::Wfi`]: struct.Wfi.html
 
-use cortex_m::{asm, interrupt};
+use cortex_m::{asm, interrupt, interrupt::Nr};
 use embedded_hal::prelude::*;
 use nb;
 
@@ -210,3 +213,196 @@ where
         });
     }
 }
+
+/// Regular sleep mode with configurable wake sources
+///
+/// Provides a [`Sleep`] implementation for the regular sleep mode and uses the
+/// [WKT] to wake the microcontroller up again, at the right time. Only clocks
+/// that the WKT supports can be used. See [`wkt::Clock`] for more details.
+///
+/// Unlike [`sleep::Regular`], this sleep mode doesn't keep other interrupts
+/// from being handled while sleeping. If an NVIC-enabled interrupt occurs, the
+/// microcontroller wakes up, the interrupt is handled, and the microcontroller
+/// goes back to sleep. If the interrupt has been configured as a wake source
+/// using [`wake_on`], the sleep ends early instead.
+///
+/// The WKT interrupt is handled internally and doesn't require an interrupt
+/// handler.
+///
+/// # Examples
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     prelude::*,
+///     Peripherals,
+///     clock::Ticks,
+///     pac::{CorePeripherals, Interrupt},
+///     sleep,
+/// };
+///
+/// let mut cp = CorePeripherals::take().unwrap();
+/// let mut p = Peripherals::take().unwrap();
+///
+/// let mut pmu    = p.PMU.split();
+/// let mut syscon = p.SYSCON.split();
+/// let mut wkt    = p.WKT.enable(&mut syscon.handle);
+///
+/// let clock = syscon.iosc_derived_clock;
+///
+/// let mut sleep = sleep::Wfi::prepare(
+///     &mut pmu.handle,
+///     &mut cp.SCB,
+///     &mut wkt,
+/// );
+///
+/// // Wake up early, if the USART0 interrupt occurs. This interrupt also needs
+/// // to be enabled in the NVIC.
+/// sleep.wake_on(Interrupt::USART0);
+///
+/// let delay = Ticks { value: 750_000, clock: &clock }; // 1000 ms
+///
+/// match sleep.sleep_until_wake(delay) {
+///     sleep::WakeReason::Timer => {
+///         // slept for the full second
+///     }
+///     sleep::WakeReason::Interrupt => {
+///         // woken up early by USART0
+///     }
+/// }
+/// ```
+///
+/// [`sleep::Regular`]: struct.Regular.html
+/// [`wake_on`]: #method.wake_on
+pub struct Wfi<'r> {
+    pmu: &'r mut pmu::Handle,
+    scb: &'r mut pac::SCB,
+    wkt: &'r mut WKT,
+    wake_sources: u32,
+}
+
+impl<'r> Wfi<'r> {
+    /// Prepare WFI sleep mode
+    ///
+    /// Returns an instance of `sleep::Wfi`, which implements [`Sleep`] and can
+    /// therefore be used to put the microcontroller to sleep.
+    ///
+    /// Requires references to various peripherals, which will be borrowed for
+    /// as long as the `sleep::Wfi` instance exists, as they will be needed for
+    /// every call to [`Sleep::sleep`].
+    pub fn prepare(
+        pmu: &'r mut pmu::Handle,
+        scb: &'r mut pac::SCB,
+        wkt: &'r mut WKT,
+    ) -> Self {
+        Wfi {
+            pmu,
+            scb,
+            wkt,
+            wake_sources: 0,
+        }
+    }
+
+    /// Add an interrupt as a wake source
+    ///
+    /// If this interrupt occurs while sleeping, the sleep ends early. The
+    /// interrupt needs to be enabled in the NVIC, or it won't wake up the
+    /// microcontroller.
+    pub fn wake_on<I>(&mut self, interrupt: I)
+    where
+        I: Nr,
+    {
+        self.wake_sources |= 0x1 << interrupt.nr();
+    }
+
+    /// Remove an interrupt from the wake sources
+    pub fn dont_wake_on<I>(&mut self, interrupt: I)
+    where
+        I: Nr,
+    {
+        self.wake_sources &= !(0x1 << interrupt.nr());
+    }
+
+    /// Sleep until the time is up, or a wake source interrupt occurs
+    ///
+    /// Works like [`Sleep::sleep`], but returns the reason why the sleep has
+    /// ended.
+    ///
+    /// [`Sleep::sleep`]: trait.Sleep.html#tymethod.sleep
+    pub fn sleep_until_wake<'clock, Clock, T>(&mut self, ticks: T) -> WakeReason
+    where
+        Clock: clock::Enabled + wkt::Clock + 'clock,
+        T: Into<Ticks<'clock, Clock>>,
+    {
+        let ticks: Ticks<Clock> = ticks.into();
+
+        // If we try to sleep for zero cycles, we'll never wake up again.
+        if ticks.value == 0 {
+            return WakeReason::Timer;
+        }
+
+        self.wkt.select_clock::<Clock>();
+        self.wkt.start(ticks.value);
+
+        loop {
+            // Within this closure, interrupt handlers won't run. This means
+            // the WKT interrupt doesn't require a handler, and we can check
+            // which interrupts are pending after waking up, before they are
+            // handled.
+            let reason = interrupt::free(|_| {
+                // Safe, because this is not going to interfere with the
+                // critical section.
+                unsafe { NVIC::unmask(Interrupt::WKT) };
+
+                self.pmu.enter_sleep_mode(self.scb);
+
+                // If we don't do this, the (possibly non-existing) interrupt
+                // handler will be called as soon as we exit this closure.
+                NVIC::mask(Interrupt::WKT);
+
+                if self.wkt.wait().is_ok() {
+                    return Some(WakeReason::Timer);
+                }
+
+                // Sound, as we're only reading from a register.
+                let pending = unsafe { (*NVIC::ptr()).ispr[0].read() };
+                if pending & self.wake_sources != 0 {
+                    return Some(WakeReason::Interrupt);
+                }
+
+                None
+            });
+
+            // Any interrupts that woke us up are handled here, after we left
+            // the critical section.
+
+            if let Some(reason) = reason {
+                return reason;
+            }
+        }
+    }
+}
+
+impl<'r, Clock> Sleep<Clock> for Wfi<'r>
+where
+    Clock: clock::Enabled + wkt::Clock,
+{
+    fn sleep<'clock, T>(&mut self, ticks: T)
+    where
+        Clock: 'clock,
+        T: Into<Ticks<'clock, Clock>>,
+    {
+        self.sleep_until_wake(ticks);
+    }
+}
+
+/// The reason why [`Wfi::sleep_until_wake`] returned
+///
+/// [`Wfi::sleep_until_wake`]: struct.Wfi.html#method.sleep_until_wake
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WakeReason {
+    /// The requested time has passed
+    Timer,
+
+    /// One of the wake source interrupts occurred
+    Interrupt,
+}
@@ -0,0 +1,464 @@
+//! Bit-banged I2C master
+//!
+//! For designs that need more I2C buses than there are I2C instances, or that
+//! need I2C on pins that the I2C instances can't use, this module provides a
+//! software implementation of an I2C master. It uses two GPIO pins for SCL and
+//! SDA, and a periodic timer (like an MRT channel) for the bit timing.
+//!
+//! Both pins need to be configured as open-drain outputs in IOCON (which can
+//! be checked using [`GpioPin::iocon`]), and both lines need external pull-up
+//! resistors. Setting a pin high then releases the
+//! line, so the slave (or another master) can pull it low. [`SoftI2c`] reads
+//! back the level of the lines, to receive data, detect slaves that stretch
+//! the clock, and detect the loss of arbitration to another master.
+//!
+//! All transfers block until they have finished. As with the
+//! [`soft_serial`] module, only moderate bus speeds are realistic. 100 kHz is
+//! feasible at the default system clock of 12 MHz.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     prelude::*,
+//!     Peripherals,
+//!     gpio::Level,
+//!     soft_i2c::SoftI2c,
+//! };
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let gpio = p.GPIO;
+//! #[cfg(feature = "845")]
+//! let gpio = p.GPIO.enable(&mut syscon.handle);
+//!
+//! let mut scl = p.pins.pio0_12;
+//! let mut sda = p.pins.pio0_13;
+//!
+//! // Start from the reset configuration, in case the pins were configured
+//! // for something else before. The HAL can only read and reset the IOCON
+//! // configuration, so the pins are switched to open-drain mode using the raw
+//! // registers.
+//! scl.reset_iocon(&mut syscon.handle);
+//! sda.reset_iocon(&mut syscon.handle);
+//! p.IOCON.pio0_12.modify(|_, w| w.od().enabled());
+//! p.IOCON.pio0_13.modify(|_, w| w.od().enabled());
+//!
+//! let scl = scl.into_output_pin(gpio.tokens.pio0_12, Level::High);
+//! let sda = sda.into_output_pin(gpio.tokens.pio0_13, Level::High);
+//! let mrt_channels = p.MRT0.split(&mut syscon.handle);
+//!
+//! // The MRT runs at 12 MHz by default. A half period of 60 ticks results in
+//! // a bus speed of 100 kHz.
+//! let mut i2c = SoftI2c::new(scl, sda, mrt_channels.mrt0, 60);
+//!
+//! let mut buffer = [0; 2];
+//! i2c.write_read(0x90, &[0x00], &mut buffer).unwrap();
+//! ```
+//!
+//! [`SoftI2c`]: struct.SoftI2c.html
+//! [`GpioPin::iocon`]: ../gpio/struct.GpioPin.html#method.iocon
+//! [`soft_serial`]: ../soft_serial/index.html
+
+use embedded_hal::{
+    blocking::i2c,
+    digital::v2::{InputPin, OutputPin},
+    timer::{CountDown, Periodic},
+};
+use nb::block;
+
+/// Bit-banged I2C master
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// Addresses have the same format as for [`I2C`], meaning the 7-bit address
+/// is in the upper 7 bits. The lowest bit is ignored.
+///
+/// # `embedded-hal` traits
+/// - [`embedded_hal::blocking::i2c::Read`] for blocking reads
+/// - [`embedded_hal::blocking::i2c::Write`] for blocking writes
+/// - [`embedded_hal::blocking::i2c::WriteIter`] for blocking writes from an
+///   iterator
+/// - [`embedded_hal::blocking::i2c::WriteRead`] for blocking writes followed
+///   by reads
+/// - [`embedded_hal::blocking::i2c::WriteIterRead`] for blocking writes from
+///   an iterator, followed by reads
+///
+/// [module documentation]: index.html
+/// [`I2C`]: ../i2c/struct.I2C.html
+/// [`embedded_hal::blocking::i2c::Read`]: #impl-Read
+/// [`embedded_hal::blocking::i2c::Write`]: #impl-Write
+/// [`embedded_hal::blocking::i2c::WriteIter`]: #impl-WriteIter
+/// [`embedded_hal::blocking::i2c::WriteRead`]: #impl-WriteRead
+/// [`embedded_hal::blocking::i2c::WriteIterRead`]: #impl-WriteIterRead
+pub struct SoftI2c<SCL, SDA, T> {
+    scl: SCL,
+    sda: SDA,
+    timer: T,
+    half_period: u32,
+    stretch_limit: u32,
+}
+
+impl<SCL, SDA, T, E> SoftI2c<SCL, SDA, T>
+where
+    SCL: OutputPin<Error = E> + InputPin<Error = E>,
+    SDA: OutputPin<Error = E> + InputPin<Error = E>,
+    T: CountDown<Time = u32> + Periodic,
+{
+    /// Create a new I2C master
+    ///
+    /// `half_period` is the duration of half a clock period, in timer ticks.
+    /// Both pins should already be high, which is the idle state of the bus.
+    ///
+    /// Slaves may stretch the clock for up to 10000 half periods (50 ms at
+    /// 100 kHz), before [`Error::StretchTimeout`] is returned. Use
+    /// [`with_stretch_limit`] to change that.
+    ///
+    /// [`Error::StretchTimeout`]: enum.Error.html#variant.StretchTimeout
+    /// [`with_stretch_limit`]: #method.with_stretch_limit
+    pub fn new(scl: SCL, sda: SDA, timer: T, half_period: u32) -> Self {
+        Self {
+            scl,
+            sda,
+            timer,
+            half_period,
+            stretch_limit: 10_000,
+        }
+    }
+
+    /// Change how long slaves may stretch the clock
+    ///
+    /// `half_periods` is the maximum time that SCL may be held low by a slave,
+    /// in multiples of the half period passed to [`new`].
+    ///
+    /// [`new`]: #method.new
+    pub fn with_stretch_limit(mut self, half_periods: u32) -> Self {
+        self.stretch_limit = half_periods;
+        self
+    }
+
+    /// Release the pins and the timer
+    pub fn free(self) -> (SCL, SDA, T) {
+        (self.scl, self.sda, self.timer)
+    }
+
+    /// Run a transaction, and end it with a stop condition
+    ///
+    /// If arbitration was lost, the bus belongs to another master. Both lines
+    /// are released in that case, without sending a stop condition.
+    fn transaction<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error<E>>,
+    {
+        self.timer.start(self.half_period);
+
+        match f(self) {
+            Err(Error::ArbitrationLoss) => {
+                let _ = self.scl.set_high();
+                let _ = self.sda.set_high();
+                Err(Error::ArbitrationLoss)
+            }
+            result => {
+                let stop = self.stop();
+                result.and(stop)
+            }
+        }
+    }
+
+    fn write_bytes<B>(&mut self, address: u8, bytes: B) -> Result<(), Error<E>>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.start()?;
+
+        if !self.write_byte(address & 0xfe)? {
+            return Err(Error::NackAddress);
+        }
+        for byte in bytes {
+            if !self.write_byte(byte)? {
+                return Err(Error::NackData);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_bytes(
+        &mut self,
+        address: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), Error<E>> {
+        self.start()?;
+
+        if !self.write_byte(address | 0x01)? {
+            return Err(Error::NackAddress);
+        }
+
+        // The last byte is not acknowledged, which tells the slave to stop
+        // sending.
+        let last = buffer.len().saturating_sub(1);
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            *byte = self.read_byte(i != last)?;
+        }
+
+        Ok(())
+    }
+
+    /// Send a start condition, or a repeated start condition
+    ///
+    /// Leaves SCL low.
+    fn start(&mut self) -> Result<(), Error<E>> {
+        // Release both lines first, in case this is a repeated start.
+        self.sda.set_high().map_err(Error::Pin)?;
+        self.delay();
+        self.release_scl()?;
+        self.delay();
+
+        // If SDA is low, another master is using the bus.
+        if self.sda.is_low().map_err(Error::Pin)? {
+            return Err(Error::ArbitrationLoss);
+        }
+
+        self.sda.set_low().map_err(Error::Pin)?;
+        self.delay();
+        self.scl.set_low().map_err(Error::Pin)?;
+
+        Ok(())
+    }
+
+    /// Send a stop condition
+    ///
+    /// Expects SCL to be low, and leaves both lines released.
+    fn stop(&mut self) -> Result<(), Error<E>> {
+        self.sda.set_low().map_err(Error::Pin)?;
+        self.delay();
+        self.release_scl()?;
+        self.delay();
+        self.sda.set_high().map_err(Error::Pin)?;
+        self.delay();
+
+        Ok(())
+    }
+
+    /// Write a byte, returning whether it was acknowledged
+    fn write_byte(&mut self, byte: u8) -> Result<bool, Error<E>> {
+        for i in (0..8).rev() {
+            self.write_bit(byte & (1 << i) != 0)?;
+        }
+
+        Ok(!self.read_bit()?)
+    }
+
+    /// Read a byte, and acknowledge it, if `ack` is `true`
+    fn read_byte(&mut self, ack: bool) -> Result<u8, Error<E>> {
+        let mut byte = 0;
+        for _ in 0..8 {
+            byte = byte << 1 | self.read_bit()? as u8;
+        }
+
+        self.write_bit(!ack)?;
+
+        Ok(byte)
+    }
+
+    /// Write a bit
+    ///
+    /// Expects SCL to be low, and leaves it low.
+    fn write_bit(&mut self, bit: bool) -> Result<(), Error<E>> {
+        if bit {
+            self.sda.set_high().map_err(Error::Pin)?;
+        } else {
+            self.sda.set_low().map_err(Error::Pin)?;
+        }
+        self.delay();
+        self.release_scl()?;
+
+        // If we released SDA, but it is low, another master is sending a 0.
+        if bit && self.sda.is_low().map_err(Error::Pin)? {
+            return Err(Error::ArbitrationLoss);
+        }
+
+        self.delay();
+        self.scl.set_low().map_err(Error::Pin)?;
+
+        Ok(())
+    }
+
+    /// Read a bit
+    ///
+    /// Expects SCL to be low, and leaves it low.
+    fn read_bit(&mut self) -> Result<bool, Error<E>> {
+        self.sda.set_high().map_err(Error::Pin)?;
+        self.delay();
+        self.release_scl()?;
+        let bit = self.sda.is_high().map_err(Error::Pin)?;
+        self.delay();
+        self.scl.set_low().map_err(Error::Pin)?;
+
+        Ok(bit)
+    }
+
+    /// Release SCL, and wait while a slave stretches the clock
+    fn release_scl(&mut self) -> Result<(), Error<E>> {
+        self.scl.set_high().map_err(Error::Pin)?;
+
+        let mut remaining = self.stretch_limit;
+        while self.scl.is_low().map_err(Error::Pin)? {
+            if remaining == 0 {
+                return Err(Error::StretchTimeout);
+            }
+            remaining -= 1;
+            self.delay();
+        }
+
+        Ok(())
+    }
+
+    fn delay(&mut self) {
+        let _ = block!(self.timer.wait());
+    }
+}
+
+impl<SCL, SDA, T, E> i2c::Write for SoftI2c<SCL, SDA, T>
+where
+    SCL: OutputPin<Error = E> + InputPin<Error = E>,
+    SDA: OutputPin<Error = E> + InputPin<Error = E>,
+    T: CountDown<Time = u32> + Periodic,
+{
+    type Error = Error<E>;
+
+    /// Write to the I2C bus
+    ///
+    /// Please refer to the [embedded-hal documentation] for details.
+    ///
+    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.5/embedded_hal/blocking/i2c/trait.Write.html#tymethod.write
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(|i2c| i2c.write_bytes(address, data.iter().copied()))
+    }
+}
+
+impl<SCL, SDA, T, E> i2c::WriteIter for SoftI2c<SCL, SDA, T>
+where
+    SCL: OutputPin<Error = E> + InputPin<Error = E>,
+    SDA: OutputPin<Error = E> + InputPin<Error = E>,
+    T: CountDown<Time = u32> + Periodic,
+{
+    type Error = Error<E>;
+
+    /// Write to the I2C bus, taking the data from an iterator
+    ///
+    /// Please refer to the [embedded-hal documentation] for details.
+    ///
+    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.5/embedded_hal/blocking/i2c/trait.WriteIter.html#tymethod.write
+    fn write<B>(&mut self, address: u8, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.transaction(|i2c| i2c.write_bytes(address, bytes))
+    }
+}
+
+impl<SCL, SDA, T, E> i2c::Read for SoftI2c<SCL, SDA, T>
+where
+    SCL: OutputPin<Error = E> + InputPin<Error = E>,
+    SDA: OutputPin<Error = E> + InputPin<Error = E>,
+    T: CountDown<Time = u32> + Periodic,
+{
+    type Error = Error<E>;
+
+    /// Read from the I2C bus
+    ///
+    /// Please refer to the [embedded-hal documentation] for details.
+    ///
+    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.5/embedded_hal/blocking/i2c/trait.Read.html#tymethod.read
+    fn read(
+        &mut self,
+        address: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(|i2c| i2c.read_bytes(address, buffer))
+    }
+}
+
+impl<SCL, SDA, T, E> i2c::WriteRead for SoftI2c<SCL, SDA, T>
+where
+    SCL: OutputPin<Error = E> + InputPin<Error = E>,
+    SDA: OutputPin<Error = E> + InputPin<Error = E>,
+    T: CountDown<Time = u32> + Periodic,
+{
+    type Error = Error<E>;
+
+    /// Write to the I2C bus, then read from it
+    ///
+    /// Both happen in a single transaction, separated by a repeated start
+    /// condition.
+    ///
+    /// Please refer to the [embedded-hal documentation] for details.
+    ///
+    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.5/embedded_hal/blocking/i2c/trait.WriteRead.html#tymethod.write_read
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(|i2c| {
+            i2c.write_bytes(address, bytes.iter().copied())?;
+            i2c.read_bytes(address, buffer)
+        })
+    }
+}
+
+impl<SCL, SDA, T, E> i2c::WriteIterRead for SoftI2c<SCL, SDA, T>
+where
+    SCL: OutputPin<Error = E> + InputPin<Error = E>,
+    SDA: OutputPin<Error = E> + InputPin<Error = E>,
+    T: CountDown<Time = u32> + Periodic,
+{
+    type Error = Error<E>;
+
+    /// Write to the I2C bus from an iterator, then read from it
+    ///
+    /// Both happen in a single transaction, separated by a repeated start
+    /// condition.
+    ///
+    /// Please refer to the [embedded-hal documentation] for details.
+    ///
+    /// [embedded-hal documentation]: https://docs.rs/embedded-hal/0.2.5/embedded_hal/blocking/i2c/trait.WriteIterRead.html#tymethod.write_iter_read
+    fn write_iter_read<B>(
+        &mut self,
+        address: u8,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.transaction(|i2c| {
+            i2c.write_bytes(address, bytes)?;
+            i2c.read_bytes(address, buffer)
+        })
+    }
+}
+
+/// A bit-banged I2C error
+#[derive(Debug)]
+pub enum Error<E> {
+    /// Another master took control of the bus
+    ArbitrationLoss,
+
+    /// The slave didn't acknowledge its address
+    NackAddress,
+
+    /// The slave didn't acknowledge a data byte
+    NackData,
+
+    /// A slave held SCL low for longer than the configured limit
+    StretchTimeout,
+
+    /// An error occurred while accessing a pin
+    Pin(E),
+}
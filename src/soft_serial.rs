@@ -0,0 +1,251 @@
+//! Bit-banged serial interface
+//!
+//! For the rare cases where all USARTs are already in use, this module provides
+//! a software implementation of an asynchronous serial interface. It uses a
+//! GPIO pin for each direction, and a periodic timer (like an MRT channel) for
+//! the bit timing. The frame format is fixed to 8 data bits, no parity, and
+//! 1 stop bit.
+//!
+//! Both [`SoftTx`] and [`SoftRx`] block for the duration of a full frame. For
+//! reliable timing, interrupts that take longer to handle than a fraction of
+//! the bit time should be disabled while a frame is being sent or received.
+//! Even then, only moderate baud rates (9600 baud or less at the default system
+//! clock of 12 MHz) are recommended.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     prelude::*,
+//!     Peripherals,
+//!     gpio::Level,
+//!     soft_serial::SoftTx,
+//! };
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let gpio = p.GPIO;
+//! #[cfg(feature = "845")]
+//! let gpio = p.GPIO.enable(&mut syscon.handle);
+//!
+//! let tx_pin = p.pins.pio0_12.into_output_pin(
+//!     gpio.tokens.pio0_12,
+//!     Level::High,
+//! );
+//! let mrt_channels = p.MRT0.split(&mut syscon.handle);
+//!
+//! // The MRT runs at 12 MHz by default. 12 MHz / 1250 = 9600 baud
+//! let mut tx = SoftTx::new(tx_pin, mrt_channels.mrt0, 1250);
+//!
+//! write!(tx, "Hello, world!\r\n").unwrap();
+//! ```
+//!
+//! [`SoftTx`]: struct.SoftTx.html
+//! [`SoftRx`]: struct.SoftRx.html
+
+use core::fmt;
+
+use embedded_hal::{
+    blocking::serial::write::Default as BlockingWriteDefault,
+    digital::v2::{InputPin, OutputPin},
+    serial::{Read, Write},
+    timer::{CountDown, Periodic},
+};
+use nb::block;
+
+/// Bit-banged serial transmitter
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// # `embedded-hal` traits
+/// - [`embedded_hal::serial::Write`] for non-blocking writes
+/// - [`embedded_hal::blocking::serial::Write`] for blocking writes
+///
+/// [module documentation]: index.html
+/// [`embedded_hal::serial::Write`]: #impl-Write%3Cu8%3E
+/// [`embedded_hal::blocking::serial::Write`]: #impl-Write
+pub struct SoftTx<P, T> {
+    pin: P,
+    timer: T,
+    bit_time: u32,
+}
+
+impl<P, T> SoftTx<P, T>
+where
+    P: OutputPin,
+    T: CountDown<Time = u32> + Periodic,
+{
+    /// Create a new transmitter
+    ///
+    /// `bit_time` is the duration of one bit, in timer ticks. The pin should
+    /// already be high, which is the idle state of the line.
+    pub fn new(pin: P, timer: T, bit_time: u32) -> Self {
+        Self {
+            pin,
+            timer,
+            bit_time,
+        }
+    }
+
+    /// Release the pin and the timer
+    pub fn free(self) -> (P, T) {
+        (self.pin, self.timer)
+    }
+}
+
+impl<P, T> Write<u8> for SoftTx<P, T>
+where
+    P: OutputPin,
+    T: CountDown<Time = u32> + Periodic,
+{
+    type Error = P::Error;
+
+    /// Writes a single word
+    ///
+    /// Blocks until the whole frame, including the stop bit, has been sent.
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.timer.start(self.bit_time);
+
+        // Start bit
+        self.pin.set_low()?;
+        let _ = block!(self.timer.wait());
+
+        for i in 0..8 {
+            if word & (1 << i) != 0 {
+                self.pin.set_high()?;
+            } else {
+                self.pin.set_low()?;
+            }
+            let _ = block!(self.timer.wait());
+        }
+
+        // Stop bit
+        self.pin.set_high()?;
+        let _ = block!(self.timer.wait());
+
+        Ok(())
+    }
+
+    /// Ensures that none of the previously written words are still buffered
+    ///
+    /// As `write` doesn't return before a frame has been sent, this never
+    /// blocks.
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<P, T> BlockingWriteDefault<u8> for SoftTx<P, T>
+where
+    P: OutputPin,
+    T: CountDown<Time = u32> + Periodic,
+{
+}
+
+impl<P, T> fmt::Write for SoftTx<P, T>
+where
+    Self: BlockingWriteDefault<u8>,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        use embedded_hal::blocking::serial::Write;
+
+        self.bwrite_all(s.as_bytes()).map_err(|_| fmt::Error)?;
+        self.bflush().map_err(|_| fmt::Error)?;
+
+        Ok(())
+    }
+}
+
+/// Bit-banged serial receiver
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// # `embedded-hal` traits
+/// - [`embedded_hal::serial::Read`]
+///
+/// [module documentation]: index.html
+/// [`embedded_hal::serial::Read`]: #impl-Read%3Cu8%3E
+pub struct SoftRx<P, T> {
+    pin: P,
+    timer: T,
+    bit_time: u32,
+}
+
+impl<P, T> SoftRx<P, T>
+where
+    P: InputPin,
+    T: CountDown<Time = u32> + Periodic,
+{
+    /// Create a new receiver
+    ///
+    /// `bit_time` is the duration of one bit, in timer ticks.
+    pub fn new(pin: P, timer: T, bit_time: u32) -> Self {
+        Self {
+            pin,
+            timer,
+            bit_time,
+        }
+    }
+
+    /// Release the pin and the timer
+    pub fn free(self) -> (P, T) {
+        (self.pin, self.timer)
+    }
+}
+
+impl<P, T> Read<u8> for SoftRx<P, T>
+where
+    P: InputPin,
+    T: CountDown<Time = u32> + Periodic,
+{
+    type Error = Error<P::Error>;
+
+    /// Reads a single word
+    ///
+    /// Returns `nb::Error::WouldBlock`, if no start bit has been detected. Once
+    /// a start bit has been detected, blocks until the whole frame has been
+    /// received.
+    ///
+    /// This method needs to be called often enough to detect the start bit
+    /// early. Calling it from the interrupt handler of a falling-edge pin
+    /// interrupt works well.
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        if self.pin.is_high().map_err(Error::Pin)? {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        // Wait until the middle of the start bit, then sample every bit in its
+        // middle.
+        self.timer.start(self.bit_time / 2);
+        let _ = block!(self.timer.wait());
+        self.timer.start(self.bit_time);
+
+        let mut word = 0;
+        for i in 0..8 {
+            let _ = block!(self.timer.wait());
+            if self.pin.is_high().map_err(Error::Pin)? {
+                word |= 1 << i;
+            }
+        }
+
+        let _ = block!(self.timer.wait());
+        if self.pin.is_low().map_err(Error::Pin)? {
+            return Err(nb::Error::Other(Error::Framing));
+        }
+
+        Ok(word)
+    }
+}
+
+/// A bit-banged serial receive error
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The stop bit was not detected
+    Framing,
+
+    /// An error occurred while reading the pin
+    Pin(E),
+}
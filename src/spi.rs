@@ -51,23 +51,40 @@
 //!     .expect("Transfer shouldn't fail");
 //! ```
 //!
+//! Instead of an `embedded_hal::spi::Mode`, one of the [`Mode0`]..[`Mode3`]
+//! types can be passed to `enable`. This makes the mode part of the `SPI`
+//! type, so drivers can require a specific one. See [`ModeTrait`] for details.
+//!
 //! Please refer to the [examples in the repository] for more example code.
 //!
+//! [`Mode0`]: struct.Mode0.html
+//! [`Mode3`]: struct.Mode3.html
+//! [`ModeTrait`]: trait.ModeTrait.html
 //! [examples in the repository]: https://github.com/lpc-rs/lpc8xx-hal/tree/master/examples
 
-use core::ops::Deref;
+use core::{marker::PhantomData, ops::Deref};
 
-use embedded_hal::spi::{FullDuplex, Mode, Phase, Polarity};
+use embedded_hal::{
+    spi::{FullDuplex, Mode, Phase, Polarity},
+    timer::CountDown,
+};
 
 use crate::{
-    init_state, pac, pins,
+    init_state,
+    pac::{self, Interrupt, NVIC},
+    pins,
     swm::{self, FunctionTrait},
     syscon::{
         self,
         clock_source::{PeripheralClock, SpiClock},
     },
+    timeout::{self, Deadline},
 };
 
+mod error;
+
+pub use self::error::Error;
+
 /// Interface to a SPI peripheral
 ///
 /// Controls the SPI. Use [`Peripherals`] to gain access to an instance of
@@ -86,9 +103,10 @@ use crate::{
 /// [`embedded_hal::spi::FullDuplex`]: #impl-FullDuplex%3Cu8%3E
 /// [`embedded_hal::blocking::spi::Transfer`]: #impl-Transfer%3CW%3E
 /// [`embedded_hal::blocking::spi::Write`]: #impl-Write%3CW%3E
-pub struct SPI<I, State = init_state::Enabled> {
+pub struct SPI<I, State = init_state::Enabled, M = Mode> {
     spi: I,
     _state: State,
+    _mode: PhantomData<M>,
 }
 
 impl<I> SPI<I, init_state::Disabled>
@@ -99,6 +117,7 @@ where
         Self {
             spi,
             _state: init_state::Disabled,
+            _mode: PhantomData,
         }
     }
 
@@ -109,7 +128,14 @@ where
     /// enabled will not compile.
     ///
     /// Consumes this instance of `SPI` and returns another instance that has
-    /// its `State` type parameter set to [`Enabled`].
+    /// its `State` type parameter set to [`Enabled`]. The SWM functions are
+    /// kept by the enabled instance, and returned by [`disable`].
+    ///
+    /// `mode` is either an [`embedded_hal::spi::Mode`], which selects the mode
+    /// at runtime, or one of [`Mode0`], [`Mode1`], [`Mode2`], and [`Mode3`].
+    /// The latter also set the `M` type parameter of the returned instance,
+    /// which allows drivers to require a specific mode in their type
+    /// signatures.
     ///
     /// # Examples
     ///
@@ -117,18 +143,26 @@ where
     ///
     /// [`Disabled`]: ../init_state/struct.Disabled.html
     /// [`Enabled`]: ../init_state/struct.Enabled.html
+    /// [`disable`]: #method.disable
+    /// [`embedded_hal::spi::Mode`]: https://docs.rs/embedded-hal/0.2/embedded_hal/spi/struct.Mode.html
+    /// [`Mode0`]: struct.Mode0.html
+    /// [`Mode1`]: struct.Mode1.html
+    /// [`Mode2`]: struct.Mode2.html
+    /// [`Mode3`]: struct.Mode3.html
     /// [`BaudRate`]: struct.BaudRate.html
     /// [module documentation]: index.html
-    pub fn enable<SckPin, MosiPin, MisoPin, CLOCK>(
+    #[allow(clippy::type_complexity)]
+    pub fn enable<SckPin, MosiPin, MisoPin, CLOCK, M>(
         self,
         clock: &SpiClock<CLOCK>,
         syscon: &mut syscon::Handle,
-        mode: Mode,
-        _: swm::Function<I::Sck, swm::state::Assigned<SckPin>>,
-        _: swm::Function<I::Mosi, swm::state::Assigned<MosiPin>>,
-        _: swm::Function<I::Miso, swm::state::Assigned<MisoPin>>,
-    ) -> SPI<I, init_state::Enabled>
+        mode: M,
+        sck: swm::Function<I::Sck, swm::state::Assigned<SckPin>>,
+        mosi: swm::Function<I::Mosi, swm::state::Assigned<MosiPin>>,
+        miso: swm::Function<I::Miso, swm::state::Assigned<MisoPin>>,
+    ) -> SPI<I, init_state::Enabled<SwmFunctions<I, SckPin, MosiPin, MisoPin>>, M>
     where
+        M: ModeTrait,
         SckPin: pins::Trait,
         MosiPin: pins::Trait,
         MisoPin: pins::Trait,
@@ -136,6 +170,138 @@ where
         I::Mosi: FunctionTrait<MosiPin>,
         I::Miso: FunctionTrait<MisoPin>,
         SpiClock<CLOCK>: PeripheralClock<I>,
+    {
+        self.configure(clock, syscon, mode.mode());
+
+        SPI {
+            spi: self.spi,
+            _state: init_state::Enabled((sck, mosi, miso)),
+            _mode: PhantomData,
+        }
+    }
+
+    /// Enable the SPI peripheral, without assigning MISO
+    ///
+    /// Works like [`enable`], except that only SCK and MOSI need to be
+    /// assigned. This is useful for devices that are only written to, like
+    /// many displays and LED drivers, as the pin that would otherwise be used
+    /// for MISO remains available for other uses.
+    ///
+    /// Since MISO is not assigned, the data that is received is meaningless.
+    /// It still needs to be read when using [`FullDuplex`], but methods like
+    /// [`write_only`] discard it in hardware.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{Peripherals, syscon::clock_source::SpiClock};
+    ///
+    /// let mut p = Peripherals::take().unwrap();
+    /// let mut swm = p.SWM.split();
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// let (spi0_sck, _) = swm.movable_functions.spi0_sck.assign(
+    ///     p.pins.pio0_13.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    /// let (spi0_mosi, _) = swm
+    ///     .movable_functions
+    ///     .spi0_mosi
+    ///     .assign(p.pins.pio0_14.into_swm_pin(), &mut swm_handle);
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let spi_clock = SpiClock::new(0);
+    /// #[cfg(feature = "845")]
+    /// let spi_clock = SpiClock::new(&syscon.iosc, 0);
+    ///
+    /// let mut spi = p.SPI0.enable_write_only(
+    ///     &spi_clock,
+    ///     &mut syscon.handle,
+    ///     embedded_hal::spi::MODE_0,
+    ///     spi0_sck,
+    ///     spi0_mosi,
+    /// );
+    ///
+    /// // PIO0_15 is still available for other uses
+    /// spi.write_only(&[0xaa, 0x55]).expect("Write shouldn't fail");
+    /// ```
+    ///
+    /// [`enable`]: #method.enable
+    /// [`FullDuplex`]: #impl-FullDuplex%3Cu8%3E
+    /// [`write_only`]: #method.write_only
+    pub fn enable_write_only<SckPin, MosiPin, CLOCK, M>(
+        self,
+        clock: &SpiClock<CLOCK>,
+        syscon: &mut syscon::Handle,
+        mode: M,
+        sck: swm::Function<I::Sck, swm::state::Assigned<SckPin>>,
+        mosi: swm::Function<I::Mosi, swm::state::Assigned<MosiPin>>,
+    ) -> SPI<I, init_state::Enabled<WriteOnlyFunctions<I, SckPin, MosiPin>>, M>
+    where
+        M: ModeTrait,
+        SckPin: pins::Trait,
+        MosiPin: pins::Trait,
+        I::Sck: FunctionTrait<SckPin>,
+        I::Mosi: FunctionTrait<MosiPin>,
+        SpiClock<CLOCK>: PeripheralClock<I>,
+    {
+        self.configure(clock, syscon, mode.mode());
+
+        SPI {
+            spi: self.spi,
+            _state: init_state::Enabled((sck, mosi)),
+            _mode: PhantomData,
+        }
+    }
+
+    /// Enable the SPI peripheral, without assigning MOSI
+    ///
+    /// Works like [`enable`], except that only SCK and MISO need to be
+    /// assigned. This is useful for devices that are only read from, like
+    /// some sensors and ADCs, as the pin that would otherwise be used for MOSI
+    /// remains available for other uses.
+    ///
+    /// Words still need to be sent to clock in the received data, but since
+    /// MOSI is not assigned, their value doesn't matter.
+    ///
+    /// [`enable`]: #method.enable
+    pub fn enable_read_only<SckPin, MisoPin, CLOCK, M>(
+        self,
+        clock: &SpiClock<CLOCK>,
+        syscon: &mut syscon::Handle,
+        mode: M,
+        sck: swm::Function<I::Sck, swm::state::Assigned<SckPin>>,
+        miso: swm::Function<I::Miso, swm::state::Assigned<MisoPin>>,
+    ) -> SPI<I, init_state::Enabled<ReadOnlyFunctions<I, SckPin, MisoPin>>, M>
+    where
+        M: ModeTrait,
+        SckPin: pins::Trait,
+        MisoPin: pins::Trait,
+        I::Sck: FunctionTrait<SckPin>,
+        I::Miso: FunctionTrait<MisoPin>,
+        SpiClock<CLOCK>: PeripheralClock<I>,
+    {
+        self.configure(clock, syscon, mode.mode());
+
+        SPI {
+            spi: self.spi,
+            _state: init_state::Enabled((sck, miso)),
+            _mode: PhantomData,
+        }
+    }
+
+    fn configure<CLOCK>(
+        &self,
+        clock: &SpiClock<CLOCK>,
+        syscon: &mut syscon::Handle,
+        mode: Mode,
+    ) where
+        SpiClock<CLOCK>: PeripheralClock<I>,
     {
         syscon.enable_clock(&self.spi);
 
@@ -164,15 +330,10 @@ where
             w.enable().enabled();
             w.master().master_mode()
         });
-
-        SPI {
-            spi: self.spi,
-            _state: init_state::Enabled(()),
-        }
     }
 }
 
-impl<I> SPI<I, init_state::Enabled>
+impl<I, Pins, M> SPI<I, init_state::Enabled<Pins>, M>
 where
     I: Instance,
 {
@@ -183,24 +344,650 @@ where
     /// disabled will not compile.
     ///
     /// Consumes this instance of `SPI` and returns another instance that has
-    /// its `State` type parameter set to [`Disabled`].
+    /// its `State` type parameter set to [`Disabled`], together with the SWM
+    /// functions that were passed to [`enable`]. Those can then be unassigned,
+    /// to reuse the pins for something else.
     ///
     /// [`Enabled`]: ../init_state/struct.Enabled.html
     /// [`Disabled`]: ../init_state/struct.Disabled.html
+    /// [`enable`]: #method.enable
     pub fn disable(
         self,
         syscon: &mut syscon::Handle,
-    ) -> SPI<I, init_state::Disabled> {
+    ) -> (SPI<I, init_state::Disabled>, Pins) {
         syscon.disable_clock(&self.spi);
 
-        SPI {
+        let spi = SPI {
             spi: self.spi,
             _state: init_state::Disabled,
+            _mode: PhantomData,
+        };
+
+        (spi, self._state.0)
+    }
+
+    /// Forget which mode has been selected at the type level
+    ///
+    /// Returns an instance whose `M` type parameter is the default,
+    /// [`embedded_hal::spi::Mode`]. This is useful for passing an instance
+    /// that was enabled with one of the [`Mode0`]..[`Mode3`] types to code that
+    /// doesn't care about the mode.
+    ///
+    /// [`embedded_hal::spi::Mode`]: https://docs.rs/embedded-hal/0.2/embedded_hal/spi/struct.Mode.html
+    /// [`Mode0`]: struct.Mode0.html
+    /// [`Mode3`]: struct.Mode3.html
+    pub fn erase_mode(self) -> SPI<I, init_state::Enabled<Pins>> {
+        SPI {
+            spi: self.spi,
+            _state: self._state,
+            _mode: PhantomData,
         }
     }
+
+    /// Enable interrupts for this instance in the NVIC
+    ///
+    /// This only enables the interrupts in the NVIC. It doesn't enable any
+    /// specific interrupt in this SPI instance.
+    pub fn enable_in_nvic(&mut self) {
+        // Safe, because there's no critical section here that this could
+        // interfere with.
+        unsafe { NVIC::unmask(I::INTERRUPT) };
+    }
+
+    /// Disable interrupts for this instance in the NVIC
+    ///
+    /// This only disables the interrupts in the NVIC. It doesn't change
+    /// anything about the interrupt configuration within this SPI instance.
+    pub fn disable_in_nvic(&mut self) {
+        NVIC::mask(I::INTERRUPT);
+    }
+
+    /// Clear this instance's interrupt pending flag in the NVIC
+    ///
+    /// This only clears the interrupt's pending flag in the NVIC. It does not
+    /// affect any of the interrupt-related flags in the peripheral.
+    pub fn clear_nvic_pending(&mut self) {
+        NVIC::unpend(I::INTERRUPT);
+    }
+
+    /// Set this instance's interrupt pending flag in the NVIC
+    ///
+    /// The interrupt handler then runs as if the interrupt had occurred, once
+    /// the interrupt is enabled in the NVIC and its priority allows it. This
+    /// does not set any of the interrupt-related flags in the peripheral.
+    pub fn set_nvic_pending(&mut self) {
+        NVIC::pend(I::INTERRUPT);
+    }
+
+    /// Indicates whether this instance's interrupt is pending in the NVIC
+    pub fn is_nvic_pending(&self) -> bool {
+        NVIC::is_pending(I::INTERRUPT)
+    }
+
+    /// Set the priority of this instance's interrupt in the NVIC
+    ///
+    /// Lower values mean higher priority. Only the two most significant bits of
+    /// `priority` are used by the hardware.
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections,
+    /// like the ones used by RTFM.
+    pub unsafe fn set_nvic_priority(&mut self, nvic: &mut NVIC, priority: u8) {
+        nvic.set_priority(I::INTERRUPT, priority);
+    }
+
+    /// Send a command, then read the response
+    ///
+    /// Sends all bytes from `tx`, discarding whatever is received in the
+    /// meantime. Then sends one dummy byte (`0xff`) for each byte of `rx`,
+    /// while filling `rx` with the received data. This is the usual way to
+    /// talk to SPI flash chips and many sensors, and doesn't require building
+    /// a combined buffer for command and response.
+    ///
+    /// Slave select is deasserted after the last byte. Blocks until all bytes
+    /// have been transferred.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// # use lpc8xx_hal::{
+    /// #     prelude::*,
+    /// #     Peripherals,
+    /// #     syscon::clock_source::SpiClock,
+    /// # };
+    /// #
+    /// # let mut p  = Peripherals::take().unwrap();
+    /// # let mut swm = p.SWM.split();
+    /// # let mut syscon = p.SYSCON.split();
+    /// #
+    /// # #[cfg(feature = "82x")]
+    /// # let mut swm_handle = swm.handle;
+    /// # #[cfg(feature = "845")]
+    /// # let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    /// #
+    /// # let (spi0_sck, _) = swm.movable_functions.spi0_sck.assign(
+    /// #     p.pins.pio0_13.into_swm_pin(),
+    /// #     &mut swm_handle,
+    /// # );
+    /// # let (spi0_mosi, _) = swm
+    /// #     .movable_functions
+    /// #     .spi0_mosi
+    /// #     .assign(p.pins.pio0_14.into_swm_pin(), &mut swm_handle);
+    /// # let (spi0_miso, _) = swm
+    /// #     .movable_functions
+    /// #     .spi0_miso
+    /// #     .assign(p.pins.pio0_15.into_swm_pin(), &mut swm_handle);
+    /// #
+    /// # #[cfg(feature = "82x")]
+    /// # let spi_clock = SpiClock::new(0);
+    /// # #[cfg(feature = "845")]
+    /// # let spi_clock = SpiClock::new(&syscon.iosc, 0);
+    /// #
+    /// # let mut spi = p.SPI0.enable(
+    /// #     &spi_clock,
+    /// #     &mut syscon.handle,
+    /// #     embedded_hal::spi::MODE_0,
+    /// #     spi0_sck,
+    /// #     spi0_mosi,
+    /// #     spi0_miso,
+    /// # );
+    /// #
+    /// // Read the JEDEC ID of an SPI flash chip
+    /// let mut id = [0; 3];
+    /// spi.transfer_split(&[0x9f], &mut id)
+    ///     .expect("Transfer shouldn't fail");
+    /// ```
+    pub fn transfer_split(
+        &mut self,
+        tx: &[u8],
+        rx: &mut [u8],
+    ) -> Result<(), Error> {
+        // Nothing is read while sending the command, so let the hardware
+        // discard the received data.
+        let rx_len = rx.len();
+        for (i, &word) in tx.iter().enumerate() {
+            let control = TxControl {
+                end_of_transfer: rx_len == 0 && i == tx.len() - 1,
+                end_of_frame: false,
+                rx_ignore: true,
+            };
+            nb::block!(self.send_with_control(word, control))?;
+        }
+
+        for (i, word) in rx.iter_mut().enumerate() {
+            let control = TxControl {
+                end_of_transfer: i == rx_len - 1,
+                ..TxControl::default()
+            };
+            nb::block!(self.send_with_control(0xff, control))?;
+            *word = nb::block!(self.read())?;
+        }
+
+        self.wait_idle();
+        self.reset_tx_control();
+
+        Ok(())
+    }
+
+    /// Send a word, controlling how it's framed
+    ///
+    /// Works like [`FullDuplex::send`], but allows for control over slave
+    /// select, delays, and whether the received word is kept. Please refer to
+    /// [`TxControl`] for details.
+    ///
+    /// Unless `control.rx_ignore` is set, the received word needs to be read
+    /// using [`FullDuplex::read`], before the next one is received.
+    ///
+    /// The hardware keeps `control` in the TXCTL register after the word has
+    /// been sent. This doesn't affect [`FullDuplex::send`], which always sends
+    /// with the default control, and the blocking methods of this API restore
+    /// the default before they return.
+    ///
+    /// [`FullDuplex::send`]: #impl-FullDuplex%3Cu8%3E
+    /// [`FullDuplex::read`]: #impl-FullDuplex%3Cu8%3E
+    /// [`TxControl`]: struct.TxControl.html
+    pub fn send_with_control(
+        &mut self,
+        word: u8,
+        control: TxControl,
+    ) -> nb::Result<(), Error> {
+        self.tx_ready()?;
+
+        // Writing TXDATCTL also latches the control bits in TXCTL, which
+        // would affect any later writes to TXDAT. All slave selects are
+        // asserted, which is the same as the default.
+        self.spi.txdatctl.write(|w| {
+            w.eot().bit(control.end_of_transfer);
+            w.eof().bit(control.end_of_frame);
+            w.rxignore().bit(control.rx_ignore);
+            unsafe {
+                w.len().bits(7);
+                w.txdat().bits(word as u16)
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Send data without reading anything back
+    ///
+    /// The received data is discarded by the hardware, so there's no need to
+    /// read it. Slave select is deasserted after the last word. Blocks until
+    /// all data has been sent.
+    pub fn write_only(&mut self, words: &[u8]) -> Result<(), Error> {
+        for (i, &word) in words.iter().enumerate() {
+            let control = TxControl {
+                end_of_transfer: i == words.len() - 1,
+                end_of_frame: false,
+                rx_ignore: true,
+            };
+            nb::block!(self.send_with_control(word, control))?;
+        }
+
+        self.wait_idle();
+        self.reset_tx_control();
+
+        Ok(())
+    }
+
+    /// Transfer data, then deassert slave select
+    ///
+    /// Works like [`Transfer::transfer`], except that slave select is
+    /// deasserted after the last word, which many devices require to
+    /// recognize the end of a command. Blocks until all data has been
+    /// transferred.
+    ///
+    /// [`Transfer::transfer`]: #impl-Transfer%3CW%3E
+    pub fn transfer_and_deselect<'w>(
+        &mut self,
+        words: &'w mut [u8],
+    ) -> Result<&'w [u8], Error> {
+        let len = words.len();
+        for (i, word) in words.iter_mut().enumerate() {
+            let control = TxControl {
+                end_of_transfer: i == len - 1,
+                ..TxControl::default()
+            };
+            nb::block!(self.send_with_control(*word, control))?;
+            *word = nb::block!(self.read())?;
+        }
+
+        self.wait_idle();
+        self.reset_tx_control();
+
+        Ok(words)
+    }
+
+    /// Transfer data, giving up after a timeout
+    ///
+    /// Works like [`Transfer::transfer`], but returns
+    /// [`timeout::Error::Timeout`], if the transfer doesn't complete within
+    /// `ticks` ticks of `timer`. The timeout applies to the whole transfer.
+    ///
+    /// As the SPI peripheral generates the clock itself, this only happens if
+    /// the peripheral has been stalled, for example by a misconfigured clock.
+    ///
+    /// [`Transfer::transfer`]: #impl-Transfer%3CW%3E
+    /// [`timeout::Error::Timeout`]: ../timeout/enum.Error.html#variant.Timeout
+    pub fn transfer_with_timeout<'w, T>(
+        &mut self,
+        words: &'w mut [u8],
+        timer: &mut T,
+        ticks: u32,
+    ) -> Result<&'w [u8], timeout::Error<Error>>
+    where
+        T: CountDown<Time = u32>,
+    {
+        let mut deadline = Deadline::start(timer, ticks);
+
+        for word in words.iter_mut() {
+            deadline.block(|| self.send(*word))?;
+            *word = deadline.block(|| self.read())?;
+        }
+
+        Ok(words)
+    }
+
+    /// Write data, giving up after a timeout
+    ///
+    /// Works like [`Write::write`], but returns [`timeout::Error::Timeout`], if
+    /// the data isn't sent within `ticks` ticks of `timer`. Please refer to
+    /// [`transfer_with_timeout`] for details.
+    ///
+    /// [`Write::write`]: #impl-Write%3CW%3E
+    /// [`timeout::Error::Timeout`]: ../timeout/enum.Error.html#variant.Timeout
+    /// [`transfer_with_timeout`]: #method.transfer_with_timeout
+    pub fn write_with_timeout<T>(
+        &mut self,
+        words: &[u8],
+        timer: &mut T,
+        ticks: u32,
+    ) -> Result<(), timeout::Error<Error>>
+    where
+        T: CountDown<Time = u32>,
+    {
+        let mut deadline = Deadline::start(timer, ticks);
+
+        for &word in words {
+            deadline.block(|| self.send(word))?;
+            deadline.block(|| self.read())?;
+        }
+
+        Ok(())
+    }
+
+    /// Configure the delays that are inserted around transfers and frames
+    ///
+    /// All delays are given in SPI clock cycles, and must be 15 or less.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any of the delays is larger than 15.
+    pub fn set_delays(&mut self, delays: Delays) {
+        assert!(delays.pre <= 15);
+        assert!(delays.post <= 15);
+        assert!(delays.frame <= 15);
+        assert!(delays.transfer <= 15);
+
+        self.spi.dly.write(|w| unsafe {
+            w.pre_delay().bits(delays.pre);
+            w.post_delay().bits(delays.post);
+            w.frame_delay().bits(delays.frame);
+            w.transfer_delay().bits(delays.transfer)
+        });
+    }
+
+    /// Read all status flags at once, and clear the events among them
+    ///
+    /// Reads the STAT register in a single volatile read, which makes this
+    /// the right way to find out what happened in an interrupt handler. The
+    /// flags that are cleared by software ([`Events::RX_OVERRUN`],
+    /// [`Events::TX_UNDERRUN`], [`Events::SLAVE_SELECT_ASSERTED`], and
+    /// [`Events::SLAVE_SELECT_DEASSERTED`]) are then cleared, if they were set.
+    /// Events that occur after the read are not lost, as only flags that have
+    /// been read are cleared.
+    ///
+    /// The other flags reflect the current state of the peripheral, and are
+    /// returned as they are.
+    ///
+    /// [`Events::RX_OVERRUN`]: struct.Events.html#associatedconstant.RX_OVERRUN
+    /// [`Events::TX_UNDERRUN`]: struct.Events.html#associatedconstant.TX_UNDERRUN
+    /// [`Events::SLAVE_SELECT_ASSERTED`]: struct.Events.html#associatedconstant.SLAVE_SELECT_ASSERTED
+    /// [`Events::SLAVE_SELECT_DEASSERTED`]: struct.Events.html#associatedconstant.SLAVE_SELECT_DEASSERTED
+    pub fn read_and_clear_events(&mut self) -> Events {
+        let events = Events::from_bits_truncate(self.spi.stat.read().bits());
+
+        let clear = events & Events::CLEARABLE;
+        if !clear.is_empty() {
+            self.spi.stat.write(|w| unsafe { w.bits(clear.bits()) });
+        }
+
+        events
+    }
+
+    /// Save the configuration of this SPI instance
+    ///
+    /// Blocks until the last word has been sent completely, then reads the
+    /// configuration, clock divider, delay, and interrupt enable registers. The
+    /// returned [`Snapshot`] can be passed to [`resume`], to restore the
+    /// configuration after the peripheral's state has been lost, for example
+    /// because it was reset or powered down while the microcontroller was in
+    /// a low-power mode.
+    ///
+    /// [`Snapshot`]: struct.Snapshot.html
+    /// [`resume`]: #method.resume
+    pub fn suspend(&mut self) -> Snapshot {
+        self.wait_idle();
+
+        Snapshot {
+            cfg: self.spi.cfg.read().bits(),
+            dly: self.spi.dly.read().bits(),
+            div: self.spi.div.read().bits(),
+            txctl: self.spi.txctl.read().bits(),
+            intenset: self.spi.intenset.read().bits(),
+        }
+    }
+
+    /// Restore a configuration that was saved using [`suspend`]
+    ///
+    /// Enables the peripheral clock, in case it has been disabled, and writes
+    /// the saved configuration back, while the SPI instance is disabled. It is
+    /// enabled again afterwards.
+    ///
+    /// [`suspend`]: #method.suspend
+    pub fn resume(&mut self, snapshot: &Snapshot, syscon: &mut syscon::Handle) {
+        syscon.enable_clock(&self.spi);
+
+        self.spi.cfg.modify(|_, w| w.enable().disabled());
+
+        // Sound, as all values have been read from the same registers.
+        unsafe {
+            self.spi.dly.write(|w| w.bits(snapshot.dly));
+            self.spi.div.write(|w| w.bits(snapshot.div));
+            self.spi.txctl.write(|w| w.bits(snapshot.txctl));
+            self.spi.intenclr.write(|w| w.bits(!snapshot.intenset));
+            self.spi.intenset.write(|w| w.bits(snapshot.intenset));
+            self.spi.cfg.write(|w| w.bits(snapshot.cfg));
+        }
+    }
+
+    fn tx_ready(&mut self) -> nb::Result<(), Error> {
+        let stat = self.spi.stat.read();
+
+        // The PAC doesn't provide a read accessor for TXUR, so we have to check
+        // the bit manually.
+        if stat.bits() & STAT_TXUR != 0 {
+            // Clear the flag, so the next send can succeed.
+            self.spi.stat.write(|w| w.txur().set_bit());
+            return Err(nb::Error::Other(Error::TxUnderrun));
+        }
+
+        if stat.txrdy().bit_is_clear() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        Ok(())
+    }
+
+    /// Wait until the last word has been sent completely
+    fn wait_idle(&mut self) {
+        while self.spi.stat.read().mstidle().bit_is_clear() {}
+    }
+
+    /// Restore the default transmit control, after `send_with_control`
+    fn reset_tx_control(&mut self) {
+        self.spi.txctl.write(|w| {
+            // 8 bit length
+            unsafe { w.len().bits(7) }
+        });
+    }
+}
+
+/// Implemented for types that select an SPI mode
+///
+/// Passed to [`SPI::enable`] and its variants. Implemented for
+/// [`embedded_hal::spi::Mode`], which selects the mode at runtime, and for
+/// [`Mode0`], [`Mode1`], [`Mode2`], and [`Mode3`], which select it at the type
+/// level.
+///
+/// A driver that only works with a specific mode can require it like this:
+///
+/// ``` no_run
+/// use lpc8xx_hal::{init_state, spi};
+///
+/// pub struct Display<I, Pins> {
+///     spi: spi::SPI<I, init_state::Enabled<Pins>, spi::Mode3>,
+/// }
+///
+/// impl<I, Pins> Display<I, Pins> {
+///     pub fn new(
+///         spi: spi::SPI<I, init_state::Enabled<Pins>, spi::Mode3>,
+///     ) -> Self {
+///         Self { spi }
+///     }
+/// }
+/// ```
+///
+/// Passing an instance that was enabled with any other mode to
+/// `Display::new` won't compile.
+///
+/// [`SPI::enable`]: struct.SPI.html#method.enable
+/// [`embedded_hal::spi::Mode`]: https://docs.rs/embedded-hal/0.2/embedded_hal/spi/struct.Mode.html
+/// [`Mode0`]: struct.Mode0.html
+/// [`Mode1`]: struct.Mode1.html
+/// [`Mode2`]: struct.Mode2.html
+/// [`Mode3`]: struct.Mode3.html
+pub trait ModeTrait {
+    /// The mode that is selected
+    fn mode(&self) -> Mode;
 }
 
-impl<I, State> SPI<I, State> {
+impl ModeTrait for Mode {
+    fn mode(&self) -> Mode {
+        *self
+    }
+}
+
+macro_rules! modes {
+    ($($name:ident, $constant:ident, $doc:expr;)*) => {
+        $(
+            #[doc = $doc]
+            ///
+            /// Selects the same mode as the corresponding `MODE_*` constant in
+            /// `embedded_hal::spi`, but at the type level. Please refer to
+            /// [`ModeTrait`] for details.
+            ///
+            /// [`ModeTrait`]: trait.ModeTrait.html
+            #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+            pub struct $name;
+
+            impl ModeTrait for $name {
+                fn mode(&self) -> Mode {
+                    embedded_hal::spi::$constant
+                }
+            }
+        )*
+    };
+}
+
+modes!(
+    Mode0, MODE_0, "SPI mode 0: Clock idles low, capture on first edge";
+    Mode1, MODE_1, "SPI mode 1: Clock idles low, capture on second edge";
+    Mode2, MODE_2, "SPI mode 2: Clock idles high, capture on first edge";
+    Mode3, MODE_3, "SPI mode 3: Clock idles high, capture on second edge";
+);
+
+/// Controls how a word is framed
+///
+/// Passed to [`SPI::send_with_control`]. The default value sends the word
+/// like [`FullDuplex::send`] does.
+///
+/// [`SPI::send_with_control`]: struct.SPI.html#method.send_with_control
+/// [`FullDuplex::send`]: struct.SPI.html#impl-FullDuplex%3Cu8%3E
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TxControl {
+    /// Deassert slave select after this word
+    ///
+    /// This ends the transfer. The post-transfer delay and the delay between
+    /// transfers (see [`Delays`]) are inserted after the word. Slave select is
+    /// asserted again, once the next word is sent.
+    ///
+    /// [`Delays`]: struct.Delays.html
+    pub end_of_transfer: bool,
+
+    /// Insert the frame delay after this word
+    ///
+    /// Slave select stays asserted. See [`Delays::frame`].
+    ///
+    /// [`Delays::frame`]: struct.Delays.html#structfield.frame
+    pub end_of_frame: bool,
+
+    /// Discard the word that is received while this word is sent
+    ///
+    /// The received word is not written to the receive buffer, so it doesn't
+    /// need to be read, and can't cause an overrun.
+    pub rx_ignore: bool,
+}
+
+/// Delays that are inserted around transfers and frames
+///
+/// Passed to [`SPI::set_delays`]. All delays are given in SPI clock cycles,
+/// and must be 15 or less. All of them are zero after reset.
+///
+/// [`SPI::set_delays`]: struct.SPI.html#method.set_delays
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Delays {
+    /// Delay between slave select being asserted and the first data bit
+    pub pre: u8,
+
+    /// Delay between the last data bit and slave select being deasserted
+    pub post: u8,
+
+    /// Delay after a word that has been sent with `end_of_frame` set
+    pub frame: u8,
+
+    /// Minimum time that slave select stays deasserted between transfers
+    pub transfer: u8,
+}
+
+/// The configuration of an SPI instance
+///
+/// Returned by [`SPI::suspend`], and passed to [`SPI::resume`].
+///
+/// [`SPI::suspend`]: struct.SPI.html#method.suspend
+/// [`SPI::resume`]: struct.SPI.html#method.resume
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+    cfg: u32,
+    dly: u32,
+    div: u32,
+    txctl: u32,
+    intenset: u32,
+}
+
+flags! {
+    /// The flags in the STAT register of an SPI instance
+    ///
+    /// Returned by [`SPI::read_and_clear_events`].
+    ///
+    /// [`SPI::read_and_clear_events`]: struct.SPI.html#method.read_and_clear_events
+    pub struct Events {
+        /// Received data is available (RXRDY)
+        const RX_READY = 0;
+
+        /// Data can be written to the transmit buffer (TXRDY)
+        const TX_READY = 1;
+
+        /// Data was received while the receive buffer was full (RXOV)
+        const RX_OVERRUN = 2;
+
+        /// Data needed to be sent while the transmit buffer was empty (TXUR)
+        const TX_UNDERRUN = 3;
+
+        /// A slave select was asserted (SSA)
+        const SLAVE_SELECT_ASSERTED = 4;
+
+        /// A slave select was deasserted (SSD)
+        const SLAVE_SELECT_DEASSERTED = 5;
+
+        /// The peripheral is stalled, waiting for data (STALLED)
+        const STALLED = 6;
+
+        /// A transfer is being ended (ENDTRANSFER)
+        const END_TRANSFER = 7;
+
+        /// The master function is idle (MSTIDLE)
+        const MASTER_IDLE = 8;
+    }
+}
+
+impl Events {
+    /// The flags that are cleared by writing a 1 to them
+    const CLEARABLE: Self = Self::from_bits_truncate(0x3c);
+}
+
+impl<I, State, M> SPI<I, State, M> {
     /// Return the raw peripheral
     ///
     /// This method serves as an escape hatch from the HAL API. It returns the
@@ -218,11 +1005,23 @@ impl<I, State> SPI<I, State> {
     }
 }
 
-impl<I: Instance> FullDuplex<u8> for SPI<I> {
-    type Error = ();
+impl<I: Instance, Pins, M> FullDuplex<u8>
+    for SPI<I, init_state::Enabled<Pins>, M>
+{
+    type Error = Error;
 
     fn read(&mut self) -> nb::Result<u8, Self::Error> {
-        if self.spi.stat.read().rxrdy().bit_is_set() {
+        let stat = self.spi.stat.read();
+
+        // The PAC doesn't provide a read accessor for RXOV, so we have to check
+        // the bit manually.
+        if stat.bits() & STAT_RXOV != 0 {
+            // Clear the flag, so the next read can succeed.
+            self.spi.stat.write(|w| w.rxov().set_bit());
+            return Err(nb::Error::Other(Error::RxOverrun));
+        }
+
+        if stat.rxrdy().bit_is_set() {
             Ok(self.spi.rxdat.read().rxdat().bits() as u8)
         } else {
             Err(nb::Error::WouldBlock)
@@ -230,17 +1029,49 @@ impl<I: Instance> FullDuplex<u8> for SPI<I> {
     }
 
     fn send(&mut self, word: u8) -> nb::Result<(), Self::Error> {
-        if self.spi.stat.read().txrdy().bit_is_set() {
-            self.spi
-                .txdat
-                .write(|w| unsafe { w.data().bits(word as u16) });
-            Ok(())
-        } else {
-            Err(nb::Error::WouldBlock)
-        }
+        // Send with the default control, in case `send_with_control` left any
+        // flags latched in TXCTL.
+        self.send_with_control(word, TxControl::default())
     }
 }
 
+/// The SWM functions that are kept by an enabled [`SPI`] instance
+///
+/// [`SPI`]: struct.SPI.html
+pub type SwmFunctions<I, SckPin, MosiPin, MisoPin> = (
+    swm::Function<<I as Instance>::Sck, swm::state::Assigned<SckPin>>,
+    swm::Function<<I as Instance>::Mosi, swm::state::Assigned<MosiPin>>,
+    swm::Function<<I as Instance>::Miso, swm::state::Assigned<MisoPin>>,
+);
+
+/// The SWM functions that are kept by a write-only [`SPI`] instance
+///
+/// See [`SPI::enable_write_only`].
+///
+/// [`SPI`]: struct.SPI.html
+/// [`SPI::enable_write_only`]: struct.SPI.html#method.enable_write_only
+pub type WriteOnlyFunctions<I, SckPin, MosiPin> = (
+    swm::Function<<I as Instance>::Sck, swm::state::Assigned<SckPin>>,
+    swm::Function<<I as Instance>::Mosi, swm::state::Assigned<MosiPin>>,
+);
+
+/// The SWM functions that are kept by a read-only [`SPI`] instance
+///
+/// See [`SPI::enable_read_only`].
+///
+/// [`SPI`]: struct.SPI.html
+/// [`SPI::enable_read_only`]: struct.SPI.html#method.enable_read_only
+pub type ReadOnlyFunctions<I, SckPin, MisoPin> = (
+    swm::Function<<I as Instance>::Sck, swm::state::Assigned<SckPin>>,
+    swm::Function<<I as Instance>::Miso, swm::state::Assigned<MisoPin>>,
+);
+
+/// Receiver overrun flag in the STAT register
+const STAT_RXOV: u32 = 0x1 << 2;
+
+/// Transmitter underrun flag in the STAT register
+const STAT_TXUR: u32 = 0x1 << 3;
+
 /// Internal trait for SPI peripherals
 ///
 /// This trait is an internal implementation detail and should neither be
@@ -251,6 +1082,9 @@ pub trait Instance:
     + syscon::ClockControl
     + syscon::ResetControl
 {
+    /// The interrupt that is triggered for this SPI peripheral
+    const INTERRUPT: Interrupt;
+
     /// The movable function that needs to be assigned to this SPI's SCK pin
     type Sck;
 
@@ -262,20 +1096,27 @@ pub trait Instance:
 }
 
 impl Instance for pac::SPI0 {
+    const INTERRUPT: Interrupt = Interrupt::SPI0;
+
     type Sck = swm::SPI0_SCK;
     type Mosi = swm::SPI0_MOSI;
     type Miso = swm::SPI0_MISO;
 }
 
 impl Instance for pac::SPI1 {
+    const INTERRUPT: Interrupt = Interrupt::SPI1;
+
     type Sck = swm::SPI1_SCK;
     type Mosi = swm::SPI1_MOSI;
     type Miso = swm::SPI1_MISO;
 }
 
-impl<I: Instance> embedded_hal::blocking::spi::transfer::Default<u8>
-    for SPI<I>
+impl<I: Instance, Pins, M> embedded_hal::blocking::spi::transfer::Default<u8>
+    for SPI<I, init_state::Enabled<Pins>, M>
 {
 }
 
-impl<I: Instance> embedded_hal::blocking::spi::write::Default<u8> for SPI<I> {}
+impl<I: Instance, Pins, M> embedded_hal::blocking::spi::write::Default<u8>
+    for SPI<I, init_state::Enabled<Pins>, M>
+{
+}
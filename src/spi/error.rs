@@ -0,0 +1,12 @@
+/// An SPI error
+///
+/// Both of these errors can only occur in slave mode, according to the
+/// description of the STAT register in the user manual.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Data was received while the receive buffer was still in use
+    RxOverrun,
+
+    /// Data needed to be transmitted, but the transmit buffer was empty
+    TxUnderrun,
+}
@@ -0,0 +1,533 @@
+//! Driver for 25-series SPI NOR flash memories
+//!
+//! This module is only available, if the `spi-flash` feature is enabled.
+//!
+//! Most serial flash memories, like the Winbond W25Q or Macronix MX25 series,
+//! share the same basic JEDEC command set. [`SpiFlash`] implements that
+//! command set on top of any SPI that implements the blocking `Transfer` and
+//! `Write` traits, like [`SPI`], and a GPIO pin that is used as chip select.
+//!
+//! Only 3-byte addresses are supported, which limits the usable part of the
+//! memory to 16 MiB.
+//!
+//! If the `embedded-storage` feature is enabled, [`SpiFlash`] implements the
+//! NOR flash traits from the `embedded-storage` crate.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     gpio,
+//!     spi_flash::{self, SpiFlash},
+//!     syscon::clock_source::SpiClock,
+//!     Peripherals,
+//! };
+//!
+//! let mut p = Peripherals::take().unwrap();
+//!
+//! let mut swm = p.SWM.split();
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! #[cfg(feature = "82x")]
+//! let gpio = p.GPIO;
+//! #[cfg(feature = "845")]
+//! let gpio = p.GPIO.enable(&mut syscon.handle);
+//!
+//! let (spi0_sck, _) = swm.movable_functions.spi0_sck.assign(
+//!     p.pins.pio0_13.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//! let (spi0_mosi, _) = swm
+//!     .movable_functions
+//!     .spi0_mosi
+//!     .assign(p.pins.pio0_14.into_swm_pin(), &mut swm_handle);
+//! let (spi0_miso, _) = swm
+//!     .movable_functions
+//!     .spi0_miso
+//!     .assign(p.pins.pio0_15.into_swm_pin(), &mut swm_handle);
+//!
+//! #[cfg(feature = "82x")]
+//! let spi_clock = SpiClock::new(0);
+//! #[cfg(feature = "845")]
+//! let spi_clock = SpiClock::new(&syscon.iosc, 0);
+//!
+//! let spi = p.SPI0.enable(
+//!     &spi_clock,
+//!     &mut syscon.handle,
+//!     embedded_hal::spi::MODE_0,
+//!     spi0_sck,
+//!     spi0_mosi,
+//!     spi0_miso,
+//! );
+//! let cs = p
+//!     .pins
+//!     .pio0_12
+//!     .into_output_pin(gpio.tokens.pio0_12, gpio::Level::High);
+//!
+//! // A W25Q16: 2 MiB
+//! let mut flash = SpiFlash::new(spi, cs, 2 * 1024 * 1024);
+//!
+//! let id = flash.read_jedec_id().unwrap();
+//! assert_eq!(id.manufacturer, 0xef);
+//!
+//! flash.erase(0, spi_flash::SECTOR_SIZE).unwrap();
+//! flash.write(0, b"Hello, flash!").unwrap();
+//!
+//! let mut buffer = [0; 13];
+//! flash.read(0, &mut buffer).unwrap();
+//! ```
+//!
+//! [`SpiFlash`]: struct.SpiFlash.html
+//! [`SPI`]: ../spi/struct.SPI.html
+
+#[cfg(feature = "embedded-storage")]
+use core::fmt::Debug;
+
+use embedded_hal::{
+    blocking::spi::{Transfer, Write},
+    digital::v2::OutputPin,
+};
+#[cfg(feature = "embedded-storage")]
+use embedded_storage::nor_flash;
+
+use crate::activity;
+
+/// The size of a page, in bytes
+///
+/// A single program operation can't cross a page boundary.
+pub const PAGE_SIZE: u32 = 256;
+
+/// The size of a sector, in bytes
+///
+/// A sector is the smallest unit that can be erased.
+pub const SECTOR_SIZE: u32 = 4096;
+
+const WRITE_ENABLE: u8 = 0x06;
+const READ_STATUS: u8 = 0x05;
+const READ_DATA: u8 = 0x03;
+const PAGE_PROGRAM: u8 = 0x02;
+const SECTOR_ERASE: u8 = 0x20;
+const READ_JEDEC_ID: u8 = 0x9f;
+
+/// The write-in-progress bit of the status register
+const STATUS_BUSY: u8 = 0x01;
+
+/// A 25-series SPI NOR flash memory
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct SpiFlash<SPI, CS> {
+    spi: SPI,
+    cs: CS,
+    capacity: u32,
+}
+
+impl<SPI, CS, E> SpiFlash<SPI, CS>
+where
+    SPI: Transfer<u8, Error = E> + Write<u8, Error = E>,
+    CS: OutputPin,
+{
+    /// Create a new instance of `SpiFlash`
+    ///
+    /// `cs` is the chip select pin, which must already be high. `capacity` is
+    /// the size of the memory, in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `capacity` is larger than 16 MiB, which can't be addressed
+    /// using 3-byte addresses.
+    pub fn new(spi: SPI, cs: CS, capacity: u32) -> Self {
+        assert!(capacity <= 1 << 24);
+
+        Self { spi, cs, capacity }
+    }
+
+    /// The size of the memory, in bytes
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Read the JEDEC ID of the memory
+    ///
+    /// Can be used to check whether the memory is connected, and which one it
+    /// is.
+    pub fn read_jedec_id(&mut self) -> Result<JedecId, Error<E, CS::Error>> {
+        let mut buffer = [READ_JEDEC_ID, 0, 0, 0];
+        self.transaction(|spi| {
+            spi.transfer(&mut buffer)?;
+            Ok(())
+        })?;
+
+        Ok(JedecId {
+            manufacturer: buffer[1],
+            memory_type: buffer[2],
+            capacity: buffer[3],
+        })
+    }
+
+    /// Read from the memory, starting at `address`
+    ///
+    /// Fills all of `buffer`.
+    pub fn read(
+        &mut self,
+        address: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), Error<E, CS::Error>> {
+        self.check_bounds(address, buffer.len())?;
+
+        let command = command(READ_DATA, address);
+        self.transaction(|spi| {
+            spi.write(&command)?;
+            spi.transfer(buffer)?;
+            Ok(())
+        })
+    }
+
+    /// Write to the memory, starting at `address`
+    ///
+    /// The data is split into pages, and each page is programmed separately.
+    /// Blocks until all pages have been programmed.
+    ///
+    /// Programming can only change bits from 1 to 0. The memory must have
+    /// been erased, for the data to be written as is.
+    pub fn write(
+        &mut self,
+        address: u32,
+        data: &[u8],
+    ) -> Result<(), Error<E, CS::Error>> {
+        self.check_bounds(address, data.len())?;
+
+        let mut address = address;
+        let mut data = data;
+
+        while !data.is_empty() {
+            let offset = address % PAGE_SIZE;
+            let len = data.len().min((PAGE_SIZE - offset) as usize);
+            let (page, rest) = data.split_at(len);
+
+            self.page_program(address, page)?;
+            self.wait_while_busy()?;
+
+            address += len as u32;
+            data = rest;
+        }
+
+        Ok(())
+    }
+
+    /// Program a single page, without waiting for it to complete
+    ///
+    /// Returns [`Error::NotAligned`], if `data` doesn't fit into the page
+    /// that `address` is in. Use [`write`] to write data of any length.
+    ///
+    /// [`Error::NotAligned`]: enum.Error.html#variant.NotAligned
+    /// [`write`]: #method.write
+    pub fn page_program(
+        &mut self,
+        address: u32,
+        data: &[u8],
+    ) -> Result<(), Error<E, CS::Error>> {
+        self.check_bounds(address, data.len())?;
+        if data.len() > (PAGE_SIZE - address % PAGE_SIZE) as usize {
+            return Err(Error::NotAligned);
+        }
+
+        self.write_enable()?;
+
+        let command = command(PAGE_PROGRAM, address);
+        self.transaction(|spi| {
+            spi.write(&command)?;
+            spi.write(data)
+        })
+    }
+
+    /// Erase the sectors from `from` up to, but not including, `to`
+    ///
+    /// Both addresses must be multiples of [`SECTOR_SIZE`]. Blocks until all
+    /// sectors have been erased. Erased memory reads as `0xff`.
+    ///
+    /// [`SECTOR_SIZE`]: constant.SECTOR_SIZE.html
+    pub fn erase(
+        &mut self,
+        from: u32,
+        to: u32,
+    ) -> Result<(), Error<E, CS::Error>> {
+        if from > to || to > self.capacity {
+            return Err(Error::OutOfBounds);
+        }
+        if from / SECTOR_SIZE * SECTOR_SIZE != from
+            || to / SECTOR_SIZE * SECTOR_SIZE != to
+        {
+            return Err(Error::NotAligned);
+        }
+
+        for address in (from..to).step_by(SECTOR_SIZE as usize) {
+            self.erase_sector(address)?;
+            self.wait_while_busy()?;
+        }
+
+        Ok(())
+    }
+
+    /// Erase the sector that contains `address`, without waiting for it to
+    /// complete
+    pub fn erase_sector(
+        &mut self,
+        address: u32,
+    ) -> Result<(), Error<E, CS::Error>> {
+        self.check_bounds(address, 1)?;
+
+        self.write_enable()?;
+
+        let command = command(SECTOR_ERASE, address);
+        self.transaction(|spi| spi.write(&command))
+    }
+
+    /// Indicates whether the memory is busy programming or erasing
+    pub fn is_busy(&mut self) -> Result<bool, Error<E, CS::Error>> {
+        let mut buffer = [READ_STATUS, 0];
+        self.transaction(|spi| {
+            spi.transfer(&mut buffer)?;
+            Ok(())
+        })?;
+
+        Ok(buffer[1] & STATUS_BUSY != 0)
+    }
+
+    /// Block until the memory is no longer busy
+    ///
+    /// Erasing a sector can take several hundred milliseconds.
+    pub fn wait_while_busy(&mut self) -> Result<(), Error<E, CS::Error>> {
+        while self.is_busy()? {
+            activity::notify();
+        }
+
+        Ok(())
+    }
+
+    /// Return the SPI and the chip select pin
+    pub fn free(self) -> (SPI, CS) {
+        (self.spi, self.cs)
+    }
+
+    fn write_enable(&mut self) -> Result<(), Error<E, CS::Error>> {
+        self.transaction(|spi| spi.write(&[WRITE_ENABLE]))
+    }
+
+    fn check_bounds(
+        &self,
+        address: u32,
+        len: usize,
+    ) -> Result<(), Error<E, CS::Error>> {
+        let end = address as u64 + len as u64;
+        if end > self.capacity as u64 {
+            return Err(Error::OutOfBounds);
+        }
+
+        Ok(())
+    }
+
+    /// Run `f` with the chip selected
+    ///
+    /// Deselects the chip afterwards, even if `f` fails.
+    fn transaction<R>(
+        &mut self,
+        f: impl FnOnce(&mut SPI) -> Result<R, E>,
+    ) -> Result<R, Error<E, CS::Error>> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        let result = f(&mut self.spi);
+        self.cs.set_high().map_err(Error::Pin)?;
+
+        result.map_err(Error::Spi)
+    }
+}
+
+/// A command byte, followed by a 3-byte address
+fn command(command: u8, address: u32) -> [u8; 4] {
+    let [_, a2, a1, a0] = address.to_be_bytes();
+    [command, a2, a1, a0]
+}
+
+/// The JEDEC ID of a memory
+///
+/// Returned by [`SpiFlash::read_jedec_id`].
+///
+/// [`SpiFlash::read_jedec_id`]: struct.SpiFlash.html#method.read_jedec_id
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct JedecId {
+    /// The manufacturer ID, like `0xef` for Winbond
+    pub manufacturer: u8,
+
+    /// The memory type, as defined by the manufacturer
+    pub memory_type: u8,
+
+    /// The capacity, usually as the base-2 logarithm of the size in bytes
+    pub capacity: u8,
+}
+
+/// An error that can occur while accessing the memory
+#[derive(Debug)]
+pub enum Error<S, P> {
+    /// The access would go beyond the end of the memory
+    OutOfBounds,
+
+    /// The address or length is not aligned as required
+    NotAligned,
+
+    /// The SPI reported an error
+    Spi(S),
+
+    /// The chip select pin reported an error
+    Pin(P),
+}
+
+#[cfg(feature = "embedded-storage")]
+impl<S, P> nor_flash::NorFlashError for Error<S, P>
+where
+    S: Debug,
+    P: Debug,
+{
+    fn kind(&self) -> nor_flash::NorFlashErrorKind {
+        match self {
+            Error::OutOfBounds => nor_flash::NorFlashErrorKind::OutOfBounds,
+            Error::NotAligned => nor_flash::NorFlashErrorKind::NotAligned,
+            _ => nor_flash::NorFlashErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(feature = "embedded-storage")]
+impl<SPI, CS, E> nor_flash::ErrorType for SpiFlash<SPI, CS>
+where
+    SPI: Transfer<u8, Error = E> + Write<u8, Error = E>,
+    CS: OutputPin,
+    E: Debug,
+    CS::Error: Debug,
+{
+    type Error = Error<E, CS::Error>;
+}
+
+#[cfg(feature = "embedded-storage")]
+impl<SPI, CS, E> nor_flash::ReadNorFlash for SpiFlash<SPI, CS>
+where
+    SPI: Transfer<u8, Error = E> + Write<u8, Error = E>,
+    CS: OutputPin,
+    E: Debug,
+    CS::Error: Debug,
+{
+    const READ_SIZE: usize = 1;
+
+    fn read(
+        &mut self,
+        offset: u32,
+        bytes: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        SpiFlash::read(self, offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity as usize
+    }
+}
+
+#[cfg(feature = "embedded-storage")]
+impl<SPI, CS, E> nor_flash::NorFlash for SpiFlash<SPI, CS>
+where
+    SPI: Transfer<u8, Error = E> + Write<u8, Error = E>,
+    CS: OutputPin,
+    E: Debug,
+    CS::Error: Debug,
+{
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = SECTOR_SIZE as usize;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        SpiFlash::erase(self, from, to)
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        SpiFlash::write(self, offset, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        gpio::Level,
+        mock::{PinMock, PinTransaction, SpiMock, SpiTransaction},
+    };
+
+    use super::SpiFlash;
+
+    /// The chip select transactions for `n` SPI transactions
+    fn selects(n: usize) -> Vec<PinTransaction> {
+        [
+            PinTransaction::Set(Level::Low),
+            PinTransaction::Set(Level::High),
+        ]
+        .repeat(n)
+    }
+
+    #[test]
+    fn write_should_split_data_at_page_boundaries() {
+        let spi = SpiMock::new(&[
+            // First page: 6 bytes, up to the page boundary
+            SpiTransaction::Write(&[0x06]),
+            SpiTransaction::Write(&[0x02, 0x00, 0x00, 0xfa]),
+            SpiTransaction::Write(&[0, 1, 2, 3, 4, 5]),
+            SpiTransaction::Transfer(&[0x05, 0x00], &[0x00, 0x00]),
+            // Second page: the remaining 4 bytes
+            SpiTransaction::Write(&[0x06]),
+            SpiTransaction::Write(&[0x02, 0x00, 0x01, 0x00]),
+            SpiTransaction::Write(&[6, 7, 8, 9]),
+            SpiTransaction::Transfer(&[0x05, 0x00], &[0x00, 0x00]),
+        ]);
+        let selects = selects(6);
+        let cs = PinMock::new(&selects);
+
+        let mut flash = SpiFlash::new(spi, cs, 2 * 1024 * 1024);
+        flash.write(250, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+
+        let (spi, cs) = flash.free();
+        spi.done();
+        cs.done();
+    }
+
+    #[test]
+    fn erase_should_erase_each_sector() {
+        let spi = SpiMock::new(&[
+            SpiTransaction::Write(&[0x06]),
+            SpiTransaction::Write(&[0x20, 0x00, 0x10, 0x00]),
+            // Busy once, then done
+            SpiTransaction::Transfer(&[0x05, 0x00], &[0x00, 0x01]),
+            SpiTransaction::Transfer(&[0x05, 0x00], &[0x00, 0x00]),
+            SpiTransaction::Write(&[0x06]),
+            SpiTransaction::Write(&[0x20, 0x00, 0x20, 0x00]),
+            SpiTransaction::Transfer(&[0x05, 0x00], &[0x00, 0x00]),
+        ]);
+        let selects = selects(7);
+        let cs = PinMock::new(&selects);
+
+        let mut flash = SpiFlash::new(spi, cs, 2 * 1024 * 1024);
+        flash.erase(0x1000, 0x3000).unwrap();
+
+        let (spi, cs) = flash.free();
+        spi.done();
+        cs.done();
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_should_reject_capacity_beyond_3_byte_addresses() {
+        let spi = SpiMock::new(&[]);
+        let cs = PinMock::new(&[]);
+
+        SpiFlash::new(spi, cs, 32 * 1024 * 1024);
+    }
+}
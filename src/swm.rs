@@ -16,8 +16,10 @@ mod fixed_functions;
 mod function_kind;
 mod functions;
 mod handle;
+mod macros;
 mod movable_functions;
 mod peripheral;
+mod swd;
 
 pub use self::{
     fixed_functions::*,
@@ -26,4 +28,5 @@ pub use self::{
     handle::Handle,
     movable_functions::*,
     peripheral::{Parts, SWM},
+    swd::{ReclaimedPins, ReclaimedSwd, Swd},
 };
@@ -113,3 +113,18 @@ where
         }
     }
 }
+
+impl<T, F> UnassignFunction<F, Analog> for Pin<T, pins::state::Analog>
+where
+    T: pins::Trait,
+    F: FunctionTrait<T, Kind = Analog>,
+{
+    type Unassigned = Pin<T, pins::state::Swm<(), ()>>;
+
+    fn unassign(self) -> Self::Unassigned {
+        Pin {
+            ty: self.ty,
+            _state: pins::state::Swm::new(),
+        }
+    }
+}
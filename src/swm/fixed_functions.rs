@@ -9,6 +9,7 @@ use super::{
 
 macro_rules! fixed_functions {
     ($(
+        $(#[$attr:meta])*
         $type:ident,
         $kind:ident,
         $register:ident,
@@ -23,13 +24,19 @@ macro_rules! fixed_functions {
         /// [`swm::Parts`]: struct.Parts.html
         #[allow(missing_docs)]
         pub struct FixedFunctions {
-            $(pub $field: Function<$type, $default_state>,)*
+            $(
+                $(#[$attr])*
+                pub $field: Function<$type, $default_state>,
+            )*
         }
 
         impl FixedFunctions {
             pub(crate) fn new() -> Self {
                 FixedFunctions {
-                    $($field: Function::new($type(())),)*
+                    $(
+                        $(#[$attr])*
+                        $field: Function::new($type(())),
+                    )*
                 }
             }
         }
@@ -41,9 +48,11 @@ macro_rules! fixed_functions {
             /// Fixed functions can be accessed through [`FixedFunctions`].
             ///
             /// [`FixedFunctions`]: struct.FixedFunctions.html
+            $(#[$attr])*
             #[allow(non_camel_case_types)]
             pub struct $type(());
 
+            $(#[$attr])*
             impl FunctionTrait<pins::$pin> for $type {
                 type Kind = $kind;
 
@@ -63,17 +72,17 @@ macro_rules! fixed_functions {
 
 #[cfg(feature = "82x")]
 fixed_functions!(
-    ACMP_I1 , Input , pinenable0, acmp_i1 , PIO0_0 , Unassigned;
-    ACMP_I2 , Input , pinenable0, acmp_i2 , PIO0_1 , Unassigned;
-    ACMP_I3 , Input , pinenable0, acmp_i3 , PIO0_14, Unassigned;
-    ACMP_I4 , Input , pinenable0, acmp_i4 , PIO0_23, Unassigned;
+    ACMP_I1 , Analog, pinenable0, acmp_i1 , PIO0_0 , Unassigned;
+    ACMP_I2 , Analog, pinenable0, acmp_i2 , PIO0_1 , Unassigned;
+    ACMP_I3 , Analog, pinenable0, acmp_i3 , PIO0_14, Unassigned;
+    ACMP_I4 , Analog, pinenable0, acmp_i4 , PIO0_23, Unassigned;
     SWCLK   , Output, pinenable0, swclk   , PIO0_3 , Assigned<pins::PIO0_3>;
     SWDIO   , Output, pinenable0, swdio   , PIO0_2 , Assigned<pins::PIO0_2>;
     XTALIN  , Input , pinenable0, xtalin  , PIO0_8 , Unassigned;
     XTALOUT , Output, pinenable0, xtalout , PIO0_9 , Unassigned;
     RESETN  , Input , pinenable0, resetn  , PIO0_5 , Assigned<pins::PIO0_5>;
     CLKIN   , Input , pinenable0, clkin   , PIO0_1 , Unassigned;
-    VDDCMP  , Input , pinenable0, vddcmp  , PIO0_6 , Unassigned;
+    VDDCMP  , Analog, pinenable0, vddcmp  , PIO0_6 , Unassigned;
     I2C0_SDA, Output, pinenable0, i2c0_sda, PIO0_11, Unassigned;
     I2C0_SCL, Output, pinenable0, i2c0_scl, PIO0_10, Unassigned;
     ADC_0   , Analog, pinenable0, adc_0   , PIO0_7 , Unassigned;
@@ -92,17 +101,17 @@ fixed_functions!(
 
 #[cfg(feature = "845")]
 fixed_functions!(
-    ACMP_I1 , Input , pinenable0, acmp_i1 , PIO0_0 , Unassigned;
-    ACMP_I2 , Input , pinenable0, acmp_i2 , PIO0_1 , Unassigned;
-    ACMP_I3 , Input , pinenable0, acmp_i3 , PIO0_14, Unassigned;
-    ACMP_I4 , Input , pinenable0, acmp_i4 , PIO0_23, Unassigned;
+    ACMP_I1 , Analog, pinenable0, acmp_i1 , PIO0_0 , Unassigned;
+    ACMP_I2 , Analog, pinenable0, acmp_i2 , PIO0_1 , Unassigned;
+    ACMP_I3 , Analog, pinenable0, acmp_i3 , PIO0_14, Unassigned;
+    ACMP_I4 , Analog, pinenable0, acmp_i4 , PIO0_23, Unassigned;
     SWCLK   , Output, pinenable0, swclk   , PIO0_3 , Assigned<pins::PIO0_3>;
     SWDIO   , Output, pinenable0, swdio   , PIO0_2 , Assigned<pins::PIO0_2>;
     XTALIN  , Input , pinenable0, xtalin  , PIO0_8 , Unassigned;
     XTALOUT , Output, pinenable0, xtalout , PIO0_9 , Unassigned;
     RESETN  , Input , pinenable0, resetn  , PIO0_5 , Assigned<pins::PIO0_5>;
     CLKIN   , Input , pinenable0, clkin   , PIO0_1 , Unassigned;
-    VDDCMP  , Input , pinenable0, vddcmp  , PIO0_6 , Unassigned;
+    VDDCMP  , Analog, pinenable0, vddcmp  , PIO0_6 , Unassigned;
     I2C0_SDA, Output, pinenable0, i2c0_sda, PIO0_11, Unassigned;
     I2C0_SCL, Output, pinenable0, i2c0_scl, PIO0_10, Unassigned;
     ADC_0   , Analog, pinenable0, adc_0   , PIO0_7 , Unassigned;
@@ -118,16 +127,28 @@ fixed_functions!(
     ADC_10  , Analog, pinenable0, adc_10  , PIO0_13, Unassigned;
     ADC_11  , Analog, pinenable0, adc_11  , PIO0_4 , Unassigned;
     DACOUT0 , Analog, pinenable0, dacout0 , PIO0_17, Unassigned;
+    #[cfg(not(feature = "33"))]
     DACOUT1 , Analog, pinenable0, dacout1 , PIO0_29, Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_X0 , Analog, pinenable0, capt_x0 , PIO0_31, Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_X1 , Analog, pinenable0, capt_x1 , PIO1_0 , Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_X2 , Analog, pinenable0, capt_x2 , PIO1_1 , Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_X3 , Analog, pinenable0, capt_x3 , PIO1_2 , Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_X4 , Analog, pinenable1, capt_x4 , PIO1_3 , Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_X5 , Analog, pinenable1, capt_x5 , PIO1_4 , Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_X6 , Analog, pinenable1, capt_x6 , PIO1_5 , Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_X7 , Analog, pinenable1, capt_x7 , PIO1_6 , Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_X8 , Analog, pinenable1, capt_x8 , PIO1_7 , Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_YL , Analog, pinenable1, capt_yl , PIO1_8 , Unassigned;
+    #[cfg(not(feature = "33"))]
     CAPT_YH , Analog, pinenable1, capt_yh , PIO1_8 , Unassigned;
 );
@@ -52,6 +52,9 @@ impl<T> Function<T, Unassigned> {
     /// - The state of the pin must allow another function of this type to be
     ///   assigned. Input functions can always be assigned, but only one output
     ///   or bidirectional function can be assigned to a given pin at any time.
+    ///   Analog functions (like ADC and comparator inputs) can only be assigned
+    ///   to a pin that has no other function assigned, and no other function
+    ///   can be assigned to the pin while an analog function is assigned.
     ///
     /// Code attempting to call this method while these requirement are not met,
     /// will not compile.
@@ -130,9 +133,11 @@ impl<T, P> Function<T, Assigned<P>> {
     ///   same pin that is provided as an argument.
     /// - The [`Pin`] must be in the SWM state ([`pins::state::Swm`]), and the
     ///   state must indicate that a function of this `Function`'s type is
-    ///   currently assigned. This should always be the case, if the previous
-    ///   condition is met, as it should be impossible to create inconsistent
-    ///   states between `Function`s and [`Pin`]s without using `unsafe`.
+    ///   currently assigned. For analog functions, the [`Pin`] must be in the
+    ///   analog state ([`pins::state::Analog`]) instead. This should always be
+    ///   the case, if the previous condition is met, as it should be impossible
+    ///   to create inconsistent states between `Function`s and [`Pin`]s without
+    ///   using `unsafe`.
     ///
     /// Code attempting to call this method while these requirement are not met,
     /// will not compile.
@@ -175,6 +180,7 @@ impl<T, P> Function<T, Assigned<P>> {
     /// [`Assigned`]: state/struct.Assigned.html
     /// [`Pin`]: ../pins/struct.Pin.html
     /// [`pins::state::Swm`]: ../pins/state/struct.Swm.html
+    /// [`pins::state::Analog`]: ../pins/state/struct.Analog.html
     pub fn unassign<S>(
         mut self,
         mut pin: Pin<P, S>,
@@ -198,8 +204,92 @@ impl<T, P> Function<T, Assigned<P>> {
 
         (function, pin.unassign())
     }
+
+    /// Move this function from one pin to another
+    ///
+    /// This is a shortcut for calling [`unassign`] with the pin that the
+    /// function is currently assigned to, followed by [`assign`] with the new
+    /// pin. The same requirements as for those two methods apply. Since fixed
+    /// functions can only be assigned to one specific pin, this method is only
+    /// useful for movable functions.
+    ///
+    /// Consumes this instance of `Function`, as well as both provided [`Pin`]s,
+    /// and returns new instances with their states updated accordingly. The
+    /// returned pins are in the order they were passed in.
+    ///
+    /// # Examples
+    ///
+    /// Move U0_TXD between two pins at runtime, for example to talk to two
+    /// devices that share the same USART:
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::Peripherals;
+    ///
+    /// let p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    /// let mut swm = p.SWM.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// let (u0_txd, pio0_0) = swm.movable_functions.u0_txd.assign(
+    ///     p.pins.pio0_0.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    /// let pio0_4 = p.pins.pio0_4.into_swm_pin();
+    ///
+    /// // Move U0_TXD to PIO0_4
+    /// let (u0_txd, pio0_0, pio0_4) =
+    ///     u0_txd.reassign(pio0_0, pio0_4, &mut swm_handle);
+    ///
+    /// // And back to PIO0_0
+    /// let (u0_txd, pio0_4, pio0_0) =
+    ///     u0_txd.reassign(pio0_4, pio0_0, &mut swm_handle);
+    /// ```
+    ///
+    /// [`unassign`]: #method.unassign
+    /// [`assign`]: #method.assign
+    /// [`Pin`]: ../pins/struct.Pin.html
+    #[allow(clippy::type_complexity)]
+    pub fn reassign<S, P2, S2>(
+        self,
+        from: Pin<P, S>,
+        to: Pin<P2, S2>,
+        swm: &mut Handle,
+    ) -> (
+        Function<T, Assigned<P2>>,
+        UnassignedPin<T, P, S>,
+        AssignedPin<T, P2, S2>,
+    )
+    where
+        T: FunctionTrait<P> + FunctionTrait<P2>,
+        P: pins::Trait,
+        S: pins::State,
+        P2: pins::Trait,
+        S2: pins::State,
+        Pin<P, S>: UnassignFunction<T, <T as FunctionTrait<P>>::Kind>,
+        Pin<P2, S2>: AssignFunction<T, <T as FunctionTrait<P2>>::Kind>,
+    {
+        let (function, from) = self.unassign(from, swm);
+        let (function, to) = function.assign(to, swm);
+
+        (function, from, to)
+    }
 }
 
+/// The type of a pin, after function `T` has been assigned to it
+type AssignedPin<T, P, S> =
+    <Pin<P, S> as AssignFunction<T, <T as FunctionTrait<P>>::Kind>>::Assigned;
+
+/// The type of a pin, after function `T` has been unassigned from it
+type UnassignedPin<T, P, S> = <Pin<P, S> as UnassignFunction<
+    T,
+    <T as FunctionTrait<P>>::Kind,
+>>::Unassigned;
+
 /// Implemented for all fixed and movable functions
 ///
 /// This trait is an internal implementation detail and should neither be
@@ -27,6 +27,24 @@ impl<STATE> Handle<STATE> {
             _state: PhantomData,
         }
     }
+
+    /// Steal the SWM handle
+    ///
+    /// This function returns an instance of `swm::Handle`, whether or not such
+    /// an instance exists somewhere else. This is meant for code that can't
+    /// access the handle the usual way, like an interrupt or panic handler.
+    ///
+    /// # Safety
+    ///
+    /// The same caveats apply as for [`Peripherals::steal`]. Most importantly,
+    /// the caller must make sure that the `STATE` type parameter matches the
+    /// actual state of the hardware, and that no other code accesses the SWM
+    /// at the same time.
+    ///
+    /// [`Peripherals::steal`]: ../struct.Peripherals.html#method.steal
+    pub unsafe fn steal() -> Self {
+        Self::new(pac::Peripherals::steal().SWM0)
+    }
 }
 
 impl Handle<init_state::Disabled> {
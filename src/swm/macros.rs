@@ -0,0 +1,83 @@
+/// Assign multiple functions to pins at once
+///
+/// Larger applications often need to assign many functions, which requires a
+/// lot of repetitive code when using [`Function::assign`] directly. This macro
+/// takes a table of function-to-pin assignments and expands into the
+/// respective [`Function::assign`] calls, transitioning each pin into the SWM
+/// state before.
+///
+/// For each entry in the table, the assigned function and the pin are bound
+/// to variables of the same name as the function and pin respectively, so they
+/// can be passed on to peripheral APIs or unassigned later.
+///
+/// All requirements of [`Function::assign`] apply. Assigning a fixed function
+/// to the wrong pin, or assigning two output functions to the same pin, will
+/// not compile. Since every pin is taken from `pins` and transitioned into the
+/// SWM state, each pin can only appear once in the table. Use
+/// [`Function::assign`] directly to assign multiple functions to the same pin.
+///
+/// Both the movable and fixed sections are optional, but if both are present,
+/// the movable section must come first.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{assign_pins, Peripherals};
+///
+/// let p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+/// let swm = p.SWM.split();
+///
+/// #[cfg(feature = "82x")]
+/// let mut swm_handle = swm.handle;
+/// #[cfg(feature = "845")]
+/// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+///
+/// assign_pins! {
+///     handle: &mut swm_handle,
+///     pins: p.pins,
+///     movable: swm.movable_functions => {
+///         u0_txd => pio0_4,
+///         u0_rxd => pio0_0,
+///         spi0_sck => pio0_12,
+///     }
+///     fixed: swm.fixed_functions => {
+///         adc_0 => pio0_7,
+///     }
+/// }
+///
+/// // `u0_txd`, `u0_rxd`, `spi0_sck`, and `adc_0` now hold the assigned
+/// // functions, `pio0_4`, `pio0_0`, `pio0_12`, and `pio0_7` the pins.
+/// let (adc_0, pio0_7) = adc_0.unassign(pio0_7, &mut swm_handle);
+/// ```
+///
+/// [`Function::assign`]: swm/struct.Function.html#method.assign
+#[macro_export]
+macro_rules! assign_pins {
+    (
+        handle: $handle:expr,
+        pins: $pins:expr,
+        $(
+            movable: $movable:expr => {
+                $($movable_function:ident => $movable_pin:ident),* $(,)?
+            }
+        )?
+        $(
+            fixed: $fixed:expr => {
+                $($fixed_function:ident => $fixed_pin:ident),* $(,)?
+            }
+        )?
+    ) => {
+        $($(
+            let ($movable_function, $movable_pin) = $movable
+                .$movable_function
+                .assign($pins.$movable_pin.into_swm_pin(), $handle);
+        )*)?
+        $($(
+            let ($fixed_function, $fixed_pin) = $fixed
+                .$fixed_function
+                .assign($pins.$fixed_pin.into_swm_pin(), $handle);
+        )*)?
+    };
+}
@@ -74,31 +74,31 @@ macro_rules! movable_functions {
             impl_function!($type, $kind, $reg_name, $reg_field, PIO0_26);
             impl_function!($type, $kind, $reg_name, $reg_field, PIO0_27);
             impl_function!($type, $kind, $reg_name, $reg_field, PIO0_28);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO0_29);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO0_30);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO0_31);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_0 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_1 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_2 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_3 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_4 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_5 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_6 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_7 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_8 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_9 );
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_10);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_11);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_12);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_13);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_14);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_15);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_16);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_17);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_18);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_19);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_20);
-            #[cfg(feature = "845")] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_21);
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO0_29);
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO0_30);
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO0_31);
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_0 );
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_1 );
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_2 );
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_3 );
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_4 );
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_5 );
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_6 );
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_7 );
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_8 );
+            #[cfg(all(feature = "845", not(feature = "33")))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_9 );
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_10);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_11);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_12);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_13);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_14);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_15);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_16);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_17);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_18);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_19);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_20);
+            #[cfg(all(feature = "845", not(any(feature = "33", feature = "48"))))] impl_function!($type, $kind, $reg_name, $reg_field, PIO1_21);
         )*
     }
 }
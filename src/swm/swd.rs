@@ -0,0 +1,203 @@
+use embedded_hal::{blocking::delay::DelayMs, digital::v2::InputPin};
+
+use crate::pins::{self, Pin};
+
+use super::{
+    fixed_functions::{SWCLK, SWDIO},
+    functions::Function,
+    handle::Handle,
+    state::{Assigned, Unassigned},
+};
+
+/// The state of a pin that has one of the SWD functions assigned
+type SwdPin = pins::state::Swm<((),), ()>;
+
+/// The SWD functions and their pins, after the pins have been reclaimed
+///
+/// The pins are unused and can be used for anything else.
+pub type ReclaimedPins = (
+    ReclaimedSwd,
+    Pin<pins::PIO0_3, pins::state::Unused>,
+    Pin<pins::PIO0_2, pins::state::Unused>,
+);
+
+/// The serial wire debug (SWD) functions, and the pins they are assigned to
+///
+/// SWCLK (PIO0_3) and SWDIO (PIO0_2) are assigned after reset, so a debugger
+/// can connect. Pin-constrained designs often need those pins for something
+/// else, but once the SWD functions are unassigned, a debugger can no longer
+/// connect to the running firmware. If the firmware does that right after
+/// reset, the board can only be recovered through ISP mode, which is easy to
+/// lock yourself out of.
+///
+/// `Swd` provides ways to reclaim the pins that leave a window for recovery,
+/// either by waiting after reset before reclaiming them, or by checking a
+/// strap pin first. Both are `unsafe`, to make the decision an explicit one.
+/// [`ReclaimedSwd::restore`] assigns the SWD functions again.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{delay::Delay, swm::Swd, CorePeripherals, Peripherals};
+///
+/// let cp = CorePeripherals::take().unwrap();
+/// let p = Peripherals::take().unwrap();
+///
+/// let mut swm = p.SWM.split();
+/// let mut syscon = p.SYSCON.split();
+///
+/// #[cfg(feature = "82x")]
+/// let mut swm_handle = swm.handle;
+/// #[cfg(feature = "845")]
+/// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+///
+/// let mut delay = Delay::new(cp.SYST);
+///
+/// let swd = Swd::new(
+///     swm.fixed_functions.swclk,
+///     swm.fixed_functions.swdio,
+///     p.pins.pio0_3,
+///     p.pins.pio0_2,
+/// );
+///
+/// // Give a debugger 2 seconds after reset to halt the core, before the
+/// // SWD pins are used for something else.
+/// let (swd, pio0_3, pio0_2) =
+///     unsafe { swd.reclaim_after(&mut delay, 2_000, &mut swm_handle) };
+///
+/// // `pio0_3` and `pio0_2` can be used like any other unused pin now.
+/// ```
+pub struct Swd {
+    swclk: Function<SWCLK, Assigned<pins::PIO0_3>>,
+    swdio: Function<SWDIO, Assigned<pins::PIO0_2>>,
+    pio0_3: Pin<pins::PIO0_3, SwdPin>,
+    pio0_2: Pin<pins::PIO0_2, SwdPin>,
+}
+
+impl Swd {
+    /// Combine the SWD functions with the pins they are assigned to
+    ///
+    /// All arguments are in the state they are in after reset.
+    pub fn new(
+        swclk: Function<SWCLK, Assigned<pins::PIO0_3>>,
+        swdio: Function<SWDIO, Assigned<pins::PIO0_2>>,
+        pio0_3: Pin<pins::PIO0_3, SwdPin>,
+        pio0_2: Pin<pins::PIO0_2, SwdPin>,
+    ) -> Self {
+        Self {
+            swclk,
+            swdio,
+            pio0_3,
+            pio0_2,
+        }
+    }
+
+    /// Reclaim the SWD pins after a delay
+    ///
+    /// Waits for `ms` milliseconds, then unassigns the SWD functions and
+    /// returns their pins. As long as the delay is long enough, a debugger can
+    /// still halt the core after reset, before it reaches this point.
+    ///
+    /// # Safety
+    ///
+    /// After this method returns, a debugger can no longer connect to the
+    /// running firmware. The caller must make sure that the delay is long
+    /// enough to allow a debugger to attach after reset, or that there is
+    /// another way to reprogram the device, like ISP mode.
+    pub unsafe fn reclaim_after<D>(
+        self,
+        delay: &mut D,
+        ms: u32,
+        swm: &mut Handle,
+    ) -> ReclaimedPins
+    where
+        D: DelayMs<u32>,
+    {
+        delay.delay_ms(ms);
+        self.reclaim(swm)
+    }
+
+    /// Reclaim the SWD pins, unless a strap pin is low
+    ///
+    /// If `strap` is low (for example, because a jumper connects it to ground),
+    /// the SWD functions stay assigned, and `Err` is returned with this
+    /// instance. The same happens, if the level of `strap` can't be read.
+    /// Otherwise, the SWD functions are unassigned, and their pins returned.
+    ///
+    /// # Safety
+    ///
+    /// After this method returns `Ok`, a debugger can no longer connect to the
+    /// running firmware. The caller must make sure that the strap pin is
+    /// accessible, or that there is another way to reprogram the device, like
+    /// ISP mode.
+    pub unsafe fn reclaim_unless<P>(
+        self,
+        strap: &P,
+        swm: &mut Handle,
+    ) -> Result<ReclaimedPins, Self>
+    where
+        P: InputPin,
+    {
+        match strap.is_high() {
+            Ok(true) => Ok(self.reclaim(swm)),
+            _ => Err(self),
+        }
+    }
+
+    /// Return the SWD functions and pins
+    #[allow(clippy::type_complexity)]
+    pub fn free(
+        self,
+    ) -> (
+        Function<SWCLK, Assigned<pins::PIO0_3>>,
+        Function<SWDIO, Assigned<pins::PIO0_2>>,
+        Pin<pins::PIO0_3, SwdPin>,
+        Pin<pins::PIO0_2, SwdPin>,
+    ) {
+        (self.swclk, self.swdio, self.pio0_3, self.pio0_2)
+    }
+
+    fn reclaim(self, swm: &mut Handle) -> ReclaimedPins {
+        let (swclk, pio0_3) = self.swclk.unassign(self.pio0_3, swm);
+        let (swdio, pio0_2) = self.swdio.unassign(self.pio0_2, swm);
+
+        let swd = ReclaimedSwd { swclk, swdio };
+
+        (swd, pio0_3.into_unused_pin(), pio0_2.into_unused_pin())
+    }
+}
+
+/// The SWD functions, after their pins have been reclaimed
+///
+/// Returned by the methods of [`Swd`] that reclaim the pins.
+///
+/// [`Swd`]: struct.Swd.html
+pub struct ReclaimedSwd {
+    swclk: Function<SWCLK, Unassigned>,
+    swdio: Function<SWDIO, Unassigned>,
+}
+
+impl ReclaimedSwd {
+    /// Assign the SWD functions to their pins again
+    ///
+    /// Makes it possible for a debugger to connect again, for example after
+    /// the firmware has been told to enter a maintenance mode.
+    pub fn restore(
+        self,
+        pio0_3: Pin<pins::PIO0_3, pins::state::Unused>,
+        pio0_2: Pin<pins::PIO0_2, pins::state::Unused>,
+        swm: &mut Handle,
+    ) -> Swd {
+        let (swclk, pio0_3) = self.swclk.assign(pio0_3.into_swm_pin(), swm);
+        let (swdio, pio0_2) = self.swdio.assign(pio0_2.into_swm_pin(), swm);
+
+        Swd::new(swclk, swdio, pio0_3, pio0_2)
+    }
+
+    /// Return the SWD functions
+    pub fn free(
+        self,
+    ) -> (Function<SWCLK, Unassigned>, Function<SWDIO, Unassigned>) {
+        (self.swclk, self.swdio)
+    }
+}
@@ -16,6 +16,9 @@ pub mod frg;
 pub use self::frg::FRG;
 
 pub mod clock_source;
+pub mod clock_tree;
+
+pub use self::clock_tree::describe;
 
 use core::marker::PhantomData;
 
@@ -23,14 +26,14 @@ use core::marker::PhantomData;
 use crate::pac::syscon::{
     pdruncfg, presetctrl as presetctrl0, starterp1,
     sysahbclkctrl as sysahbclkctrl0, PDRUNCFG, PRESETCTRL as PRESETCTRL0,
-    STARTERP1, SYSAHBCLKCTRL as SYSAHBCLKCTRL0, UARTCLKDIV, UARTFRGDIV,
-    UARTFRGMULT,
+    STARTERP1, SYSAHBCLKCTRL as SYSAHBCLKCTRL0, SYSRSTSTAT, UARTCLKDIV,
+    UARTFRGDIV, UARTFRGMULT, WDTOSCCTRL,
 };
 
 #[cfg(feature = "845")]
 use crate::pac::syscon::{
     pdruncfg, presetctrl0, starterp1, sysahbclkctrl0, FCLKSEL, PDRUNCFG,
-    PRESETCTRL0, STARTERP1, SYSAHBCLKCTRL0,
+    PRESETCTRL0, STARTERP1, SYSAHBCLKCTRL0, SYSRSTSTAT, WDTOSCCTRL,
 };
 
 use crate::{clock, init_state, pac, reg_proxy::RegProxy};
@@ -73,6 +76,7 @@ impl SYSCON {
                 presetctrl0: RegProxy::new(),
                 starterp1: RegProxy::new(),
                 sysahbclkctrl: RegProxy::new(),
+                sysrststat: RegProxy::new(),
                 #[cfg(feature = "845")]
                 fclksel: RegProxy::new(),
             },
@@ -95,10 +99,15 @@ impl SYSCON {
             },
 
             iosc_derived_clock: IoscDerivedClock::new(),
+            wdt_osc: clock_source::WdtOsc::new(),
             #[cfg(feature = "845")]
             frg0: FRG::new(),
             #[cfg(feature = "845")]
             frg1: FRG::new(),
+            #[cfg(feature = "845")]
+            main_clock: clock_source::MainClock::new(),
+            #[cfg(feature = "845")]
+            fro_div: clock_source::FroDiv::new(),
         }
     }
 
@@ -163,6 +172,9 @@ pub struct Parts {
     /// The 750 kHz internal oscillator/IRC/FRO-derived clock
     pub iosc_derived_clock: IoscDerivedClock<init_state::Enabled>,
 
+    /// The watchdog oscillator
+    pub wdt_osc: clock_source::WdtOsc<init_state::Disabled>,
+
     #[cfg(feature = "845")]
     /// Fractional Baud Rate Generator 0
     pub frg0: FRG<frg::FRG0>,
@@ -170,6 +182,14 @@ pub struct Parts {
     #[cfg(feature = "845")]
     /// Fractional Baud Rate Generator 1
     pub frg1: FRG<frg::FRG1>,
+
+    #[cfg(feature = "845")]
+    /// The main clock, as a clock source for peripherals
+    pub main_clock: clock_source::MainClock,
+
+    #[cfg(feature = "845")]
+    /// The FRO clock divided by 2, as a clock source for peripherals
+    pub fro_div: clock_source::FroDiv,
 }
 
 /// Handle to the SYSCON peripheral
@@ -187,6 +207,7 @@ pub struct Handle {
     presetctrl0: RegProxy<PRESETCTRL0>,
     starterp1: RegProxy<STARTERP1>,
     sysahbclkctrl: RegProxy<SYSAHBCLKCTRL0>,
+    sysrststat: RegProxy<SYSRSTSTAT>,
     #[cfg(feature = "845")]
     pub(crate) fclksel: RegProxy<FCLKSEL>,
 }
@@ -207,7 +228,23 @@ impl Handle {
             .modify(|_, w| peripheral.disable_clock(w));
     }
 
+    /// Enable the clock of IOCON
+    ///
+    /// The HAL doesn't own the IOCON peripheral, so it can't be passed to
+    /// [`enable_clock`] from within the HAL.
+    ///
+    /// [`enable_clock`]: #method.enable_clock
+    pub(crate) fn enable_iocon_clock(&mut self) {
+        self.sysahbclkctrl.modify(|_, w| w.iocon().set_bit());
+    }
+
     /// Assert peripheral reset
+    ///
+    /// Holds a peripheral or other hardware component in reset, until
+    /// [`clear_reset`] is called. See [`reset`] for a method that does both.
+    ///
+    /// [`clear_reset`]: #method.clear_reset
+    /// [`reset`]: #method.reset
     pub fn assert_reset<P: ResetControl>(&mut self, peripheral: &P) {
         self.presetctrl0.modify(|_, w| peripheral.assert_reset(w));
     }
@@ -221,6 +258,24 @@ impl Handle {
         self.presetctrl0.modify(|_, w| peripheral.clear_reset(w));
     }
 
+    /// Reset a peripheral
+    ///
+    /// Asserts, then clears the reset of a peripheral or other hardware
+    /// component. This returns all of its registers to their reset values,
+    /// which can be used to recover a peripheral that is stuck in some state,
+    /// without resetting the whole chip.
+    ///
+    /// Since all configuration is lost, this is mostly useful for peripherals
+    /// that are used through the raw registers. Peripheral APIs that support
+    /// resetting provide their own methods for that, which restore the
+    /// configuration afterwards (for example [`I2C::reset`]).
+    ///
+    /// [`I2C::reset`]: ../i2c/struct.I2C.html#method.reset
+    pub fn reset<P: ResetControl>(&mut self, peripheral: &P) {
+        self.assert_reset(peripheral);
+        self.clear_reset(peripheral);
+    }
+
     /// Provide power to an analog block
     ///
     /// HAL users usually won't have to call this method themselves, as other
@@ -255,6 +310,58 @@ impl Handle {
     {
         self.starterp1.modify(|_, w| I::disable(w));
     }
+
+    /// Determine the cause of the last reset
+    ///
+    /// The reset status flags are not cleared by a reset (except for the
+    /// power-on reset), so more than one cause might be reported, if the flags
+    /// haven't been cleared using [`clear_reset_cause`] since the previous
+    /// reset.
+    ///
+    /// [`clear_reset_cause`]: #method.clear_reset_cause
+    pub fn reset_cause(&self) -> ResetCause {
+        let sysrststat = self.sysrststat.read();
+
+        ResetCause {
+            power_on: sysrststat.por().bit_is_set(),
+            external: sysrststat.extrst().bit_is_set(),
+            watchdog: sysrststat.wdt().bit_is_set(),
+            brown_out: sysrststat.bod().bit_is_set(),
+            system: sysrststat.sysrst().bit_is_set(),
+        }
+    }
+
+    /// Clear the reset status flags
+    pub fn clear_reset_cause(&mut self) {
+        self.sysrststat.write(|w| {
+            w.por().set_bit();
+            w.extrst().set_bit();
+            w.wdt().set_bit();
+            w.bod().set_bit();
+            w.sysrst().set_bit()
+        });
+    }
+}
+
+/// The cause of a reset, as returned by [`Handle::reset_cause`]
+///
+/// [`Handle::reset_cause`]: struct.Handle.html#method.reset_cause
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ResetCause {
+    /// Power-on reset
+    pub power_on: bool,
+
+    /// Reset via the RESET pin
+    pub external: bool,
+
+    /// Reset by the watchdog timer
+    pub watchdog: bool,
+
+    /// Reset by brown-out detection
+    pub brown_out: bool,
+
+    /// System reset, requested by software (see `SCB::sys_reset`)
+    pub system: bool,
 }
 
 /// Brown-out detection
@@ -363,6 +470,17 @@ impl UARTFRG {
     pub fn set_frgdiv(&mut self, value: u8) {
         self.uartfrgdiv.write(|w| unsafe { w.div().bits(value) });
     }
+
+    /// Make the current settings permanent, for use as the USART clock
+    ///
+    /// Consumes the UART FRG, so its settings can no longer be changed. The
+    /// returned [`Shared`] clock can be passed to the clock configurations of
+    /// all USART instances.
+    ///
+    /// [`Shared`]: clock_source/struct.Shared.html
+    pub fn share(self) -> clock_source::Shared<Self> {
+        clock_source::Shared::new(self)
+    }
 }
 
 /// Internal trait for controlling peripheral clocks
@@ -471,11 +589,13 @@ impl ClockControl for pac::GPIO {
 /// implemented nor used outside of LPC8xx HAL. Any incompatible changes to this
 /// trait won't be considered breaking changes.
 ///
-/// Please refer to [`syscon::Handle::assert_reset`] and
-/// [`syscon::Handle::clear_reset`] for the public API that uses this trait.
+/// Please refer to [`syscon::Handle::assert_reset`],
+/// [`syscon::Handle::clear_reset`], and [`syscon::Handle::reset`] for the
+/// public API that uses this trait.
 ///
 /// [`syscon::Handle::assert_reset`]: struct.Handle.html#method.assert_reset
 /// [`syscon::Handle::clear_reset`]: struct.Handle.html#method.clear_reset
+/// [`syscon::Handle::reset`]: struct.Handle.html#method.reset
 pub trait ResetControl {
     /// Internal method to assert peripheral reset
     fn assert_reset<'w>(
@@ -725,6 +845,10 @@ wakeup_interrupt!(BodWakeup, bod);
 wakeup_interrupt!(WktWakeup, wkt);
 wakeup_interrupt!(I2c2Wakeup, i2c2);
 wakeup_interrupt!(I2c3Wakeup, i2c3);
+#[cfg(feature = "845")]
+wakeup_interrupt!(Usart3Wakeup, uart3);
+#[cfg(feature = "845")]
+wakeup_interrupt!(Usart4Wakeup, uart4);
 
 reg!(PDRUNCFG, PDRUNCFG, pac::SYSCON, pdruncfg);
 #[cfg(feature = "82x")]
@@ -732,6 +856,8 @@ reg!(PRESETCTRL0, PRESETCTRL0, pac::SYSCON, presetctrl);
 #[cfg(feature = "845")]
 reg!(PRESETCTRL0, PRESETCTRL0, pac::SYSCON, presetctrl0);
 reg!(STARTERP1, STARTERP1, pac::SYSCON, starterp1);
+reg!(SYSRSTSTAT, SYSRSTSTAT, pac::SYSCON, sysrststat);
+reg!(WDTOSCCTRL, WDTOSCCTRL, pac::SYSCON, wdtoscctrl);
 #[cfg(feature = "82x")]
 reg!(SYSAHBCLKCTRL0, SYSAHBCLKCTRL0, pac::SYSCON, sysahbclkctrl);
 #[cfg(feature = "845")]
@@ -10,7 +10,14 @@ pub use clocksource_82x::*;
 #[cfg(feature = "845")]
 pub use clocksource_845::*;
 
-use crate::syscon;
+use cortex_m::{interrupt, peripheral::syst::SystClkSource};
+
+use crate::{
+    clock, init_state,
+    pac::{self, syscon::WDTOSCCTRL, SYST},
+    reg_proxy::RegProxy,
+    syscon,
+};
 
 /// Internal trait used configure clocking of peripheals
 ///
@@ -33,6 +40,73 @@ pub trait PeripheralClockSource {
     /// This is not available (or required) on LPC82x.
     #[cfg(feature = "845")]
     const CLOCK: crate::pac::syscon::fclksel::SEL_A;
+
+    /// The frequency of this clock source in Hz, if known
+    ///
+    /// This is not available (or required) on LPC82x.
+    #[cfg(feature = "845")]
+    fn frequency(clocks: &syscon::clock_tree::ClockTree) -> Option<u32>;
+}
+
+/// An error that can occur, when computing a clock configuration for a
+/// specific frequency
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FrequencyError {
+    /// The frequency of the clock source is not known
+    ///
+    /// This happens, if the clock source depends on an external clock, whose
+    /// frequency hasn't been passed to [`describe`].
+    ///
+    /// [`describe`]: ../fn.describe.html
+    UnknownSource,
+
+    /// The requested frequency can't be derived from the clock source
+    Unachievable,
+}
+
+/// Compute DIVVAL for the highest frequency that doesn't exceed `target`
+///
+/// Used for the SPI clock, which is the source divided by `DIVVAL + 1`.
+pub(crate) fn divval_for(
+    source: Option<u32>,
+    target: u32,
+) -> Result<(u16, u32), FrequencyError> {
+    let source = source.ok_or(FrequencyError::UnknownSource)?;
+    if source == 0 || target == 0 {
+        return Err(FrequencyError::Unachievable);
+    }
+
+    // Round the divider up, to not exceed the target frequency.
+    let divider = (source - 1) / target + 1;
+    if divider > 0x1_0000 {
+        return Err(FrequencyError::Unachievable);
+    }
+
+    Ok(((divider - 1) as u16, source / divider))
+}
+
+/// A configurable clock source that is shared between peripherals
+///
+/// Clock sources with settings, like the fractional generators, can clock
+/// multiple peripherals at the same time. The clock configurations of those
+/// peripherals (baud rate prescalers, clock dividers, etc.) are computed for a
+/// specific frequency of the clock source, so changing its settings after the
+/// fact would silently break all peripherals that are already using it.
+///
+/// To prevent this, configurable clock sources can only be used to create
+/// clock configurations, once they have been turned into a `Shared` clock.
+/// This consumes the clock source, which makes its settings permanent. All
+/// peripherals clocked by a `Shared` clock are guaranteed to agree on its
+/// settings, and any attempt to reconfigure it will not compile.
+///
+/// `Shared` clocks are created using `FRG::share` on LPC845, and
+/// `UARTFRG::share` on LPC82x.
+pub struct Shared<C>(C);
+
+impl<C> Shared<C> {
+    pub(crate) fn new(clock: C) -> Self {
+        Self(clock)
+    }
 }
 
 /// Internal trait used for defining the fclksel index for a peripheral
@@ -47,6 +121,166 @@ pub trait PeripheralClockSelector {
     const REGISTER_NUM: usize;
 }
 
+/// The maximum SCL frequency supported by the I2C peripherals (Fast-mode Plus)
+pub const I2C_MAX_FREQUENCY: u32 = 1_000_000;
+
+/// I2C clock divider and SCL timing
+///
+/// Computes the values of the I2C `CLKDIV` and `MSTTIME` registers for a
+/// requested SCL frequency, and reports the frequency that is actually
+/// achieved. Can be passed to [`I2cClock::from_timing`].
+///
+/// The SCL frequency is the frequency of the I2C peripheral clock, divided by
+/// `divval + 1`, divided again by the number of clock cycles SCL is high plus
+/// the number of cycles it is low. Each of those can be between 2 and 9
+/// cycles.
+///
+/// # Example
+///
+/// ```
+/// use lpc8xx_hal::syscon::clock_source::I2cTiming;
+///
+/// let timing = I2cTiming::new(12_000_000, 400_000);
+/// assert_eq!(timing.frequency, 400_000);
+///
+/// // Keep SCL low for about 2/3 of the period
+/// let timing = I2cTiming::with_low_percent(12_000_000, 100_000, 67);
+/// assert_eq!(timing.frequency, 100_000);
+/// assert_eq!((timing.high, timing.low), (2, 4));
+/// ```
+///
+/// [`I2cClock::from_timing`]: struct.I2cClock.html#method.from_timing
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct I2cTiming {
+    /// The value of `CLKDIV.DIVVAL`
+    ///
+    /// The peripheral clock is divided by `divval + 1`.
+    pub divval: u16,
+
+    /// The number of divided clock cycles that SCL is high
+    pub high: u8,
+
+    /// The number of divided clock cycles that SCL is low
+    pub low: u8,
+
+    /// The SCL frequency that is achieved, in Hz
+    pub frequency: u32,
+}
+
+impl I2cTiming {
+    /// Compute the timing for a requested SCL frequency
+    ///
+    /// `source` is the frequency of the I2C peripheral clock, `scl` the
+    /// requested SCL frequency, both in Hz.
+    ///
+    /// The achieved frequency is the highest one that doesn't exceed the
+    /// requested frequency. SCL is low for about 60% of the period, which
+    /// satisfies the minimum low and high times of the I2C specification for
+    /// standard mode, fast mode, and Fast-mode Plus.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `scl` is 0 or larger than [`I2C_MAX_FREQUENCY`].
+    ///
+    /// [`I2C_MAX_FREQUENCY`]: constant.I2C_MAX_FREQUENCY.html
+    pub fn new(source: u32, scl: u32) -> Self {
+        Self::with_low_percent(source, scl, 60)
+    }
+
+    /// Compute the timing for a requested SCL frequency and duty cycle
+    ///
+    /// Works like [`new`], except that SCL is low for about `low_percent`
+    /// percent of the period, rounded up to whole clock cycles. This can be
+    /// used to meet device-specific setup and hold requirements. As SCL high
+    /// and low times are limited to 2 to 9 clock cycles each, the requested
+    /// duty cycle is only approximated. Check `high` and `low` for the actual
+    /// values.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `scl` is 0 or larger than [`I2C_MAX_FREQUENCY`], or if
+    /// `low_percent` is 0 or larger than 99.
+    ///
+    /// [`new`]: #method.new
+    /// [`I2C_MAX_FREQUENCY`]: constant.I2C_MAX_FREQUENCY.html
+    pub fn with_low_percent(source: u32, scl: u32, low_percent: u8) -> Self {
+        assert!(low_percent > 0 && low_percent < 100);
+
+        let (divval, high, low) = i2c_timing(source, scl, low_percent.into());
+        let frequency =
+            source / ((u32::from(divval) + 1) * u32::from(high + low));
+
+        Self {
+            divval,
+            high,
+            low,
+            frequency,
+        }
+    }
+}
+
+/// Compute the I2C clock divider and SCL high/low times
+///
+/// `source` is the frequency of the I2C peripheral clock, `scl` the requested
+/// SCL frequency, both in Hz. Returns the `CLKDIV` value and the number of
+/// divided clock cycles SCL is high and low, in that order.
+///
+/// The achieved frequency is the highest one that doesn't exceed the requested
+/// frequency. SCL is low for at least `low_percent` percent of the period, as
+/// far as the limits of the high and low times allow. If several dividers
+/// achieve the same frequency, the one that comes closest to `low_percent` is
+/// used.
+///
+/// Panics, if `scl` is 0 or larger than [`I2C_MAX_FREQUENCY`].
+///
+/// [`I2C_MAX_FREQUENCY`]: constant.I2C_MAX_FREQUENCY.html
+fn i2c_timing(source: u32, scl: u32, low_percent: u32) -> (u16, u8, u8) {
+    assert!(scl > 0 && scl <= I2C_MAX_FREQUENCY);
+
+    let mut best: Option<(u32, u32, u32, u32)> = None;
+
+    // SCL high and low times can each be between 2 and 9 clock cycles.
+    for cycles in 4..=18 {
+        let div = source.div_ceil(scl * cycles).max(1);
+        if div > 0x1_0000 {
+            continue;
+        }
+
+        let achieved = source / (div * cycles);
+        let error = duty_error(cycles, low_percent);
+        let better = match best {
+            Some((_, _, best_achieved, best_error)) => {
+                (achieved, best_error) >= (best_achieved, error)
+            }
+            None => true,
+        };
+        if better {
+            best = Some((div, cycles, achieved, error));
+        }
+    }
+
+    // If the requested frequency is too low to be reached, use the lowest
+    // possible frequency instead.
+    let (div, cycles, _, _) = best.unwrap_or((0x1_0000, 18, 0, 0));
+
+    let low = scl_low(cycles, low_percent);
+    let high = cycles - low;
+
+    ((div - 1) as u16, high as u8, low as u8)
+}
+
+/// The number of clock cycles SCL is low, out of a period of `cycles`
+fn scl_low(cycles: u32, low_percent: u32) -> u32 {
+    (cycles * low_percent)
+        .div_ceil(100)
+        .clamp(2.max(cycles.saturating_sub(9)), 9.min(cycles - 2))
+}
+
+/// The deviation from the requested duty cycle, in tenths of a percent
+fn duty_error(cycles: u32, low_percent: u32) -> u32 {
+    (scl_low(cycles, low_percent) * 1000 / cycles).abs_diff(low_percent * 10)
+}
+
 /// A struct containing the clock configuration for the ADC peripheral
 pub struct AdcClock {
     pub(crate) caldiv: u8,
@@ -70,3 +304,277 @@ impl AdcClock {
         Self { caldiv: 24, div: 0 }
     }
 }
+
+/// The frequency of the watchdog oscillator, before its divider
+///
+/// This is the value of the `FREQSEL` field in the `WDTOSCCTRL` register. The
+/// frequencies are nominal values. The actual frequency of the watchdog
+/// oscillator can deviate from these by up to 40%, which is why
+/// [`WdtOsc::calibrate`] exists.
+///
+/// [`WdtOsc::calibrate`]: struct.WdtOsc.html#method.calibrate
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WdtOscFrequency {
+    /// 0.6 MHz
+    Khz600 = 0x1,
+    /// 1.05 MHz
+    Khz1050 = 0x2,
+    /// 1.4 MHz
+    Khz1400 = 0x3,
+    /// 1.75 MHz
+    Khz1750 = 0x4,
+    /// 2.1 MHz
+    Khz2100 = 0x5,
+    /// 2.4 MHz
+    Khz2400 = 0x6,
+    /// 2.7 MHz
+    Khz2700 = 0x7,
+    /// 3.0 MHz
+    Khz3000 = 0x8,
+    /// 3.25 MHz
+    Khz3250 = 0x9,
+    /// 3.5 MHz
+    Khz3500 = 0xa,
+    /// 3.75 MHz
+    Khz3750 = 0xb,
+    /// 4.0 MHz
+    Khz4000 = 0xc,
+    /// 4.2 MHz
+    Khz4200 = 0xd,
+    /// 4.4 MHz
+    Khz4400 = 0xe,
+    /// 4.6 MHz
+    Khz4600 = 0xf,
+}
+
+impl WdtOscFrequency {
+    /// The nominal frequency in Hz
+    pub const fn hz(self) -> u32 {
+        match self {
+            WdtOscFrequency::Khz600 => 600_000,
+            WdtOscFrequency::Khz1050 => 1_050_000,
+            WdtOscFrequency::Khz1400 => 1_400_000,
+            WdtOscFrequency::Khz1750 => 1_750_000,
+            WdtOscFrequency::Khz2100 => 2_100_000,
+            WdtOscFrequency::Khz2400 => 2_400_000,
+            WdtOscFrequency::Khz2700 => 2_700_000,
+            WdtOscFrequency::Khz3000 => 3_000_000,
+            WdtOscFrequency::Khz3250 => 3_250_000,
+            WdtOscFrequency::Khz3500 => 3_500_000,
+            WdtOscFrequency::Khz3750 => 3_750_000,
+            WdtOscFrequency::Khz4000 => 4_000_000,
+            WdtOscFrequency::Khz4200 => 4_200_000,
+            WdtOscFrequency::Khz4400 => 4_400_000,
+            WdtOscFrequency::Khz4600 => 4_600_000,
+        }
+    }
+}
+
+/// The watchdog oscillator
+///
+/// A low-power oscillator, whose output is divided by an even number between 2
+/// and 64. It clocks the windowed watchdog timer (WWDT), which counts at a
+/// quarter of its frequency.
+///
+/// The frequency of the watchdog oscillator is very imprecise. Its
+/// [`clock::Frequency`] implementation returns the nominal frequency, unless
+/// it has been measured using [`calibrate`].
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     prelude::*,
+///     pac::CorePeripherals,
+///     syscon::clock_source::WdtOscFrequency,
+///     Peripherals,
+/// };
+///
+/// let mut cp = CorePeripherals::take().unwrap();
+/// let mut p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+///
+/// // 0.6 MHz / 64 = 9.375 kHz
+/// let mut wdt_osc = syscon.wdt_osc.enable(
+///     WdtOscFrequency::Khz600,
+///     64,
+///     &mut syscon.handle,
+/// );
+///
+/// // Measure the actual frequency, using the 12 MHz system clock
+/// let hz = wdt_osc.calibrate(
+///     &mut p.WWDT,
+///     &mut cp.SYST,
+///     12_000_000,
+///     &mut syscon.handle,
+/// );
+/// ```
+///
+/// [`clock::Frequency`]: ../../clock/trait.Frequency.html
+/// [`calibrate`]: #method.calibrate
+pub struct WdtOsc<State = init_state::Enabled> {
+    wdtoscctrl: RegProxy<WDTOSCCTRL>,
+    hz: u32,
+    _state: State,
+}
+
+impl WdtOsc<init_state::Disabled> {
+    pub(crate) fn new() -> Self {
+        Self {
+            wdtoscctrl: RegProxy::new(),
+            hz: 0,
+            _state: init_state::Disabled,
+        }
+    }
+
+    /// Configure and enable the watchdog oscillator
+    ///
+    /// The output frequency is `frequency`, divided by `divider`, which must
+    /// be an even number between 2 and 64.
+    ///
+    /// This method is only available, if `WdtOsc` is in the [`Disabled`]
+    /// state. Code that attempts to call this method when the oscillator is
+    /// already enabled will not compile.
+    ///
+    /// Consumes this instance of `WdtOsc` and returns another instance that
+    /// has its `State` type parameter set to [`Enabled`]. That new instance
+    /// implements [`clock::Enabled`], which might be required by APIs that need
+    /// an enabled clock.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `divider` is odd, smaller than 2, or larger than 64.
+    ///
+    /// [`Disabled`]: ../../init_state/struct.Disabled.html
+    /// [`Enabled`]: ../../init_state/struct.Enabled.html
+    /// [`clock::Enabled`]: ../../clock/trait.Enabled.html
+    pub fn enable(
+        self,
+        frequency: WdtOscFrequency,
+        divider: u8,
+        syscon: &mut syscon::Handle,
+    ) -> WdtOsc<init_state::Enabled> {
+        // `is_multiple_of` would require Rust 1.87.
+        #[allow(clippy::manual_is_multiple_of)]
+        let even = divider % 2 == 0;
+        assert!((2..=64).contains(&divider) && even);
+
+        self.wdtoscctrl.write(|w| unsafe {
+            w.freqsel().bits(frequency as u8);
+            w.divsel().bits(divider / 2 - 1)
+        });
+        syscon.pdruncfg.modify(|_, w| w.wdtosc_pd().clear_bit());
+
+        WdtOsc {
+            wdtoscctrl: self.wdtoscctrl,
+            hz: frequency.hz() / u32::from(divider),
+            _state: init_state::Enabled(()),
+        }
+    }
+}
+
+impl WdtOsc<init_state::Enabled> {
+    /// Disable the watchdog oscillator
+    ///
+    /// This method is only available, if `WdtOsc` is in the [`Enabled`] state.
+    /// Code that attempts to call this method when the oscillator is already
+    /// disabled will not compile.
+    ///
+    /// Consumes this instance of `WdtOsc` and returns another instance that
+    /// has its `State` type parameter set to [`Disabled`].
+    ///
+    /// [`Enabled`]: ../../init_state/struct.Enabled.html
+    /// [`Disabled`]: ../../init_state/struct.Disabled.html
+    pub fn disable(
+        self,
+        syscon: &mut syscon::Handle,
+    ) -> WdtOsc<init_state::Disabled> {
+        syscon.pdruncfg.modify(|_, w| w.wdtosc_pd().set_bit());
+
+        WdtOsc {
+            wdtoscctrl: self.wdtoscctrl,
+            hz: 0,
+            _state: init_state::Disabled,
+        }
+    }
+
+    /// Measure the actual frequency of the watchdog oscillator
+    ///
+    /// Counts the ticks of the system clock, as measured by SysTick, during a
+    /// number of WWDT ticks. `system_clock` is the frequency of the system
+    /// clock in Hz, which is derived from the IRC/FRO by default. The IRC/FRO
+    /// is factory-trimmed to within a few percent, so the result is much more
+    /// accurate than the nominal frequency.
+    ///
+    /// Returns the measured frequency, which is also returned by this clock's
+    /// [`clock::Frequency`] implementation from now on. Blocks for 1024 cycles
+    /// of the watchdog oscillator, with interrupts disabled.
+    ///
+    /// This must be called before the watchdog is configured, and before
+    /// SysTick is used for anything else (like [`Delay`]). The WWDT is started
+    /// with the largest possible timeout, in interrupt mode, so it won't reset
+    /// the microcontroller. Please note that the WWDT can't be stopped again,
+    /// once it has been started.
+    ///
+    /// [`clock::Frequency`]: ../../clock/trait.Frequency.html
+    /// [`Delay`]: ../../delay/struct.Delay.html
+    pub fn calibrate(
+        &mut self,
+        wwdt: &mut pac::WWDT,
+        syst: &mut SYST,
+        system_clock: u32,
+        syscon: &mut syscon::Handle,
+    ) -> u32 {
+        // The number of WWDT ticks to measure. The WWDT counts at a quarter
+        // of the oscillator frequency. Even at the lowest frequency, this
+        // won't overflow the 24-bit SysTick counter at 30 MHz.
+        const TICKS: u32 = 256;
+
+        syscon.enable_clock(wwdt);
+
+        wwdt.tc.write(|w| unsafe { w.count().bits(0xff_ffff) });
+        wwdt.mod_.modify(|_, w| w.wden().run());
+
+        syst.set_clock_source(SystClkSource::Core);
+        syst.set_reload(0xff_ffff);
+        syst.clear_current();
+        syst.enable_counter();
+
+        let elapsed = interrupt::free(|_| {
+            // The timer is only started, and its timeout loaded, by feeding.
+            wwdt.feed.write(|w| unsafe { w.feed().bits(0xaa) });
+            wwdt.feed.write(|w| unsafe { w.feed().bits(0x55) });
+
+            // The reloaded value takes a few cycles to show up.
+            while wwdt.tv.read().count().bits() < 0xff_0000 {}
+
+            // Start measuring at the beginning of a tick.
+            let first = wwdt.tv.read().count().bits();
+            while wwdt.tv.read().count().bits() == first {}
+            let start = SYST::get_current();
+
+            let target = first - 1 - TICKS;
+            while wwdt.tv.read().count().bits() > target {}
+            let end = SYST::get_current();
+
+            start.wrapping_sub(end) & 0xff_ffff
+        });
+
+        syst.disable_counter();
+
+        let hz = u64::from(system_clock) * u64::from(TICKS) * 4
+            / u64::from(elapsed.max(1));
+        self.hz = hz as u32;
+
+        self.hz
+    }
+}
+
+impl clock::Frequency for WdtOsc<init_state::Enabled> {
+    fn hz(&self) -> u32 {
+        self.hz
+    }
+}
+
+impl clock::Enabled for WdtOsc<init_state::Enabled> {}
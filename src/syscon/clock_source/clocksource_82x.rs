@@ -1,24 +1,30 @@
 use core::marker::PhantomData;
 
-use crate::syscon::{self, UARTFRG};
+use crate::syscon::{self, clock_tree::ClockTree, UARTFRG};
 
-use super::{PeripheralClock, PeripheralClockSource};
+use super::{
+    divval_for, FrequencyError, I2cTiming, PeripheralClock,
+    PeripheralClockSource, Shared,
+};
 
-impl PeripheralClockSource for UARTFRG {}
+impl PeripheralClockSource for Shared<UARTFRG> {}
 
 /// A struct containing the clock configuration for a peripheral
+///
+/// Its constructors are `const fn`s, so the configuration can be computed at
+/// compile time.
 pub struct I2cClock<PeriphClock> {
     pub(crate) divval: u16,
     pub(crate) mstsclhigh: u8,
     pub(crate) mstscllow: u8,
-    _periphclock: PhantomData<PeriphClock>,
+    _periphclock: PhantomData<fn() -> PeriphClock>,
 }
 
 impl<PERIPH: crate::i2c::Instance> I2cClock<PERIPH> {
     /// Create the clock config for the i2c peripheral
     ///
     /// mstclhigh & mstcllow have to be between 2-9
-    pub fn new(divval: u16, mstsclhigh: u8, mstscllow: u8) -> Self {
+    pub const fn new(divval: u16, mstsclhigh: u8, mstscllow: u8) -> Self {
         assert!(mstsclhigh > 1 && mstsclhigh < 10);
         assert!(mstscllow > 1 && mstscllow < 10);
         Self {
@@ -29,10 +35,37 @@ impl<PERIPH: crate::i2c::Instance> I2cClock<PERIPH> {
         }
     }
 
+    /// Create the clock config for a specific SCL frequency
+    ///
+    /// `source` is the frequency of the main clock, `scl` the requested SCL
+    /// frequency, both in Hz. The clock divider and SCL high/low times are
+    /// computed such, that the actual frequency is as close as possible to the
+    /// requested one, without exceeding it.
+    ///
+    /// Frequencies up to 1 MHz (Fast-mode Plus) are supported. Fast-mode Plus
+    /// additionally requires the I2C mode of the SDA and SCL pins to be set to
+    /// Fast-mode Plus in IOCON.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `scl` is 0 or larger than 1 MHz.
+    pub fn new_with_frequency(source: u32, scl: u32) -> Self {
+        Self::from_timing(&I2cTiming::new(source, scl))
+    }
+
+    /// Create the clock config from a computed timing
+    ///
+    /// See [`I2cTiming`] for details.
+    ///
+    /// [`I2cTiming`]: struct.I2cTiming.html
+    pub const fn from_timing(timing: &I2cTiming) -> Self {
+        Self::new(timing.divval, timing.high, timing.low)
+    }
+
     /// Create a new i2c clock config for 400 kHz
     ///
     /// Assumes the internal oscillator runs at 12 MHz
-    pub fn new_400khz() -> Self {
+    pub const fn new_400khz() -> Self {
         Self {
             divval: 5,
             mstsclhigh: 0,
@@ -40,6 +73,16 @@ impl<PERIPH: crate::i2c::Instance> I2cClock<PERIPH> {
             _periphclock: PhantomData,
         }
     }
+
+    /// Create a new i2c clock config for 1 MHz (Fast-mode Plus)
+    ///
+    /// Assumes the internal oscillator runs at 12 MHz
+    ///
+    /// Fast-mode Plus requires the I2C mode of the SDA and SCL pins to be set
+    /// to Fast-mode Plus in IOCON.
+    pub fn new_1mhz() -> Self {
+        Self::new_with_frequency(12_000_000, 1_000_000)
+    }
 }
 
 impl<PERIPH: crate::i2c::Instance> PeripheralClock<PERIPH>
@@ -51,20 +94,65 @@ impl<PERIPH: crate::i2c::Instance> PeripheralClock<PERIPH>
 }
 
 /// A struct containing the clock configuration for a peripheral
+///
+/// Its constructors are `const fn`s, so the configuration can be computed at
+/// compile time.
 pub struct SpiClock<PERIPH> {
     pub(crate) divval: u16,
+    frequency: Option<u32>,
     // The fields in the DLY register are ignored, since SSEL & EOF aren't used
-    _periphclock: PhantomData<PERIPH>,
+    _periphclock: PhantomData<fn() -> PERIPH>,
 }
 
 impl<PERIPH: crate::spi::Instance> SpiClock<PERIPH> {
     /// Create the clock config for the spi peripheral
-    pub fn new(divval: u16) -> Self {
+    ///
+    /// The SCK frequency is the frequency of the system clock, divided by
+    /// `divval + 1`.
+    pub const fn new(divval: u16) -> Self {
         Self {
             divval,
+            frequency: None,
             _periphclock: PhantomData,
         }
     }
+
+    /// Create the clock config for a specific SCK frequency
+    ///
+    /// `sck` is the requested frequency in Hz. The frequency of the system
+    /// clock is taken from `clocks`, as returned by [`syscon::describe`]. The
+    /// clock divider is computed such, that the actual frequency is as close
+    /// as possible to the requested one, without exceeding it. The actual
+    /// frequency can be queried using [`frequency`].
+    ///
+    /// Returns an error, if the frequency of the system clock is not known, or
+    /// if `sck` is zero or too low to be reached from it.
+    ///
+    /// [`syscon::describe`]: ../fn.describe.html
+    /// [`frequency`]: #method.frequency
+    pub fn from_frequency(
+        sck: u32,
+        clocks: &ClockTree,
+    ) -> Result<Self, FrequencyError> {
+        let (divval, frequency) =
+            divval_for(clocks.system_clock.frequency, sck)?;
+
+        Ok(Self {
+            divval,
+            frequency: Some(frequency),
+            _periphclock: PhantomData,
+        })
+    }
+
+    /// The SCK frequency in Hz, if known
+    ///
+    /// This is only known, if the config was created using
+    /// [`from_frequency`].
+    ///
+    /// [`from_frequency`]: #method.from_frequency
+    pub const fn frequency(&self) -> Option<u32> {
+        self.frequency
+    }
 }
 
 impl<PERIPH: crate::spi::Instance> PeripheralClock<PERIPH>
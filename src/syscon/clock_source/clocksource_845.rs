@@ -2,10 +2,13 @@ use core::marker::PhantomData;
 
 use crate::{
     pac::{self, syscon::fclksel::SEL_A},
-    syscon::{self, frg, IOSC},
+    syscon::{self, clock_tree::ClockTree, frg, IOSC},
 };
 
-use super::{PeripheralClock, PeripheralClockSelector, PeripheralClockSource};
+use super::{
+    divval_for, FrequencyError, I2cTiming, PeripheralClock,
+    PeripheralClockSelector, PeripheralClockSource, Shared,
+};
 
 macro_rules! periph_clock_selector {
     ($peripheral:ident, $num:expr) => {
@@ -22,24 +25,80 @@ periph_clock_selector!(I2C3, 8);
 periph_clock_selector!(SPI0, 9);
 periph_clock_selector!(SPI1, 10);
 
-impl PeripheralClockSource for frg::FRG<frg::FRG0> {
+impl PeripheralClockSource for Shared<frg::FRG<frg::FRG0>> {
     const CLOCK: SEL_A = SEL_A::FRG0CLK;
+
+    fn frequency(clocks: &ClockTree) -> Option<u32> {
+        clocks.frg[0].frequency
+    }
 }
 
-impl PeripheralClockSource for frg::FRG<frg::FRG1> {
+impl PeripheralClockSource for Shared<frg::FRG<frg::FRG1>> {
     const CLOCK: SEL_A = SEL_A::FRG1CLK;
+
+    fn frequency(clocks: &ClockTree) -> Option<u32> {
+        clocks.frg[1].frequency
+    }
 }
 
 impl PeripheralClockSource for IOSC {
     const CLOCK: SEL_A = SEL_A::FRO;
+
+    fn frequency(clocks: &ClockTree) -> Option<u32> {
+        clocks.fro.frequency
+    }
+}
+
+/// The main clock, as a clock source for peripherals
+///
+/// Can be used to create clock configurations for the serial peripherals,
+/// like any other clock source. Since the main clock also clocks the core,
+/// its frequency depends on the system's clock configuration, so the
+/// frequency-based constructors need to be told what it is.
+pub struct MainClock(PhantomData<*const ()>);
+
+impl MainClock {
+    pub(crate) fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl PeripheralClockSource for MainClock {
+    const CLOCK: SEL_A = SEL_A::MAIN_CLK;
+
+    fn frequency(clocks: &ClockTree) -> Option<u32> {
+        clocks.main_clock.frequency
+    }
+}
+
+/// The FRO clock divided by 2 (fro_div), as a clock source for peripherals
+///
+/// Runs at 6 MHz, if the FRO is left at its default frequency of 12 MHz.
+pub struct FroDiv(PhantomData<*const ()>);
+
+impl FroDiv {
+    pub(crate) fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl PeripheralClockSource for FroDiv {
+    const CLOCK: SEL_A = SEL_A::FRO_DIV;
+
+    fn frequency(clocks: &ClockTree) -> Option<u32> {
+        clocks.fro.frequency.map(|f| f / 2)
+    }
 }
 
 /// A struct containing the clock configuration for a peripheral
+///
+/// Its constructors are `const fn`s, so the configuration can be computed at
+/// compile time.
 pub struct I2cClock<PeriphClock> {
     pub(crate) divval: u16,
     pub(crate) mstsclhigh: u8,
     pub(crate) mstscllow: u8,
-    _periphclock: PhantomData<PeriphClock>,
+    _periphclock: PhantomData<fn() -> PeriphClock>,
 }
 
 impl<PERIPH: PeripheralClockSelector, CLOCK: PeripheralClockSource>
@@ -48,7 +107,12 @@ impl<PERIPH: PeripheralClockSelector, CLOCK: PeripheralClockSource>
     /// Create the clock config for the i2c peripheral
     ///
     /// mstclhigh & mstcllow have to be between 2-9
-    pub fn new(_: &CLOCK, divval: u16, mstsclhigh: u8, mstscllow: u8) -> Self {
+    pub const fn new(
+        _: &CLOCK,
+        divval: u16,
+        mstsclhigh: u8,
+        mstscllow: u8,
+    ) -> Self {
         assert!(mstsclhigh > 1 && mstsclhigh < 10);
         assert!(mstscllow > 1 && mstscllow < 10);
         Self {
@@ -58,13 +122,40 @@ impl<PERIPH: PeripheralClockSelector, CLOCK: PeripheralClockSource>
             _periphclock: PhantomData,
         }
     }
+
+    /// Create the clock config for a specific SCL frequency
+    ///
+    /// `source` is the frequency of the selected clock source, `scl` the
+    /// requested SCL frequency, both in Hz. The clock divider and SCL high/low
+    /// times are computed such, that the actual frequency is as close as
+    /// possible to the requested one, without exceeding it.
+    ///
+    /// Frequencies up to 1 MHz (Fast-mode Plus) are supported. Fast-mode Plus
+    /// additionally requires the I2C mode of the SDA and SCL pins to be set to
+    /// Fast-mode Plus in IOCON.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `scl` is 0 or larger than 1 MHz.
+    pub fn new_with_frequency(clock: &CLOCK, source: u32, scl: u32) -> Self {
+        Self::from_timing(clock, &I2cTiming::new(source, scl))
+    }
+
+    /// Create the clock config from a computed timing
+    ///
+    /// See [`I2cTiming`] for details.
+    ///
+    /// [`I2cTiming`]: struct.I2cTiming.html
+    pub const fn from_timing(clock: &CLOCK, timing: &I2cTiming) -> Self {
+        Self::new(clock, timing.divval, timing.high, timing.low)
+    }
 }
 
 impl<PERIPH: PeripheralClockSelector> I2cClock<(PERIPH, IOSC)> {
     /// Create a new i2c clock config for 400 kHz
     ///
     /// Assumes the internal oscillator runs at 12 MHz
-    pub fn new_400khz() -> Self {
+    pub const fn new_400khz() -> Self {
         Self {
             divval: 5,
             mstsclhigh: 0,
@@ -72,6 +163,23 @@ impl<PERIPH: PeripheralClockSelector> I2cClock<(PERIPH, IOSC)> {
             _periphclock: PhantomData,
         }
     }
+
+    /// Create a new i2c clock config for 1 MHz (Fast-mode Plus)
+    ///
+    /// Assumes the internal oscillator runs at 12 MHz
+    ///
+    /// Fast-mode Plus requires the I2C mode of the SDA and SCL pins to be set
+    /// to Fast-mode Plus in IOCON.
+    pub fn new_1mhz() -> Self {
+        let timing = I2cTiming::new(12_000_000, 1_000_000);
+
+        Self {
+            divval: timing.divval,
+            mstsclhigh: timing.high - 2,
+            mstscllow: timing.low - 2,
+            _periphclock: PhantomData,
+        }
+    }
 }
 
 impl<PERIPH: PeripheralClockSelector, CLOCK: PeripheralClockSource>
@@ -84,22 +192,69 @@ impl<PERIPH: PeripheralClockSelector, CLOCK: PeripheralClockSource>
 }
 
 /// A struct containing the clock configuration for a peripheral
+///
+/// Its constructors are `const fn`s, so the configuration can be computed at
+/// compile time.
 pub struct SpiClock<PeriphClock> {
     pub(crate) divval: u16,
+    frequency: Option<u32>,
     // The fields in the DLY register are ignored, since SSEL & EOF aren't used
-    _periphclock: PhantomData<PeriphClock>,
+    _periphclock: PhantomData<fn() -> PeriphClock>,
 }
 
 impl<PERIPH: PeripheralClockSelector, CLOCK: PeripheralClockSource>
     SpiClock<(PERIPH, CLOCK)>
 {
     /// Create the clock config for the spi peripheral
-    pub fn new(_: &CLOCK, divval: u16) -> Self {
+    ///
+    /// The SCK frequency is the frequency of the clock source, divided by
+    /// `divval + 1`.
+    pub const fn new(_: &CLOCK, divval: u16) -> Self {
         Self {
             divval,
+            frequency: None,
             _periphclock: PhantomData,
         }
     }
+
+    /// Create the clock config for a specific SCK frequency
+    ///
+    /// `sck` is the requested frequency in Hz. The frequency of the clock
+    /// source is taken from `clocks`, as returned by [`syscon::describe`].
+    /// The clock divider is computed such, that the actual frequency is as
+    /// close as possible to the requested one, without exceeding it. The
+    /// actual frequency can be queried using [`frequency`].
+    ///
+    /// Returns an error, if the frequency of the clock source is not known, or
+    /// if `sck` is zero or too low to be reached from it.
+    ///
+    /// [`syscon::describe`]: ../fn.describe.html
+    /// [`frequency`]: #method.frequency
+    pub fn from_frequency(
+        _: &CLOCK,
+        sck: u32,
+        clocks: &ClockTree,
+    ) -> Result<Self, FrequencyError> {
+        let (divval, frequency) = divval_for(CLOCK::frequency(clocks), sck)?;
+
+        Ok(Self {
+            divval,
+            frequency: Some(frequency),
+            _periphclock: PhantomData,
+        })
+    }
+}
+
+impl<PeriphClock> SpiClock<PeriphClock> {
+    /// The SCK frequency in Hz, if known
+    ///
+    /// This is only known, if the config was created using
+    /// [`from_frequency`].
+    ///
+    /// [`from_frequency`]: #method.from_frequency
+    pub const fn frequency(&self) -> Option<u32> {
+        self.frequency
+    }
 }
 
 impl<PERIPH: PeripheralClockSelector, CLOCK: PeripheralClockSource>
@@ -0,0 +1,525 @@
+//! Description of the clock tree configuration
+//!
+//! See [`describe`].
+//!
+//! [`describe`]: fn.describe.html
+
+use crate::pac;
+
+/// Describe the current configuration of the clock tree
+///
+/// Reads the clock source selection and divider registers, and computes the
+/// resulting frequencies. The returned [`ClockTree`] implements `Debug`, so it
+/// can be logged during bring-up, to show how exactly the chip is clocked.
+///
+/// `external` is the frequency of the external clock (system oscillator or
+/// CLKIN) in Hz, if there is one. Frequencies that depend on the external
+/// clock are `None`, if it is not passed.
+///
+/// The frequencies of the internal oscillators are nominal values:
+///
+/// - The frequency of the watchdog oscillator is only accurate to ±40%.
+/// - On LPC845, the FRO oscillator is assumed to run at its default frequency
+///   of 24 MHz. If it has been changed using the boot ROM API, the computed
+///   frequencies are wrong.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::syscon;
+///
+/// // This board has a 12 MHz crystal.
+/// let clocks = syscon::describe(Some(12_000_000));
+///
+/// // `clocks` can now be logged, for example using `write!(serial, ...)`.
+/// let system_clock = clocks.system_clock.frequency;
+/// ```
+///
+/// [`ClockTree`]: struct.ClockTree.html
+pub fn describe(external: Option<u32>) -> ClockTree {
+    // Sound, as we only read from registers.
+    let syscon = unsafe { &*pac::SYSCON::ptr() };
+
+    describe_registers(syscon, external)
+}
+
+/// The configuration of the clock tree, as returned by [`describe`]
+///
+/// [`describe`]: fn.describe.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ClockTree {
+    /// The system PLL
+    pub pll: Pll,
+
+    /// The FRO clock (LPC845 only)
+    ///
+    /// This is the output of the FRO oscillator, divided by 2 unless
+    /// FRO_DIRECT is set.
+    #[cfg(feature = "845")]
+    pub fro: Clock,
+
+    /// The main clock
+    pub main_clock: Clock,
+
+    /// The system clock, which clocks the core, memories, and peripherals
+    ///
+    /// This is the main clock, divided by SYSAHBCLKDIV.
+    pub system_clock: DividedClock,
+
+    /// The clock that drives the UART fractional generator (LPC82x only)
+    ///
+    /// This is the main clock, divided by UARTCLKDIV.
+    #[cfg(feature = "82x")]
+    pub uart_clock: DividedClock,
+
+    /// The UART fractional generator, which clocks all USARTs (LPC82x only)
+    #[cfg(feature = "82x")]
+    pub uart_frg: Frg,
+
+    /// The fractional generators FRG0 and FRG1 (LPC845 only)
+    #[cfg(feature = "845")]
+    pub frg: [Frg; 2],
+
+    /// The peripheral clocks, as selected by FCLKSEL (LPC845 only)
+    ///
+    /// In register order: USART0-4, I2C0-3, SPI0-1.
+    #[cfg(feature = "845")]
+    pub peripheral_clocks: [Clock; 11],
+
+    /// The ADC clock (LPC845 only)
+    #[cfg(feature = "845")]
+    pub adc_clock: DividedClock,
+
+    /// The SCT clock (LPC845 only)
+    #[cfg(feature = "845")]
+    pub sct_clock: DividedClock,
+
+    /// The clock output (CLKOUT)
+    pub clock_out: DividedClock,
+}
+
+/// A clock and its source
+///
+/// Part of [`ClockTree`].
+///
+/// [`ClockTree`]: struct.ClockTree.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Clock {
+    /// The source of the clock
+    pub source: Source,
+
+    /// The frequency of the clock in Hz, if known
+    pub frequency: Option<u32>,
+}
+
+/// A clock with a divider, and its source
+///
+/// Part of [`ClockTree`].
+///
+/// [`ClockTree`]: struct.ClockTree.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DividedClock {
+    /// The source of the clock
+    pub source: Source,
+
+    /// The value of the divider
+    ///
+    /// A divider of 0 disables the clock.
+    pub divider: u8,
+
+    /// The frequency of the clock in Hz, if known
+    ///
+    /// This is 0, if the clock is disabled.
+    pub frequency: Option<u32>,
+}
+
+/// The configuration of the system PLL
+///
+/// Part of [`ClockTree`].
+///
+/// [`ClockTree`]: struct.ClockTree.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Pll {
+    /// The source of the PLL input
+    pub source: Source,
+
+    /// Whether the PLL is powered
+    pub powered: bool,
+
+    /// Whether the PLL has locked
+    pub locked: bool,
+
+    /// The feedback divider (MSEL + 1), by which the input is multiplied
+    pub multiplier: u8,
+
+    /// The post divider (2 ^ PSEL), which determines the CCO frequency
+    pub post_divider: u8,
+
+    /// The frequency of the PLL output in Hz, if known
+    ///
+    /// This is computed from the configuration, whether the PLL is powered
+    /// and locked or not.
+    pub frequency: Option<u32>,
+}
+
+/// The configuration of a fractional generator
+///
+/// Part of [`ClockTree`].
+///
+/// [`ClockTree`]: struct.ClockTree.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Frg {
+    /// The source of the fractional generator
+    pub source: Source,
+
+    /// The multiplier (MULT)
+    pub mult: u8,
+
+    /// The divider (DIV), which needs to be 0xff
+    pub div: u8,
+
+    /// The frequency of the output in Hz, if known
+    ///
+    /// This is `source / (1 + mult / (div + 1))`.
+    pub frequency: Option<u32>,
+}
+
+/// A clock source, as selected by one of the clock multiplexers
+///
+/// Part of [`ClockTree`].
+///
+/// [`ClockTree`]: struct.ClockTree.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Source {
+    /// The internal RC oscillator (12 MHz)
+    #[cfg(feature = "82x")]
+    Irc,
+
+    /// The free running oscillator (FRO)
+    #[cfg(feature = "845")]
+    Fro,
+
+    /// The FRO, divided by 2
+    #[cfg(feature = "845")]
+    FroDiv,
+
+    /// The system oscillator
+    SysOsc,
+
+    /// The CLKIN pin
+    ClkIn,
+
+    /// The watchdog oscillator
+    WatchdogOsc,
+
+    /// The input of the system PLL
+    #[cfg(feature = "82x")]
+    PllInput,
+
+    /// The output of the system PLL
+    PllOutput,
+
+    /// The main clock
+    MainClock,
+
+    /// The main clock, divided by UARTCLKDIV
+    #[cfg(feature = "82x")]
+    UartClock,
+
+    /// Fractional generator 0
+    #[cfg(feature = "845")]
+    Frg0,
+
+    /// Fractional generator 1
+    #[cfg(feature = "845")]
+    Frg1,
+
+    /// No clock, or a reserved value, is selected
+    None,
+}
+
+/// The frequencies that are known so far, while describing the clock tree
+#[derive(Default)]
+struct Frequencies {
+    oscillator: Option<u32>,
+    external: Option<u32>,
+    watchdog: Option<u32>,
+    pll_input: Option<u32>,
+    pll_output: Option<u32>,
+    main: Option<u32>,
+    #[cfg(feature = "82x")]
+    uart: Option<u32>,
+    #[cfg(feature = "845")]
+    frg: [Option<u32>; 2],
+}
+
+impl Frequencies {
+    fn of(&self, source: Source) -> Option<u32> {
+        match source {
+            #[cfg(feature = "82x")]
+            Source::Irc => self.oscillator,
+            #[cfg(feature = "845")]
+            Source::Fro => self.oscillator,
+            #[cfg(feature = "845")]
+            Source::FroDiv => self.oscillator.map(|f| f / 2),
+            Source::SysOsc | Source::ClkIn => self.external,
+            Source::WatchdogOsc => self.watchdog,
+            #[cfg(feature = "82x")]
+            Source::PllInput => self.pll_input,
+            Source::PllOutput => self.pll_output,
+            Source::MainClock => self.main,
+            #[cfg(feature = "82x")]
+            Source::UartClock => self.uart,
+            #[cfg(feature = "845")]
+            Source::Frg0 => self.frg[0],
+            #[cfg(feature = "845")]
+            Source::Frg1 => self.frg[1],
+            Source::None => Some(0),
+        }
+    }
+
+    fn clock(&self, source: Source) -> Clock {
+        Clock {
+            source,
+            frequency: self.of(source),
+        }
+    }
+
+    fn divided(&self, source: Source, divider: u32) -> DividedClock {
+        let divider = (divider & 0xff) as u8;
+
+        DividedClock {
+            source,
+            divider,
+            frequency: self.of(source).map(|f| match divider {
+                0 => 0,
+                divider => f / u32::from(divider),
+            }),
+        }
+    }
+
+    fn frg(&self, source: Source, mult: u32, div: u32) -> Frg {
+        let mult = (mult & 0xff) as u8;
+        let div = (div & 0xff) as u8;
+
+        let frequency = self.of(source).map(|f| {
+            let div = u64::from(div) + 1;
+            (u64::from(f) * div / (div + u64::from(mult))) as u32
+        });
+
+        Frg {
+            source,
+            mult,
+            div,
+            frequency,
+        }
+    }
+
+    fn pll(
+        &mut self,
+        source: Source,
+        syscon: &pac::syscon::RegisterBlock,
+    ) -> Pll {
+        let ctrl = syscon.syspllctrl.read().bits();
+        let multiplier = (ctrl & 0x1f) as u8 + 1;
+        let post_divider = 1 << ((ctrl >> 5) & 0x3);
+
+        self.pll_input = self.of(source);
+        self.pll_output = self.pll_input.map(|f| f * u32::from(multiplier));
+
+        Pll {
+            source,
+            powered: syscon.pdruncfg.read().bits() & PDRUNCFG_SYSPLL_PD == 0,
+            locked: syscon.syspllstat.read().bits() & 0x1 != 0,
+            multiplier,
+            post_divider,
+            frequency: self.pll_output,
+        }
+    }
+}
+
+#[cfg(feature = "82x")]
+fn describe_registers(
+    syscon: &pac::syscon::RegisterBlock,
+    external: Option<u32>,
+) -> ClockTree {
+    let mut frequencies = Frequencies {
+        oscillator: Some(12_000_000),
+        external,
+        watchdog: watchdog_frequency(syscon.wdtoscctrl.read().bits()),
+        ..Frequencies::default()
+    };
+
+    let pll_source = match syscon.syspllclksel.read().bits() & 0x3 {
+        0 => Source::Irc,
+        1 => Source::SysOsc,
+        3 => Source::ClkIn,
+        _ => Source::None,
+    };
+    let pll = frequencies.pll(pll_source, syscon);
+
+    let main_source = match syscon.mainclksel.read().bits() & 0x3 {
+        0 => Source::Irc,
+        1 => Source::PllInput,
+        2 => Source::WatchdogOsc,
+        _ => Source::PllOutput,
+    };
+    let main_clock = frequencies.clock(main_source);
+    frequencies.main = main_clock.frequency;
+
+    let uart_clock =
+        frequencies.divided(Source::MainClock, syscon.uartclkdiv.read().bits());
+    frequencies.uart = uart_clock.frequency;
+
+    let clock_out_source = match syscon.clkoutsel.read().bits() & 0x3 {
+        0 => Source::Irc,
+        1 => Source::SysOsc,
+        2 => Source::WatchdogOsc,
+        _ => Source::MainClock,
+    };
+
+    ClockTree {
+        pll,
+        main_clock,
+        system_clock: frequencies
+            .divided(Source::MainClock, syscon.sysahbclkdiv.read().bits()),
+        uart_clock,
+        uart_frg: frequencies.frg(
+            Source::UartClock,
+            syscon.uartfrgmult.read().bits(),
+            syscon.uartfrgdiv.read().bits(),
+        ),
+        clock_out: frequencies
+            .divided(clock_out_source, syscon.clkoutdiv.read().bits()),
+    }
+}
+
+#[cfg(feature = "845")]
+fn describe_registers(
+    syscon: &pac::syscon::RegisterBlock,
+    external: Option<u32>,
+) -> ClockTree {
+    const FRO_DIRECT: u32 = 0x1 << 17;
+
+    // The FRO oscillator runs at 24 MHz by default. Unless FRO_DIRECT is set,
+    // the FRO clock is divided by 2.
+    let oscillator = if syscon.frooscctrl.read().bits() & FRO_DIRECT != 0 {
+        24_000_000
+    } else {
+        12_000_000
+    };
+
+    let mut frequencies = Frequencies {
+        oscillator: Some(oscillator),
+        external,
+        watchdog: watchdog_frequency(syscon.wdtoscctrl.read().bits()),
+        ..Frequencies::default()
+    };
+
+    let external_source = if syscon.extclksel.read().bits() & 0x1 == 0 {
+        Source::SysOsc
+    } else {
+        Source::ClkIn
+    };
+    let pre_pll_source = |sel: u32| match sel & 0x3 {
+        0 => Source::Fro,
+        1 => external_source,
+        2 => Source::WatchdogOsc,
+        _ => Source::FroDiv,
+    };
+
+    let fro = frequencies.clock(Source::Fro);
+
+    let pll_source = pre_pll_source(syscon.syspllclksel.read().bits());
+    let pll = frequencies.pll(pll_source, syscon);
+
+    let main_source = if syscon.mainclkpllsel.read().bits() & 0x1 != 0 {
+        Source::PllOutput
+    } else {
+        pre_pll_source(syscon.mainclksel.read().bits())
+    };
+    let main_clock = frequencies.clock(main_source);
+    frequencies.main = main_clock.frequency;
+
+    let frg_source = |sel: u32| match sel & 0x3 {
+        0 => Source::Fro,
+        1 => Source::MainClock,
+        2 => Source::PllOutput,
+        _ => Source::None,
+    };
+    let frg0 = frequencies.frg(
+        frg_source(syscon.frg0.frgclksel.read().bits()),
+        syscon.frg0.frgmult.read().bits(),
+        syscon.frg0.frgdiv.read().bits(),
+    );
+    let frg1 = frequencies.frg(
+        frg_source(syscon.frg1.frgclksel.read().bits()),
+        syscon.frg1.frgmult.read().bits(),
+        syscon.frg1.frgdiv.read().bits(),
+    );
+    frequencies.frg = [frg0.frequency, frg1.frequency];
+
+    let mut peripheral_clocks = [frequencies.clock(Source::None); 11];
+    for (clock, fclksel) in peripheral_clocks.iter_mut().zip(&syscon.fclksel) {
+        let source = match fclksel.read().bits() & 0x7 {
+            0 => Source::Fro,
+            1 => Source::MainClock,
+            2 => Source::Frg0,
+            3 => Source::Frg1,
+            4 => Source::FroDiv,
+            _ => Source::None,
+        };
+        *clock = frequencies.clock(source);
+    }
+
+    let adc_source = match syscon.adcclksel.read().bits() & 0x3 {
+        0 => Source::Fro,
+        1 => Source::PllOutput,
+        _ => Source::None,
+    };
+    let sct_source = frg_source(syscon.sctclksel.read().bits());
+    let clock_out_source = match syscon.clkoutsel.read().bits() & 0x7 {
+        0 => Source::Fro,
+        1 => Source::MainClock,
+        2 => Source::PllOutput,
+        3 => external_source,
+        4 => Source::WatchdogOsc,
+        _ => Source::None,
+    };
+
+    ClockTree {
+        pll,
+        fro,
+        main_clock,
+        system_clock: frequencies
+            .divided(Source::MainClock, syscon.sysahbclkdiv.read().bits()),
+        frg: [frg0, frg1],
+        peripheral_clocks,
+        adc_clock: frequencies
+            .divided(adc_source, syscon.adcclkdiv.read().bits()),
+        sct_clock: frequencies
+            .divided(sct_source, syscon.sctclkdiv.read().bits()),
+        clock_out: frequencies
+            .divided(clock_out_source, syscon.clkoutdiv.read().bits()),
+    }
+}
+
+/// Compute the nominal frequency of the watchdog oscillator from WDTOSCCTRL
+fn watchdog_frequency(wdtoscctrl: u32) -> Option<u32> {
+    const FREQUENCIES: [u32; 16] = [
+        0, 600_000, 1_050_000, 1_400_000, 1_750_000, 2_100_000, 2_400_000,
+        2_700_000, 3_000_000, 3_250_000, 3_500_000, 3_750_000, 4_000_000,
+        4_200_000, 4_400_000, 4_600_000,
+    ];
+
+    let divsel = wdtoscctrl & 0x1f;
+    let freqsel = (wdtoscctrl >> 5) & 0xf;
+
+    match FREQUENCIES[freqsel as usize] {
+        0 => None,
+        frequency => Some(frequency / (2 * (1 + divsel))),
+    }
+}
+
+/// The SYSPLL_PD bit in PDRUNCFG
+const PDRUNCFG_SYSPLL_PD: u32 = 0x1 << 7;
@@ -6,6 +6,7 @@ use crate::{
         syscon::frg::{FRGCLKSEL, FRGDIV, FRGMULT},
     },
     reg_proxy::{Reg, RegProxy},
+    syscon::clock_source::Shared,
 };
 
 /// Clock sources for the FRG
@@ -15,7 +16,11 @@ pub use crate::pac::syscon::frg::frgclksel::SEL_A as Clock;
 
 /// Fractional generator
 ///
-/// Can be used as a clock source for serial peripherals.
+/// Can be used as a clock source for serial peripherals, after it has been
+/// configured and turned into a [`Shared`] clock using [`FRG::share`].
+///
+/// [`Shared`]: ../clock_source/struct.Shared.html
+/// [`FRG::share`]: #method.share
 pub struct FRG<I: Instance> {
     div: RegProxy<I::Div>,
     mult: RegProxy<I::Mult>,
@@ -50,6 +55,43 @@ where
         // Safe, as all `u8` values are valid.
         self.mult.write(|w| unsafe { w.bits(mult.into()) });
     }
+
+    /// Make the current settings permanent, for use as a peripheral clock
+    ///
+    /// Consumes the FRG, so its settings can no longer be changed. The
+    /// returned [`Shared`] clock can be passed to the clock configurations of
+    /// any number of peripherals.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{
+    ///     Peripherals,
+    ///     syscon::{clock_source::SpiClock, frg},
+    ///     usart,
+    /// };
+    ///
+    /// let p = Peripherals::take().unwrap();
+    /// let mut syscon = p.SYSCON.split();
+    ///
+    /// syscon.frg0.select_clock(frg::Clock::FRO);
+    /// syscon.frg0.set_mult(22);
+    /// syscon.frg0.set_div(0xff);
+    /// let frg0 = syscon.frg0.share();
+    ///
+    /// // Both configurations are based on the same FRG settings
+    /// let usart_clock = usart::Clock::new(&frg0, 0, 16);
+    /// let spi_clock = SpiClock::new(&frg0, 5);
+    /// # let _: SpiClock<(lpc8xx_hal::pac::SPI0, _)> = spi_clock;
+    ///
+    /// // This would not compile:
+    /// // syscon.frg0.set_mult(0);
+    /// ```
+    ///
+    /// [`Shared`]: ../clock_source/struct.Shared.html
+    pub fn share(self) -> Shared<Self> {
+        Shared::new(self)
+    }
 }
 
 /// Internal implementation detail
@@ -0,0 +1,219 @@
+//! Timeouts for blocking operations
+//!
+//! Most blocking operations in this HAL wait for the hardware, or for another
+//! device, without any limit. If a device doesn't respond, because it's
+//! disconnected or has locked up, they hang forever. This module provides a
+//! way to give up after a while instead.
+//!
+//! [`with_timeout`] repeatedly calls a non-blocking operation until it
+//! completes, or until a timer expires. A [`Deadline`] does the same for a
+//! series of operations that share a single timeout. Any timer that implements
+//! `CountDown` can be used, like an MRT channel.
+//!
+//! The I2C, SPI, and USART APIs also provide timeout-enabled variants of their
+//! blocking operations, which are built on this module:
+//!
+//! - [`I2C::write_with_timeout`], [`I2C::read_with_timeout`], and
+//!   [`I2C::write_read_with_timeout`]
+//! - [`SPI::transfer_with_timeout`] and [`SPI::write_with_timeout`]
+//! - [`usart::Rx::read_with_timeout`] and
+//!   [`usart::Tx::bwrite_all_with_timeout`]
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     prelude::*,
+//!     timeout::{self, with_timeout},
+//!     usart, Peripherals,
+//! };
+//!
+//! let mut p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//! let mut swm = p.SWM.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! #[cfg(feature = "82x")]
+//! let clock_config = {
+//!     syscon.uartfrg.set_clkdiv(6);
+//!     syscon.uartfrg.set_frgmult(22);
+//!     syscon.uartfrg.set_frgdiv(0xff);
+//!     let uartfrg = syscon.uartfrg.share();
+//!     usart::Clock::new(&uartfrg, 0, 16)
+//! };
+//! #[cfg(feature = "845")]
+//! let clock_config = usart::Clock::new_with_baudrate(115200);
+//!
+//! let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+//!     p.pins.pio0_0.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//! let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+//!     p.pins.pio0_4.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//!
+//! let mut serial = p.USART0.enable(
+//!     &clock_config,
+//!     &mut syscon.handle,
+//!     u0_rxd,
+//!     u0_txd,
+//! );
+//!
+//! // The MRT runs at 12 MHz
+//! let mut timer = p.MRT0.split(&mut syscon.handle).mrt0;
+//!
+//! // Wait for a response for up to 100 ms
+//! serial.bwrite_all(b"ping\n").unwrap();
+//! match with_timeout(&mut timer, 1_200_000, || serial.rx.read()) {
+//!     Ok(_response) => {
+//!         // process response
+//!     }
+//!     Err(timeout::Error::Timeout) => {
+//!         // the other side didn't respond
+//!     }
+//!     Err(timeout::Error::Other(_)) => {
+//!         // a character was received with an error
+//!     }
+//! }
+//! ```
+//!
+//! [`with_timeout`]: fn.with_timeout.html
+//! [`Deadline`]: struct.Deadline.html
+//! [`I2C::write_with_timeout`]: ../i2c/struct.I2C.html#method.write_with_timeout
+//! [`I2C::read_with_timeout`]: ../i2c/struct.I2C.html#method.read_with_timeout
+//! [`I2C::write_read_with_timeout`]: ../i2c/struct.I2C.html#method.write_read_with_timeout
+//! [`SPI::transfer_with_timeout`]: ../spi/struct.SPI.html#method.transfer_with_timeout
+//! [`SPI::write_with_timeout`]: ../spi/struct.SPI.html#method.write_with_timeout
+//! [`usart::Rx::read_with_timeout`]: ../usart/struct.Rx.html#method.read_with_timeout
+//! [`usart::Tx::bwrite_all_with_timeout`]: ../usart/struct.Tx.html#method.bwrite_all_with_timeout
+
+use embedded_hal::timer::CountDown;
+
+use crate::activity;
+
+/// Repeat a non-blocking operation, until it completes or the timer expires
+///
+/// Starts `timer` with `ticks`, then calls `op` until it returns something
+/// other than `WouldBlock`. Returns [`Error::Timeout`], if the timer expires
+/// first.
+///
+/// `op` is called at least once, even if `ticks` is zero.
+///
+/// [`Error::Timeout`]: enum.Error.html#variant.Timeout
+pub fn with_timeout<T, F, R, E>(
+    timer: &mut T,
+    ticks: u32,
+    op: F,
+) -> Result<R, Error<E>>
+where
+    T: CountDown<Time = u32>,
+    F: FnMut() -> nb::Result<R, E>,
+{
+    Deadline::start(timer, ticks).block(op)
+}
+
+/// A timeout that is shared by a series of operations
+///
+/// Use this instead of [`with_timeout`], if a timeout should apply to a whole
+/// transaction, instead of each single step.
+///
+/// [`with_timeout`]: fn.with_timeout.html
+pub struct Deadline<'t, T> {
+    timer: &'t mut T,
+    expired: bool,
+}
+
+impl<'t, T> Deadline<'t, T>
+where
+    T: CountDown<Time = u32>,
+{
+    /// Start the timer and create a `Deadline` that expires with it
+    ///
+    /// The timer must not be used for anything else, while the `Deadline`
+    /// exists.
+    pub fn start(timer: &'t mut T, ticks: u32) -> Self {
+        timer.start(ticks);
+
+        Self {
+            timer,
+            expired: false,
+        }
+    }
+
+    /// Indicates whether the deadline has passed
+    ///
+    /// Once this has returned `true`, it keeps returning `true`, even if the
+    /// timer is periodic.
+    pub fn has_expired(&mut self) -> bool {
+        if !self.expired {
+            self.expired = self.timer.wait().is_ok();
+        }
+
+        self.expired
+    }
+
+    /// Repeat a non-blocking operation, until it completes or the deadline
+    /// passes
+    ///
+    /// `op` is called at least once, even if the deadline has already passed.
+    pub fn block<F, R, E>(&mut self, mut op: F) -> Result<R, Error<E>>
+    where
+        F: FnMut() -> nb::Result<R, E>,
+    {
+        loop {
+            match op() {
+                Ok(result) => return Ok(result),
+                Err(nb::Error::Other(err)) => return Err(Error::Other(err)),
+                Err(nb::Error::WouldBlock) => {}
+            }
+
+            if self.has_expired() {
+                return Err(Error::Timeout);
+            }
+
+            activity::notify();
+        }
+    }
+}
+
+/// Something that can expire, while an operation waits
+///
+/// Lets the blocking operations share their implementation with their
+/// timeout-enabled variants.
+pub(crate) trait Expire {
+    fn has_expired(&mut self) -> bool;
+}
+
+impl<T> Expire for Deadline<'_, T>
+where
+    T: CountDown<Time = u32>,
+{
+    fn has_expired(&mut self) -> bool {
+        Deadline::has_expired(self)
+    }
+}
+
+/// Never expires, for operations without a timeout
+pub(crate) struct Never;
+
+impl Expire for Never {
+    fn has_expired(&mut self) -> bool {
+        false
+    }
+}
+
+/// An error that can occur while waiting with a timeout
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The operation didn't complete in time
+    Timeout,
+
+    /// The operation itself returned an error
+    Other(E),
+}
@@ -0,0 +1,357 @@
+//! Software timers, driven by a single MRT channel
+//!
+//! The LPC800 series only has a handful of hardware timers. [`TimerQueue`]
+//! multiplexes one MRT channel, allowing you to schedule any number of one-shot
+//! or periodic timers, up to a fixed capacity. No heap allocation is required.
+//!
+//! Whenever a timer expires, its callback is called from the MRT interrupt
+//! handler. The queue needs to be notified of the interrupt by calling
+//! [`TimerQueue::handle_interrupt`]. Callbacks run in interrupt context, so
+//! they should be short. A common pattern is to just set a flag that is then
+//! handled by the main loop.
+//!
+//! The timer queue needs to be accessed from both the interrupt handler and
+//! the code that schedules the timers. This requires some form of
+//! synchronization, like a `cortex_m::interrupt::Mutex` or the resources of
+//! a framework like RTFM.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use core::sync::atomic::{AtomicBool, Ordering};
+//!
+//! use lpc8xx_hal::{Peripherals, timer_queue::TimerQueue};
+//!
+//! static BLINK: AtomicBool = AtomicBool::new(false);
+//!
+//! fn blink() {
+//!     BLINK.store(true, Ordering::Relaxed);
+//! }
+//!
+//! fn timeout() {}
+//!
+//! let p = Peripherals::take().unwrap();
+//!
+//! let mut syscon = p.SYSCON.split();
+//! let mrt_channels = p.MRT0.split(&mut syscon.handle);
+//!
+//! let mut timers = TimerQueue::<_, 4>::new(mrt_channels.mrt0);
+//!
+//! // The MRT runs at 12 MHz by default
+//! let blink_timer = timers.schedule_periodic(6_000_000, blink).unwrap();
+//! let timeout_timer = timers.schedule(60_000_000, timeout).unwrap();
+//!
+//! // Call this from the MRT0 interrupt handler
+//! timers.handle_interrupt();
+//!
+//! // Timers can be canceled before they expire
+//! timers.cancel(timeout_timer);
+//! ```
+//!
+//! [`TimerQueue`]: struct.TimerQueue.html
+//! [`TimerQueue::handle_interrupt`]: struct.TimerQueue.html#method.handle_interrupt
+
+use embedded_hal::timer::{Cancel as _, CountDown as _};
+
+use crate::mrt::{self, Channel};
+
+/// A queue of software timers, driven by an MRT channel
+///
+/// `N` is the maximum number of timers that can be scheduled at the same time.
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct TimerQueue<T, const N: usize>
+where
+    T: mrt::Trait,
+{
+    channel: Channel<T>,
+    timers: [Option<Entry>; N],
+    generations: [u16; N],
+
+    /// The time at which the current interval of the MRT channel started
+    start: u64,
+
+    /// The length of the current interval, or 0, if the channel is stopped
+    interval: u32,
+}
+
+impl<T, const N: usize> TimerQueue<T, N>
+where
+    T: mrt::Trait,
+{
+    /// Create a new timer queue
+    ///
+    /// Enables the interrupt of the MRT channel. You still need to enable the
+    /// MRT interrupt in the NVIC, and call [`handle_interrupt`] from the
+    /// interrupt handler.
+    ///
+    /// [`handle_interrupt`]: #method.handle_interrupt
+    pub fn new(mut channel: Channel<T>) -> Self {
+        let _ = channel.cancel();
+        channel.enable_interrupt();
+
+        Self {
+            channel,
+            timers: [None; N],
+            generations: [0; N],
+            start: 0,
+            interval: 0,
+        }
+    }
+
+    /// Schedule a one-shot timer
+    ///
+    /// `callback` is called once, `delay` MRT clock ticks from now.
+    ///
+    /// Returns [`Error::QueueFull`], if `N` timers are already scheduled.
+    ///
+    /// [`Error::QueueFull`]: enum.Error.html#variant.QueueFull
+    pub fn schedule(
+        &mut self,
+        delay: u32,
+        callback: fn(),
+    ) -> Result<TimerId, Error> {
+        self.insert(delay, None, callback)
+    }
+
+    /// Schedule a periodic timer
+    ///
+    /// `callback` is called every `period` MRT clock ticks, until the timer is
+    /// canceled. The deadlines are computed from the previous deadline, not
+    /// from the time the callback was called, so interrupt latency doesn't
+    /// accumulate over time.
+    ///
+    /// Returns [`Error::QueueFull`], if `N` timers are already scheduled.
+    ///
+    /// [`Error::QueueFull`]: enum.Error.html#variant.QueueFull
+    pub fn schedule_periodic(
+        &mut self,
+        period: u32,
+        callback: fn(),
+    ) -> Result<TimerId, Error> {
+        self.insert(period, Some(period), callback)
+    }
+
+    /// Cancel a timer
+    ///
+    /// Does nothing, if the timer has already expired or has been canceled.
+    pub fn cancel(&mut self, timer: TimerId) {
+        if self.is_scheduled(timer) {
+            self.timers[timer.index] = None;
+        }
+    }
+
+    /// Indicates whether a timer is still scheduled
+    ///
+    /// Returns `false`, once a one-shot timer has expired, or after any timer
+    /// has been canceled.
+    pub fn is_scheduled(&self, timer: TimerId) -> bool {
+        self.timers[timer.index].is_some()
+            && self.generations[timer.index] == timer.generation
+    }
+
+    /// Handle the MRT interrupt
+    ///
+    /// Calls the callbacks of all timers that have expired and programs the
+    /// MRT channel for the next deadline. Must be called from the MRT
+    /// interrupt handler.
+    ///
+    /// The MRT interrupt is shared between all channels. It's safe to call
+    /// this method, if the interrupt was caused by another channel.
+    pub fn handle_interrupt(&mut self) {
+        if !self.advance() {
+            return;
+        }
+
+        let now = self.now();
+        for entry in self.timers.iter_mut() {
+            let expired = match entry {
+                Some(e) if e.deadline <= now => e,
+                _ => continue,
+            };
+
+            (expired.callback)();
+
+            match expired.period {
+                Some(period) => expired.deadline += u64::from(period),
+                None => *entry = None,
+            }
+        }
+
+        self.reprogram();
+    }
+
+    /// Release the MRT channel
+    ///
+    /// Stops the channel and disables its interrupt. All scheduled timers are
+    /// discarded.
+    pub fn free(mut self) -> Channel<T> {
+        let _ = self.channel.cancel();
+        self.channel.disable_interrupt();
+
+        self.channel
+    }
+
+    fn insert(
+        &mut self,
+        delay: u32,
+        period: Option<u32>,
+        callback: fn(),
+    ) -> Result<TimerId, Error> {
+        let index = self
+            .timers
+            .iter()
+            .position(|entry| entry.is_none())
+            .ok_or(Error::QueueFull)?;
+
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.timers[index] = Some(Entry {
+            deadline: self.now() + u64::from(delay),
+            period,
+            callback,
+        });
+
+        self.reprogram();
+
+        Ok(TimerId {
+            index,
+            generation: self.generations[index],
+        })
+    }
+
+    /// Account for the end of the current interval of the MRT channel
+    ///
+    /// Consumes the channel's interrupt flag. If it was set, the channel has
+    /// reloaded automatically, and a new interval of the same length has
+    /// started. Returns whether that was the case.
+    ///
+    /// This is the only place where the interrupt flag is consumed and `start`
+    /// is advanced, so no interval can get lost.
+    fn advance(&mut self) -> bool {
+        if self.channel.wait().is_err() {
+            return false;
+        }
+
+        self.start += u64::from(self.interval);
+        true
+    }
+
+    /// The current time, in MRT clock ticks since the queue was created
+    ///
+    /// Time doesn't advance while no timers are scheduled.
+    fn now(&self) -> u64 {
+        if self.interval == 0 {
+            return self.start;
+        }
+
+        let value = self.channel.value();
+
+        // If the interval has ended, but the interrupt flag hasn't been
+        // consumed yet, the value has to be read again, as the reload could
+        // have happened after the first read.
+        let reloaded = if self.channel.has_finished() {
+            Some(self.channel.value())
+        } else {
+            None
+        };
+
+        self.start + elapsed(self.interval, value, reloaded)
+    }
+
+    fn reprogram(&mut self) {
+        // Starting the channel below clears the interrupt flag. Account for
+        // an interval that has ended, before that happens.
+        self.advance();
+        let now = self.now();
+
+        let next = self
+            .timers
+            .iter()
+            .flatten()
+            .map(|entry| entry.deadline)
+            .min();
+
+        self.start = now;
+
+        match next {
+            Some(deadline) => {
+                let interval = deadline.saturating_sub(now);
+                let interval = interval.clamp(1, u64::from(mrt::MAX_VALUE));
+
+                self.interval = interval as u32;
+                self.channel.start(self.interval);
+            }
+            None => {
+                self.interval = 0;
+                let _ = self.channel.cancel();
+            }
+        }
+    }
+}
+
+/// The time since the start of the current interval of the MRT channel
+///
+/// `value` is the value of the channel. `reloaded` is the value read after
+/// the channel's interrupt flag has been found set, or `None`, if it hasn't.
+fn elapsed(interval: u32, value: u32, reloaded: Option<u32>) -> u64 {
+    match reloaded {
+        Some(value) => {
+            u64::from(interval) + u64::from(interval.saturating_sub(value))
+        }
+        None => u64::from(interval.saturating_sub(value)),
+    }
+}
+
+/// Identifies a timer that has been scheduled in a [`TimerQueue`]
+///
+/// [`TimerQueue`]: struct.TimerQueue.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TimerId {
+    index: usize,
+    generation: u16,
+}
+
+/// An error that can occur when scheduling a timer
+#[derive(Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The queue is full
+    QueueFull,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    deadline: u64,
+    period: Option<u32>,
+    callback: fn(),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::elapsed;
+
+    #[test]
+    fn elapsed_should_count_down_from_interval() {
+        assert_eq!(elapsed(1000, 1000, None), 0);
+        assert_eq!(elapsed(1000, 400, None), 600);
+    }
+
+    #[test]
+    fn elapsed_should_include_interval_that_has_not_been_handled() {
+        // The channel has reloaded, but the interrupt flag is still set, as
+        // it would be inside a critical section.
+        assert_eq!(elapsed(1000, 990, Some(990)), 1010);
+    }
+
+    #[test]
+    fn elapsed_should_not_go_backwards_across_reload() {
+        // The first read happens right before the reload, the second one
+        // right after.
+        let before = elapsed(1000, 1, None);
+        let after = elapsed(1000, 1, Some(1000));
+
+        assert!(after >= before);
+        assert_eq!(after, 1000);
+    }
+}
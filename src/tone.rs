@@ -0,0 +1,403 @@
+//! Tone generation for buzzers and piezo speakers
+//!
+//! [`Tone`] plays square wave tones on a PWM output of the SCT or CTimer, which
+//! is enough to drive a piezo buzzer for audible feedback, like key clicks,
+//! beeps, or short melodies. Tones are generated by changing the period of the
+//! PWM timer, at a duty cycle of 50%.
+//!
+//! Since all PWM outputs of a timer share the same period, changing it affects
+//! the other outputs of that timer too. The timer should therefore be
+//! dedicated to tone generation.
+//!
+//! Tones can be played while blocking, using [`Tone::play`] and
+//! [`Tone::play_notes`]. A [`Melody`] can be played in the background instead,
+//! by calling [`Melody::update`] every millisecond (for example from an MRT
+//! interrupt handler), or by passing a periodic timer to [`Melody::poll`].
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     delay::Delay,
+//!     prelude::*,
+//!     tone::{Note, Pitch, Tone},
+//!     CorePeripherals,
+//!     Peripherals,
+//! };
+//!
+//! let cp = CorePeripherals::take().unwrap();
+//! let p = Peripherals::take().unwrap();
+//!
+//! let swm = p.SWM.split();
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! // Count at 1 MHz (the system clock runs at 12 MHz by default). The
+//! // initial period doesn't matter, as `Tone` overwrites it.
+//! let channels = p.SCT0.start_pwm(1000, 11, &mut syscon.handle);
+//!
+//! let (output, _) = swm.movable_functions.sct_out0.assign(
+//!     p.pins.pio0_14.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//! let mut buzzer = Tone::new(channels.out0.attach(output), 1_000_000);
+//!
+//! let mut delay = Delay::new(cp.SYST);
+//!
+//! // A short beep
+//! buzzer.play(2_000, 100, &mut delay);
+//!
+//! // A short jingle
+//! buzzer.play_notes(
+//!     &[
+//!         Note::new(Pitch::C.frequency(5), 150),
+//!         Note::new(Pitch::E.frequency(5), 150),
+//!         Note::rest(50),
+//!         Note::new(Pitch::G.frequency(5), 300),
+//!     ],
+//!     &mut delay,
+//! );
+//! ```
+//!
+//! [`Tone`]: struct.Tone.html
+//! [`Tone::play`]: struct.Tone.html#method.play
+//! [`Tone::play_notes`]: struct.Tone.html#method.play_notes
+//! [`Melody`]: struct.Melody.html
+//! [`Melody::update`]: struct.Melody.html#method.update
+//! [`Melody::poll`]: struct.Melody.html#method.poll
+
+use embedded_hal::{blocking::delay::DelayMs, timer::CountDown, PwmPin};
+use void::Void;
+
+/// Plays tones on a PWM output
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct Tone<P> {
+    pin: P,
+    clock: u32,
+}
+
+impl<P> Tone<P>
+where
+    P: TonePin,
+{
+    /// Create a new tone generator
+    ///
+    /// `clock` is the frequency at which the PWM timer counts, in Hz. This is
+    /// the frequency of the timer's clock, divided by its prescaler. It limits
+    /// both the highest frequency that can be played (half of `clock`) and the
+    /// accuracy of all frequencies. A clock of 1 MHz is a good choice for
+    /// audible tones.
+    ///
+    /// The output is silent, until a tone is played.
+    pub fn new(mut pin: P, clock: u32) -> Self {
+        pin.silence();
+        Self { pin, clock }
+    }
+
+    /// Start playing a tone
+    ///
+    /// Plays a tone of the given frequency (in Hz), until another tone is
+    /// started, or the tone is stopped. A frequency of 0 stops the tone.
+    /// Frequencies above half of the clock passed to [`new`] are played at
+    /// half of the clock.
+    ///
+    /// [`new`]: #method.new
+    pub fn start(&mut self, frequency: u32) {
+        if frequency == 0 {
+            self.stop();
+            return;
+        }
+
+        let period = (self.clock / frequency).max(2);
+        self.pin.set_period(period);
+        self.pin.set_duty(period / 2);
+    }
+
+    /// Stop playing the current tone
+    pub fn stop(&mut self) {
+        self.pin.silence();
+    }
+
+    /// Play a tone for a duration, blocking until it has finished
+    ///
+    /// Plays a tone of the given frequency (in Hz), for `ms` milliseconds.
+    /// The tone is stopped afterwards.
+    pub fn play<D>(&mut self, frequency: u32, ms: u32, delay: &mut D)
+    where
+        D: DelayMs<u32>,
+    {
+        self.start(frequency);
+        delay.delay_ms(ms);
+        self.stop();
+    }
+
+    /// Play a sequence of notes, blocking until all have finished
+    ///
+    /// See [`Melody`] for playing notes without blocking.
+    ///
+    /// [`Melody`]: struct.Melody.html
+    pub fn play_notes<D>(&mut self, notes: &[Note], delay: &mut D)
+    where
+        D: DelayMs<u32>,
+    {
+        for note in notes {
+            self.start(note.frequency);
+            delay.delay_ms(note.duration);
+        }
+        self.stop();
+    }
+
+    /// Stop playing and release the PWM output
+    pub fn free(mut self) -> P {
+        self.stop();
+        self.pin
+    }
+}
+
+/// A note, as played by [`Tone`]
+///
+/// [`Tone`]: struct.Tone.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Note {
+    /// The frequency of the note in Hz, 0 for a rest
+    pub frequency: u32,
+
+    /// The duration of the note in milliseconds
+    pub duration: u32,
+}
+
+impl Note {
+    /// Create a note with the given frequency (in Hz) and duration (in ms)
+    ///
+    /// Use [`Pitch::frequency`] to get the frequency of a musical note.
+    ///
+    /// [`Pitch::frequency`]: enum.Pitch.html#method.frequency
+    pub const fn new(frequency: u32, duration: u32) -> Self {
+        Self {
+            frequency,
+            duration,
+        }
+    }
+
+    /// Create a rest (silence) with the given duration (in ms)
+    pub const fn rest(duration: u32) -> Self {
+        Self::new(0, duration)
+    }
+}
+
+/// The pitch classes of the chromatic scale
+///
+/// Sharps are denoted by an `s` suffix, so `Cs` is C♯ (or D♭).
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Pitch {
+    C,
+    Cs,
+    D,
+    Ds,
+    E,
+    F,
+    Fs,
+    G,
+    Gs,
+    A,
+    As,
+    B,
+}
+
+impl Pitch {
+    /// The frequency of this pitch in the given octave, in Hz
+    ///
+    /// Uses scientific pitch notation and equal temperament, so A in octave 4
+    /// is 440 Hz, and C in octave 4 is middle C. Frequencies are rounded to
+    /// the nearest Hz.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `octave` is larger than 8.
+    pub const fn frequency(self, octave: u8) -> u32 {
+        // The frequencies of octave 8. Each lower octave halves them.
+        const FREQUENCIES: [u32; 12] = [
+            4186, 4435, 4699, 4978, 5274, 5588, 5920, 6272, 6645, 7040, 7459,
+            7902,
+        ];
+
+        assert!(octave <= 8);
+
+        let shift = 8 - octave as u32;
+        let frequency = FREQUENCIES[self as usize];
+
+        (frequency + (1 << shift >> 1)) >> shift
+    }
+}
+
+/// A sequence of notes that is played in the background
+///
+/// Unlike [`Tone::play_notes`], this doesn't block. The melody needs to be
+/// advanced once per millisecond, either by calling [`update`], or by passing
+/// a periodic timer to [`poll`].
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     prelude::*,
+///     tone::{Melody, Note, Pitch, Tone},
+///     Peripherals,
+/// };
+///
+/// let p = Peripherals::take().unwrap();
+///
+/// let swm = p.SWM.split();
+/// let mut syscon = p.SYSCON.split();
+///
+/// #[cfg(feature = "82x")]
+/// let mut swm_handle = swm.handle;
+/// #[cfg(feature = "845")]
+/// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+///
+/// let channels = p.SCT0.start_pwm(1000, 11, &mut syscon.handle);
+/// let (output, _) = swm.movable_functions.sct_out0.assign(
+///     p.pins.pio0_14.into_swm_pin(),
+///     &mut swm_handle,
+/// );
+/// let mut buzzer = Tone::new(channels.out0.attach(output), 1_000_000);
+///
+/// static NOTES: [Note; 3] = [
+///     Note::new(Pitch::G.frequency(4), 200),
+///     Note::rest(100),
+///     Note::new(Pitch::C.frequency(5), 400),
+/// ];
+/// let mut melody = Melody::new(&NOTES);
+///
+/// // Tick every millisecond (the MRT runs at 12 MHz by default)
+/// let mut timer = p.MRT0.split(&mut syscon.handle).mrt0;
+/// timer.start(12_000u32);
+///
+/// loop {
+///     match melody.poll(&mut buzzer, &mut timer) {
+///         Ok(()) => break, // melody has finished
+///         Err(_) => {
+///             // do other work
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Tone::play_notes`]: struct.Tone.html#method.play_notes
+/// [`update`]: #method.update
+/// [`poll`]: #method.poll
+pub struct Melody<'a> {
+    notes: &'a [Note],
+
+    /// The index of the next note
+    next: usize,
+
+    /// The milliseconds left of the current note
+    remaining: u32,
+}
+
+impl<'a> Melody<'a> {
+    /// Create a melody from a sequence of notes
+    pub const fn new(notes: &'a [Note]) -> Self {
+        Self {
+            notes,
+            next: 0,
+            remaining: 0,
+        }
+    }
+
+    /// Advance the melody by one millisecond
+    ///
+    /// Starts the next note on `tone`, once the current one has finished.
+    /// Returns `true`, while the melody is playing, and `false` once all notes
+    /// have been played. The tone is stopped at that point.
+    pub fn update<P>(&mut self, tone: &mut Tone<P>) -> bool
+    where
+        P: TonePin,
+    {
+        while self.remaining == 0 {
+            let note = match self.notes.get(self.next) {
+                Some(note) => note,
+                None => {
+                    tone.stop();
+                    return false;
+                }
+            };
+
+            tone.start(note.frequency);
+            self.next += 1;
+            self.remaining = note.duration;
+        }
+
+        self.remaining -= 1;
+        true
+    }
+
+    /// Advance the melody, whenever a periodic timer has expired
+    ///
+    /// The timer needs to have been started with an interval of one
+    /// millisecond. Returns `Ok`, once all notes have been played, and
+    /// `nb::Error::WouldBlock` before that.
+    pub fn poll<P, T>(
+        &mut self,
+        tone: &mut Tone<P>,
+        timer: &mut T,
+    ) -> nb::Result<(), Void>
+    where
+        P: TonePin,
+        T: CountDown,
+    {
+        if timer.wait().is_err() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        if self.update(tone) {
+            Err(nb::Error::WouldBlock)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Start playing the melody from the beginning
+    ///
+    /// The next call to [`update`] or [`poll`] starts the first note.
+    ///
+    /// [`update`]: #method.update
+    /// [`poll`]: #method.poll
+    pub fn restart(&mut self) {
+        self.next = 0;
+        self.remaining = 0;
+    }
+}
+
+/// Implemented for PWM outputs that can be used to play tones
+///
+/// This trait is an internal implementation detail and should neither be
+/// implemented nor used outside of LPC8xx HAL. Any changes to this trait won't
+/// be considered breaking changes.
+pub trait TonePin: PwmPin<Duty = u32> {
+    /// Change the period of the PWM timer
+    ///
+    /// `period` is the number of timer ticks per period. The new period takes
+    /// effect at the start of the next period. As the period is shared by all
+    /// PWM outputs of the timer, it changes for all of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `period` is zero.
+    fn set_period(&mut self, period: u32);
+
+    /// Hold the output low
+    ///
+    /// Not all timers do that at a duty cycle of 0, so this depends on the
+    /// timer. Playing a tone sets the duty cycle again.
+    fn silence(&mut self);
+}
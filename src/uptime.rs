@@ -0,0 +1,199 @@
+//! Uptime keeping, driven by the self-wake-up timer (WKT)
+//!
+//! The LPC800 series doesn't have a real-time clock. [`Uptime`] fills part of
+//! that gap, by keeping track of the time since it was created, in
+//! milliseconds, using the WKT. When running from the low-power clock, the WKT
+//! keeps counting in deep-sleep and power-down modes, so the uptime stays
+//! correct across those periods. Since the WKT can wake up the microcontroller,
+//! `Uptime` can be used as the wake-up source at the same time.
+//!
+//! The WKT needs to be restarted whenever it reaches zero. This is done by
+//! [`Uptime::handle_interrupt`], which needs to be called from the WKT
+//! interrupt handler. Any time between the WKT reaching zero and the interrupt
+//! being handled is lost, so the interrupt should have a high priority. Longer
+//! periods mean that less time is lost overall.
+//!
+//! If the `systick-uptime` feature is enabled, [`SysTickUptime`] provides an
+//! alternative, that uses SysTick to maintain a global millisecond counter.
+//! It doesn't need to be driven by the application, and the current time can
+//! be read from anywhere, using [`Instant::now`].
+//!
+//! # Accuracy
+//!
+//! The low-power clock is not very accurate. Its frequency can be off from its
+//! nominal 10 kHz by tens of percent, and changes with temperature and supply
+//! voltage. If accuracy is important, the actual frequency should be measured
+//! against a more accurate clock (like the main clock, or a time source
+//! external to the microcontroller), and passed to [`Uptime::calibrate`].
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{Peripherals, uptime::Uptime};
+//!
+//! let mut p = Peripherals::take().unwrap();
+//!
+//! let mut pmu = p.PMU.split();
+//! let mut syscon = p.SYSCON.split();
+//!
+//! let low_power_clock = pmu.low_power_clock.enable(&mut pmu.handle);
+//! let wkt = p.WKT.enable(&mut syscon.handle);
+//!
+//! // Restart the WKT every 10 seconds
+//! let mut uptime = Uptime::new(wkt, &low_power_clock, 100_000);
+//!
+//! // Call this from the WKT interrupt handler
+//! uptime.handle_interrupt();
+//!
+//! let now = uptime.now_ms();
+//! ```
+//!
+//! [`Uptime`]: struct.Uptime.html
+//! [`Uptime::handle_interrupt`]: struct.Uptime.html#method.handle_interrupt
+//! [`Uptime::calibrate`]: struct.Uptime.html#method.calibrate
+//! [`SysTickUptime`]: struct.SysTickUptime.html
+//! [`Instant::now`]: struct.Instant.html#method.now
+
+#[cfg(feature = "systick-uptime")]
+mod systick;
+
+#[cfg(feature = "systick-uptime")]
+pub use self::systick::{Instant, SysTickUptime};
+
+use embedded_hal::timer::CountDown as _;
+
+use crate::{
+    clock,
+    wkt::{self, WKT},
+};
+
+/// Keeps track of the time since its creation, using the WKT
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct Uptime {
+    wkt: WKT,
+    frequency: u32,
+    period: u32,
+
+    /// WKT clock ticks before the current period started
+    ticks: u64,
+
+    /// The uptime at `base_ticks`, in milliseconds
+    base_ms: u64,
+
+    /// The tick count, when the frequency was last changed
+    base_ticks: u64,
+}
+
+impl Uptime {
+    /// Start keeping track of the uptime
+    ///
+    /// Selects `clock` as the WKT's clock source, and starts the WKT with
+    /// `period`, in clock ticks. The uptime starts at zero.
+    ///
+    /// The WKT interrupt needs to be enabled in the NVIC, and
+    /// [`handle_interrupt`] needs to be called from the interrupt handler.
+    ///
+    /// [`handle_interrupt`]: #method.handle_interrupt
+    pub fn new<C>(mut wkt: WKT, clock: &C, period: u32) -> Self
+    where
+        C: wkt::Clock + clock::Frequency + clock::Enabled,
+    {
+        let period = period.max(1);
+
+        wkt.select_clock::<C>();
+        wkt.start(period);
+
+        Self {
+            wkt,
+            frequency: clock.hz(),
+            period,
+            ticks: 0,
+            base_ms: 0,
+            base_ticks: 0,
+        }
+    }
+
+    /// Handle the WKT interrupt
+    ///
+    /// Accounts for the WKT period that has just ended, and restarts the WKT.
+    /// Must be called from the WKT interrupt handler. Does nothing, if the WKT
+    /// hasn't reached zero yet.
+    pub fn handle_interrupt(&mut self) {
+        if self.wkt.wait().is_err() {
+            return;
+        }
+
+        self.ticks += u64::from(self.period);
+        self.wkt.start(self.period);
+    }
+
+    /// The time since this instance was created, in milliseconds
+    pub fn now_ms(&self) -> u64 {
+        let ticks = self.ticks() - self.base_ticks;
+        self.base_ms + ticks * 1000 / u64::from(self.frequency)
+    }
+
+    /// The number of WKT clock ticks since this instance was created
+    ///
+    /// Unlike [`now_ms`], this isn't affected by [`calibrate`]. Comparing the
+    /// tick count at two points in time against a more accurate reference can
+    /// be used to determine the actual clock frequency.
+    ///
+    /// [`now_ms`]: #method.now_ms
+    /// [`calibrate`]: #method.calibrate
+    pub fn ticks(&self) -> u64 {
+        let elapsed = self.period.saturating_sub(self.wkt.value());
+        self.ticks + u64::from(elapsed)
+    }
+
+    /// The clock frequency that is currently assumed, in Hz
+    pub fn frequency(&self) -> u32 {
+        self.frequency
+    }
+
+    /// Correct the assumed clock frequency
+    ///
+    /// `ticks` is the number of clock ticks (as returned by [`ticks`]) that
+    /// were counted during an interval of `reference_ms` milliseconds, as
+    /// measured by a more accurate reference. From now on, the uptime
+    /// advances according to the frequency computed from those values. The
+    /// uptime that has accumulated so far is not changed.
+    ///
+    /// Does nothing, if either value is zero.
+    ///
+    /// [`ticks`]: #method.ticks
+    pub fn calibrate(&mut self, ticks: u64, reference_ms: u64) {
+        if ticks == 0 || reference_ms == 0 {
+            return;
+        }
+
+        let frequency = ticks * 1000 / reference_ms;
+        self.set_frequency(frequency.clamp(1, u64::from(u32::MAX)) as u32);
+    }
+
+    /// Set the assumed clock frequency, in Hz
+    ///
+    /// From now on, the uptime advances according to the new frequency. The
+    /// uptime that has accumulated so far is not changed.
+    ///
+    /// Does nothing, if `frequency` is zero.
+    pub fn set_frequency(&mut self, frequency: u32) {
+        if frequency == 0 {
+            return;
+        }
+
+        self.base_ms = self.now_ms();
+        self.base_ticks = self.ticks();
+        self.frequency = frequency;
+    }
+
+    /// Release the WKT
+    ///
+    /// The WKT keeps running until it reaches zero.
+    pub fn free(self) -> WKT {
+        self.wkt
+    }
+}
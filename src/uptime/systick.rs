@@ -0,0 +1,187 @@
+use core::{
+    cell::Cell,
+    ops::{Add, Sub},
+    time::Duration,
+};
+
+use cortex_m::{
+    interrupt::{self, Mutex},
+    peripheral::syst::SystClkSource,
+};
+
+use crate::pac::SYST;
+
+/// The number of milliseconds since SysTick was started
+static MILLIS: Mutex<Cell<u64>> = Mutex::new(Cell::new(0));
+
+/// Keeps a global millisecond counter, using SysTick
+///
+/// This struct is only available, if the `systick-uptime` feature is enabled.
+/// It configures SysTick to fire its exception once per millisecond, and
+/// provides a SysTick handler that increments a 64-bit counter, which won't
+/// overflow for as long as the microcontroller is going to run. The counter
+/// can be read from anywhere using [`Instant::now`].
+///
+/// Since this feature provides the SysTick handler, the application can't
+/// define its own. SysTick also can't be used for anything else (like
+/// [`Delay`]) while the counter is running.
+///
+/// Unlike [`Uptime`], this doesn't need to be driven by the application, but
+/// SysTick doesn't run in deep-sleep and power-down modes, so the counter
+/// doesn't advance while in those modes.
+///
+/// # Example
+///
+/// ``` no_run
+/// use core::time::Duration;
+///
+/// use lpc8xx_hal::{
+///     pac::CorePeripherals,
+///     uptime::{Instant, SysTickUptime},
+/// };
+///
+/// let cp = CorePeripherals::take().unwrap();
+///
+/// // The system clock runs at 12 MHz by default
+/// let uptime = SysTickUptime::start(cp.SYST, 12_000_000);
+///
+/// let start = Instant::now();
+/// while start.elapsed() < Duration::from_millis(500) {
+///     // do stuff
+/// }
+/// ```
+///
+/// [`Instant::now`]: struct.Instant.html#method.now
+/// [`Delay`]: ../delay/struct.Delay.html
+/// [`Uptime`]: struct.Uptime.html
+pub struct SysTickUptime {
+    syst: SYST,
+}
+
+impl SysTickUptime {
+    /// Start the millisecond counter
+    ///
+    /// `system_clock` is the frequency of the system clock in Hz. The counter
+    /// continues from its previous value, if it has been started before.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `system_clock` is below 1 kHz, or too high for SysTick's
+    /// 24-bit counter to count a millisecond.
+    pub fn start(mut syst: SYST, system_clock: u32) -> Self {
+        let reload = system_clock / 1000;
+        assert!(reload > 0 && reload <= 0x100_0000);
+
+        syst.set_clock_source(SystClkSource::Core);
+        syst.set_reload(reload - 1);
+        syst.clear_current();
+        syst.enable_interrupt();
+        syst.enable_counter();
+
+        Self { syst }
+    }
+
+    /// Stop the millisecond counter and release SysTick
+    ///
+    /// The counter keeps its value, so [`Instant::now`] still returns the
+    /// point in time at which it was stopped.
+    ///
+    /// [`Instant::now`]: struct.Instant.html#method.now
+    pub fn free(mut self) -> SYST {
+        self.syst.disable_interrupt();
+        self.syst.disable_counter();
+
+        self.syst
+    }
+}
+
+/// A point in time, as measured by [`SysTickUptime`]
+///
+/// Instants have a resolution of one millisecond, and are monotonic, as long
+/// as the counter is running.
+///
+/// [`SysTickUptime`]: struct.SysTickUptime.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Instant {
+    millis: u64,
+}
+
+impl Instant {
+    /// The current point in time
+    ///
+    /// Can be called from any context, including interrupt handlers. Returns
+    /// zero, if the counter hasn't been started yet.
+    pub fn now() -> Self {
+        Self {
+            millis: interrupt::free(|cs| MILLIS.borrow(cs).get()),
+        }
+    }
+
+    /// Create an instant from a number of milliseconds since the start
+    pub const fn from_millis(millis: u64) -> Self {
+        Self { millis }
+    }
+
+    /// The number of milliseconds since the counter was started
+    pub const fn as_millis(&self) -> u64 {
+        self.millis
+    }
+
+    /// The time that has passed since this instant
+    pub fn elapsed(&self) -> Duration {
+        Self::now().duration_since(*self)
+    }
+
+    /// The time that has passed between `earlier` and this instant
+    ///
+    /// Returns zero, if `earlier` is actually later than this instant.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        Duration::from_millis(self.millis.saturating_sub(earlier.millis))
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self {
+        Self {
+            millis: self.millis + rhs.as_millis() as u64,
+        }
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self {
+        Self {
+            millis: self.millis.saturating_sub(rhs.as_millis() as u64),
+        }
+    }
+}
+
+impl Sub for Instant {
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Duration {
+        self.duration_since(rhs)
+    }
+}
+
+// Defining an exception handler only makes sense on the target. This also
+// keeps the doc tests building on the host.
+#[cfg(target_os = "none")]
+mod handler {
+    use cortex_m::interrupt;
+    use cortex_m_rt::exception;
+
+    use super::MILLIS;
+
+    #[exception]
+    fn SysTick() {
+        interrupt::free(|cs| {
+            let millis = MILLIS.borrow(cs);
+            millis.set(millis.get() + 1);
+        });
+    }
+}
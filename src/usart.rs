@@ -34,7 +34,8 @@
 //!     syscon.uartfrg.set_clkdiv(6);
 //!     syscon.uartfrg.set_frgmult(22);
 //!     syscon.uartfrg.set_frgdiv(0xff);
-//!     usart::Clock::new(&syscon.uartfrg, 0, 16)
+//!     let uartfrg = syscon.uartfrg.share();
+//!     usart::Clock::new(&uartfrg, 0, 16)
 //! };
 //! #[cfg(feature = "845")]
 //! let clock_config = usart::Clock::new_with_baudrate(115200);
@@ -68,15 +69,33 @@
 //! [examples in the repository]: https://github.com/lpc-rs/lpc8xx-hal/tree/master/examples
 
 mod clock;
+mod frame_receiver;
+mod half_duplex;
 mod instances;
 mod peripheral;
 mod rx;
+mod shared_tx;
+#[cfg(feature = "serial-stats")]
+mod stats;
 mod tx;
+mod tx_dma;
+mod tx_fifo;
 
 pub use self::{
     clock::Clock,
+    frame_receiver::{FrameError, FrameReceiver},
+    half_duplex::HalfDuplex,
     instances::Instance,
-    peripheral::USART,
-    rx::{Error, Rx},
+    peripheral::{
+        AutobaudError, Events, Parity, RxOnlyFunctions, SelfTestError,
+        Snapshot, StopBits, SwmFunctions, TxOnlyFunctions, USART,
+    },
+    rx::{Character, Error, Rx},
+    shared_tx::SharedTx,
     tx::Tx,
+    tx_dma::TxDma,
+    tx_fifo::TxFifo,
 };
+
+#[cfg(feature = "serial-stats")]
+pub use self::stats::{RxStats, TxStats};
@@ -8,10 +8,57 @@ use crate::syscon::{
 use super::instances::Instance;
 
 /// Defines the clock configuration for a USART instance
+///
+/// All constructors are `const fn`s, so a clock configuration can be computed
+/// at compile time. Where no clock source token is required, it can live in a
+/// `const` or `static`:
+///
+/// ```
+/// # #[cfg(feature = "845")]
+/// # {
+/// use lpc8xx_hal::{syscon::IOSC, usart};
+///
+/// static CLOCK: usart::Clock<IOSC> = usart::Clock::new_with_baudrate(115200);
+/// # }
+/// ```
+///
+/// Invalid arguments make the compilation fail in that case, instead of
+/// panicking at runtime.
+///
+/// The type parameter is the clock source that the configuration was computed
+/// for. On LPC845, each USART instance selects its own clock source, and
+/// `USART::enable` selects the source of the configuration it is passed. This
+/// means different instances can run from different sources:
+///
+/// ``` no_run
+/// # #[cfg(feature = "845")]
+/// # {
+/// use lpc8xx_hal::{syscon::frg, usart, Peripherals};
+///
+/// let p = Peripherals::take().unwrap();
+/// let mut syscon = p.SYSCON.split();
+///
+/// syscon.frg0.select_clock(frg::Clock::FRO);
+/// syscon.frg0.set_mult(22);
+/// syscon.frg0.set_div(0xff);
+/// let frg0 = syscon.frg0.share();
+///
+/// // For the instance that is passed this configuration, selects FRG0
+/// let clock_a = usart::Clock::new(&frg0, 0, 16);
+///
+/// // Selects the main clock, which runs at 12 MHz by default
+/// let clock_b =
+///     usart::Clock::new_with_source(&syscon.main_clock, 12_000_000, 9600);
+///
+/// // Selects fro_div, which runs at 6 MHz by default
+/// let clock_c =
+///     usart::Clock::new_with_source(&syscon.fro_div, 6_000_000, 115200);
+/// # }
+/// ```
 pub struct Clock<Clock> {
     pub(crate) psc: u16,
     pub(crate) osrval: u8,
-    pub(crate) _clock: PhantomData<Clock>,
+    pub(crate) _clock: PhantomData<fn() -> Clock>,
 }
 
 impl<C> Clock<C>
@@ -21,7 +68,7 @@ where
     /// Create the clock config for the uart
     ///
     /// `osrval` has to be between 5-16
-    pub fn new(_: &C, psc: u16, osrval: u8) -> Self {
+    pub const fn new(_: &C, psc: u16, osrval: u8) -> Self {
         let osrval = osrval - 1;
         assert!(osrval > 3 && osrval < 0x10);
 
@@ -31,10 +78,87 @@ where
             _clock: PhantomData,
         }
     }
+
+    /// Create a clock config for a baud rate, given the source frequency
+    ///
+    /// `source` is the frequency of the clock source in Hz. Searches all
+    /// oversample rates (5-16) for the combination with the baud rate
+    /// generator that comes closest to `baudrate`. If multiple combinations
+    /// are equally close, the highest oversample rate is used, as that makes
+    /// reception more robust.
+    ///
+    /// Lower oversample rates allow higher baud rates from a given source. For
+    /// example, 1 Mbaud can only be reached from a 12 MHz source with an
+    /// oversample rate of 6 or 12. Use [`baudrate`] to check how close the
+    /// result is to the requested baud rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `baudrate` is 0, or higher than `source / 5`.
+    ///
+    /// [`baudrate`]: #method.baudrate
+    pub const fn new_with_source(_: &C, source: u32, baudrate: u32) -> Self {
+        Self::search(source, baudrate)
+    }
+}
+
+impl<C> Clock<C> {
+    /// Compute the baud rate that results from this configuration
+    ///
+    /// `source` is the frequency of the clock source in Hz.
+    pub const fn baudrate(&self, source: u32) -> u32 {
+        source / ((self.psc as u32 + 1) * (self.osrval as u32 + 1))
+    }
+
+    /// The oversample rate of this configuration
+    ///
+    /// This is the number of clock cycles per bit after the baud rate
+    /// generator, between 5 and 16.
+    pub const fn oversampling(&self) -> u8 {
+        self.osrval + 1
+    }
+
+    const fn search(source: u32, baudrate: u32) -> Self {
+        assert!(baudrate > 0 && baudrate <= source / 5);
+
+        let mut best_psc = 0;
+        let mut best_osr = 16;
+        let mut best_error = u32::MAX;
+
+        let mut osr = 16;
+        while osr >= 5 {
+            // Round to the nearest divider, but don't go below 1.
+            let bit = baudrate * osr;
+            let mut div = (source + bit / 2) / bit;
+            if div == 0 {
+                div = 1;
+            }
+            if div > 0x10000 {
+                div = 0x10000;
+            }
+
+            let actual = source / (div * osr);
+            let error = actual.abs_diff(baudrate);
+            if error < best_error {
+                best_psc = div - 1;
+                best_osr = osr;
+                best_error = error;
+            }
+
+            osr -= 1;
+        }
+
+        Self {
+            psc: best_psc as u16,
+            osrval: best_osr as u8 - 1,
+            _clock: PhantomData,
+        }
+    }
 }
 
 #[cfg(feature = "82x")]
-impl<I> PeripheralClock<I> for Clock<crate::syscon::UARTFRG>
+impl<I> PeripheralClock<I>
+    for Clock<crate::syscon::clock_source::Shared<crate::syscon::UARTFRG>>
 where
     I: Instance,
 {
@@ -47,23 +171,13 @@ where
 impl Clock<crate::syscon::IOSC> {
     /// Create a new configuration with a specified baudrate
     ///
-    /// Assumes the internal oscillator runs at 12 MHz
-    pub fn new_with_baudrate(baudrate: u32) -> Self {
-        // We want something with 5% tolerance
-        let calc = baudrate * 20;
-        let mut osrval = 5;
-        for i in (5..=16).rev() {
-            if calc * (i as u32) < 12_000_000 {
-                osrval = i;
-            }
-        }
-        let psc = (12_000_000 / (baudrate * osrval as u32) - 1) as u16;
-        let osrval = osrval - 1;
-        Self {
-            psc,
-            osrval,
-            _clock: PhantomData,
-        }
+    /// Assumes the internal oscillator runs at 12 MHz. Picks the oversample
+    /// rate that gets closest to `baudrate`, as described for
+    /// [`new_with_source`].
+    ///
+    /// [`new_with_source`]: #method.new_with_source
+    pub const fn new_with_baudrate(baudrate: u32) -> Self {
+        Self::search(12_000_000, baudrate)
     }
 }
 
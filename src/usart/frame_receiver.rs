@@ -0,0 +1,185 @@
+use embedded_hal::serial::Read;
+
+/// Collects received characters into frames that end with a delimiter
+///
+/// Many protocols send their data in frames that are terminated by a specific
+/// character, like the newline at the end of a line of text. `FrameReceiver`
+/// collects characters into a buffer, until the delimiter arrives, and then
+/// notifies the caller that a complete frame is available.
+///
+/// The USART's hardware address detection can only match the 9th bit of a
+/// character, which doesn't help with 8-bit protocols. `FrameReceiver` does
+/// the matching in software instead. It is meant to be fed from the RXRDY
+/// interrupt handler (see [`USART::enable_rxrdy`]), so the delimiter is
+/// detected right after it is received, without polling. Calling
+/// [`receive`] from the main loop works too, as long as it is called often
+/// enough to not miss any characters.
+///
+/// [`new`] is a `const fn`, so a `FrameReceiver` can be placed in a `static`,
+/// to share it between the interrupt handler and the main loop.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::usart::FrameReceiver;
+///
+/// # let mut rx: lpc8xx_hal::usart::Rx<lpc8xx_hal::pac::USART0> =
+/// #     unsafe { lpc8xx_hal::usart::Rx::steal() };
+/// #
+/// // Receives lines of up to 64 characters
+/// let mut lines = FrameReceiver::<64>::new(b'\n');
+///
+/// loop {
+///     // This would typically be done in the interrupt handler.
+///     if lines.receive(&mut rx).is_ok() {
+///         let line = lines.frame().unwrap();
+///         // process line
+///
+///         lines.clear();
+///     }
+/// }
+/// ```
+///
+/// [`USART::enable_rxrdy`]: struct.USART.html#method.enable_rxrdy
+/// [`receive`]: #method.receive
+/// [`new`]: #method.new
+pub struct FrameReceiver<const N: usize> {
+    buffer: [u8; N],
+    len: usize,
+    delimiter: u8,
+    complete: bool,
+    dropped: usize,
+}
+
+impl<const N: usize> FrameReceiver<N> {
+    /// Create a new instance of `FrameReceiver`
+    ///
+    /// Frames are terminated by `delimiter`, and can be up to `N` characters
+    /// long, not counting the delimiter.
+    pub const fn new(delimiter: u8) -> Self {
+        Self {
+            buffer: [0; N],
+            len: 0,
+            delimiter,
+            complete: false,
+            dropped: 0,
+        }
+    }
+
+    /// Receive all characters that are available
+    ///
+    /// Reads from `rx`, until no more characters are available, or the
+    /// delimiter has been received. Returns `Ok`, once a frame is complete,
+    /// and `nb::Error::WouldBlock` otherwise.
+    ///
+    /// While a complete frame is waiting to be discarded using [`clear`],
+    /// characters are still read, so the RXRDY interrupt doesn't fire again
+    /// right away, but they are dropped. [`dropped`] returns how many
+    /// characters were lost that way.
+    ///
+    /// If the frame doesn't fit into the buffer, it is discarded, and
+    /// [`FrameError::Overflow`] is returned. Characters received after that
+    /// are collected into the next frame, which will most likely lack its
+    /// beginning. A receive error also discards the current frame.
+    ///
+    /// [`clear`]: #method.clear
+    /// [`dropped`]: #method.dropped
+    /// [`FrameError::Overflow`]: enum.FrameError.html#variant.Overflow
+    pub fn receive<R>(
+        &mut self,
+        rx: &mut R,
+    ) -> nb::Result<(), FrameError<R::Error>>
+    where
+        R: Read<u8>,
+    {
+        if self.complete {
+            self.drop_pending(rx);
+            return Ok(());
+        }
+
+        loop {
+            let character = match rx.read() {
+                Ok(character) => character,
+                Err(nb::Error::WouldBlock) => {
+                    return Err(nb::Error::WouldBlock)
+                }
+                Err(nb::Error::Other(err)) => {
+                    self.len = 0;
+                    return Err(nb::Error::Other(FrameError::Serial(err)));
+                }
+            };
+
+            if character == self.delimiter {
+                self.complete = true;
+                return Ok(());
+            }
+
+            if self.len >= N {
+                self.len = 0;
+                return Err(nb::Error::Other(FrameError::Overflow));
+            }
+
+            self.buffer[self.len] = character;
+            self.len += 1;
+        }
+    }
+
+    fn drop_pending<R>(&mut self, rx: &mut R)
+    where
+        R: Read<u8>,
+    {
+        loop {
+            match rx.read() {
+                Ok(_) | Err(nb::Error::Other(_)) => {
+                    self.dropped = self.dropped.saturating_add(1)
+                }
+                Err(nb::Error::WouldBlock) => return,
+            }
+        }
+    }
+
+    /// Access the complete frame, without the delimiter
+    ///
+    /// Returns `None`, if no frame has been completed yet.
+    pub fn frame(&self) -> Option<&[u8]> {
+        if self.complete {
+            Some(&self.buffer[..self.len])
+        } else {
+            None
+        }
+    }
+
+    /// Indicates whether a complete frame is available
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Returns the number of characters dropped since the frame was complete
+    ///
+    /// Characters that arrive while a complete frame is still available are
+    /// dropped. This counter is reset by [`clear`].
+    ///
+    /// [`clear`]: #method.clear
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Discard the current frame, and start receiving the next one
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.complete = false;
+        self.dropped = 0;
+    }
+}
+
+/// An error reported by [`FrameReceiver::receive`]
+///
+/// [`FrameReceiver::receive`]: struct.FrameReceiver.html#method.receive
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FrameError<E> {
+    /// The frame was longer than the buffer
+    Overflow,
+
+    /// An error occurred while receiving a character
+    Serial(E),
+}
@@ -0,0 +1,237 @@
+use embedded_hal::{
+    blocking::serial::write::Default as BlockingWriteDefault,
+    serial::{Read, Write},
+};
+use void::Void;
+
+use crate::init_state;
+
+use super::{
+    instances::Instance,
+    peripheral::{SwmFunctions, USART},
+    rx::Error,
+};
+
+/// A USART in single-wire half-duplex mode
+///
+/// In single-wire half-duplex mode, the USART transmits and receives on the
+/// same pin. This is used by bus protocols, where multiple devices share a
+/// single data line, like the ones used by Dynamixel servos or some sensors.
+///
+/// `HalfDuplex` is created from a [`USART`] whose RX and TX functions are
+/// assigned to the same pin. This requirement is enforced at compile time.
+///
+/// # Direction management
+///
+/// The LPC8xx USART has no dedicated single-wire mode, so the following
+/// approach is used:
+///
+/// - The pin must be configured as open-drain in IOCON (see [`Pin::iocon`]
+///   and [`Pin::reset_iocon`]). This way, the USART only drives the line low
+///   while sending a zero bit, and releases it otherwise, allowing other
+///   devices to drive it. The line needs a pull-up resistor. The internal
+///   pull-up, which is enabled by default, is only strong enough for low baud
+///   rates and short wires.
+/// - Since the receiver listens on the same pin, it receives every character
+///   that is sent. `HalfDuplex` discards those echoes, so [`Read::read`] only
+///   returns characters sent by other devices.
+/// - Each echo is compared to the character that was sent. If they don't
+///   match, another device has driven the line at the same time, and a
+///   collision is recorded (see [`is_collision_detected`]).
+///
+/// A character is only written, once the echo of the previous character has
+/// been received. Once [`Write::flush`] returns, the transmission is complete,
+/// and the line has been released for other devices to respond. No additional
+/// turnaround time is required.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     prelude::*,
+///     Peripherals,
+///     usart::{self, HalfDuplex},
+/// };
+///
+/// let mut p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+/// let mut swm = p.SWM.split();
+///
+/// #[cfg(feature = "82x")]
+/// let mut swm_handle = swm.handle;
+/// #[cfg(feature = "845")]
+/// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+///
+/// // The HAL can only read and reset the IOCON configuration, so open-drain
+/// // mode needs to be enabled using the PAC.
+/// let mut pio0_4 = p.pins.pio0_4;
+/// pio0_4.reset_iocon(&mut syscon.handle);
+/// p.IOCON.pio0_4.modify(|_, w| w.od().enabled());
+///
+/// #[cfg(feature = "82x")]
+/// let clock_config = {
+///     syscon.uartfrg.set_clkdiv(6);
+///     syscon.uartfrg.set_frgmult(22);
+///     syscon.uartfrg.set_frgdiv(0xff);
+///     let uartfrg = syscon.uartfrg.share();
+///     usart::Clock::new(&uartfrg, 0, 16)
+/// };
+/// #[cfg(feature = "845")]
+/// let clock_config = usart::Clock::new_with_baudrate(115200);
+///
+/// // Assign RX and TX to the same pin
+/// let (u0_txd, pio0_4) = swm.movable_functions.u0_txd.assign(
+///     pio0_4.into_swm_pin(),
+///     &mut swm_handle,
+/// );
+/// let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+///     pio0_4,
+///     &mut swm_handle,
+/// );
+///
+/// let usart = p.USART0.enable(
+///     &clock_config,
+///     &mut syscon.handle,
+///     u0_rxd,
+///     u0_txd,
+/// );
+/// let mut serial = HalfDuplex::new(usart);
+///
+/// // Send a request, then wait for the response
+/// serial.bwrite_all(&[0xff, 0xff, 0x01, 0x02, 0x01, 0xfb]);
+/// serial.bflush();
+/// let response = nb::block!(serial.read());
+/// ```
+///
+/// [`USART`]: struct.USART.html
+/// [`Read::read`]: #method.read
+/// [`Write::flush`]: #method.flush
+/// [`is_collision_detected`]: #method.is_collision_detected
+/// [`Pin::iocon`]: ../pins/struct.Pin.html#method.iocon
+/// [`Pin::reset_iocon`]: ../pins/struct.Pin.html#method.reset_iocon
+pub struct HalfDuplex<I: Instance, Pin> {
+    usart: USART<I, init_state::Enabled<SwmFunctions<I, Pin, Pin>>>,
+    echo: Option<u8>,
+    collision: bool,
+}
+
+impl<I, Pin> HalfDuplex<I, Pin>
+where
+    I: Instance,
+{
+    /// Put the USART into single-wire half-duplex mode
+    ///
+    /// The pin needs to be configured as open-drain. Please refer to the
+    /// [struct documentation] for details.
+    ///
+    /// [struct documentation]: struct.HalfDuplex.html
+    pub fn new(
+        usart: USART<I, init_state::Enabled<SwmFunctions<I, Pin, Pin>>>,
+    ) -> Self {
+        Self {
+            usart,
+            echo: None,
+            collision: false,
+        }
+    }
+
+    /// Indicates whether a collision has been detected
+    ///
+    /// Returns `true`, if a character that was received back while sending
+    /// didn't match the character that was sent, since the flag was last
+    /// cleared using [`clear_collision`].
+    ///
+    /// [`clear_collision`]: #method.clear_collision
+    pub fn is_collision_detected(&self) -> bool {
+        self.collision
+    }
+
+    /// Clear the collision flag
+    pub fn clear_collision(&mut self) {
+        self.collision = false;
+    }
+
+    /// Return the USART
+    ///
+    /// Any echo that hasn't been received yet will be returned by the next
+    /// read from the USART.
+    pub fn free(
+        self,
+    ) -> USART<I, init_state::Enabled<SwmFunctions<I, Pin, Pin>>> {
+        self.usart
+    }
+
+    /// Discard the echo of the last character sent, if available
+    ///
+    /// Returns `true`, if the echo is still pending.
+    fn discard_echo(&mut self) -> bool {
+        let sent = match self.echo {
+            Some(sent) => sent,
+            None => return false,
+        };
+
+        match self.usart.rx.read_with_status() {
+            Ok(character) => {
+                if character.data != sent || character.framing_error {
+                    self.collision = true;
+                }
+            }
+            Err(nb::Error::Other(_)) => {
+                // The echo has been overwritten by another character, which
+                // means the line wasn't free while we were sending.
+                self.collision = true;
+            }
+            Err(nb::Error::WouldBlock) => return true,
+        }
+
+        self.echo = None;
+        false
+    }
+}
+
+impl<I, Pin> Read<u8> for HalfDuplex<I, Pin>
+where
+    I: Instance,
+{
+    type Error = Error;
+
+    /// Reads a single word that was sent by another device
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        if self.discard_echo() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.usart.read()
+    }
+}
+
+impl<I, Pin> Write<u8> for HalfDuplex<I, Pin>
+where
+    I: Instance,
+{
+    type Error = Void;
+
+    /// Writes a single word, once the previous one has been sent
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        if self.discard_echo() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.usart.write(word)?;
+        self.echo = Some(word);
+
+        Ok(())
+    }
+
+    /// Ensures that all words have been sent and the line has been released
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        if self.discard_echo() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.usart.flush()
+    }
+}
+
+impl<I, Pin> BlockingWriteDefault<u8> for HalfDuplex<I, Pin> where I: Instance {}
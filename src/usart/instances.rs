@@ -23,11 +23,17 @@ pub trait Instance:
     /// A pointer to this instance's register block
     const REGISTERS: *const pac::usart0::RegisterBlock;
 
+    /// The number of this instance (e.g. `0` for USART0)
+    const NUM: usize;
+
     /// The movable function that needs to be assigned to this USART's RX pin
     type Rx;
 
     /// The movable function that needs to be assigned to this USART's TX pin
     type Tx;
+
+    /// Enables this USART's interrupt as a wake-up source
+    type Wakeup: syscon::WakeUpInterrupt;
 }
 
 macro_rules! instances {
@@ -38,7 +44,8 @@ macro_rules! instances {
             $module:ident,
             $interrupt:ident,
             $rx:ident,
-            $tx:ident;
+            $tx:ident,
+            $wakeup:ident;
         )*
     ) => {
         $(
@@ -46,9 +53,11 @@ macro_rules! instances {
                 const INTERRUPT: Interrupt = Interrupt::$interrupt;
                 const REGISTERS: *const pac::usart0::RegisterBlock =
                     pac::$instance::ptr();
+                const NUM: usize = $clock_num;
 
                 type Rx = swm::$rx;
                 type Tx = swm::$tx;
+                type Wakeup = syscon::$wakeup;
             }
 
             impl PeripheralClockSelector for pac::$instance {
@@ -59,13 +68,13 @@ macro_rules! instances {
 }
 
 instances!(
-    USART0, 0, usart0, USART0, U0_RXD, U0_TXD;
-    USART1, 1, usart1, USART1, U1_RXD, U1_TXD;
-    USART2, 2, usart2, USART2, U2_RXD, U2_TXD;
+    USART0, 0, usart0, USART0, U0_RXD, U0_TXD, Usart0Wakeup;
+    USART1, 1, usart1, USART1, U1_RXD, U1_TXD, Usart1Wakeup;
+    USART2, 2, usart2, USART2, U2_RXD, U2_TXD, Usart2Wakeup;
 );
 
 #[cfg(feature = "845")]
 instances!(
-    USART3, 3, usart3, PIN_INT6_USART3, U3_RXD, U3_TXD;
-    USART4, 4, usart4, PIN_INT7_USART4, U4_RXD, U4_TXD;
+    USART3, 3, usart3, PIN_INT6_USART3, U3_RXD, U3_TXD, Usart3Wakeup;
+    USART4, 4, usart4, PIN_INT7_USART4, U4_RXD, U4_TXD, Usart4Wakeup;
 );
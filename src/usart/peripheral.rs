@@ -4,11 +4,11 @@ use embedded_hal::{
     blocking::serial::write::Default as BlockingWriteDefault,
     serial::{Read, Write},
 };
-use void::Void;
+use void::{ResultVoidExt, Void};
 
 use crate::{
     dma, init_state,
-    pac::NVIC,
+    pac::{usart0::cfg, NVIC},
     pins,
     swm::{self, FunctionTrait},
     syscon::{self, clock_source::PeripheralClock},
@@ -17,10 +17,36 @@ use crate::{
 use super::{
     clock::Clock,
     instances::Instance,
-    rx::{Error, Rx},
+    rx::{Character, Error, Rx},
     tx::Tx,
 };
 
+/// The SWM functions that are kept by an enabled [`USART`] instance
+///
+/// [`USART`]: struct.USART.html
+pub type SwmFunctions<I, RxPin, TxPin> = (
+    swm::Function<<I as Instance>::Rx, swm::state::Assigned<RxPin>>,
+    swm::Function<<I as Instance>::Tx, swm::state::Assigned<TxPin>>,
+);
+
+/// The SWM function that is kept by a send-only [`USART`] instance
+///
+/// See [`USART::enable_tx_only`].
+///
+/// [`USART`]: struct.USART.html
+/// [`USART::enable_tx_only`]: struct.USART.html#method.enable_tx_only
+pub type TxOnlyFunctions<I, TxPin> =
+    swm::Function<<I as Instance>::Tx, swm::state::Assigned<TxPin>>;
+
+/// The SWM function that is kept by a receive-only [`USART`] instance
+///
+/// See [`USART::enable_rx_only`].
+///
+/// [`USART`]: struct.USART.html
+/// [`USART::enable_rx_only`]: struct.USART.html#method.enable_rx_only
+pub type RxOnlyFunctions<I, RxPin> =
+    swm::Function<<I as Instance>::Rx, swm::state::Assigned<RxPin>>;
+
 /// Interface to a USART peripheral
 ///
 /// Controls the USART.  Use [`Peripherals`] to gain access to an instance of
@@ -28,7 +54,9 @@ use super::{
 ///
 /// You can either use this struct as-is, if you need to send and receive in the
 /// same place, or you can move the `rx` and `tx` fields out of this struct, to
-/// use the sender and receiver from different contexts.
+/// use the sender and receiver from different contexts. Please note that once
+/// you've moved those fields out, you can no longer call [`disable`] to get
+/// back the SWM functions.
 ///
 /// Please refer to the [module documentation] for more information.
 ///
@@ -39,6 +67,7 @@ use super::{
 ///
 ///
 /// [`Peripherals`]: ../struct.Peripherals.html
+/// [`disable`]: #method.disable
 /// [module documentation]: index.html
 /// [`embedded_hal::serial::Read`]: #impl-Read%3Cu8%3E
 /// [`embedded_hal::serial::Write`]: #impl-Write%3Cu8%3E
@@ -75,7 +104,8 @@ where
     /// enabled will not compile.
     ///
     /// Consumes this instance of `USART` and returns another instance that has
-    /// its `State` type parameter set to [`Enabled`].
+    /// its `State` type parameter set to [`Enabled`]. The SWM functions are
+    /// kept by the enabled instance, and returned by [`disable`].
     ///
     /// # Limitations
     ///
@@ -89,21 +119,139 @@ where
     ///
     /// [`Disabled`]: ../init_state/struct.Disabled.html
     /// [`Enabled`]: ../init_state/struct.Enabled.html
+    /// [`disable`]: #method.disable
     /// [`BaudRate`]: struct.BaudRate.html
     /// [module documentation]: index.html
     pub fn enable<RxPin, TxPin, CLOCK>(
         self,
         clock: &Clock<CLOCK>,
         syscon: &mut syscon::Handle,
-        _: swm::Function<I::Rx, swm::state::Assigned<RxPin>>,
-        _: swm::Function<I::Tx, swm::state::Assigned<TxPin>>,
-    ) -> USART<I, init_state::Enabled>
+        rx: swm::Function<I::Rx, swm::state::Assigned<RxPin>>,
+        tx: swm::Function<I::Tx, swm::state::Assigned<TxPin>>,
+    ) -> USART<I, init_state::Enabled<SwmFunctions<I, RxPin, TxPin>>>
     where
         RxPin: pins::Trait,
         TxPin: pins::Trait,
         I::Rx: FunctionTrait<RxPin>,
         I::Tx: FunctionTrait<TxPin>,
         Clock<CLOCK>: PeripheralClock<I>,
+    {
+        self.configure(clock, syscon);
+
+        USART {
+            rx: Rx::new(), // can't use `self.rx`, due to state
+            tx: Tx::new(), // can't use `self.tx`, due to state
+            usart: self.usart,
+            _state: init_state::Enabled((rx, tx)),
+        }
+    }
+
+    /// Enable the USART for sending only
+    ///
+    /// Works like [`enable`], except that only the TXD function needs to be
+    /// assigned. This is useful for applications that only send data, like
+    /// debug logging, as the pin that would otherwise be used for RXD
+    /// remains available for other uses.
+    ///
+    /// Since RXD is not assigned, nothing is ever received.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{prelude::*, usart, Peripherals};
+    ///
+    /// let mut p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    /// let mut swm = p.SWM.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let clock_config = {
+    ///     syscon.uartfrg.set_clkdiv(6);
+    ///     syscon.uartfrg.set_frgmult(22);
+    ///     syscon.uartfrg.set_frgdiv(0xff);
+    ///     let uartfrg = syscon.uartfrg.share();
+    ///     usart::Clock::new(&uartfrg, 0, 16)
+    /// };
+    /// #[cfg(feature = "845")]
+    /// let clock_config = usart::Clock::new_with_baudrate(115200);
+    ///
+    /// let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+    ///     p.pins.pio0_4.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    ///
+    /// let mut serial =
+    ///     p.USART0.enable_tx_only(&clock_config, &mut syscon.handle, u0_txd);
+    ///
+    /// serial.bwrite_all(b"Hello, world!");
+    /// ```
+    ///
+    /// [`enable`]: #method.enable
+    pub fn enable_tx_only<TxPin, CLOCK>(
+        self,
+        clock: &Clock<CLOCK>,
+        syscon: &mut syscon::Handle,
+        tx: swm::Function<I::Tx, swm::state::Assigned<TxPin>>,
+    ) -> USART<I, init_state::Enabled<TxOnlyFunctions<I, TxPin>>>
+    where
+        TxPin: pins::Trait,
+        I::Tx: FunctionTrait<TxPin>,
+        Clock<CLOCK>: PeripheralClock<I>,
+    {
+        self.configure(clock, syscon);
+
+        USART {
+            rx: Rx::new(), // can't use `self.rx`, due to state
+            tx: Tx::new(), // can't use `self.tx`, due to state
+            usart: self.usart,
+            _state: init_state::Enabled(tx),
+        }
+    }
+
+    /// Enable the USART for receiving only
+    ///
+    /// Works like [`enable`], except that only the RXD function needs to be
+    /// assigned. This is useful for applications that only receive data, as
+    /// the pin that would otherwise be used for TXD remains available for
+    /// other uses.
+    ///
+    /// Since TXD is not assigned, anything that is sent doesn't leave the
+    /// microcontroller.
+    ///
+    /// [`enable`]: #method.enable
+    pub fn enable_rx_only<RxPin, CLOCK>(
+        self,
+        clock: &Clock<CLOCK>,
+        syscon: &mut syscon::Handle,
+        rx: swm::Function<I::Rx, swm::state::Assigned<RxPin>>,
+    ) -> USART<I, init_state::Enabled<RxOnlyFunctions<I, RxPin>>>
+    where
+        RxPin: pins::Trait,
+        I::Rx: FunctionTrait<RxPin>,
+        Clock<CLOCK>: PeripheralClock<I>,
+    {
+        self.configure(clock, syscon);
+
+        USART {
+            rx: Rx::new(), // can't use `self.rx`, due to state
+            tx: Tx::new(), // can't use `self.tx`, due to state
+            usart: self.usart,
+            _state: init_state::Enabled(rx),
+        }
+    }
+
+    fn configure<CLOCK>(
+        &self,
+        clock: &Clock<CLOCK>,
+        syscon: &mut syscon::Handle,
+    ) where
+        Clock<CLOCK>: PeripheralClock<I>,
     {
         syscon.enable_clock(&self.usart);
 
@@ -139,17 +287,10 @@ where
             w.txdis().enabled();
             w.autobaud().disabled()
         });
-
-        USART {
-            rx: Rx::new(), // can't use `self.rx`, due to state
-            tx: Tx::new(), // can't use `self.tx`, due to state
-            usart: self.usart,
-            _state: init_state::Enabled(()),
-        }
     }
 }
 
-impl<I> USART<I, init_state::Enabled>
+impl<I, Pins> USART<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
@@ -160,22 +301,27 @@ where
     /// disabled will not compile.
     ///
     /// Consumes this instance of `USART` and returns another instance that has
-    /// its `State` type parameter set to [`Disabled`].
+    /// its `State` type parameter set to [`Disabled`], together with the SWM
+    /// functions that were passed to [`enable`]. Those can then be unassigned,
+    /// to reuse the pins for something else.
     ///
     /// [`Enabled`]: ../init_state/struct.Enabled.html
     /// [`Disabled`]: ../init_state/struct.Disabled.html
+    /// [`enable`]: #method.enable
     pub fn disable(
         self,
         syscon: &mut syscon::Handle,
-    ) -> USART<I, init_state::Disabled> {
+    ) -> (USART<I, init_state::Disabled>, Pins) {
         syscon.disable_clock(&self.usart);
 
-        USART {
+        let usart = USART {
             rx: Rx::new(), // can't use `self.rx`, due to state
             tx: Tx::new(), // can't use `self.tx`, due to state
             usart: self.usart,
             _state: init_state::Disabled,
-        }
+        };
+
+        (usart, self._state.0)
     }
 
     /// Enable interrupts for this instance in the NVIC
@@ -196,7 +342,7 @@ where
         NVIC::mask(I::INTERRUPT);
     }
 
-    /// Clear's this instance's interrupt pending flag in the NVIC
+    /// Clear this instance's interrupt pending flag in the NVIC
     ///
     /// This only clears the interrupt's pending flag in the NVIC. It does not
     /// affect any of the interrupt-related flags in the peripheral.
@@ -204,6 +350,33 @@ where
         NVIC::unpend(I::INTERRUPT);
     }
 
+    /// Set this instance's interrupt pending flag in the NVIC
+    ///
+    /// The interrupt handler then runs as if the interrupt had occurred, once
+    /// the interrupt is enabled in the NVIC and its priority allows it. This
+    /// does not set any of the interrupt-related flags in the peripheral.
+    pub fn set_nvic_pending(&mut self) {
+        NVIC::pend(I::INTERRUPT);
+    }
+
+    /// Indicates whether this instance's interrupt is pending in the NVIC
+    pub fn is_nvic_pending(&self) -> bool {
+        NVIC::is_pending(I::INTERRUPT)
+    }
+
+    /// Set the priority of this instance's interrupt in the NVIC
+    ///
+    /// Lower values mean higher priority. Only the two most significant bits of
+    /// `priority` are used by the hardware.
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections,
+    /// like the ones used by RTFM.
+    pub unsafe fn set_nvic_priority(&mut self, nvic: &mut NVIC, priority: u8) {
+        nvic.set_priority(I::INTERRUPT, priority);
+    }
+
     /// Enable the RXRDY interrupt
     ///
     /// See [`Rx::enable_rxrdy`].
@@ -222,6 +395,81 @@ where
         self.rx.disable_rxrdy()
     }
 
+    /// Enable the start bit interrupt
+    ///
+    /// See [`Rx::enable_start`].
+    ///
+    /// [`Rx::enable_start`]: struct.Rx.html#method.enable_start
+    pub fn enable_start(&mut self) {
+        self.rx.enable_start()
+    }
+
+    /// Disable the start bit interrupt
+    ///
+    /// See [`Rx::disable_start`].
+    ///
+    /// [`Rx::disable_start`]: struct.Rx.html#method.disable_start
+    pub fn disable_start(&mut self) {
+        self.rx.disable_start()
+    }
+
+    /// Clear the start bit flag
+    ///
+    /// See [`Rx::clear_start`].
+    ///
+    /// [`Rx::clear_start`]: struct.Rx.html#method.clear_start
+    pub fn clear_start(&mut self) {
+        self.rx.clear_start()
+    }
+
+    /// Enable wake-up from deep-sleep and power-down modes on incoming data
+    ///
+    /// Enables the start bit interrupt and configures the USART interrupt as a
+    /// wake-up source. A start bit on the RX line then wakes up the device,
+    /// provided the interrupt is also enabled in the NVIC (see
+    /// [`enable_in_nvic`]). The interrupt handler needs to clear the start bit
+    /// flag using [`clear_start`].
+    ///
+    /// In asynchronous mode, the USART's clock isn't running in deep-sleep or
+    /// power-down mode. Only the start bit detection works without it. This
+    /// has the following consequences:
+    /// - The character whose start bit woke up the device is lost, unless the
+    ///   clocks are up and running again before its first data bit is sampled.
+    ///   This is only realistic at low baud rates, and only when waking up
+    ///   from deep-sleep mode, as the IRC/FRO takes longer to start up after
+    ///   power-down mode.
+    /// - Protocols should account for that, for example by sending a dummy
+    ///   character before each command, or by having the device acknowledge
+    ///   that it's awake before any real data is sent.
+    /// - The IRC/FRO must be configured to power up on wake-up (which is the
+    ///   default), as it clocks the USART.
+    ///
+    /// [`enable_in_nvic`]: #method.enable_in_nvic
+    /// [`clear_start`]: #method.clear_start
+    pub fn enable_wakeup(&mut self, syscon: &mut syscon::Handle) {
+        self.clear_start();
+        self.enable_start();
+        syscon.enable_interrupt_wakeup::<I::Wakeup>();
+    }
+
+    /// Disable wake-up from deep-sleep and power-down modes on incoming data
+    ///
+    /// Disables the start bit interrupt and removes the USART interrupt from
+    /// the wake-up sources.
+    pub fn disable_wakeup(&mut self, syscon: &mut syscon::Handle) {
+        syscon.disable_interrupt_wakeup::<I::Wakeup>();
+        self.disable_start();
+    }
+
+    /// Read a character, along with its status flags
+    ///
+    /// See [`Rx::read_with_status`].
+    ///
+    /// [`Rx::read_with_status`]: struct.Rx.html#method.read_with_status
+    pub fn read_with_status(&mut self) -> nb::Result<Character, Error> {
+        self.rx.read_with_status()
+    }
+
     /// Enable the TXRDY interrupt
     ///
     /// See [`Tx::enable_txrdy`].
@@ -239,6 +487,500 @@ where
     pub fn disable_txrdy(&mut self) {
         self.tx.disable_txrdy()
     }
+
+    /// Enable loopback mode
+    ///
+    /// In loopback mode, the receiver is internally connected to the
+    /// transmitter, so every character that is sent is also received. The RX
+    /// pin is ignored, while the TX pin keeps working as usual, so anything
+    /// sent in loopback mode still goes out on the wire.
+    ///
+    /// Blocks until the transmitter is idle, as the user manual requires the
+    /// USART to be idle and disabled while its configuration is changed.
+    pub fn enable_loopback(&mut self) {
+        self.set_loopback(true);
+    }
+
+    /// Disable loopback mode
+    ///
+    /// See [`enable_loopback`].
+    ///
+    /// [`enable_loopback`]: #method.enable_loopback
+    pub fn disable_loopback(&mut self) {
+        self.set_loopback(false);
+    }
+
+    /// Change the number of stop bits
+    ///
+    /// [`enable`] configures the USART for one stop bit. Some protocols, like
+    /// DMX512, require two.
+    ///
+    /// Blocks until the transmitter is idle, as the user manual requires the
+    /// USART to be idle and disabled while its configuration is changed.
+    ///
+    /// [`enable`]: #method.enable
+    pub fn set_stop_bits(&mut self, stop_bits: StopBits) {
+        self.modify_cfg(|w| match stop_bits {
+            StopBits::One => w.stoplen().bit_1(),
+            StopBits::Two => w.stoplen().bits_2(),
+        });
+    }
+
+    /// Change the parity
+    ///
+    /// [`enable`] configures the USART for no parity. Received characters with
+    /// a parity error are reported as [`Error::Parity`].
+    ///
+    /// Blocks until the transmitter is idle, as the user manual requires the
+    /// USART to be idle and disabled while its configuration is changed.
+    ///
+    /// [`enable`]: #method.enable
+    /// [`Error::Parity`]: enum.Error.html#variant.Parity
+    pub fn set_parity(&mut self, parity: Parity) {
+        self.modify_cfg(|w| match parity {
+            Parity::None => w.paritysel().no_parity(),
+            Parity::Even => w.paritysel().even_parity(),
+            Parity::Odd => w.paritysel().odd_parity(),
+        });
+    }
+
+    /// Invert the polarity of the received signal
+    ///
+    /// This makes it possible to connect devices that use an inverted UART
+    /// signal, like SBUS receivers, without an external inverter. Only the
+    /// receiver is affected.
+    ///
+    /// Blocks until the transmitter is idle, as the user manual requires the
+    /// USART to be idle and disabled while its configuration is changed.
+    pub fn enable_rx_inversion(&mut self) {
+        self.modify_cfg(|w| w.rxpol().inverted());
+    }
+
+    /// Return the received signal to its standard polarity
+    ///
+    /// See [`enable_rx_inversion`].
+    ///
+    /// [`enable_rx_inversion`]: #method.enable_rx_inversion
+    pub fn disable_rx_inversion(&mut self) {
+        self.modify_cfg(|w| w.rxpol().standard());
+    }
+
+    /// Configure the USART for receiving SBUS
+    ///
+    /// SBUS is the protocol used by many RC receivers. It uses an inverted
+    /// UART signal with 8 data bits, even parity, and 2 stop bits (8E2), at
+    /// 100 kbaud. This method configures the framing and polarity. The baud
+    /// rate needs to be set up using the [`Clock`] passed to [`enable`].
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{prelude::*, usart, Peripherals};
+    ///
+    /// let mut p = Peripherals::take().unwrap();
+    ///
+    /// let mut syscon = p.SYSCON.split();
+    /// let mut swm = p.SWM.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// // 12 MHz / (8 * 15) = 100 kbaud
+    /// #[cfg(feature = "82x")]
+    /// let clock_config = {
+    ///     syscon.uartfrg.set_clkdiv(1);
+    ///     syscon.uartfrg.set_frgmult(0);
+    ///     syscon.uartfrg.set_frgdiv(0xff);
+    ///     let uartfrg = syscon.uartfrg.share();
+    ///     usart::Clock::new(&uartfrg, 7, 15)
+    /// };
+    /// #[cfg(feature = "845")]
+    /// let clock_config = usart::Clock::new_with_baudrate(100_000);
+    ///
+    /// let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+    ///     p.pins.pio0_0.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    ///
+    /// let mut serial =
+    ///     p.USART0.enable_rx_only(&clock_config, &mut syscon.handle, u0_rxd);
+    /// serial.configure_sbus();
+    ///
+    /// // SBUS frames are 25 bytes long, starting with 0x0f
+    /// let byte = nb::block!(serial.read());
+    /// ```
+    ///
+    /// [`Clock`]: struct.Clock.html
+    /// [`enable`]: #method.enable
+    pub fn configure_sbus(&mut self) {
+        self.modify_cfg(|w| {
+            w.datalen().bit_8();
+            w.paritysel().even_parity();
+            w.stoplen().bits_2();
+            w.rxpol().inverted()
+        });
+    }
+
+    /// Check the transmitter and receiver by sending a pattern to itself
+    ///
+    /// Enables loopback mode, discards any characters already waiting in the
+    /// receiver, then sends `pattern` one byte at a time, checking that each
+    /// byte is received back correctly. Loopback mode is disabled again
+    /// afterwards, regardless of the result.
+    ///
+    /// This is useful as a production test for boards whose USART goes
+    /// off-board, as it verifies the USART and its clock configuration without
+    /// requiring anything to be connected. It doesn't verify the pins or any
+    /// external circuitry. Since the TX pin stays active in loopback mode,
+    /// whatever is connected to it receives the pattern.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// # use lpc8xx_hal::{prelude::*, Peripherals, usart};
+    /// #
+    /// # let mut p = Peripherals::take().unwrap();
+    /// #
+    /// # let mut syscon = p.SYSCON.split();
+    /// # let mut swm = p.SWM.split();
+    /// #
+    /// # #[cfg(feature = "82x")]
+    /// # let mut swm_handle = swm.handle;
+    /// # #[cfg(feature = "845")]
+    /// # let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    /// #
+    /// # #[cfg(feature = "82x")]
+    /// # let clock_config = {
+    /// #     syscon.uartfrg.set_clkdiv(6);
+    /// #     syscon.uartfrg.set_frgmult(22);
+    /// #     syscon.uartfrg.set_frgdiv(0xff);
+    /// #     let uartfrg = syscon.uartfrg.share();
+    /// #     usart::Clock::new(&uartfrg, 0, 16)
+    /// # };
+    /// # #[cfg(feature = "845")]
+    /// # let clock_config = usart::Clock::new_with_baudrate(115200);
+    /// #
+    /// # let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+    /// #     p.pins.pio0_0.into_swm_pin(),
+    /// #     &mut swm_handle,
+    /// # );
+    /// # let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+    /// #     p.pins.pio0_4.into_swm_pin(),
+    /// #     &mut swm_handle,
+    /// # );
+    /// #
+    /// let mut serial = p.USART0.enable(
+    ///     &clock_config,
+    ///     &mut syscon.handle,
+    ///     u0_rxd,
+    ///     u0_txd,
+    /// );
+    ///
+    /// if let Err(error) = serial.self_test(&[0x00, 0x55, 0xaa, 0xff]) {
+    ///     // report the failure
+    /// }
+    /// ```
+    pub fn self_test(&mut self, pattern: &[u8]) -> Result<(), SelfTestError> {
+        self.enable_loopback();
+        let result = self.check_pattern(pattern);
+        self.disable_loopback();
+
+        result
+    }
+
+    /// Start automatic baud rate detection
+    ///
+    /// The USART measures the length of the start bit of the next received
+    /// character, and sets its baud rate generator accordingly. The oversample
+    /// rate is left as configured, so the detected baud rate must be within
+    /// range of it. Use [`poll_autobaud`] to wait for the detection to
+    /// complete, and [`baudrate`] to find out which baud rate was detected.
+    ///
+    /// Since only the start bit is measured, the first data bit of the
+    /// character must be a one. The remote side is typically asked to send
+    /// `'A'` or `'a'`, or a command starting with those (like the `AT` prefix
+    /// used by modems). The character used for the measurement is not
+    /// reliably received, so any data that is pending afterwards should be
+    /// discarded.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// # use lpc8xx_hal::{prelude::*, Peripherals, usart};
+    /// #
+    /// # let mut p = Peripherals::take().unwrap();
+    /// #
+    /// # let mut syscon = p.SYSCON.split();
+    /// # let mut swm = p.SWM.split();
+    /// #
+    /// # #[cfg(feature = "82x")]
+    /// # let mut swm_handle = swm.handle;
+    /// # #[cfg(feature = "845")]
+    /// # let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    /// #
+    /// # #[cfg(feature = "82x")]
+    /// # let clock_config = {
+    /// #     syscon.uartfrg.set_clkdiv(6);
+    /// #     syscon.uartfrg.set_frgmult(22);
+    /// #     syscon.uartfrg.set_frgdiv(0xff);
+    /// #     let uartfrg = syscon.uartfrg.share();
+    /// #     usart::Clock::new(&uartfrg, 0, 16)
+    /// # };
+    /// # #[cfg(feature = "845")]
+    /// # let clock_config = usart::Clock::new_with_baudrate(115200);
+    /// #
+    /// # let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+    /// #     p.pins.pio0_0.into_swm_pin(),
+    /// #     &mut swm_handle,
+    /// # );
+    /// # let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+    /// #     p.pins.pio0_4.into_swm_pin(),
+    /// #     &mut swm_handle,
+    /// # );
+    /// #
+    /// let mut serial = p.USART0.enable(
+    ///     &clock_config,
+    ///     &mut syscon.handle,
+    ///     u0_rxd,
+    ///     u0_txd,
+    /// );
+    ///
+    /// serial.start_autobaud();
+    /// match nb::block!(serial.poll_autobaud()) {
+    ///     Ok(()) => {
+    ///         // Assuming the USART's clock source runs at 12 MHz
+    ///         let baudrate = serial.baudrate(12_000_000);
+    ///     }
+    ///     Err(usart::AutobaudError::Overflow) => {
+    ///         // The baud rate is too low to be measured
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`poll_autobaud`]: #method.poll_autobaud
+    /// [`baudrate`]: #method.baudrate
+    pub fn start_autobaud(&mut self) {
+        self.usart.stat.write(|w| w.aberr().set_bit());
+        self.usart.ctl.modify(|_, w| w.autobaud().enabled());
+    }
+
+    /// Check whether automatic baud rate detection has finished
+    ///
+    /// Returns `nb::Error::WouldBlock`, while the USART is still waiting for a
+    /// character to measure. If an error is returned, automatic baud rate
+    /// detection has been stopped, and can be restarted using
+    /// [`start_autobaud`].
+    ///
+    /// [`start_autobaud`]: #method.start_autobaud
+    pub fn poll_autobaud(&mut self) -> nb::Result<(), AutobaudError> {
+        if self.usart.stat.read().aberr().bit_is_set() {
+            self.usart.stat.write(|w| w.aberr().set_bit());
+            self.usart.ctl.modify(|_, w| w.autobaud().disabled());
+            return Err(nb::Error::Other(AutobaudError::Overflow));
+        }
+
+        // The hardware clears the flag, once it has set the baud rate.
+        if self.usart.ctl.read().autobaud().is_enabled() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        Ok(())
+    }
+
+    /// Stop automatic baud rate detection
+    ///
+    /// Does nothing, if automatic baud rate detection isn't running. The baud
+    /// rate generator keeps its current value.
+    pub fn cancel_autobaud(&mut self) {
+        self.usart.ctl.modify(|_, w| w.autobaud().disabled());
+    }
+
+    /// Compute the current baud rate
+    ///
+    /// Reads the baud rate generator and oversample rate from the peripheral,
+    /// so this reflects the result of automatic baud rate detection. `source`
+    /// is the frequency of the USART's clock source in Hz.
+    pub fn baudrate(&self, source: u32) -> u32 {
+        let brgval = self.usart.brg.read().brgval().bits() as u32;
+        let osrval = self.usart.osr.read().osrval().bits() as u32;
+
+        source / ((brgval + 1) * (osrval + 1))
+    }
+
+    /// Read all status flags at once, and clear the events among them
+    ///
+    /// Reads the STAT register in a single volatile read, which makes this
+    /// the right way to find out what happened in an interrupt handler. The
+    /// flags that are cleared by software (see [`Events`]) are then cleared,
+    /// if they were set. Events that occur after the read are not lost, as
+    /// only flags that have been read are cleared.
+    ///
+    /// The other flags reflect the current state of the peripheral, and are
+    /// returned as they are.
+    ///
+    /// Please note that receive errors that are cleared by this method are no
+    /// longer reported by [`Read::read`].
+    ///
+    /// [`Events`]: struct.Events.html
+    /// [`Read::read`]: #impl-Read%3Cu8%3E
+    pub fn read_and_clear_events(&mut self) -> Events {
+        let events = Events::from_bits_truncate(self.usart.stat.read().bits());
+
+        let clear = events & Events::CLEARABLE;
+        if !clear.is_empty() {
+            self.usart.stat.write(|w| unsafe { w.bits(clear.bits()) });
+        }
+
+        events
+    }
+
+    fn check_pattern(&mut self, pattern: &[u8]) -> Result<(), SelfTestError> {
+        // Discard anything received before the test started.
+        while !matches!(self.rx.read(), Err(nb::Error::WouldBlock)) {}
+
+        for (index, &expected) in pattern.iter().enumerate() {
+            nb::block!(self.tx.write(expected)).void_unwrap();
+            nb::block!(self.tx.flush()).void_unwrap();
+
+            // The receiver has sampled the stop bit before the transmitter
+            // becomes idle, so the character must be available by now.
+            let received = match self.rx.read() {
+                Ok(received) => received,
+                Err(nb::Error::WouldBlock) => {
+                    return Err(SelfTestError::NoData { index })
+                }
+                Err(nb::Error::Other(error)) => {
+                    return Err(SelfTestError::Receive { index, error })
+                }
+            };
+
+            if received != expected {
+                return Err(SelfTestError::Mismatch {
+                    index,
+                    expected,
+                    received,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Save the configuration of this USART instance
+    ///
+    /// Blocks until the transmitter is idle, then reads the configuration,
+    /// baud rate, and interrupt enable registers. The returned [`Snapshot`] can
+    /// be passed to [`resume`], to restore the configuration after the
+    /// peripheral's state has been lost, for example because it was reset or
+    /// powered down while the microcontroller was in a low-power mode.
+    ///
+    /// # Example
+    ///
+    /// ``` no_run
+    /// use lpc8xx_hal::{prelude::*, usart, CorePeripherals, Peripherals};
+    ///
+    /// let mut cp = CorePeripherals::take().unwrap();
+    /// let mut p = Peripherals::take().unwrap();
+    ///
+    /// let mut pmu = p.PMU.split();
+    /// let mut syscon = p.SYSCON.split();
+    /// let mut swm = p.SWM.split();
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let mut swm_handle = swm.handle;
+    /// #[cfg(feature = "845")]
+    /// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+    ///
+    /// #[cfg(feature = "82x")]
+    /// let clock_config = {
+    ///     syscon.uartfrg.set_clkdiv(6);
+    ///     syscon.uartfrg.set_frgmult(22);
+    ///     syscon.uartfrg.set_frgdiv(0xff);
+    ///     let uartfrg = syscon.uartfrg.share();
+    ///     usart::Clock::new(&uartfrg, 0, 16)
+    /// };
+    /// #[cfg(feature = "845")]
+    /// let clock_config = usart::Clock::new_with_baudrate(115200);
+    ///
+    /// let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+    ///     p.pins.pio0_4.into_swm_pin(),
+    ///     &mut swm_handle,
+    /// );
+    ///
+    /// let mut serial =
+    ///     p.USART0.enable_tx_only(&clock_config, &mut syscon.handle, u0_txd);
+    ///
+    /// let snapshot = serial.suspend();
+    /// // Safe, as long as PDAWAKECFG hasn't been changed
+    /// unsafe { pmu.handle.enter_power_down_mode(&mut cp.SCB) };
+    /// serial.resume(&snapshot, &mut syscon.handle);
+    ///
+    /// serial.bwrite_all(b"Good morning!");
+    /// ```
+    ///
+    /// [`Snapshot`]: struct.Snapshot.html
+    /// [`resume`]: #method.resume
+    pub fn suspend(&mut self) -> Snapshot {
+        nb::block!(self.tx.flush()).void_unwrap();
+
+        Snapshot {
+            cfg: self.usart.cfg.read().bits(),
+            ctl: self.usart.ctl.read().bits(),
+            brg: self.usart.brg.read().bits(),
+            osr: self.usart.osr.read().bits(),
+            addr: self.usart.addr.read().bits(),
+            intenset: self.usart.intenset.read().bits(),
+        }
+    }
+
+    /// Restore a configuration that was saved using [`suspend`]
+    ///
+    /// Enables the peripheral clock, in case it has been disabled, and writes
+    /// the saved configuration back, while the USART is disabled. The USART
+    /// is enabled again afterwards.
+    ///
+    /// [`suspend`]: #method.suspend
+    pub fn resume(&mut self, snapshot: &Snapshot, syscon: &mut syscon::Handle) {
+        syscon.enable_clock(&self.usart);
+
+        self.usart.cfg.modify(|_, w| w.enable().disabled());
+
+        // Sound, as all values have been read from the same registers.
+        unsafe {
+            self.usart.brg.write(|w| w.bits(snapshot.brg));
+            self.usart.osr.write(|w| w.bits(snapshot.osr));
+            self.usart.addr.write(|w| w.bits(snapshot.addr));
+            self.usart.ctl.write(|w| w.bits(snapshot.ctl));
+            self.usart.intenclr.write(|w| w.bits(!snapshot.intenset));
+            self.usart.intenset.write(|w| w.bits(snapshot.intenset));
+            self.usart.cfg.write(|w| w.bits(snapshot.cfg));
+        }
+    }
+
+    fn set_loopback(&mut self, loopback: bool) {
+        self.modify_cfg(|w| {
+            if loopback {
+                w.loop_().loopback()
+            } else {
+                w.loop_().normal()
+            }
+        });
+    }
+
+    fn modify_cfg<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut cfg::W) -> &mut cfg::W,
+    {
+        // According to the user manual, section 13.6.1, the USART must not be
+        // sending or receiving data while CFG is written to, and it must be
+        // disabled.
+        nb::block!(self.tx.flush()).void_unwrap();
+
+        self.usart.cfg.modify(|_, w| w.enable().disabled());
+        self.usart.cfg.modify(|_, w| f(w).enable().enabled());
+    }
 }
 
 impl<I, State> USART<I, State>
@@ -262,7 +1004,7 @@ where
     }
 }
 
-impl<I> Read<u8> for USART<I, init_state::Enabled>
+impl<I, Pins> Read<u8> for USART<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
@@ -274,7 +1016,7 @@ where
     }
 }
 
-impl<I> Write<u8> for USART<I, init_state::Enabled>
+impl<I, Pins> Write<u8> for USART<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
@@ -291,12 +1033,12 @@ where
     }
 }
 
-impl<I> BlockingWriteDefault<u8> for USART<I, init_state::Enabled> where
+impl<I, Pins> BlockingWriteDefault<u8> for USART<I, init_state::Enabled<Pins>> where
     I: Instance
 {
 }
 
-impl<I> fmt::Write for USART<I, init_state::Enabled>
+impl<I, Pins> fmt::Write for USART<I, init_state::Enabled<Pins>>
 where
     Self: BlockingWriteDefault<u8>,
     I: Instance,
@@ -307,7 +1049,7 @@ where
     }
 }
 
-impl<I> dma::Dest for USART<I, init_state::Enabled>
+impl<I, Pins> dma::Dest for USART<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
@@ -321,3 +1063,157 @@ where
         self.tx.end_addr()
     }
 }
+
+/// An error reported by [`USART::self_test`]
+///
+/// `index` is the position within the test pattern of the byte that caused
+/// the error.
+///
+/// [`USART::self_test`]: struct.USART.html#method.self_test
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SelfTestError {
+    /// A byte was sent, but nothing was received
+    NoData {
+        /// The position of the byte in the pattern
+        index: usize,
+    },
+
+    /// A byte was received with an error
+    Receive {
+        /// The position of the byte in the pattern
+        index: usize,
+
+        /// The receive error
+        error: Error,
+    },
+
+    /// A different byte was received than the one that was sent
+    Mismatch {
+        /// The position of the byte in the pattern
+        index: usize,
+
+        /// The byte that was sent
+        expected: u8,
+
+        /// The byte that was received
+        received: u8,
+    },
+}
+
+/// The number of stop bits, as passed to [`USART::set_stop_bits`]
+///
+/// [`USART::set_stop_bits`]: struct.USART.html#method.set_stop_bits
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StopBits {
+    /// One stop bit
+    One,
+
+    /// Two stop bits
+    Two,
+}
+
+/// The parity, as passed to [`USART::set_parity`]
+///
+/// [`USART::set_parity`]: struct.USART.html#method.set_parity
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Parity {
+    /// No parity bit
+    None,
+
+    /// Even parity
+    Even,
+
+    /// Odd parity
+    Odd,
+}
+
+/// The configuration of a USART instance
+///
+/// Returned by [`USART::suspend`], and passed to [`USART::resume`].
+///
+/// [`USART::suspend`]: struct.USART.html#method.suspend
+/// [`USART::resume`]: struct.USART.html#method.resume
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+    cfg: u32,
+    ctl: u32,
+    brg: u32,
+    osr: u32,
+    addr: u32,
+    intenset: u32,
+}
+
+/// An error reported by [`USART::poll_autobaud`]
+///
+/// [`USART::poll_autobaud`]: struct.USART.html#method.poll_autobaud
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AutobaudError {
+    /// The start bit was too long to be measured
+    ///
+    /// Either the baud rate is too low for the current clock configuration, or
+    /// the line was held low, for example by a break condition.
+    Overflow,
+}
+
+flags! {
+    /// The flags in the STAT register of a USART instance
+    ///
+    /// Returned by [`USART::read_and_clear_events`]. `DELTA_CTS`, `OVERRUN`,
+    /// `DELTA_RX_BREAK`, `START`, `FRAMING_ERROR`, `PARITY_ERROR`, `NOISE`,
+    /// and `AUTOBAUD_ERROR` are cleared by that method. The other flags
+    /// reflect the current state of the peripheral.
+    ///
+    /// [`USART::read_and_clear_events`]: struct.USART.html#method.read_and_clear_events
+    pub struct Events {
+        /// Received data is available (RXRDY)
+        const RX_READY = 0;
+
+        /// The receiver is idle (RXIDLE)
+        const RX_IDLE = 1;
+
+        /// Data can be written to the transmit buffer (TXRDY)
+        const TX_READY = 2;
+
+        /// The transmitter is idle (TXIDLE)
+        const TX_IDLE = 3;
+
+        /// The CTS input is high (CTS)
+        const CTS = 4;
+
+        /// The CTS input has changed (DELTACTS)
+        const DELTA_CTS = 5;
+
+        /// The transmitter has been disabled (TXDISSTAT)
+        const TX_DISABLED = 6;
+
+        /// A character was received while the receive buffer was full
+        /// (OVERRUNINT)
+        const OVERRUN = 8;
+
+        /// A break condition is being received (RXBRK)
+        const RX_BREAK = 10;
+
+        /// A break condition has started or ended (DELTARXBRK)
+        const DELTA_RX_BREAK = 11;
+
+        /// A start bit was detected (START)
+        const START = 12;
+
+        /// A character was received with a missing stop bit (FRAMERRINT)
+        const FRAMING_ERROR = 13;
+
+        /// A character was received with a parity error (PARITYERRINT)
+        const PARITY_ERROR = 14;
+
+        /// A character was received with noise (RXNOISEINT)
+        const NOISE = 15;
+
+        /// Automatic baud rate detection has failed (ABERR)
+        const AUTOBAUD_ERROR = 16;
+    }
+}
+
+impl Events {
+    /// The flags that are cleared by writing a 1 to them
+    const CLEARABLE: Self = Self::from_bits_truncate(0x0001_f920);
+}
@@ -1,8 +1,14 @@
 use core::marker::PhantomData;
 
-use crate::{embedded_hal::serial::Read, init_state};
+use crate::{
+    embedded_hal::{serial::Read, timer::CountDown},
+    init_state,
+    timeout::{self, with_timeout},
+};
 
 use super::instances::Instance;
+#[cfg(feature = "serial-stats")]
+use super::stats::{self, RxStats};
 
 /// USART receiver
 ///
@@ -26,9 +32,27 @@ where
             _state: PhantomData,
         }
     }
+
+    /// Steal the USART receiver
+    ///
+    /// This function returns an instance of `Rx`, whether or not such an
+    /// instance exists somewhere else. This is meant for code that can't
+    /// access the receiver the usual way, like an interrupt or panic handler.
+    ///
+    /// # Safety
+    ///
+    /// The same caveats apply as for [`Peripherals::steal`]. Most importantly,
+    /// the caller must make sure that the USART instance is actually in the
+    /// state indicated by the `State` type parameter, and that no other code
+    /// uses the receiver at the same time.
+    ///
+    /// [`Peripherals::steal`]: ../struct.Peripherals.html#method.steal
+    pub unsafe fn steal() -> Self {
+        Self::new()
+    }
 }
 
-impl<I> Rx<I, init_state::Enabled>
+impl<I, Pins> Rx<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
@@ -53,17 +77,75 @@ where
 
         usart.intenclr.write(|w| w.rxrdyclr().set_bit());
     }
-}
 
-impl<I> Read<u8> for Rx<I, init_state::Enabled>
-where
-    I: Instance,
-{
-    type Error = Error;
+    /// Enable the start bit interrupt
+    ///
+    /// The interrupt is triggered when the start bit of a character has been
+    /// detected. The start bit flag stays set until cleared using
+    /// [`clear_start`], so make sure to call that from the interrupt handler.
+    ///
+    /// The interrupt will not actually work unless the interrupts for this
+    /// peripheral have also been enabled in the NVIC. See
+    /// [`USART::enable_in_nvic`].
+    ///
+    /// [`clear_start`]: #method.clear_start
+    /// [`USART::enable_in_nvic`]: struct.USART.html#method.enable_in_nvic
+    pub fn enable_start(&mut self) {
+        // Sound, as we're only writing atomically to a stateless register.
+        let usart = unsafe { &*I::REGISTERS };
 
-    fn read(&mut self) -> nb::Result<u8, Self::Error> {
-        // Sound, as we're only reading from `stat`, and `rxdatastat` is
-        // exclusively accessed by this method.
+        usart.intenset.write(|w| w.starten().set_bit());
+    }
+
+    /// Disable the start bit interrupt
+    pub fn disable_start(&mut self) {
+        // Sound, as we're only writing atomically to a stateless register.
+        let usart = unsafe { &*I::REGISTERS };
+
+        usart.intenclr.write(|w| w.startclr().set_bit());
+    }
+
+    /// Indicates whether a start bit has been detected
+    ///
+    /// Returns `true`, if a start bit has been detected since the flag was
+    /// last cleared using [`clear_start`].
+    ///
+    /// [`clear_start`]: #method.clear_start
+    pub fn is_start_detected(&self) -> bool {
+        // Sound, as we're only reading from `stat`.
+        let usart = unsafe { &*I::REGISTERS };
+
+        usart.stat.read().start().bit_is_set()
+    }
+
+    /// Clear the start bit flag
+    pub fn clear_start(&mut self) {
+        // Sound, as we're only writing atomically to a stateless register.
+        let usart = unsafe { &*I::REGISTERS };
+
+        usart.stat.write(|w| w.start().set_bit());
+    }
+
+    /// Read a character, along with its status flags
+    ///
+    /// Reads the RXDATSTAT register, which contains the received character
+    /// along with the error flags that belong to this specific character. This
+    /// is the only reliable way to associate framing, parity, and noise errors
+    /// with specific characters, as the corresponding flags in the STAT
+    /// register might already refer to a later character at high baud rates.
+    ///
+    /// Unlike [`Read::read`], this method doesn't treat those flags as errors.
+    /// The character is returned, and it's up to the caller to decide what to
+    /// do with it. The only error returned by this method is
+    /// [`Error::Overrun`], as an overrun can't be attributed to a specific
+    /// character.
+    ///
+    /// [`Read::read`]: #method.read
+    /// [`Error::Overrun`]: enum.Error.html#variant.Overrun
+    pub fn read_with_status(&mut self) -> nb::Result<Character, Error> {
+        // Sound, as we're only reading from `stat` (apart from clearing the
+        // overrun flag, which is a stateless, atomic write), and `rxdatastat`
+        // is exclusively accessed by this method.
         let usart = unsafe { &*I::REGISTERS };
 
         let stat = usart.stat.read();
@@ -73,28 +155,130 @@ where
         }
 
         if stat.rxrdy().bit_is_set() {
+            if stat.overrunint().bit_is_set() {
+                // The overrun flag is sticky. Clear it, so the next read can
+                // succeed.
+                usart.stat.write(|w| w.overrunint().set_bit());
+
+                #[cfg(feature = "serial-stats")]
+                stats::update_rx::<I>(|stats| {
+                    stats.overruns = stats.overruns.wrapping_add(1);
+                });
+
+                return Err(nb::Error::Other(Error::Overrun));
+            }
+
             // It's important to read this register all at once, as reading
             // it changes the status flags.
             let rx_dat_stat = usart.rxdatstat.read();
 
-            if stat.overrunint().bit_is_set() {
-                Err(nb::Error::Other(Error::Overrun))
-            } else if rx_dat_stat.framerr().bit_is_set() {
-                Err(nb::Error::Other(Error::Framing))
-            } else if rx_dat_stat.parityerr().bit_is_set() {
-                Err(nb::Error::Other(Error::Parity))
-            } else if rx_dat_stat.rxnoise().bit_is_set() {
-                Err(nb::Error::Other(Error::Noise))
-            } else {
+            let character = Character {
                 // `bits` returns `u16`, but at most 9 bits are used. We've
                 // configured UART to use only 8 bits, so we can safely cast to
                 // `u8`.
-                Ok(rx_dat_stat.rxdat().bits() as u8)
-            }
+                data: rx_dat_stat.rxdat().bits() as u8,
+                framing_error: rx_dat_stat.framerr().bit_is_set(),
+                parity_error: rx_dat_stat.parityerr().bit_is_set(),
+                noise: rx_dat_stat.rxnoise().bit_is_set(),
+            };
+
+            #[cfg(feature = "serial-stats")]
+            stats::update_rx::<I>(|stats| {
+                let framing = character.framing_error as u32;
+                let parity = character.parity_error as u32;
+                let noise = character.noise as u32;
+
+                stats.bytes_received = stats.bytes_received.wrapping_add(1);
+                stats.framing_errors =
+                    stats.framing_errors.wrapping_add(framing);
+                stats.parity_errors = stats.parity_errors.wrapping_add(parity);
+                stats.noise_errors = stats.noise_errors.wrapping_add(noise);
+            });
+
+            Ok(character)
         } else {
             Err(nb::Error::WouldBlock)
         }
     }
+
+    /// Wait for a character, giving up after a timeout
+    ///
+    /// Works like [`Read::read`], but blocks until a character is received,
+    /// or `ticks` ticks of `timer` have passed. In the latter case,
+    /// [`timeout::Error::Timeout`] is returned.
+    ///
+    /// [`Read::read`]: #method.read
+    /// [`timeout::Error::Timeout`]: ../timeout/enum.Error.html#variant.Timeout
+    pub fn read_with_timeout<T>(
+        &mut self,
+        timer: &mut T,
+        ticks: u32,
+    ) -> Result<u8, timeout::Error<Error>>
+    where
+        T: CountDown<Time = u32>,
+    {
+        with_timeout(timer, ticks, || self.read())
+    }
+
+    /// Return the statistics of this receiver
+    ///
+    /// The statistics are kept per USART instance, and count the characters
+    /// that are read using this API. Only available, if the `serial-stats`
+    /// feature is enabled.
+    #[cfg(feature = "serial-stats")]
+    pub fn stats(&self) -> RxStats {
+        stats::rx::<I>()
+    }
+
+    /// Reset the statistics of this receiver
+    ///
+    /// Returns the statistics from before the reset. Only available, if the
+    /// `serial-stats` feature is enabled.
+    #[cfg(feature = "serial-stats")]
+    pub fn reset_stats(&mut self) -> RxStats {
+        stats::take_rx::<I>()
+    }
+}
+
+impl<I, Pins> Read<u8> for Rx<I, init_state::Enabled<Pins>>
+where
+    I: Instance,
+{
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        let character = self.read_with_status()?;
+
+        if character.framing_error {
+            Err(nb::Error::Other(Error::Framing))
+        } else if character.parity_error {
+            Err(nb::Error::Other(Error::Parity))
+        } else if character.noise {
+            Err(nb::Error::Other(Error::Noise))
+        } else {
+            Ok(character.data)
+        }
+    }
+}
+
+/// A received character, along with its status flags
+///
+/// Returned by [`Rx::read_with_status`].
+///
+/// [`Rx::read_with_status`]: struct.Rx.html#method.read_with_status
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Character {
+    /// The received character
+    pub data: u8,
+
+    /// The character was received with a stop bit missing
+    pub framing_error: bool,
+
+    /// The character was received with a parity error
+    pub parity_error: bool,
+
+    /// The character was corrupted by noise
+    pub noise: bool,
 }
 
 /// A USART error
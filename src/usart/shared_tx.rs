@@ -0,0 +1,150 @@
+use core::{
+    cell::RefCell,
+    fmt::{self, Write as _},
+};
+
+use cortex_m::interrupt::{self, Mutex};
+
+use crate::init_state;
+
+use super::{instances::Instance, tx::Tx};
+
+/// A USART transmitter that can be shared between the main loop and
+/// interrupt handlers
+///
+/// Writing to a [`Tx`] from multiple contexts, for example to log from both
+/// the main loop and an interrupt handler, garbles the output, as an
+/// interrupt can preempt a write in the middle of a line. `SharedTx` prevents
+/// that, by writing each message within a critical section.
+///
+/// `SharedTx` is meant to be put into a `static`, and implements
+/// `write_fmt`, so it can be used with `write!` and `writeln!`. Each call
+/// writes its complete output, before any other context can write.
+///
+/// Interrupts are disabled while a message is written, which takes about 87
+/// µs per character at 115200 baud. Keep messages short, if interrupt latency
+/// matters.
+///
+/// # Example
+///
+/// ``` no_run
+/// use lpc8xx_hal::{
+///     pac::USART0,
+///     pins::PIO0_4,
+///     usart::{self, SharedTx, TxOnlyFunctions},
+///     Peripherals,
+/// };
+///
+/// static LOG: SharedTx<USART0, TxOnlyFunctions<USART0, PIO0_4>> =
+///     SharedTx::new();
+///
+/// let mut p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+/// let mut swm = p.SWM.split();
+///
+/// #[cfg(feature = "82x")]
+/// let mut swm_handle = swm.handle;
+/// #[cfg(feature = "845")]
+/// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+///
+/// #[cfg(feature = "82x")]
+/// let clock_config = {
+///     syscon.uartfrg.set_clkdiv(6);
+///     syscon.uartfrg.set_frgmult(22);
+///     syscon.uartfrg.set_frgdiv(0xff);
+///     let uartfrg = syscon.uartfrg.share();
+///     usart::Clock::new(&uartfrg, 0, 16)
+/// };
+/// #[cfg(feature = "845")]
+/// let clock_config = usart::Clock::new_with_baudrate(115200);
+///
+/// let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+///     p.pins.pio0_4.into_swm_pin(),
+///     &mut swm_handle,
+/// );
+///
+/// let serial =
+///     p.USART0.enable_tx_only(&clock_config, &mut syscon.handle, u0_txd);
+/// LOG.init(serial.tx);
+///
+/// // The same can be done from an interrupt handler
+/// writeln!(LOG, "Started").unwrap();
+/// ```
+///
+/// [`Tx`]: struct.Tx.html
+pub struct SharedTx<I, Pins> {
+    tx: Mutex<RefCell<Option<EnabledTx<I, Pins>>>>,
+}
+
+type EnabledTx<I, Pins> = Tx<I, init_state::Enabled<Pins>>;
+
+impl<I, Pins> SharedTx<I, Pins>
+where
+    I: Instance,
+{
+    /// Create a `SharedTx` without a transmitter
+    ///
+    /// Writing to it fails, until a transmitter is passed to [`init`].
+    ///
+    /// [`init`]: #method.init
+    pub const fn new() -> Self {
+        Self {
+            tx: Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    /// Pass the transmitter that is shared
+    ///
+    /// Returns the transmitter that was passed before, if any.
+    pub fn init(
+        &self,
+        tx: Tx<I, init_state::Enabled<Pins>>,
+    ) -> Option<Tx<I, init_state::Enabled<Pins>>> {
+        interrupt::free(|cs| self.tx.borrow(cs).replace(Some(tx)))
+    }
+
+    /// Take the transmitter back
+    ///
+    /// Writing fails afterwards, until another transmitter is passed to
+    /// [`init`].
+    ///
+    /// [`init`]: #method.init
+    pub fn release(&self) -> Option<Tx<I, init_state::Enabled<Pins>>> {
+        interrupt::free(|cs| self.tx.borrow(cs).take())
+    }
+
+    /// Write formatted output, without being interrupted
+    ///
+    /// This is what `write!` and `writeln!` call. Blocks until all output has
+    /// been sent. Returns an error, if no transmitter has been passed to
+    /// [`init`].
+    ///
+    /// [`init`]: #method.init
+    pub fn write_fmt(&self, args: fmt::Arguments) -> fmt::Result {
+        interrupt::free(|cs| {
+            let mut tx = self.tx.borrow(cs).borrow_mut();
+            let tx = tx.as_mut().ok_or(fmt::Error)?;
+
+            tx.write_fmt(args)
+        })
+    }
+
+    /// Write a string, without being interrupted
+    ///
+    /// Works like [`write_fmt`], for output that doesn't need formatting.
+    ///
+    /// [`write_fmt`]: #method.write_fmt
+    pub fn write_str(&self, s: &str) -> fmt::Result {
+        self.write_fmt(format_args!("{}", s))
+    }
+}
+
+impl<I, Pins> Default for SharedTx<I, Pins>
+where
+    I: Instance,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
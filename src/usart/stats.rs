@@ -0,0 +1,118 @@
+use core::cell::Cell;
+
+use cortex_m::interrupt::{self, Mutex};
+
+use super::instances::Instance;
+
+#[cfg(feature = "82x")]
+const INSTANCES: usize = 3;
+#[cfg(feature = "845")]
+const INSTANCES: usize = 5;
+
+/// Statistics of a USART receiver
+///
+/// Returned by [`Rx::stats`]. The counters wrap around on overflow.
+///
+/// Only available, if the `serial-stats` feature is enabled.
+///
+/// [`Rx::stats`]: struct.Rx.html#method.stats
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RxStats {
+    /// The number of characters that have been read
+    ///
+    /// Includes characters that were received with a framing error, parity
+    /// error, or noise.
+    pub bytes_received: u32,
+
+    /// The number of times a character was lost, because the previous one
+    /// hadn't been read yet
+    pub overruns: u32,
+
+    /// The number of characters that were received with a framing error
+    pub framing_errors: u32,
+
+    /// The number of characters that were received with a parity error
+    pub parity_errors: u32,
+
+    /// The number of characters that were received with noise
+    pub noise_errors: u32,
+}
+
+impl RxStats {
+    const fn new() -> Self {
+        Self {
+            bytes_received: 0,
+            overruns: 0,
+            framing_errors: 0,
+            parity_errors: 0,
+            noise_errors: 0,
+        }
+    }
+}
+
+/// Statistics of a USART transmitter
+///
+/// Returned by [`Tx::stats`]. The counters wrap around on overflow.
+///
+/// Only available, if the `serial-stats` feature is enabled.
+///
+/// [`Tx::stats`]: struct.Tx.html#method.stats
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TxStats {
+    /// The number of characters that have been written
+    ///
+    /// Characters that are sent using DMA are not counted.
+    pub bytes_sent: u32,
+}
+
+impl TxStats {
+    const fn new() -> Self {
+        Self { bytes_sent: 0 }
+    }
+}
+
+// `Rx` and `Tx` are zero-sized and can be stolen, so the statistics are kept
+// per instance, instead of in those structs.
+#[allow(clippy::declare_interior_mutable_const)]
+const RX_ZERO: Cell<RxStats> = Cell::new(RxStats::new());
+#[allow(clippy::declare_interior_mutable_const)]
+const TX_ZERO: Cell<TxStats> = Cell::new(TxStats::new());
+
+static RX_STATS: Mutex<[Cell<RxStats>; INSTANCES]> =
+    Mutex::new([RX_ZERO; INSTANCES]);
+static TX_STATS: Mutex<[Cell<TxStats>; INSTANCES]> =
+    Mutex::new([TX_ZERO; INSTANCES]);
+
+pub(super) fn update_rx<I: Instance>(f: impl FnOnce(&mut RxStats)) {
+    interrupt::free(|cs| {
+        let cell = &RX_STATS.borrow(cs)[I::NUM];
+        let mut stats = cell.get();
+        f(&mut stats);
+        cell.set(stats);
+    })
+}
+
+pub(super) fn update_tx<I: Instance>(f: impl FnOnce(&mut TxStats)) {
+    interrupt::free(|cs| {
+        let cell = &TX_STATS.borrow(cs)[I::NUM];
+        let mut stats = cell.get();
+        f(&mut stats);
+        cell.set(stats);
+    })
+}
+
+pub(super) fn rx<I: Instance>() -> RxStats {
+    interrupt::free(|cs| RX_STATS.borrow(cs)[I::NUM].get())
+}
+
+pub(super) fn tx<I: Instance>() -> TxStats {
+    interrupt::free(|cs| TX_STATS.borrow(cs)[I::NUM].get())
+}
+
+pub(super) fn take_rx<I: Instance>() -> RxStats {
+    interrupt::free(|cs| RX_STATS.borrow(cs)[I::NUM].replace(RxStats::new()))
+}
+
+pub(super) fn take_tx<I: Instance>() -> TxStats {
+    interrupt::free(|cs| TX_STATS.borrow(cs)[I::NUM].replace(TxStats::new()))
+}
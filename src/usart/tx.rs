@@ -1,14 +1,22 @@
 use core::{fmt, marker::PhantomData};
 
+use cortex_m::interrupt;
+
 use embedded_hal::{
     blocking::serial::write::Default as BlockingWriteDefault, serial::Write,
+    timer::CountDown,
 };
 use nb::block;
 use void::Void;
 
-use crate::{dma, init_state};
+use crate::{
+    dma, init_state,
+    timeout::{self, Deadline},
+};
 
 use super::instances::Instance;
+#[cfg(feature = "serial-stats")]
+use super::stats::{self, TxStats};
 
 /// USART transmitter
 ///
@@ -33,9 +41,27 @@ where
             _state: PhantomData,
         }
     }
+
+    /// Steal the USART transmitter
+    ///
+    /// This function returns an instance of `Tx`, whether or not such an
+    /// instance exists somewhere else. This is meant for code that can't
+    /// access the transmitter the usual way, like an interrupt or panic handler.
+    ///
+    /// # Safety
+    ///
+    /// The same caveats apply as for [`Peripherals::steal`]. Most importantly,
+    /// the caller must make sure that the USART instance is actually in the
+    /// state indicated by the `State` type parameter, and that no other code
+    /// uses the transmitter at the same time.
+    ///
+    /// [`Peripherals::steal`]: ../struct.Peripherals.html#method.steal
+    pub unsafe fn steal() -> Self {
+        Self::new()
+    }
 }
 
-impl<I> Tx<I, init_state::Enabled>
+impl<I, Pins> Tx<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
@@ -60,9 +86,74 @@ where
 
         usart.intenclr.write(|w| w.txrdyclr().set_bit());
     }
+
+    /// Start sending a break
+    ///
+    /// Holds the TXD line low, until [`stop_break`] is called. The break only
+    /// starts once the character that is currently being sent, if any, has
+    /// been sent completely. Call [`flush`] first, to make sure the break
+    /// starts right away.
+    ///
+    /// [`stop_break`]: #method.stop_break
+    /// [`flush`]: #impl-Write%3Cu8%3E
+    pub fn start_break(&mut self) {
+        set_break::<I>(true);
+    }
+
+    /// Stop sending a break
+    ///
+    /// Releases the TXD line, which then stays high until the next character
+    /// is sent.
+    pub fn stop_break(&mut self) {
+        set_break::<I>(false);
+    }
+
+    /// Write a buffer, giving up after a timeout
+    ///
+    /// Works like [`bwrite_all`], but returns [`timeout::Error::Timeout`], if
+    /// the buffer hasn't been written within `ticks` ticks of `timer`.
+    ///
+    /// [`bwrite_all`]: #impl-Write
+    /// [`timeout::Error::Timeout`]: ../timeout/enum.Error.html#variant.Timeout
+    pub fn bwrite_all_with_timeout<T>(
+        &mut self,
+        buffer: &[u8],
+        timer: &mut T,
+        ticks: u32,
+    ) -> Result<(), timeout::Error<Void>>
+    where
+        T: CountDown<Time = u32>,
+    {
+        let mut deadline = Deadline::start(timer, ticks);
+
+        for &word in buffer {
+            deadline.block(|| self.write(word))?;
+        }
+
+        Ok(())
+    }
+
+    /// Return the statistics of this transmitter
+    ///
+    /// The statistics are kept per USART instance, and count the characters
+    /// that are written using this API. Only available, if the `serial-stats`
+    /// feature is enabled.
+    #[cfg(feature = "serial-stats")]
+    pub fn stats(&self) -> TxStats {
+        stats::tx::<I>()
+    }
+
+    /// Reset the statistics of this transmitter
+    ///
+    /// Returns the statistics from before the reset. Only available, if the
+    /// `serial-stats` feature is enabled.
+    #[cfg(feature = "serial-stats")]
+    pub fn reset_stats(&mut self) -> TxStats {
+        stats::take_tx::<I>()
+    }
 }
 
-impl<I> Write<u8> for Tx<I, init_state::Enabled>
+impl<I, Pins> Write<u8> for Tx<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
@@ -81,6 +172,11 @@ where
             // This is sound, as all `u8` values are valid here.
             unsafe { w.txdat().bits(word as u16) });
 
+        #[cfg(feature = "serial-stats")]
+        stats::update_tx::<I>(|stats| {
+            stats.bytes_sent = stats.bytes_sent.wrapping_add(1);
+        });
+
         Ok(())
     }
 
@@ -96,10 +192,12 @@ where
     }
 }
 
-impl<I> BlockingWriteDefault<u8> for Tx<I, init_state::Enabled> where I: Instance
-{}
+impl<I, Pins> BlockingWriteDefault<u8> for Tx<I, init_state::Enabled<Pins>> where
+    I: Instance
+{
+}
 
-impl<I> fmt::Write for Tx<I, init_state::Enabled>
+impl<I, Pins> fmt::Write for Tx<I, init_state::Enabled<Pins>>
 where
     Self: BlockingWriteDefault<u8>,
     I: Instance,
@@ -114,7 +212,7 @@ where
     }
 }
 
-impl<I> dma::Dest for Tx<I, init_state::Enabled>
+impl<I, Pins> dma::Dest for Tx<I, init_state::Enabled<Pins>>
 where
     I: Instance,
 {
@@ -130,3 +228,22 @@ where
         (unsafe { &(*I::REGISTERS).txdat }) as *const _ as *mut u8
     }
 }
+
+fn set_break<I>(enable: bool)
+where
+    I: Instance,
+{
+    // Sound, as TXBRKEN is only written by this function, and the
+    // read-modify-write of CTL is protected by a critical section.
+    let usart = unsafe { &*I::REGISTERS };
+
+    interrupt::free(|_| {
+        usart.ctl.modify(|_, w| {
+            if enable {
+                w.txbrken().continous()
+            } else {
+                w.txbrken().normal()
+            }
+        });
+    });
+}
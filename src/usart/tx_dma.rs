@@ -0,0 +1,307 @@
+use core::{cmp, fmt, slice};
+
+use crate::{
+    dma::{self, ChannelTrait, Transfer},
+    init_state,
+};
+
+/// Writes to a USART transmitter using DMA, without waiting for it
+///
+/// Writing to a USART blocks until all data has been sent, which stalls the
+/// caller for a long time at low baud rates, for example when logging from
+/// the main loop. `TxDma` avoids that, by copying the data into one of two
+/// buffers. While one buffer is being sent using DMA, the other is filled.
+/// Once the transfer has finished, the buffers switch roles.
+///
+/// Writes return immediately, as long as the data fits into the buffer that
+/// is currently being filled. If both buffers are full, writes wait until the
+/// current transfer has finished.
+///
+/// Data that is written while a transfer is in progress is only sent, once
+/// `TxDma` has noticed that the transfer has finished. This happens whenever
+/// data is written, but [`poll`] should also be called regularly (for example
+/// from the main loop), to make sure that no data is left sitting in the
+/// buffer.
+///
+/// # Example
+///
+/// ``` no_run
+/// use core::fmt::Write as _;
+///
+/// use lpc8xx_hal::{
+///     cortex_m::singleton,
+///     Peripherals,
+///     dma,
+///     usart::{self, TxDma},
+/// };
+///
+/// let p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+/// let mut swm = p.SWM.split();
+///
+/// #[cfg(feature = "82x")]
+/// let mut swm_handle = swm.handle;
+/// #[cfg(feature = "845")]
+/// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+///
+/// #[cfg(feature = "82x")]
+/// let clock_config = {
+///     syscon.uartfrg.set_clkdiv(6);
+///     syscon.uartfrg.set_frgmult(22);
+///     syscon.uartfrg.set_frgdiv(0xff);
+///     let uartfrg = syscon.uartfrg.share();
+///     usart::Clock::new(&uartfrg, 0, 16)
+/// };
+/// #[cfg(feature = "845")]
+/// let clock_config = usart::Clock::new_with_baudrate(115200);
+///
+/// let (u0_rxd, _) = swm.movable_functions.u0_rxd.assign(
+///     p.pins.pio0_0.into_swm_pin(),
+///     &mut swm_handle,
+/// );
+/// let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+///     p.pins.pio0_4.into_swm_pin(),
+///     &mut swm_handle,
+/// );
+///
+/// let serial = p.USART0.enable(
+///     &clock_config,
+///     &mut syscon.handle,
+///     u0_rxd,
+///     u0_txd,
+/// );
+///
+/// let descriptors = singleton!(: dma::DescriptorTable =
+///     dma::DescriptorTable::new()).unwrap();
+/// let dma = p.DMA.split(descriptors);
+/// let dma_handle = dma.handle.enable(&mut syscon.handle);
+///
+/// let buffer_a = singleton!(: [u8; 64] = [0; 64]).unwrap();
+/// let buffer_b = singleton!(: [u8; 64] = [0; 64]).unwrap();
+///
+/// // USART0 TX is connected to DMA channel 1
+/// let mut log = TxDma::new(
+///     dma.channels.channel1.enable(&dma_handle),
+///     serial.tx,
+///     buffer_a,
+///     buffer_b,
+/// );
+///
+/// let mut i = 0u32;
+/// loop {
+///     // Returns immediately, while the previous line is still being sent
+///     writeln!(log, "Iteration {}", i).unwrap();
+///     i += 1;
+///
+///     // Make sure buffered data is sent eventually
+///     log.poll();
+/// }
+/// ```
+///
+/// [`poll`]: #method.poll
+pub struct TxDma<'dma, T, D>
+where
+    T: ChannelTrait,
+{
+    state: Option<State<'dma, T, D>>,
+
+    /// The start and length of both buffers
+    ///
+    /// Kept as pointers, as a part of a buffer is owned by the DMA transfer,
+    /// while one is in progress.
+    buffers: [(*mut u8, usize); 2],
+
+    /// The index of the buffer that is currently being filled
+    current: usize,
+
+    /// The number of bytes in the current buffer
+    len: usize,
+}
+
+impl<'dma, T, D> TxDma<'dma, T, D>
+where
+    T: ChannelTrait,
+    D: dma::Dest,
+{
+    /// Create a new instance of `TxDma`
+    ///
+    /// `channel` must be the DMA channel that is connected to the TX DMA
+    /// request of the USART instance that `dest` belongs to. `dest` is usually
+    /// a USART transmitter ([`Tx`]), but can be any DMA destination.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if either buffer is empty or longer than 1024 bytes, which is
+    /// the maximum length of a DMA transfer.
+    ///
+    /// [`Tx`]: struct.Tx.html
+    pub fn new(
+        channel: dma::Channel<T, init_state::Enabled<&'dma dma::Handle>>,
+        dest: D,
+        buffer_a: &'static mut [u8],
+        buffer_b: &'static mut [u8],
+    ) -> Self {
+        for buffer in &[&buffer_a, &buffer_b] {
+            assert!(!buffer.is_empty() && buffer.len() <= 1024);
+        }
+
+        Self {
+            state: Some(State::Idle { channel, dest }),
+            buffers: [
+                (buffer_a.as_mut_ptr(), buffer_a.len()),
+                (buffer_b.as_mut_ptr(), buffer_b.len()),
+            ],
+            current: 0,
+            len: 0,
+        }
+    }
+
+    /// Start sending buffered data, if the previous transfer has finished
+    ///
+    /// Never blocks. Should be called regularly, to make sure that all written
+    /// data is sent eventually.
+    pub fn poll(&mut self) {
+        let state = match self.state.take() {
+            Some(State::Busy { transfer }) if !transfer.is_active() => {
+                let (channel, _, dest) = transfer.into_parts();
+                State::Idle { channel, dest }
+            }
+            Some(state) => state,
+            None => unreachable!(),
+        };
+
+        let state = match state {
+            State::Idle { channel, dest } if self.len > 0 => {
+                let (buffer, _) = self.buffers[self.current];
+
+                // Sound, as the range `0..len` is within the current buffer,
+                // and the other buffer, which is filled from now on, is no
+                // longer accessed by a DMA transfer.
+                let data =
+                    unsafe { slice::from_raw_parts_mut(buffer, self.len) };
+
+                self.current ^= 1;
+                self.len = 0;
+
+                State::Busy {
+                    transfer: channel.start_transfer(data, dest),
+                }
+            }
+            state => state,
+        };
+
+        self.state = Some(state);
+    }
+
+    /// Write all of `data`
+    ///
+    /// Returns immediately, as long as there's enough room in the buffers.
+    /// Otherwise, waits until enough data has been sent.
+    pub fn write_all(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let (buffer, capacity) = self.buffers[self.current];
+
+            let free = capacity - self.len;
+            if free == 0 {
+                // Both buffers are full. Wait for the current transfer.
+                self.poll();
+                continue;
+            }
+
+            let n = cmp::min(free, data.len());
+
+            // Sound, as the range `len..len + n` is within the current buffer,
+            // which is never accessed by a DMA transfer.
+            let free =
+                unsafe { slice::from_raw_parts_mut(buffer.add(self.len), n) };
+            free.copy_from_slice(&data[..n]);
+            self.len += n;
+            data = &data[n..];
+        }
+
+        self.poll();
+    }
+
+    /// Ensures that all written data has been sent
+    pub fn flush(&mut self) -> nb::Result<(), D::Error> {
+        self.poll();
+
+        match &mut self.state {
+            Some(State::Idle { dest, .. }) if self.len == 0 => dest.wait(),
+            _ => Err(nb::Error::WouldBlock),
+        }
+    }
+
+    /// Wait until all written data has been sent, then release all resources
+    ///
+    /// Returns the DMA channel, the destination, and both buffers, in the
+    /// order they were passed to [`new`].
+    ///
+    /// [`new`]: #method.new
+    #[allow(clippy::type_complexity)]
+    pub fn free(
+        mut self,
+    ) -> Result<
+        (
+            dma::Channel<T, init_state::Enabled<&'dma dma::Handle>>,
+            D,
+            &'static mut [u8],
+            &'static mut [u8],
+        ),
+        D::Error,
+    > {
+        nb::block!(self.flush())?;
+
+        match self.state {
+            Some(State::Idle { channel, dest }) => {
+                let [(a, a_len), (b, b_len)] = self.buffers;
+
+                // Sound, as these are the buffers that were passed to `new`,
+                // and no DMA transfer is in progress.
+                let (a, b) = unsafe {
+                    (
+                        slice::from_raw_parts_mut(a, a_len),
+                        slice::from_raw_parts_mut(b, b_len),
+                    )
+                };
+
+                Ok((channel, dest, a, b))
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+// Sound, as the buffers are owned exclusively, just like the
+// `&'static mut [u8]`s they were created from.
+unsafe impl<'dma, T, D> Send for TxDma<'dma, T, D>
+where
+    T: ChannelTrait,
+    State<'dma, T, D>: Send,
+{
+}
+
+impl<'dma, T, D> fmt::Write for TxDma<'dma, T, D>
+where
+    T: ChannelTrait,
+    D: dma::Dest,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_all(s.as_bytes());
+        Ok(())
+    }
+}
+
+enum State<'dma, T, D>
+where
+    T: ChannelTrait,
+{
+    Idle {
+        channel: dma::Channel<T, init_state::Enabled<&'dma dma::Handle>>,
+        dest: D,
+    },
+    Busy {
+        transfer: Transfer<'dma, T, D>,
+    },
+}
@@ -0,0 +1,365 @@
+use core::{cmp, fmt, slice};
+
+use embedded_hal::serial::Write as _;
+use void::Void;
+
+use crate::{
+    dma::{self, ChannelTrait, Transfer},
+    init_state,
+};
+
+use super::{instances::Instance, tx::Tx};
+
+/// A buffered USART transmitter, that switches to DMA for larger amounts of
+/// data
+///
+/// Sending each byte from the TXRDY interrupt handler is simple, but at high
+/// baud rates, the interrupt load becomes significant: At 1 Mbaud, the
+/// interrupt fires every 10 µs. Sending everything using DMA avoids that, but
+/// setting up a transfer for a few bytes isn't worth it either.
+///
+/// `TxFifo` queues written data in a ring buffer, and does whatever is
+/// cheaper:
+///
+/// - While fewer than `threshold` bytes are queued, they are written one by
+///   one, whenever the transmitter is ready. The TXRDY interrupt is enabled
+///   for this, so [`poll`] can be called from the interrupt handler.
+/// - Once `threshold` or more bytes are queued, the contiguous part of the
+///   queue is sent in a single DMA transfer. The TXRDY interrupt is disabled
+///   in the meantime. DMA transfers don't trigger an interrupt, so [`poll`]
+///   needs to be called from somewhere else (like the main loop, or a timer
+///   interrupt handler) to notice that a transfer has finished.
+///
+/// The best threshold depends on the baud rate, and on how the data is
+/// written. A good starting point is the number of bytes that are sent in the
+/// time it takes to handle a few interrupts. It can be changed at any time
+/// using [`set_threshold`].
+///
+/// # Example
+///
+/// ``` no_run
+/// use core::fmt::Write as _;
+///
+/// use lpc8xx_hal::{
+///     cortex_m::singleton,
+///     Peripherals,
+///     dma,
+///     usart::{self, TxFifo},
+/// };
+///
+/// let p = Peripherals::take().unwrap();
+///
+/// let mut syscon = p.SYSCON.split();
+/// let mut swm = p.SWM.split();
+///
+/// #[cfg(feature = "82x")]
+/// let mut swm_handle = swm.handle;
+/// #[cfg(feature = "845")]
+/// let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+///
+/// #[cfg(feature = "82x")]
+/// let clock_config = {
+///     syscon.uartfrg.set_clkdiv(6);
+///     syscon.uartfrg.set_frgmult(22);
+///     syscon.uartfrg.set_frgdiv(0xff);
+///     let uartfrg = syscon.uartfrg.share();
+///     usart::Clock::new(&uartfrg, 0, 16)
+/// };
+/// #[cfg(feature = "845")]
+/// let clock_config = usart::Clock::new_with_baudrate(115200);
+///
+/// let (u0_txd, _) = swm.movable_functions.u0_txd.assign(
+///     p.pins.pio0_4.into_swm_pin(),
+///     &mut swm_handle,
+/// );
+///
+/// let serial =
+///     p.USART0.enable_tx_only(&clock_config, &mut syscon.handle, u0_txd);
+///
+/// let descriptors = singleton!(: dma::DescriptorTable =
+///     dma::DescriptorTable::new()).unwrap();
+/// let dma = p.DMA.split(descriptors);
+/// let dma_handle = dma.handle.enable(&mut syscon.handle);
+///
+/// let buffer = singleton!(: [u8; 256] = [0; 256]).unwrap();
+///
+/// // USART0 TX is connected to DMA channel 1. Use DMA for 16 bytes or more.
+/// let mut fifo = TxFifo::new(
+///     dma.channels.channel1.enable(&dma_handle),
+///     serial.tx,
+///     buffer,
+///     16,
+/// );
+///
+/// let mut i = 0u32;
+/// loop {
+///     writeln!(fifo, "Iteration {}", i).unwrap();
+///     i += 1;
+///
+///     // Also call this from the USART0 interrupt handler
+///     fifo.poll();
+/// }
+/// ```
+///
+/// [`poll`]: #method.poll
+/// [`set_threshold`]: #method.set_threshold
+pub struct TxFifo<'dma, T, I, Pins>
+where
+    T: ChannelTrait,
+    I: Instance,
+{
+    state: Option<State<'dma, T, I, Pins>>,
+
+    /// The start of the ring buffer
+    ///
+    /// Kept as a pointer, as a part of the buffer is owned by the DMA transfer,
+    /// while one is in progress.
+    buffer: *mut u8,
+    capacity: usize,
+
+    /// The index of the oldest queued byte
+    read: usize,
+
+    /// The number of queued bytes, including those in a DMA transfer
+    len: usize,
+
+    threshold: usize,
+}
+
+impl<'dma, T, I, Pins> TxFifo<'dma, T, I, Pins>
+where
+    T: ChannelTrait,
+    I: Instance,
+{
+    /// Create a new instance of `TxFifo`
+    ///
+    /// `channel` must be the DMA channel that is connected to the TX DMA
+    /// request of the USART instance that `tx` belongs to. Data is queued in
+    /// `buffer`. Once `threshold` or more bytes are queued, they are sent
+    /// using DMA.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `buffer` is empty, or `threshold` is zero.
+    pub fn new(
+        channel: dma::Channel<T, init_state::Enabled<&'dma dma::Handle>>,
+        tx: Tx<I, init_state::Enabled<Pins>>,
+        buffer: &'static mut [u8],
+        threshold: usize,
+    ) -> Self {
+        assert!(!buffer.is_empty());
+        assert!(threshold > 0);
+
+        Self {
+            state: Some(State::Idle { channel, tx }),
+            buffer: buffer.as_mut_ptr(),
+            capacity: buffer.len(),
+            read: 0,
+            len: 0,
+            threshold,
+        }
+    }
+
+    /// The number of queued bytes, at which DMA starts being used
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Change the number of queued bytes, at which DMA starts being used
+    ///
+    /// Takes effect the next time [`poll`] is called.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `threshold` is zero.
+    ///
+    /// [`poll`]: #method.poll
+    pub fn set_threshold(&mut self, threshold: usize) {
+        assert!(threshold > 0);
+        self.threshold = threshold;
+    }
+
+    /// The number of bytes that are queued, but haven't been sent yet
+    pub fn queued(&self) -> usize {
+        self.len
+    }
+
+    /// Queue as much of `data` as fits into the buffer
+    ///
+    /// Never blocks. Returns the number of bytes that have been queued.
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        let mut written = 0;
+
+        while written < data.len() && self.len < self.capacity {
+            let write = (self.read + self.len) % self.capacity;
+            let n = cmp::min(
+                data.len() - written,
+                // Contiguous free space, up to the end of the buffer or the
+                // start of the queued data.
+                cmp::min(self.capacity - write, self.capacity - self.len),
+            );
+
+            // Sound, as the range `write..write + n` is within the buffer and
+            // not part of the queued data, which means it's not being read by
+            // a DMA transfer.
+            let free =
+                unsafe { slice::from_raw_parts_mut(self.buffer.add(write), n) };
+            free.copy_from_slice(&data[written..written + n]);
+
+            self.len += n;
+            written += n;
+        }
+
+        self.poll();
+
+        written
+    }
+
+    /// Queue all of `data`
+    ///
+    /// Returns immediately, as long as there's enough room in the buffer.
+    /// Otherwise, waits until enough data has been sent.
+    pub fn write_all(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let n = self.write(data);
+            data = &data[n..];
+        }
+    }
+
+    /// Send queued data
+    ///
+    /// Never blocks. Needs to be called from the USART interrupt handler, and
+    /// regularly from elsewhere, while a DMA transfer could be in progress.
+    /// Please refer to the [struct documentation] for details.
+    ///
+    /// [struct documentation]: struct.TxFifo.html
+    pub fn poll(&mut self) {
+        let state = match self.state.take() {
+            Some(State::Busy { transfer }) if !transfer.is_active() => {
+                let (channel, data, tx) = transfer.into_parts();
+
+                self.read = (self.read + data.len()) % self.capacity;
+                self.len -= data.len();
+
+                State::Idle { channel, tx }
+            }
+            Some(state) => state,
+            None => unreachable!(),
+        };
+
+        let state = match state {
+            State::Idle { channel, mut tx } if self.len >= self.threshold => {
+                tx.disable_txrdy();
+
+                let n = cmp::min(
+                    cmp::min(self.len, self.capacity - self.read),
+                    // The maximum length of a DMA transfer
+                    1024,
+                );
+
+                // Sound, as the range `read..read + n` is queued data, which
+                // isn't written to until the transfer has finished.
+                let data = unsafe {
+                    slice::from_raw_parts_mut(self.buffer.add(self.read), n)
+                };
+
+                State::Busy {
+                    transfer: channel.start_transfer(data, tx),
+                }
+            }
+            State::Idle { channel, mut tx } => {
+                while self.len > 0 {
+                    // Sound, as `read` is within the buffer, and no DMA
+                    // transfer is in progress.
+                    let byte = unsafe { *self.buffer.add(self.read) };
+                    if tx.write(byte).is_err() {
+                        break;
+                    }
+
+                    self.read = (self.read + 1) % self.capacity;
+                    self.len -= 1;
+                }
+
+                if self.len > 0 {
+                    tx.enable_txrdy();
+                } else {
+                    tx.disable_txrdy();
+                }
+
+                State::Idle { channel, tx }
+            }
+            state => state,
+        };
+
+        self.state = Some(state);
+    }
+
+    /// Ensures that all queued data has been sent
+    pub fn flush(&mut self) -> nb::Result<(), Void> {
+        self.poll();
+
+        match &mut self.state {
+            Some(State::Idle { tx, .. }) if self.len == 0 => tx.flush(),
+            _ => Err(nb::Error::WouldBlock),
+        }
+    }
+
+    /// Wait until all queued data has been sent, then release all resources
+    #[allow(clippy::type_complexity)]
+    pub fn free(
+        mut self,
+    ) -> (
+        dma::Channel<T, init_state::Enabled<&'dma dma::Handle>>,
+        Tx<I, init_state::Enabled<Pins>>,
+        &'static mut [u8],
+    ) {
+        nb::block!(self.flush()).unwrap_or_else(|err| match err {});
+
+        match self.state {
+            Some(State::Idle { channel, tx }) => {
+                // Sound, as this is the buffer that was passed to `new`, and
+                // no DMA transfer is in progress.
+                let buffer = unsafe {
+                    slice::from_raw_parts_mut(self.buffer, self.capacity)
+                };
+
+                (channel, tx, buffer)
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+// Sound, as the buffer is owned exclusively, just like the `&'static mut [u8]`
+// it was created from.
+unsafe impl<'dma, T, I, Pins> Send for TxFifo<'dma, T, I, Pins>
+where
+    T: ChannelTrait,
+    I: Instance,
+    State<'dma, T, I, Pins>: Send,
+{
+}
+
+impl<'dma, T, I, Pins> fmt::Write for TxFifo<'dma, T, I, Pins>
+where
+    T: ChannelTrait,
+    I: Instance,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_all(s.as_bytes());
+        Ok(())
+    }
+}
+
+enum State<'dma, T, I, Pins>
+where
+    T: ChannelTrait,
+{
+    Idle {
+        channel: dma::Channel<T, init_state::Enabled<&'dma dma::Handle>>,
+        tx: Tx<I, init_state::Enabled<Pins>>,
+    },
+    Busy {
+        transfer: Transfer<'dma, T, Tx<I, init_state::Enabled<Pins>>>,
+    },
+}
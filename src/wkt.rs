@@ -30,13 +30,14 @@
 //!
 //! [examples in the repository]: https://github.com/lpc-rs/lpc8xx-hal/tree/master/examples
 
-use embedded_hal::timer;
+use cortex_m::{interrupt, peripheral::syst::SystClkSource};
+use embedded_hal::timer::{self, CountDown as _};
 use nb;
 use void::Void;
 
 use crate::{
     init_state,
-    pac::{self, wkt::ctrl},
+    pac::{self, wkt::ctrl, Interrupt, NVIC, SYST},
     pmu::LowPowerClock,
     syscon::{self, IoscDerivedClock},
 };
@@ -115,6 +116,45 @@ impl WKT<init_state::Enabled> {
         }
     }
 
+    /// Enable interrupts for this instance in the NVIC
+    ///
+    /// This only enables the interrupts in the NVIC. It doesn't enable any
+    /// specific interrupt in this WKT instance.
+    pub fn enable_in_nvic(&mut self) {
+        // Safe, because there's no critical section here that this could
+        // interfere with.
+        unsafe { NVIC::unmask(Interrupt::WKT) };
+    }
+
+    /// Disable interrupts for this instance in the NVIC
+    ///
+    /// This only disables the interrupts in the NVIC. It doesn't change
+    /// anything about the interrupt configuration within this WKT instance.
+    pub fn disable_in_nvic(&mut self) {
+        NVIC::mask(Interrupt::WKT);
+    }
+
+    /// Clear this instance's interrupt pending flag in the NVIC
+    ///
+    /// This only clears the interrupt's pending flag in the NVIC. It does not
+    /// affect any of the interrupt-related flags in the peripheral.
+    pub fn clear_nvic_pending(&mut self) {
+        NVIC::unpend(Interrupt::WKT);
+    }
+
+    /// Set the priority of this instance's interrupt in the NVIC
+    ///
+    /// Lower values mean higher priority. Only the two most significant bits of
+    /// `priority` are used by the hardware.
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections,
+    /// like the ones used by RTFM.
+    pub unsafe fn set_nvic_priority(&mut self, nvic: &mut NVIC, priority: u8) {
+        nvic.set_priority(Interrupt::WKT, priority);
+    }
+
     /// Select the clock that runs the self-wake-up timer
     ///
     /// This method is only available if the WKT is enabled. Code attempting to
@@ -141,6 +181,82 @@ impl WKT<init_state::Enabled> {
             w
         });
     }
+
+    /// Returns the current timer value
+    ///
+    /// The timer counts down from the value passed to `start`, and stops once
+    /// it reaches zero.
+    pub fn value(&self) -> u32 {
+        self.wkt.count.read().value().bits()
+    }
+
+    /// Measure the frequency of the selected clock against the system clock
+    ///
+    /// Lets the timer count down `ticks` cycles of the clock that has been
+    /// selected using [`select_clock`], while SysTick counts cycles of the
+    /// system clock. `system_clock` is the frequency of the system clock in
+    /// Hz, which is derived from the IRC/FRO by default. The IRC/FRO is
+    /// factory-trimmed to within a few percent, so this can be used to
+    /// calibrate the low-power clock at runtime, whose frequency can be off
+    /// from its nominal 10 kHz by tens of percent.
+    ///
+    /// Returns the measured frequency in Hz, or `None`, if the measurement
+    /// took more than 2^24 cycles of the system clock, which is the range of
+    /// SysTick. At 12 MHz, that's about 1.4 seconds, which means that up to
+    /// about 10000 ticks of the low-power clock can be measured.
+    ///
+    /// Blocks for `ticks + 1` cycles of the selected clock, with interrupts
+    /// disabled. Any count down that was in progress is aborted. SysTick is
+    /// reconfigured and disabled afterwards, so this must not be called while
+    /// SysTick is used for anything else (like [`Delay`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `ticks` is zero or `u32::MAX`.
+    ///
+    /// [`select_clock`]: #method.select_clock
+    /// [`Delay`]: ../delay/struct.Delay.html
+    pub fn measure_clock(
+        &mut self,
+        syst: &mut SYST,
+        system_clock: u32,
+        ticks: u32,
+    ) -> Option<u32> {
+        assert!(ticks > 0 && ticks < u32::MAX);
+
+        syst.set_clock_source(SystClkSource::Core);
+        syst.set_reload(0xff_ffff);
+        syst.clear_current();
+        syst.enable_counter();
+
+        let cycles = interrupt::free(|_| {
+            // Count one additional tick, so the measurement can start at the
+            // beginning of a tick.
+            self.start(ticks + 1);
+            while self.value() > ticks {}
+
+            // Clear the flag that indicates a wrap-around.
+            syst.has_wrapped();
+            let start = SYST::get_current();
+
+            while self.wait().is_err() {}
+
+            let end = SYST::get_current();
+            if syst.has_wrapped() {
+                return None;
+            }
+
+            Some(start - end)
+        });
+
+        syst.disable_counter();
+
+        let cycles = cycles?;
+        let hz = u64::from(system_clock) * u64::from(ticks)
+            / u64::from(cycles.max(1));
+
+        Some(hz as u32)
+    }
 }
 
 impl timer::CountDown for WKT<init_state::Enabled> {
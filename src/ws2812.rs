@@ -0,0 +1,180 @@
+//! Driver for WS2812 smart LEDs (also known as NeoPixels)
+//!
+//! WS2812 LEDs are controlled by a single data line, using a timing-sensitive
+//! protocol. Generating that timing by toggling a GPIO pin is not reliable at
+//! the clock frequencies of the LPC800 series, so this driver uses the MOSI
+//! line of an SPI peripheral instead.
+//!
+//! Each bit of the LED protocol is encoded as 4 bits on the SPI bus: `1110`
+//! for a 1, `1000` for a 0. For this to match the WS2812 timing, the SPI clock
+//! must run at 3 MHz (±10%), in SPI mode 0. With the default system clock of
+//! 12 MHz, this means a clock divider value of 3.
+//!
+//! Only the MOSI line needs to be connected to the LEDs. SCK and MISO still
+//! need to be assigned to pins, but those pins can be left unconnected.
+//!
+//! # Example
+//!
+//! ``` no_run
+//! use lpc8xx_hal::{
+//!     prelude::*,
+//!     Peripherals,
+//!     syscon::clock_source::SpiClock,
+//!     ws2812::{Rgb, Ws2812},
+//! };
+//!
+//! let mut p  = Peripherals::take().unwrap();
+//! let mut swm = p.SWM.split();
+//! let mut syscon = p.SYSCON.split();
+//!
+//! #[cfg(feature = "82x")]
+//! let mut swm_handle = swm.handle;
+//! #[cfg(feature = "845")]
+//! let mut swm_handle = swm.handle.enable(&mut syscon.handle);
+//!
+//! let (spi0_sck, _) = swm.movable_functions.spi0_sck.assign(
+//!     p.pins.pio0_13.into_swm_pin(),
+//!     &mut swm_handle,
+//! );
+//! let (spi0_mosi, _) = swm
+//!     .movable_functions
+//!     .spi0_mosi
+//!     .assign(p.pins.pio0_14.into_swm_pin(), &mut swm_handle);
+//! let (spi0_miso, _) = swm
+//!     .movable_functions
+//!     .spi0_miso
+//!     .assign(p.pins.pio0_15.into_swm_pin(), &mut swm_handle);
+//!
+//! // 12 MHz / (3 + 1) = 3 MHz
+//! #[cfg(feature = "82x")]
+//! let spi_clock = SpiClock::new(3);
+//! #[cfg(feature = "845")]
+//! let spi_clock = SpiClock::new(&syscon.iosc, 3);
+//!
+//! let spi = p.SPI0.enable(
+//!     &spi_clock,
+//!     &mut syscon.handle,
+//!     embedded_hal::spi::MODE_0,
+//!     spi0_sck,
+//!     spi0_mosi,
+//!     spi0_miso,
+//! );
+//!
+//! let mut leds = Ws2812::new(spi);
+//!
+//! let mut pixels = [Rgb::default(); 8];
+//! pixels[0] = Rgb::new(255, 0, 0);
+//! pixels[1] = Rgb::new(0, 255, 0).scale(64);
+//!
+//! leds.write(pixels.iter().cloned())
+//!     .expect("Failed to write to LEDs");
+//! ```
+
+use embedded_hal::blocking::spi;
+
+/// Driver for a chain of WS2812 LEDs
+///
+/// Please refer to the [module documentation] for more information.
+///
+/// [module documentation]: index.html
+pub struct Ws2812<SPI> {
+    spi: SPI,
+}
+
+impl<SPI> Ws2812<SPI>
+where
+    SPI: spi::Write<u8>,
+{
+    /// Create a new instance of the driver
+    ///
+    /// Expects the SPI peripheral to be configured as described in the
+    /// [module documentation].
+    ///
+    /// [module documentation]: index.html
+    pub fn new(spi: SPI) -> Self {
+        Self { spi }
+    }
+
+    /// Write colors to the LEDs
+    ///
+    /// The first color goes to the first LED in the chain, the second color to
+    /// the second LED, and so forth. Blocks until all colors have been sent
+    /// and the LEDs have latched them.
+    pub fn write<I>(&mut self, pixels: I) -> Result<(), SPI::Error>
+    where
+        I: IntoIterator<Item = Rgb>,
+    {
+        for pixel in pixels {
+            let mut buffer = [0; 12];
+
+            // The LEDs expect the colors in GRB order
+            let colors = [pixel.g, pixel.r, pixel.b];
+            for (color, chunk) in colors.iter().zip(buffer.chunks_mut(4)) {
+                encode(*color, chunk);
+            }
+
+            self.spi.write(&buffer)?;
+        }
+
+        // Keep the line low for more than 50 µs, to latch the colors
+        self.spi.write(&[0; RESET_BYTES])?;
+
+        Ok(())
+    }
+
+    /// Return the SPI peripheral
+    pub fn free(self) -> SPI {
+        self.spi
+    }
+}
+
+/// An RGB color
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Rgb {
+    /// The red component
+    pub r: u8,
+
+    /// The green component
+    pub g: u8,
+
+    /// The blue component
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Create a color from its components
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Scale the color by a brightness value
+    ///
+    /// A brightness of 255 leaves the color unchanged, 0 turns it off.
+    pub fn scale(self, brightness: u8) -> Self {
+        let scale = |c: u8| ((c as u16 * (brightness as u16 + 1)) >> 8) as u8;
+
+        Self {
+            r: scale(self.r),
+            g: scale(self.g),
+            b: scale(self.b),
+        }
+    }
+}
+
+/// 64 bytes take 171 µs at 3 MHz
+const RESET_BYTES: usize = 64;
+
+/// Encode a byte into 4 SPI bytes, two LED protocol bits per SPI byte
+fn encode(byte: u8, buffer: &mut [u8]) {
+    const ONE: u8 = 0b1110;
+    const ZERO: u8 = 0b1000;
+
+    for (i, b) in buffer.iter_mut().enumerate() {
+        let bits = byte >> (6 - i * 2);
+
+        let high = if bits & 0b10 != 0 { ONE } else { ZERO };
+        let low = if bits & 0b01 != 0 { ONE } else { ZERO };
+
+        *b = (high << 4) | low;
+    }
+}